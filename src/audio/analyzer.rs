@@ -5,49 +5,201 @@
 
 use num_complex::Complex;
 use rustfft::{Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
 use std::sync::Arc;
 
-use super::utils::KickDetector;
+use super::utils::{KickDetector, OnsetEnvelope};
 use crate::utils::DetectionConfig;
 
-/// Number of frequency bands for visualization
-pub const NUM_BANDS: usize = 8;
+/// Number of frequency bands for visualization unless `num_bands` is configured
+pub const DEFAULT_NUM_BANDS: usize = 8;
+/// Range accepted for `num_bands`. Fewer than the default split would leave
+/// visualizations written for 8 bands without their upper bands
+pub const MIN_NUM_BANDS: usize = DEFAULT_NUM_BANDS;
+pub const MAX_NUM_BANDS: usize = 32;
 
-/// FFT size - needs to be large enough for good low-frequency resolution
+/// Default FFT size - needs to be large enough for good low-frequency resolution
 /// At 44.1kHz: 2048 gives ~21.5 Hz bins (good for 20-60 Hz bass range)
-const FFT_SIZE: usize = 2048;
-
-/// Frequency band boundaries (Hz) for 44.1kHz sample rate
+const DEFAULT_FFT_SIZE: usize = 2048;
+
+/// Supported FFT sizes (rounded up to a power of two)
+const MIN_FFT_SIZE: usize = 512;
+const MAX_FFT_SIZE: usize = 16384;
+
+/// Frame rate that frame-count settings (config cooldowns, `last_mark` steps) refer to
+const NOMINAL_FRAME_RATE: f32 = 60.0;
+
+/// Longest frame step fed to the analysis; a stalled frame (window drag,
+/// suspend) would otherwise expire every cooldown and beat window at once
+const MAX_FRAME_DT: f32 = 0.25;
+
+/// Windows compared by transition detection (seconds)
+const RECENT_WINDOW: f32 = 1.0;
+const LONG_WINDOW: f32 = 5.0;
+
+/// Range accepted for a manually tapped tempo
+const MIN_MANUAL_BPM: f32 = 40.0;
+const MAX_MANUAL_BPM: f32 = 240.0;
+
+/// Beats per bar for `bar_phase` (4/4 is assumed; other meters aren't detected)
+const BEATS_PER_BAR: usize = 4;
+/// Smoothed onset strength (dB-ish, 0-1) by which beat one must lead the other slots
+const MIN_DOWNBEAT_ACCENT: f32 = 0.01;
+
+/// `hue_from_centroid` range: centroids are log-mapped from MIN (red) to MAX (violet)
+const CENTROID_HUE_MIN_HZ: f32 = 20.0;
+const CENTROID_HUE_MAX_HZ: f32 = 20000.0;
+/// Hue (degrees) at the top of the range; stops at violet instead of wrapping to red
+const CENTROID_HUE_MAX_DEGREES: f32 = 280.0;
+
+/// `loudness_db` reported for digital silence (and the level it starts from)
+const LOUDNESS_FLOOR_DB: f32 = -100.0;
+/// Per-frame smoothing of `loudness_db` (~0.4s to settle at 60fps, like a
+/// momentary loudness meter)
+const LOUDNESS_SMOOTHING: f32 = 0.9;
+
+/// Frequency band boundaries (Hz) for the default band count, mapped to FFT bins
+/// for the actual sample rate. Other counts are spaced logarithmically over the same range.
 /// Sub-bass, Bass, Low-mid, Mid, Upper-mid, Presence, Brilliance, Air
-const BAND_EDGES: [f32; NUM_BANDS + 1] = [
+const BAND_EDGES: [f32; DEFAULT_NUM_BANDS + 1] = [
     20.0, 60.0, 250.0, 500.0, 2000.0, 4000.0, 6000.0, 12000.0, 20000.0,
 ];
 
-/// Number of spectrum bins to expose (half of FFT size, up to Nyquist)
-pub const SPECTRUM_SIZE: usize = FFT_SIZE / 2;
+/// Band center frequencies (Hz) splitting `bass`, `mids` and `treble`
+const BASS_MAX_HZ: f32 = 250.0;
+const MIDS_MAX_HZ: f32 = 4000.0;
+/// Upper end of the low side compared against `treble` by transition detection
+const TRANSITION_LOW_MAX_HZ: f32 = 500.0;
+
+/// Number of spectrum bins exposed with the default FFT size (half of it, up to Nyquist)
+const DEFAULT_SPECTRUM_SIZE: usize = DEFAULT_FFT_SIZE / 2;
+
+/// Points in `AudioAnalysis::waveform`, enough for a smooth scope across the window
+const WAVEFORM_POINTS: usize = 512;
+
+/// Number of pitch classes in the chromagram (C, C#, D, ... B)
+pub const NUM_PITCH_CLASSES: usize = 12;
+
+/// Frequency span folded into the chromagram: A2 to A6 (4 octaves).
+/// Lower notes are only a few bins apart and would smear across classes
+const CHROMA_MIN_HZ: f32 = 110.0;
+const CHROMA_MAX_HZ: f32 = 1760.0;
+
+/// Noise gate: per-frame smoothing of the learned floor while quiet
+const NOISE_FLOOR_SMOOTHING: f32 = 0.95;
+/// Noise gate: seconds after which the next quiet frame replaces the floor instead
+/// of blending into it, so a hum that went away is forgotten
+const NOISE_FLOOR_RELEARN_SECS: f32 = 30.0;
+/// Noise gate: multiple of the floor subtracted (headroom for the hum's own wobble)
+const NOISE_GATE_MARGIN: f32 = 1.5;
+
+/// Beat events kept for `take_beat_events`; older ones are dropped when nobody drains them
+const MAX_QUEUED_BEAT_EVENTS: usize = 64;
+
+/// A beat onset confirmed by the analyzer (the frame `beat_onset` is set).
+/// Queued until drained with `AudioAnalyzer::take_beat_events`, so a consumer
+/// that doesn't look at every frame still sees each beat exactly once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatEvent {
+    /// Analyzer clock in seconds (sum of the frame steps analyzed so far)
+    pub time: f32,
+    /// Bass energy relative to its running average (1.5 at the onset threshold)
+    pub strength: f32,
+    /// Loudest band on the onset frame, by absolute level (`bands_raw` is
+    /// normalized per band, so a quiet band's click can top it)
+    pub dominant_band: usize,
+}
+
+/// Window applied to each FFT frame (`fft_window` in the config).
+/// Narrower main lobes resolve close tones, lower side lobes keep loud bass
+/// from leaking into quiet neighboring bands
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowFn {
+    /// Good all-rounder (-31 dB side lobes)
+    #[default]
+    Hann,
+    /// Slightly narrower main lobe than Hann, higher far side lobes (-43 dB nearest)
+    Hamming,
+    /// 4-term Blackman-Harris: widest main lobe, almost no leakage (-92 dB)
+    BlackmanHarris,
+    /// No window: sharpest transients and bins, worst leakage (-13 dB)
+    Rectangular,
+}
+
+impl WindowFn {
+    /// Periodic window coefficients for an FFT of `size` samples
+    pub fn coefficients(self, size: usize) -> Vec<f32> {
+        let phase = |i: usize| 2.0 * std::f32::consts::PI * i as f32 / size as f32;
+        (0..size)
+            .map(|i| match self {
+                WindowFn::Hann => 0.5 * (1.0 - phase(i).cos()),
+                WindowFn::Hamming => 0.54 - 0.46 * phase(i).cos(),
+                WindowFn::BlackmanHarris => {
+                    let x = phase(i);
+                    0.35875 - 0.48829 * x.cos() + 0.14128 * (2.0 * x).cos()
+                        - 0.01168 * (3.0 * x).cos()
+                }
+                WindowFn::Rectangular => 1.0,
+            })
+            .collect()
+    }
+}
 
 /// Pre-computed analysis results - no allocations needed by visualizations
 #[derive(Clone)]
 pub struct AudioAnalysis {
-    /// Energy in each frequency band (0-1, smoothed)
-    pub bands: [f32; NUM_BANDS],
-    /// Full frequency spectrum magnitudes (0-1 normalized, SPECTRUM_SIZE bins)
-    /// Index 0 = DC, Index N = N * sample_rate / FFT_SIZE Hz
+    /// Energy in each frequency band, lowest first (0-1, smoothed).
+    /// `num_bands` long (8 by default); every per-band field has the same length
+    pub bands: Vec<f32>,
+    /// Full frequency spectrum magnitudes (0-1 normalized, fft_size / 2 bins)
+    /// Index 0 = DC, Index N = N * sample_rate / fft_size Hz
     pub spectrum: Vec<f32>,
     /// Difference from previous frame's spectrum (for velocity/change visualization)
     pub spectrum_diff: Vec<f32>,
+    /// Sum of positive `spectrum_diff` relative to its running max (0-1).
+    /// Spikes on broadband transients (snares, claps, crashes), not just bass onsets
+    pub spectral_flux: f32,
+    /// Left channel spectrum (same layout as `spectrum`)
+    /// Only computed per channel when `stereo_spectrum` is enabled, otherwise mirrors `spectrum`
+    pub spectrum_left: Vec<f32>,
+    /// Right channel spectrum (same layout as `spectrum`)
+    pub spectrum_right: Vec<f32>,
+    /// Left channel band energies (0-1, smoothed, same scale as `bands`)
+    /// Only computed per channel when `stereo_spectrum` is enabled, otherwise mirrors `bands`
+    pub bands_left: Vec<f32>,
+    /// Right channel band energies (0-1, smoothed, same scale as `bands`)
+    pub bands_right: Vec<f32>,
+    /// Stereo width from left/right correlation (0 = mono, 0.5 = uncorrelated
+    /// or one-sided, 1 = out of phase). 0 unless `stereo_spectrum` is enabled
+    pub stereo_width: f32,
     /// Bands normalized relative to tracked min/max range (can be outside 0-1)
     /// If a band oscillates between 0.6-0.9, this maps it to 0.0-1.0 range
-    pub bands_normalized: [f32; NUM_BANDS],
-    /// Overall energy/volume (0-1)
+    pub bands_normalized: Vec<f32>,
+    /// Overall energy/volume (0-1, smoothed)
     pub energy: f32,
+    /// Band energies before attack/decay smoothing (0-1).
+    /// Reacts instantly to transients but jitters; prefer `bands` for stable motion
+    pub bands_raw: Vec<f32>,
+    /// Energy before smoothing (loudest raw band, 0-1), for tight percussive triggers
+    pub energy_raw: f32,
+    /// RMS of this frame's time-domain samples (linear, 1.0 = full-scale square wave)
+    pub rms: f32,
+    /// This frame's time-domain samples (-1 to 1), oldest first, downsampled to
+    /// `WAVEFORM_POINTS` by keeping the largest swing of each stretch so peaks survive
+    pub waveform: Vec<f32>,
+    /// Smoothed loudness in dBFS (sine-referenced: a full-scale sine reads 0 dB,
+    /// silence -100). Unweighted, so an approximation of LUFS rather than the real thing
+    pub loudness_db: f32,
     /// Whether a musical transition was detected
     pub transition_detected: bool,
-    /// Bass energy (bands 0-1 combined)
+    /// Bass energy (bands centered below 250 Hz, 0-1 with the default bands)
     pub bass: f32,
-    /// Mid energy (bands 2-4 combined)
+    /// Mid energy (bands centered 250 Hz-4 kHz, 2-4 with the default bands)
     pub mids: f32,
-    /// Treble energy (bands 5-7 combined)
+    /// Treble energy (bands centered above 4 kHz, 5-7 with the default bands)
     pub treble: f32,
     /// Difference between current energy and lagged energy (can be negative)
     pub energy_diff: f32,
@@ -55,9 +207,28 @@ pub struct AudioAnalysis {
     pub zoom_direction_shift: bool,
     /// Estimated tempo in beats per minute (smoothed)
     pub bpm: f32,
-    /// Index of the dominant frequency band (0-7, updated max once per second)
+    /// Position within the current beat (0 = on the beat, rising towards 1).
+    /// Advanced by `latency_compensation_ms` so it leads the raw analysis, 0 when no tempo yet
+    pub beat_phase: f32,
+    /// Predicted beat: true on the frame `beat_phase` wraps around (also latency-compensated)
+    pub is_beat: bool,
+    /// Position within the current bar (0 = on the downbeat, rising towards 1).
+    /// Assumes 4/4: a bar is four beats of `beat_phase`, with beat one at the slot
+    /// whose bass onsets have been strongest. 0 when no tempo yet
+    pub bar_phase: f32,
+    /// True on the `is_beat` frame that starts a new bar
+    pub is_downbeat: bool,
+    /// Beat one is placed by a clear accent. Until then (e.g. equal kicks) bars
+    /// start on an arbitrary beat and `is_downbeat` is only a guess
+    pub bar_locked: bool,
+    /// Detected beat: true on the frame a bass onset is accepted as a beat (not compensated)
+    pub beat_onset: bool,
+    /// Per band (same length as `bands`): true on the frame the band rises sharply
+    /// above its recent average (`band_onset_ratio`), e.g. a snare in the mids
+    pub band_onset: Vec<bool>,
+    /// Index of the dominant frequency band (into `bands`, updated max once per second)
     pub dominant_band: usize,
-    /// Steps (frames) since last drastic band change (resets on major energy shift)
+    /// Time since last drastic band change, in 60fps steps (resets on major energy shift)
     pub last_mark: u32,
     /// Whether a visualization change should be triggered (drastic change + high energy)
     pub viz_change_triggered: bool,
@@ -73,6 +244,9 @@ pub struct AudioAnalysis {
     // Beat subdivision detection
     /// Whether a break/subdivision pattern was detected
     pub break_detected: bool,
+    /// No music: `energy` has stayed below `silence_threshold` for `silence_frames`
+    /// (or the device stopped delivering samples). Clears as soon as audio returns
+    pub silence_detected: bool,
 
     // Instrument/complexity detection
     /// Whether spectral complexity increased (instrument added)
@@ -81,6 +255,13 @@ pub struct AudioAnalysis {
     pub instrument_removed: bool,
     /// Weighted average frequency (spectral centroid in Hz)
     pub spectral_centroid: f32,
+    /// Spread between the loudest and quietest band (0-1, smoothed).
+    /// High for sparse sounds (an isolated kick), low for a dense wall of sound.
+    pub spectral_contrast: f32,
+    /// Energy per pitch class, C = 0 through B = 11 (0-1 relative to the strongest, smoothed)
+    pub chroma: [f32; NUM_PITCH_CLASSES],
+    /// Index of the strongest pitch class in `chroma` (9 = A)
+    pub dominant_pitch_class: usize,
 
     // Kick detection (multi-band onset detection)
     /// Whether a kick drum was detected this frame
@@ -98,11 +279,22 @@ pub struct AudioAnalysis {
 impl Default for AudioAnalysis {
     fn default() -> Self {
         Self {
-            bands: [0.0; NUM_BANDS],
-            spectrum: vec![0.0; SPECTRUM_SIZE],
-            spectrum_diff: vec![0.0; SPECTRUM_SIZE],
-            bands_normalized: [0.0; NUM_BANDS],
+            bands: vec![0.0; DEFAULT_NUM_BANDS],
+            spectrum: vec![0.0; DEFAULT_SPECTRUM_SIZE],
+            spectrum_diff: vec![0.0; DEFAULT_SPECTRUM_SIZE],
+            spectral_flux: 0.0,
+            spectrum_left: vec![0.0; DEFAULT_SPECTRUM_SIZE],
+            spectrum_right: vec![0.0; DEFAULT_SPECTRUM_SIZE],
+            bands_left: vec![0.0; DEFAULT_NUM_BANDS],
+            bands_right: vec![0.0; DEFAULT_NUM_BANDS],
+            stereo_width: 0.0,
+            bands_normalized: vec![0.0; DEFAULT_NUM_BANDS],
             energy: 0.0,
+            bands_raw: vec![0.0; DEFAULT_NUM_BANDS],
+            energy_raw: 0.0,
+            rms: 0.0,
+            waveform: vec![0.0; WAVEFORM_POINTS],
+            loudness_db: LOUDNESS_FLOOR_DB,
             transition_detected: false,
             bass: 0.0,
             mids: 0.0,
@@ -110,6 +302,13 @@ impl Default for AudioAnalysis {
             energy_diff: 0.0,
             zoom_direction_shift: false,
             bpm: 0.0,
+            beat_phase: 0.0,
+            is_beat: false,
+            bar_phase: 0.0,
+            is_downbeat: false,
+            bar_locked: false,
+            beat_onset: false,
+            band_onset: vec![false; DEFAULT_NUM_BANDS],
             dominant_band: 0,
             last_mark: 600, // Start at max (10 seconds at 60fps)
             viz_change_triggered: false,
@@ -119,10 +318,14 @@ impl Default for AudioAnalysis {
             rise_rate: 0.0,
             // Break detection
             break_detected: false,
+            silence_detected: false,
             // Instrument detection
             instrument_added: false,
             instrument_removed: false,
             spectral_centroid: 1000.0,
+            spectral_contrast: 0.0,
+            chroma: [0.0; NUM_PITCH_CLASSES],
+            dominant_pitch_class: 0,
             // Kick detection
             kick_detected: false,
             kick_confidence: 0.0,
@@ -133,40 +336,192 @@ impl Default for AudioAnalysis {
     }
 }
 
+impl AudioAnalysis {
+    /// Default (silent) analysis with `num_bands` zeroed bands
+    fn with_num_bands(num_bands: usize) -> Self {
+        Self {
+            bands: vec![0.0; num_bands],
+            bands_left: vec![0.0; num_bands],
+            bands_right: vec![0.0; num_bands],
+            bands_normalized: vec![0.0; num_bands],
+            bands_raw: vec![0.0; num_bands],
+            band_onset: vec![false; num_bands],
+            ..Self::default()
+        }
+    }
+
+    /// The same frame with its one-shot events (beats, onsets, detections)
+    /// cleared, for extra animation steps that must not fire them again
+    pub fn without_events(&self) -> Self {
+        Self {
+            transition_detected: false,
+            zoom_direction_shift: false,
+            is_beat: false,
+            is_downbeat: false,
+            beat_onset: false,
+            band_onset: vec![false; self.band_onset.len()],
+            viz_change_triggered: false,
+            punch_detected: false,
+            break_detected: false,
+            instrument_added: false,
+            instrument_removed: false,
+            kick_detected: false,
+            ..self.clone()
+        }
+    }
+
+    /// Hue in degrees for `spectral_centroid`, log-mapped across 20 Hz-20 kHz:
+    /// red for bass through yellow, green and blue to violet for air, so
+    /// visualizations that use it agree on what color a sound is
+    pub fn hue_from_centroid(&self) -> f32 {
+        let octaves = (self.spectral_centroid.max(CENTROID_HUE_MIN_HZ) / CENTROID_HUE_MIN_HZ).log2();
+        let range = (CENTROID_HUE_MAX_HZ / CENTROID_HUE_MIN_HZ).log2();
+        (octaves / range).clamp(0.0, 1.0) * CENTROID_HUE_MAX_DEGREES
+    }
+}
+
+/// Per-channel spectrum state for stereo analysis.
+/// Uses the same dB scaling and adaptive min/max normalization as the main spectrum.
+struct ChannelSpectrum {
+    fft_buffer: Vec<Complex<f32>>,
+    spectrum: Vec<f32>,
+    spectrum_min: f32,
+    spectrum_max: f32,
+    bands: Vec<f32>,
+}
+
+impl ChannelSpectrum {
+    fn new(fft_size: usize, num_bands: usize) -> Self {
+        Self {
+            fft_buffer: vec![Complex::new(0.0, 0.0); fft_size],
+            spectrum: vec![0.0; fft_size / 2],
+            spectrum_min: 0.0,
+            spectrum_max: 0.0,
+            bands: vec![0.0; num_bands],
+        }
+    }
+
+    /// Update smoothed band energies from the last `process` call. Bands are
+    /// normalized against the mono band range so both channels share its scale
+    /// (a silent channel stays at 0 instead of adapting up to full range).
+    /// Smoothed with the same per-band attack/decay as the mono bands.
+    fn update_bands(
+        &mut self,
+        band_bins: &[(usize, usize)],
+        band_mins: &[f32],
+        band_maxs: &[f32],
+        attack: &[f32],
+        decay: &[f32],
+    ) {
+        for (i, &(low, high)) in band_bins.iter().enumerate() {
+            if high <= low {
+                continue;
+            }
+            let energy: f32 = self.fft_buffer[low..high].iter().map(|c| c.norm_sqr()).sum();
+            let db = 10.0 * (energy / (high - low) as f32 + 1e-10).log10();
+            let rough_normalized = ((db + 100.0) / 160.0).clamp(0.0, 1.0);
+            let range = (band_maxs[i] - band_mins[i]).max(0.01);
+            let normalized = ((rough_normalized - band_mins[i]) / range).clamp(0.0, 1.0);
+
+            let rate = if normalized > self.bands[i] { attack[i] } else { decay[i] };
+            self.bands[i] = self.bands[i] * (1.0 - rate) + normalized * rate;
+        }
+    }
+
+    fn process(&mut self, fft: &dyn Fft<f32>, window: &[f32], samples: &[f32]) {
+        const SPECTRUM_MIN_DRIFT: f32 = 0.99;
+        const SPECTRUM_MAX_DRIFT: f32 = 0.999;
+
+        let sample_count = samples.len().min(self.fft_buffer.len());
+        for (i, bin) in self.fft_buffer.iter_mut().enumerate() {
+            *bin = if i < sample_count {
+                Complex::new(samples[i] * window[i], 0.0)
+            } else {
+                Complex::new(0.0, 0.0)
+            };
+        }
+        fft.process(&mut self.fft_buffer);
+
+        let mut frame_min = f32::MAX;
+        let mut frame_max = f32::MIN;
+        for i in 1..self.spectrum.len() {
+            let db = 10.0 * (self.fft_buffer[i].norm_sqr() + 1e-10).log10();
+            let rough_normalized = ((db + 100.0) / 160.0).clamp(0.0, 1.0);
+            self.spectrum[i] = rough_normalized;
+            frame_min = frame_min.min(rough_normalized);
+            frame_max = frame_max.max(rough_normalized);
+        }
+
+        if frame_min < self.spectrum_min || self.spectrum_min == 0.0 {
+            self.spectrum_min = frame_min;
+        } else {
+            self.spectrum_min =
+                self.spectrum_min * SPECTRUM_MIN_DRIFT + frame_min * (1.0 - SPECTRUM_MIN_DRIFT);
+        }
+        if frame_max > self.spectrum_max {
+            self.spectrum_max = frame_max;
+        } else {
+            self.spectrum_max =
+                self.spectrum_max * SPECTRUM_MAX_DRIFT + frame_max * (1.0 - SPECTRUM_MAX_DRIFT);
+        }
+
+        let range = (self.spectrum_max - self.spectrum_min).max(0.01);
+        for value in self.spectrum.iter_mut().skip(1) {
+            *value = ((*value - self.spectrum_min) / range).clamp(0.0, 1.0);
+        }
+    }
+}
+
 /// Centralized audio analyzer - performs FFT once and extracts all needed metrics
 pub struct AudioAnalyzer {
     // FFT resources (pre-allocated)
     fft: Arc<dyn Fft<f32>>,
     fft_buffer: Vec<Complex<f32>>,
     fft_window: Vec<f32>,
-
-    // Band bin ranges (pre-computed)
-    band_bins: [(usize, usize); NUM_BANDS],
+    fft_size: usize,
+    sample_rate: f32,
+
+    // Band layout (pre-computed): bin range and edge frequencies per band, plus
+    // the bands each aggregate averages
+    band_bins: Vec<(usize, usize)>,
+    band_edges: Vec<f32>,
+    bass_bands: Range<usize>,
+    mid_bands: Range<usize>,
+    treble_bands: Range<usize>,
+    transition_low_bands: Range<usize>,
+    // (bin, pitch class) for every bin folded into the chromagram (pre-computed)
+    chroma_bins: Vec<(usize, usize)>,
 
     // Smoothed values
-    smoothed_bands: [f32; NUM_BANDS],
+    smoothed_bands: Vec<f32>,
     smoothed_energy: f32,
     lagged_energy: f32,
+    loudness_db: f32,
 
-    // Transition detection state
-    energy_history: Vec<f32>,
-    freq_ratio_history: Vec<f32>,
-    history_idx: usize,
+    // Transition detection state: (dt, energy, freq_ratio) per frame, covering LONG_WINDOW
+    transition_history: VecDeque<(f32, f32, f32)>,
+    transition_history_time: f32,
     was_high_energy: bool,
     was_high_freq: bool,
 
     // Peak detection
-    prev_bands: [f32; NUM_BANDS],
+    prev_bands: Vec<f32>,
+
+    // Per-band onset detection: running average of each band's amplitude, and whether it
+    // was above the onset threshold last frame (onsets fire on the rising edge)
+    band_averages: Vec<f32>,
+    bands_above: Vec<bool>,
 
     // Min/max tracking for normalization (slowly drift towards 0)
-    band_mins: [f32; NUM_BANDS],
-    band_maxs: [f32; NUM_BANDS],
+    band_mins: Vec<f32>,
+    band_maxs: Vec<f32>,
 
     // Zoom direction shift detection
     prev_energy_diff: f32,
 
     // BPM detection
     beat_times: Vec<f32>,      // Timestamps of recent beats (in seconds)
+    beat_strengths: Vec<f32>,  // Peak bass level (dB scale) of each tracked beat
     last_beat_time: f32,       // Last detected beat time
     smoothed_bpm: f32,         // Smoothed BPM estimate
     locked_bpm: f32,           // Locked BPM (only updates with high confidence)
@@ -174,16 +529,29 @@ pub struct AudioAnalyzer {
     frame_time: f32,           // Accumulated time for timestamping
     prev_bass_energy: f32,     // Previous frame's bass energy for onset detection
     bass_energy_avg: f32,      // Running average of bass energy for threshold
-    low_bass_frames: u32,      // Frames with low bass (for break detection)
+    low_bass_time: f32,        // Seconds of low bass (for break detection)
+    prev_beat_phase: f32,      // Beat clock phase on the previous frame (wrap = predicted beat)
+    manual_bpm: Option<f32>,   // Tapped tempo, overrides the detected one until cleared
+    external_bpm: Option<f32>, // Tempo from an external clock (Link), overrides both
+    external_phase: Option<f32>, // Beat phase from the external clock, replaces the onset clock
+    beat_events: Vec<BeatEvent>, // Confirmed onsets not yet taken
+
+    // Bar tracking (assumes 4/4)
+    beat_count: usize,                     // Beat clock wraps so far
+    onset_slot: Option<usize>,             // Beat-in-bar slot (count % 4) of the latest onset
+    slot_strengths: [f32; BEATS_PER_BAR],  // Smoothed onset strength per slot
+    downbeat_slot: usize,                  // Slot treated as beat one
+    prev_bar_phase: f32,
 
     // Dominant band detection
     dominant_band: usize,           // Current dominant band index
     last_dominant_update_time: f32, // Last time dominant band was updated
 
     // Drastic band change detection (last_mark)
-    last_mark: u32,                    // Frames since last drastic change
-    reference_bands: [f32; NUM_BANDS], // Reference bands for comparison
-    viz_change_cooldown: u32,          // Cooldown frames before viz_change can trigger again
+    last_mark: u32,              // 60fps steps since last drastic change
+    mark_elapsed: f32,           // Seconds since last drastic change
+    reference_bands: Vec<f32>,   // Reference bands for comparison
+    viz_change_cooldown: f32,    // Seconds before viz_change can trigger again
 
     // Frame skipping for performance
     frame_count: u32,
@@ -191,15 +559,26 @@ pub struct AudioAnalyzer {
 
     // Punch detection state
     energy_floor: f32,
-    punch_cooldown: u32,
+    punch_cooldown: f32,
+
+    // Break detection state (silence-based, seconds)
+    time_since_beat: f32,
+    break_cooldown: f32,
 
-    // Break detection state (silence-based)
-    frames_since_beat: u32,
-    break_cooldown: u32,
+    // Silence detection state (seconds below the silence threshold)
+    quiet_time: f32,
+
+    // Noise gate: learned per-band amplitude of the background, and seconds since it
+    // was last relearned from scratch
+    noise_floor: Vec<f32>,
+    noise_floor_age: f32,
 
     // Spectral complexity tracking
     spectral_complexity: f32,
     prev_spectral_complexity: f32,
+    smoothed_contrast: f32,
+    smoothed_width: f32,
+    smoothed_chroma: [f32; NUM_PITCH_CLASSES],
 
     // Full spectrum tracking (pre-allocated, reused each frame)
     spectrum: Vec<f32>,
@@ -207,55 +586,76 @@ pub struct AudioAnalyzer {
     spectrum_diff: Vec<f32>,
     spectrum_min: f32,
     spectrum_max: f32,
+    flux_max: f32, // Running max of the raw flux sum (decays slowly)
+
+    // Per-channel spectrum (only used when stereo_spectrum is enabled)
+    left_spectrum: ChannelSpectrum,
+    right_spectrum: ChannelSpectrum,
 
     // Detection configuration (from config file)
     detection_config: DetectionConfig,
 
     // Kick detector (multi-band onset detection)
     kick_detector: KickDetector,
+
+    // Short-window bass envelope (onset timing at lower latency than the FFT)
+    onset_envelope: OnsetEnvelope,
 }
 
 impl AudioAnalyzer {
+    /// Analyzer for a device running at `sample_rate`, with the FFT size from the config
     pub fn with_config(sample_rate: f32, detection_config: DetectionConfig) -> Self {
+        let fft_size = detection_config.fft_size();
+        Self::with_fft_size(sample_rate, fft_size, detection_config)
+    }
+
+    /// Analyzer with an explicit FFT size (rounded up to a power of two, 512-16384).
+    /// Band bins, spectrum length and the window all follow `sample_rate` and `fft_size`.
+    pub fn with_fft_size(sample_rate: f32, fft_size: usize, detection_config: DetectionConfig) -> Self {
+        let fft_size = fft_size.next_power_of_two().clamp(MIN_FFT_SIZE, MAX_FFT_SIZE);
+        let spectrum_size = fft_size / 2;
         let mut planner = FftPlanner::new();
-        let fft = planner.plan_fft_forward(FFT_SIZE);
+        let fft = planner.plan_fft_forward(fft_size);
 
-        // Pre-compute Hann window
-        let fft_window: Vec<f32> = (0..FFT_SIZE)
-            .map(|i| 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos()))
-            .collect();
+        // Pre-compute the window
+        let fft_window = detection_config.fft_window().coefficients(fft_size);
 
         // Pre-compute which FFT bins correspond to each frequency band
-        let bin_width = sample_rate / FFT_SIZE as f32;
-        let mut band_bins = [(0usize, 0usize); NUM_BANDS];
-
-        for i in 0..NUM_BANDS {
-            let low_bin = (BAND_EDGES[i] / bin_width).floor() as usize;
-            let high_bin = (BAND_EDGES[i + 1] / bin_width).ceil() as usize;
-            band_bins[i] = (low_bin.max(1), high_bin.min(FFT_SIZE / 2));
-        }
+        let num_bands = detection_config.num_bands();
+        let band_edges = band_edges(num_bands);
+        let band_bins = band_bins(&band_edges, sample_rate, fft_size);
 
-        const HISTORY_SIZE: usize = 300; // ~5 seconds at 60fps for stable detection
         const BPM_HISTORY_SIZE: usize = 16; // Track last 16 beats for stable BPM
 
         Self {
             fft,
-            fft_buffer: vec![Complex::new(0.0, 0.0); FFT_SIZE],
+            fft_buffer: vec![Complex::new(0.0, 0.0); fft_size],
             fft_window,
+            fft_size,
+            sample_rate,
             band_bins,
-            smoothed_bands: [0.0; NUM_BANDS],
+            bass_bands: bands_between(&band_edges, 0.0, BASS_MAX_HZ),
+            mid_bands: bands_between(&band_edges, BASS_MAX_HZ, MIDS_MAX_HZ),
+            treble_bands: bands_between(&band_edges, MIDS_MAX_HZ, f32::INFINITY),
+            transition_low_bands: bands_between(&band_edges, 0.0, TRANSITION_LOW_MAX_HZ),
+            band_edges,
+            chroma_bins: chroma_bins(sample_rate, fft_size),
+            smoothed_bands: vec![0.0; num_bands],
             smoothed_energy: 0.0,
             lagged_energy: 0.0,
-            energy_history: vec![0.0; HISTORY_SIZE],
-            freq_ratio_history: vec![0.0; HISTORY_SIZE],
-            history_idx: 0,
+            loudness_db: LOUDNESS_FLOOR_DB,
+            transition_history: VecDeque::new(),
+            transition_history_time: 0.0,
             was_high_energy: false,
             was_high_freq: false,
-            prev_bands: [0.0; NUM_BANDS],
-            band_mins: [0.0; NUM_BANDS],
-            band_maxs: [0.0; NUM_BANDS],
+            prev_bands: vec![0.0; num_bands],
+            band_averages: vec![0.0; num_bands],
+            bands_above: vec![false; num_bands],
+            band_mins: vec![0.0; num_bands],
+            band_maxs: vec![0.0; num_bands],
             prev_energy_diff: 0.0,
             beat_times: Vec::with_capacity(BPM_HISTORY_SIZE),
+            beat_strengths: Vec::with_capacity(BPM_HISTORY_SIZE),
             last_beat_time: 0.0,
             smoothed_bpm: 0.0,
             locked_bpm: 0.0,
@@ -263,50 +663,223 @@ impl AudioAnalyzer {
             frame_time: 0.0,
             prev_bass_energy: 0.0,
             bass_energy_avg: 0.0,
-            low_bass_frames: 0,
+            low_bass_time: 0.0,
+            prev_beat_phase: 0.0,
+            manual_bpm: None,
+            external_bpm: None,
+            external_phase: None,
+            beat_events: Vec::new(),
+            beat_count: 0,
+            onset_slot: None,
+            slot_strengths: [0.0; BEATS_PER_BAR],
+            downbeat_slot: 0,
+            prev_bar_phase: 0.0,
             dominant_band: 0,
             last_dominant_update_time: 0.0,
             last_mark: 600, // Start at max (10 seconds at 60fps)
-            reference_bands: [0.0; NUM_BANDS],
-            viz_change_cooldown: 0,
+            mark_elapsed: 10.0,
+            reference_bands: vec![0.0; num_bands],
+            viz_change_cooldown: 0.0,
             frame_count: 0,
-            last_analysis: AudioAnalysis::default(),
+            last_analysis: AudioAnalysis::with_num_bands(num_bands),
             // Punch detection
             energy_floor: 0.0,
-            punch_cooldown: 0,
+            punch_cooldown: 0.0,
             // Break detection
-            frames_since_beat: 0,
-            break_cooldown: 0,
+            time_since_beat: 0.0,
+            break_cooldown: 0.0,
+            // Silence detection
+            quiet_time: 0.0,
+            // Noise gate (the first quiet frame learns the floor)
+            noise_floor: vec![0.0; num_bands],
+            noise_floor_age: NOISE_FLOOR_RELEARN_SECS,
             // Spectral complexity
             spectral_complexity: 0.0,
             prev_spectral_complexity: 0.0,
+            smoothed_contrast: 0.0,
+            smoothed_width: 0.0,
+            smoothed_chroma: [0.0; NUM_PITCH_CLASSES],
             // Full spectrum tracking (pre-allocated)
-            spectrum: vec![0.0; SPECTRUM_SIZE],
-            prev_spectrum: vec![0.0; SPECTRUM_SIZE],
-            spectrum_diff: vec![0.0; SPECTRUM_SIZE],
+            spectrum: vec![0.0; spectrum_size],
+            prev_spectrum: vec![0.0; spectrum_size],
+            spectrum_diff: vec![0.0; spectrum_size],
             spectrum_min: 0.0,
             spectrum_max: 0.0,
+            flux_max: 0.0,
+            // Per-channel spectrum
+            left_spectrum: ChannelSpectrum::new(fft_size, num_bands),
+            right_spectrum: ChannelSpectrum::new(fft_size, num_bands),
             // Detection config
             detection_config,
             // Kick detector
-            kick_detector: KickDetector::new(sample_rate, FFT_SIZE),
+            kick_detector: KickDetector::new(sample_rate, fft_size),
+            onset_envelope: OnsetEnvelope::new(sample_rate),
+        }
+    }
+
+    /// FFT size in use (after rounding); the number of samples each frame should supply
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Follow a device sample rate change (e.g. after switching devices).
+    /// Re-maps the band and kick bins; other state carries over.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate == self.sample_rate || sample_rate <= 0.0 {
+            return;
         }
+        self.sample_rate = sample_rate;
+        self.band_bins = band_bins(&self.band_edges, sample_rate, self.fft_size);
+        self.chroma_bins = chroma_bins(sample_rate, self.fft_size);
+        self.kick_detector = KickDetector::new(sample_rate, self.fft_size);
+        self.onset_envelope = OnsetEnvelope::new(sample_rate);
+    }
+
+    /// Lock the tempo to a manually tapped BPM until `clear_manual_bpm`.
+    /// The beat clock restarts now, so the tap that set it lands on the beat.
+    pub fn set_manual_bpm(&mut self, bpm: f32) {
+        self.manual_bpm = Some(bpm.clamp(MIN_MANUAL_BPM, MAX_MANUAL_BPM));
+        self.last_beat_time = self.frame_time;
+    }
+
+    /// Go back to the detected tempo (detection kept running meanwhile)
+    pub fn clear_manual_bpm(&mut self) {
+        self.manual_bpm = None;
+    }
+
+    /// Follow an external beat clock (Ableton Link) until cleared. Its tempo
+    /// overrides tapped and detected ones; its phase, when known, replaces the
+    /// onset-driven beat clock as is (the session is already aligned to what is
+    /// heard, so no latency compensation). Detection keeps running meanwhile.
+    #[cfg_attr(not(feature = "link"), allow(dead_code))]
+    pub fn set_external_beat(&mut self, bpm: f32, beat_phase: Option<f32>) {
+        self.external_bpm = Some(bpm).filter(|bpm| *bpm > 0.0);
+        self.external_phase = beat_phase.filter(|_| self.external_bpm.is_some());
+    }
+
+    /// Go back to the tapped or detected tempo
+    #[cfg_attr(not(feature = "link"), allow(dead_code))]
+    pub fn clear_external_beat(&mut self) {
+        self.external_bpm = None;
+        self.external_phase = None;
+    }
+
+    /// Tempo driving the beat clock: external, then tapped, otherwise the detected one
+    fn tempo(&self) -> f32 {
+        self.external_bpm
+            .or(self.manual_bpm)
+            .unwrap_or(self.smoothed_bpm)
+    }
+
+    /// Current beat clock phase, latency-compensated unless an external clock drives it
+    fn clock_phase(&self) -> f32 {
+        self.external_phase.unwrap_or_else(|| {
+            beat_phase(
+                self.frame_time,
+                self.last_beat_time,
+                self.tempo(),
+                self.detection_config.latency_compensation_ms() / 1000.0,
+            )
+        })
+    }
+
+    /// Beats confirmed since the last call, oldest first
+    pub fn take_beat_events(&mut self) -> Vec<BeatEvent> {
+        std::mem::take(&mut self.beat_events)
+    }
+
+    /// Whether per-channel spectrum analysis is enabled (doubles FFT work)
+    pub fn stereo_enabled(&self) -> bool {
+        self.detection_config.stereo_spectrum()
     }
 
-    /// Analyze audio samples. Call once per frame.
-    /// Returns cached result if called multiple times per frame.
+    /// Analyze mono samples plus left/right channels. Call once per frame instead of `analyze_with_dt`.
+    /// Runs an extra spectrum pass per channel when `stereo_spectrum` is enabled.
+    pub fn analyze_stereo(
+        &mut self,
+        samples: &[f32],
+        left: &[f32],
+        right: &[f32],
+        dt: f32,
+    ) -> AudioAnalysis {
+        self.analyze_with_dt(samples, dt);
+
+        if self.stereo_enabled() && !samples.is_empty() {
+            self.left_spectrum
+                .process(self.fft.as_ref(), &self.fft_window, left);
+            self.right_spectrum
+                .process(self.fft.as_ref(), &self.fft_window, right);
+            let attack = self.detection_config.band_attack();
+            let decay = self.detection_config.band_decay();
+            self.left_spectrum
+                .update_bands(&self.band_bins, &self.band_mins, &self.band_maxs, &attack, &decay);
+            self.right_spectrum
+                .update_bands(&self.band_bins, &self.band_mins, &self.band_maxs, &attack, &decay);
+            self.last_analysis
+                .spectrum_left
+                .clone_from(&self.left_spectrum.spectrum);
+            self.last_analysis
+                .spectrum_right
+                .clone_from(&self.right_spectrum.spectrum);
+            self.last_analysis
+                .bands_left
+                .clone_from(&self.left_spectrum.bands);
+            self.last_analysis
+                .bands_right
+                .clone_from(&self.right_spectrum.bands);
+
+            const WIDTH_SMOOTHING: f32 = 0.9;
+            self.smoothed_width = self.smoothed_width * WIDTH_SMOOTHING
+                + stereo_width(left, right) * (1.0 - WIDTH_SMOOTHING);
+            self.last_analysis.stereo_width = self.smoothed_width;
+        }
+
+        self.last_analysis.clone()
+    }
+
+    /// Analyze audio samples assuming a steady 60fps frame clock.
+    #[allow(dead_code)]
     pub fn analyze(&mut self, samples: &[f32]) -> AudioAnalysis {
+        self.analyze_with_dt(samples, 1.0 / NOMINAL_FRAME_RATE)
+    }
+
+    /// Analyze audio samples. Call once per frame with the seconds elapsed since
+    /// the previous call; beat timing, cooldowns and history windows follow it.
+    /// Level smoothing stays per-frame.
+    pub fn analyze_with_dt(&mut self, samples: &[f32], dt: f32) -> AudioAnalysis {
         self.frame_count = self.frame_count.wrapping_add(1);
 
-        // Update frame time (assuming ~60fps = 0.0167s per frame)
-        const FRAME_DELTA: f32 = 1.0 / 60.0;
-        self.frame_time += FRAME_DELTA;
+        let dt = dt.clamp(0.0, MAX_FRAME_DT);
+        self.frame_time += dt;
+
+        // Starved device: report silence without feeding it into the adaptive
+        // trackers or smoothing, so levels resume where they left off
+        if samples.is_empty() {
+            return self.silent_analysis(dt);
+        }
 
-        // Take FFT_SIZE samples from the input (or pad with zeros)
-        let sample_count = samples.len().min(FFT_SIZE);
+        // Take fft_size samples from the input (or pad with zeros)
+        let sample_count = samples.len().min(self.fft_size);
+
+        // Loudness from the raw samples, before the window attenuates the edges
+        let rms = (samples[..sample_count].iter().map(|s| s * s).sum::<f32>()
+            / sample_count as f32)
+            .sqrt();
+        let waveform = downsample_waveform(&samples[..sample_count], WAVEFORM_POINTS);
+        let frame_db = rms_to_dbfs(rms);
+        self.loudness_db =
+            self.loudness_db * LOUDNESS_SMOOTHING + frame_db * (1.0 - LOUDNESS_SMOOTHING);
+
+        // Short path: bass level of the newest ~12 ms, for onset timing only
+        // (the FFT below spreads a kick over the whole window before it registers)
+        let (fast_onset, fast_level) = if self.detection_config.fast_onsets() {
+            self.onset_envelope.process(&samples[..sample_count], dt)
+        } else {
+            (false, 0.0)
+        };
 
         // Apply window and fill buffer (reusing pre-allocated buffer)
-        for i in 0..FFT_SIZE {
+        for i in 0..self.fft_size {
             if i < sample_count {
                 self.fft_buffer[i] = Complex::new(samples[i] * self.fft_window[i], 0.0);
             } else {
@@ -317,8 +890,23 @@ impl AudioAnalyzer {
         // Perform FFT
         self.fft.process(&mut self.fft_buffer);
 
+        // Noise gate: frames below the threshold teach the per-band floor
+        let noise_gate = self.detection_config.noise_gate();
+        let quiet = noise_gate && frame_db < self.detection_config.noise_gate_threshold_db();
+        self.noise_floor_age += dt;
+        let relearn = quiet && self.noise_floor_age >= NOISE_FLOOR_RELEARN_SECS;
+        if relearn {
+            self.noise_floor_age = 0.0;
+        }
+        let floor_smoothing = frame_decay(NOISE_FLOOR_SMOOTHING, dt);
+
         // Calculate band energies
-        let mut bands_raw = [0.0f32; NUM_BANDS];
+        let num_bands = self.band_bins.len();
+        let mut bands_raw = vec![0.0f32; num_bands];
+        // dB-scaled band levels before adaptive normalization (used for beat accents)
+        let mut bands_rough = vec![0.0f32; num_bands];
+        // Linear RMS magnitude per band (per-band onsets compare these to their average)
+        let mut band_amplitudes = vec![0.0f32; num_bands];
 
         for (i, &(low, high)) in self.band_bins.iter().enumerate() {
             if high > low {
@@ -328,19 +916,35 @@ impl AudioAnalyzer {
                     .sum();
 
                 // Normalize and convert to dB-ish scale
-                let avg_energy = energy / (high - low) as f32;
+                let mut amplitude = (energy / (high - low) as f32).sqrt();
+
+                // Subtract the learned noise floor before anything adapts to it
+                if noise_gate {
+                    let floor = &mut self.noise_floor[i];
+                    if relearn {
+                        *floor = amplitude;
+                    } else if quiet {
+                        *floor = *floor * floor_smoothing + amplitude * (1.0 - floor_smoothing);
+                    }
+                    amplitude = (amplitude - *floor * NOISE_GATE_MARGIN).max(0.0);
+                }
+                let avg_energy = amplitude * amplitude;
+                band_amplitudes[i] = amplitude;
 
                 // Convert to dB scale and do initial rough normalization
                 let db = 10.0 * (avg_energy + 1e-10).log10();
                 let rough_normalized = ((db + 100.0) / 160.0).clamp(0.0, 1.0); // Rough -100 to +60 dB range
+                bands_rough[i] = rough_normalized;
 
                 // Adaptive normalization: track min/max of the output (0-1 range)
                 // This creates perceptual adaptation - sustained intensity becomes less intense
                 const MIN_DRIFT: f32 = 0.985; // Faster drift towards current (~1 sec at 60fps)
                 const MAX_DRIFT: f32 = 0.985;
 
-                // Update minimum - track lowest output, slowly drift up towards current
-                if rough_normalized < self.band_mins[i] || self.band_mins[i] == 0.0 {
+                // Update minimum - track lowest output, slowly drift up towards current.
+                // A zero minimum is re-seeded, except behind the gate where 0 is the floor
+                let unseeded = self.band_mins[i] == 0.0 && !noise_gate;
+                if rough_normalized < self.band_mins[i] || unseeded {
                     self.band_mins[i] = rough_normalized;
                 } else {
                     // Drift upwards towards current value
@@ -366,6 +970,28 @@ impl AudioAnalyzer {
             }
         }
 
+        // Spectral contrast on the un-adapted dB levels (adaptive normalization
+        // would flatten any sustained spread)
+        const CONTRAST_SMOOTHING: f32 = 0.9;
+        let contrast = spectral_contrast(&bands_rough);
+        self.smoothed_contrast =
+            self.smoothed_contrast * CONTRAST_SMOOTHING + contrast * (1.0 - CONTRAST_SMOOTHING);
+
+        // Chromagram: fold bin magnitudes into pitch classes, relative to the strongest
+        const CHROMA_SMOOTHING: f32 = 0.8;
+        let mut chroma = [0.0f32; NUM_PITCH_CLASSES];
+        for &(bin, pitch_class) in &self.chroma_bins {
+            chroma[pitch_class] += self.fft_buffer[bin].norm();
+        }
+        let chroma_peak = chroma.iter().cloned().fold(0.0f32, f32::max);
+        for (smoothed, &value) in self.smoothed_chroma.iter_mut().zip(&chroma) {
+            let relative = if chroma_peak > 1e-6 { value / chroma_peak } else { 0.0 };
+            *smoothed = *smoothed * CHROMA_SMOOTHING + relative * (1.0 - CHROMA_SMOOTHING);
+        }
+        let dominant_pitch_class = (0..NUM_PITCH_CLASSES)
+            .max_by(|&a, &b| self.smoothed_chroma[a].total_cmp(&self.smoothed_chroma[b]))
+            .unwrap_or(0);
+
         // Calculate full spectrum magnitudes (for visualizations that want specific frequencies)
         // Reuse pre-allocated buffers to avoid allocations per frame
         const SPECTRUM_MIN_DRIFT: f32 = 0.99;   // Min adapts in ~1-2 seconds
@@ -374,7 +1000,7 @@ impl AudioAnalyzer {
         // First pass: compute rough normalized values and find current frame's min/max
         let mut frame_min = f32::MAX;
         let mut frame_max = f32::MIN;
-        for i in 1..SPECTRUM_SIZE {
+        for i in 1..self.spectrum.len() {
             let magnitude = self.fft_buffer[i].norm_sqr();
             let db = 10.0 * (magnitude + 1e-10).log10();
             let rough_normalized = ((db + 100.0) / 160.0).clamp(0.0, 1.0);
@@ -401,27 +1027,34 @@ impl AudioAnalyzer {
 
         // Second pass: normalize all values using global min/max and compute diff
         let range = (self.spectrum_max - self.spectrum_min).max(0.01);
-        for i in 1..SPECTRUM_SIZE {
+        let mut flux = 0.0;
+        for i in 1..self.spectrum.len() {
             let rough_normalized = self.spectrum[i];
             let normalized = ((rough_normalized - self.spectrum_min) / range).clamp(0.0, 1.0);
             // Compute diff from previous frame's normalized value
             self.spectrum_diff[i] = normalized - self.prev_spectrum[i];
+            flux += self.spectrum_diff[i].max(0.0);
             // Store current for next frame's diff calculation
             self.prev_spectrum[i] = normalized;
             self.spectrum[i] = normalized;
         }
 
-        // Smooth bands (fast attack, faster decay so they don't stay high)
-        let attack = 0.7;
-        let decay = 0.25; // Faster decay (was 0.15) so bands drop quicker
-        for i in 0..NUM_BANDS {
-            if bands_raw[i] > self.smoothed_bands[i] {
-                self.smoothed_bands[i] =
-                    self.smoothed_bands[i] * (1.0 - attack) + bands_raw[i] * attack;
+        // Spectral flux: rising energy across all bins, scaled by its recent peak
+        const FLUX_MAX_DECAY: f32 = 0.995; // Peak halves in ~2 seconds at 60fps
+        const MIN_FLUX_MAX: f32 = 1.0;     // Keeps quiet passages from reading as full-scale
+        self.flux_max = (self.flux_max * frame_decay(FLUX_MAX_DECAY, dt)).max(flux).max(MIN_FLUX_MAX);
+        let spectral_flux = flux / self.flux_max;
+
+        // Smooth bands (per-band attack/decay, by default fast attack and faster decay)
+        let band_attack = self.detection_config.band_attack();
+        let band_decay = self.detection_config.band_decay();
+        for i in 0..num_bands {
+            let factor = if bands_raw[i] > self.smoothed_bands[i] {
+                band_attack[i]
             } else {
-                self.smoothed_bands[i] =
-                    self.smoothed_bands[i] * (1.0 - decay) + bands_raw[i] * decay;
-            }
+                band_decay[i]
+            };
+            self.smoothed_bands[i] = self.smoothed_bands[i] * (1.0 - factor) + bands_raw[i] * factor;
         }
 
         // Calculate overall energy (use max band value instead of average)
@@ -440,28 +1073,44 @@ impl AudioAnalyzer {
         let energy_diff = self.smoothed_energy - self.lagged_energy;
 
         self.prev_energy_diff = energy_diff;
-        self.prev_bands = bands_raw;
+        self.prev_bands.clone_from(&bands_raw);
+
+        let band_onset = self.detect_band_onsets(&band_amplitudes, &bands_raw, dt);
 
         // Transition detection
-        let transition_detected = self.detect_transition(energy_raw, &bands_raw);
+        let transition_detected = self.detect_transition(energy_raw, &bands_raw, dt);
 
         // BPM detection using bass onset detection
         // Use sub-bass + bass bands for beat detection (where kick drums live)
-        let bass_energy = (bands_raw[0] + bands_raw[1]) / 2.0;
+        let bass_energy = band_mean(&bands_raw, &self.bass_bands);
+        let bass_level = band_mean(&bands_rough, &self.bass_bands);
+
+        // Track the peak level of the latest beat shortly after its onset
+        // (the onset frame often only catches the start of the hit)
+        const ACCENT_WINDOW: f32 = 0.1;
+        if self.frame_time - self.last_beat_time < ACCENT_WINDOW {
+            if let Some(strength) = self.beat_strengths.last_mut() {
+                *strength = strength.max(bass_level);
+            }
+        }
 
         // Update running average of bass energy (very slow adaptation for stability)
+        // (drives the onset threshold, so it adapts over time rather than frames)
         const BASS_AVG_DECAY: f32 = 0.995; // ~3 seconds to adapt at 60fps
-        self.bass_energy_avg = self.bass_energy_avg * BASS_AVG_DECAY + bass_energy * (1.0 - BASS_AVG_DECAY);
+        let bass_avg_decay = frame_decay(BASS_AVG_DECAY, dt);
+        self.bass_energy_avg = self.bass_energy_avg * bass_avg_decay + bass_energy * (1.0 - bass_avg_decay);
 
         // Track low bass periods (breaks in techno)
         const LOW_BASS_THRESHOLD: f32 = 0.15; // Bass below this = likely in a break
-        const BREAK_FRAMES: u32 = 30;         // ~0.5 sec of low bass = break
+        const BREAK_TIME: f32 = 0.5;          // Seconds of low bass = break
         if bass_energy < LOW_BASS_THRESHOLD {
-            self.low_bass_frames = self.low_bass_frames.saturating_add(1);
+            self.low_bass_time += dt;
         } else {
-            self.low_bass_frames = self.low_bass_frames.saturating_sub(2); // Faster recovery
+            self.low_bass_time = (self.low_bass_time - dt * 2.0).max(0.0); // Faster recovery
         }
-        let in_break = self.low_bass_frames > BREAK_FRAMES;
+        let in_break = self.low_bass_time > BREAK_TIME;
+
+        let mut beat_onset = false;
 
         // During breaks: freeze BPM updates, use locked value
         // This prevents BPM drift when kicks drop out
@@ -473,12 +1122,15 @@ impl AudioAnalyzer {
             }
         } else {
             // Normal beat detection when not in break
-            // Detect beat: bass energy rising sharply above recent average
+            // Detect beat: bass energy rising sharply above recent average, or the
+            // short path catching the kick first (the interval check below keeps
+            // the band path from counting it again a frame later)
             const BEAT_THRESHOLD_RATIO: f32 = 1.5; // Current must be 50% above average
             const MIN_BASS_FOR_BEAT: f32 = 0.2;    // Minimum absolute bass level
-            let is_onset = bass_energy > self.prev_bass_energy
-                && bass_energy > self.bass_energy_avg * BEAT_THRESHOLD_RATIO
-                && bass_energy > MIN_BASS_FOR_BEAT;
+            let is_onset = fast_onset
+                || (bass_energy > self.prev_bass_energy
+                    && bass_energy > self.bass_energy_avg * BEAT_THRESHOLD_RATIO
+                    && bass_energy > MIN_BASS_FOR_BEAT);
 
             if is_onset {
                 let time_since_last_beat = self.frame_time - self.last_beat_time;
@@ -489,12 +1141,27 @@ impl AudioAnalyzer {
                 const MAX_BEAT_INTERVAL: f32 = 1.5;
                 if time_since_last_beat >= MIN_BEAT_INTERVAL && time_since_last_beat <= MAX_BEAT_INTERVAL {
                     self.beat_times.push(self.frame_time);
+                    self.beat_strengths.push(bass_level);
                     self.last_beat_time = self.frame_time;
+                    beat_onset = true;
+
+                    if self.beat_events.len() >= MAX_QUEUED_BEAT_EVENTS {
+                        self.beat_events.remove(0);
+                    }
+                    let dominant_band = (0..num_bands)
+                        .max_by(|&a, &b| bands_rough[a].total_cmp(&bands_rough[b]))
+                        .unwrap_or(0);
+                    self.beat_events.push(BeatEvent {
+                        time: self.frame_time,
+                        strength: bass_energy / self.bass_energy_avg.max(1e-3),
+                        dominant_band,
+                    });
 
                     // Keep only last 16 beats (~8-16 seconds of history for stable BPM)
                     const MAX_BEAT_HISTORY: usize = 16;
                     if self.beat_times.len() > MAX_BEAT_HISTORY {
                         self.beat_times.remove(0);
+                        self.beat_strengths.remove(0);
                     }
 
                     // Calculate BPM from intervals between beats
@@ -510,7 +1177,19 @@ impl AudioAnalyzer {
                         let median_interval = intervals[intervals.len() / 2];
 
                         // Convert to BPM (beats per minute)
-                        let instant_bpm = 60.0 / median_interval;
+                        let mut instant_bpm = 60.0 / median_interval;
+
+                        // Alternating strong/weak hits mean we're tracking off-beats too:
+                        // the real beat is every other onset
+                        if accents_alternate(&self.beat_strengths) && instant_bpm / 2.0 >= 60.0 {
+                            instant_bpm /= 2.0;
+                        }
+
+                        // Octave-error correction: a reading at ~half or ~double the locked
+                        // tempo is the same tempo counted differently, keep the locked octave
+                        if self.locked_bpm > 0.0 {
+                            instant_bpm = fold_to_octave(instant_bpm, self.locked_bpm);
+                        }
 
                         // Clamp to reasonable BPM range (60-200)
                         let clamped_bpm = instant_bpm.clamp(60.0, 200.0);
@@ -556,13 +1235,20 @@ impl AudioAnalyzer {
 
         self.prev_bass_energy = bass_energy;
 
+        // Beat clock: free-runs at the smoothed tempo from the last onset, shifted
+        // earlier by the configured latency so on-beat animations land when heard
+        let beat_phase = self.clock_phase();
+        let is_beat = self.tempo() > 0.0 && beat_phase < self.prev_beat_phase;
+        let (bar_phase, is_downbeat) = self.advance_bar(beat_phase, beat_onset);
+        self.prev_beat_phase = beat_phase;
+
         // Update dominant band (max once per second)
         const DOMINANT_UPDATE_INTERVAL: f32 = 1.0; // 1 second
         if self.frame_time - self.last_dominant_update_time >= DOMINANT_UPDATE_INTERVAL {
             // Find the band with the highest smoothed energy
             let mut max_band = 0;
             let mut max_energy = self.smoothed_bands[0];
-            for i in 1..NUM_BANDS {
+            for i in 1..num_bands {
                 if self.smoothed_bands[i] > max_energy {
                     max_energy = self.smoothed_bands[i];
                     max_band = i;
@@ -579,8 +1265,9 @@ impl AudioAnalyzer {
         const BASE_THRESHOLD: f32 = 2.0; // Base threshold for detecting drastic change
         const MIN_THRESHOLD: f32 = 0.30; // Minimum threshold to ensure effort is required (stricter)
 
-        // Increment last_mark (capped at MAX_MARK_STEPS)
-        self.last_mark = (self.last_mark + 1).min(MAX_MARK_STEPS);
+        // Advance last_mark by elapsed time (capped at MAX_MARK_STEPS)
+        self.mark_elapsed += dt;
+        self.last_mark = mark_steps(self.mark_elapsed).min(MAX_MARK_STEPS);
 
         // Calculate adaptive threshold: gets easier over time but stays above minimum
         let divisor = (self.last_mark as f32 / 10.0).clamp(MIN_DIVISOR, MAX_DIVISOR);
@@ -588,61 +1275,59 @@ impl AudioAnalyzer {
 
         // Detect drastic change by comparing current smoothed bands to reference bands
         let mut max_band_change = 0.0f32;
-        for i in 0..NUM_BANDS {
+        for i in 0..num_bands {
             let change = (self.smoothed_bands[i] - self.reference_bands[i]).abs();
             max_band_change = max_band_change.max(change);
         }
 
         // If drastic change detected, reset last_mark and update reference
-        if max_band_change >= adaptive_threshold {
+        // Zoom direction shift only happens on the frame a drastic change occurs (last_mark 1)
+        let zoom_direction_shift = max_band_change >= adaptive_threshold;
+        if zoom_direction_shift {
+            self.mark_elapsed = 0.0;
             self.last_mark = 1;
-            self.reference_bands = self.smoothed_bands;
+            self.reference_bands.clone_from(&self.smoothed_bands);
         }
 
-        // Zoom direction shift only happens when last_mark is 1 (drastic change just occurred)
-        let zoom_direction_shift = self.last_mark == 1;
-
-        // Decrement viz_change cooldown
-        if self.viz_change_cooldown > 0 {
-            self.viz_change_cooldown -= 1;
-        }
+        // Count down viz_change cooldown
+        self.viz_change_cooldown = (self.viz_change_cooldown - dt).max(0.0);
 
         // Visualization change triggers when zoom shift happens with high energy
         // Requires cooldown to have expired (prevents rapid re-triggering)
         const VIZ_CHANGE_ENERGY_THRESHOLD: f32 = 0.95;
-        const VIZ_CHANGE_COOLDOWN_FRAMES: u32 = 180; // 3 seconds at 60fps
+        const VIZ_CHANGE_COOLDOWN: f32 = 3.0; // Seconds
         let viz_change_triggered = zoom_direction_shift
             && self.smoothed_energy >= VIZ_CHANGE_ENERGY_THRESHOLD
-            && self.viz_change_cooldown == 0;
+            && self.viz_change_cooldown <= 0.0;
 
         if viz_change_triggered {
-            self.viz_change_cooldown = VIZ_CHANGE_COOLDOWN_FRAMES;
+            self.viz_change_cooldown = VIZ_CHANGE_COOLDOWN;
         }
 
         // New detection methods
-        let (punch_detected, energy_floor, rise_rate) = self.detect_punch(self.smoothed_energy);
-        let break_detected = self.detect_break(transition_detected, self.smoothed_energy);
-        let bands_copy = self.smoothed_bands; // Copy to avoid borrow conflict
+        let (punch_detected, energy_floor, rise_rate) = self.detect_punch(self.smoothed_energy, fast_level, dt);
+        let break_detected = self.detect_break(transition_detected, self.smoothed_energy, dt);
+        let silence_detected = self.detect_silence(self.smoothed_energy, dt);
+        let bands_copy = self.smoothed_bands.clone(); // Copy to avoid borrow conflict
         let (instrument_added, instrument_removed, spectral_centroid) =
             self.detect_instrument_changes(&bands_copy);
 
         // Multi-band kick detection using full spectrum
-        let kick_detected = self.kick_detector.process(&self.spectrum, FRAME_DELTA);
+        let kick_detected = self.kick_detector.process(&self.spectrum, dt);
         let kick_confidence = self.kick_detector.confidence();
         let kick_time_since = self.kick_detector.time_since_kick();
         let kick_envelopes = self.kick_detector.band_envelopes();
         let kick_flux = self.kick_detector.band_flux();
 
         // Compute aggregate values
-        let bass = (self.smoothed_bands[0] + self.smoothed_bands[1]) / 2.0;
-        let mids = (self.smoothed_bands[2] + self.smoothed_bands[3] + self.smoothed_bands[4]) / 3.0;
-        let treble =
-            (self.smoothed_bands[5] + self.smoothed_bands[6] + self.smoothed_bands[7]) / 3.0;
+        let bass = band_mean(&self.smoothed_bands, &self.bass_bands);
+        let mids = band_mean(&self.smoothed_bands, &self.mid_bands);
+        let treble = band_mean(&self.smoothed_bands, &self.treble_bands);
 
         // Compute normalized bands relative to tracked min/max
         // This allows values outside [0, 1] when current is outside the tracked range
-        let mut bands_normalized = [0.0f32; NUM_BANDS];
-        for i in 0..NUM_BANDS {
+        let mut bands_normalized = vec![0.0f32; num_bands];
+        for i in 0..num_bands {
             let range = self.band_maxs[i] - self.band_mins[i];
             if range > 0.01 {
                 bands_normalized[i] = (self.smoothed_bands[i] - self.band_mins[i]) / range;
@@ -652,18 +1337,36 @@ impl AudioAnalyzer {
         }
 
         self.last_analysis = AudioAnalysis {
-            bands: self.smoothed_bands,
+            bands: self.smoothed_bands.clone(),
             spectrum: self.spectrum.clone(),
             spectrum_diff: self.spectrum_diff.clone(),
+            spectral_flux,
+            spectrum_left: self.spectrum.clone(),
+            spectrum_right: self.spectrum.clone(),
+            bands_left: self.smoothed_bands.clone(),
+            bands_right: self.smoothed_bands.clone(),
+            stereo_width: 0.0,
             bands_normalized,
             energy: self.smoothed_energy,
+            bands_raw,
+            energy_raw,
+            rms,
+            waveform,
+            loudness_db: self.loudness_db,
             transition_detected,
             bass,
             mids,
             treble,
             energy_diff,
             zoom_direction_shift,
-            bpm: self.smoothed_bpm,
+            bpm: self.tempo(),
+            beat_phase,
+            is_beat,
+            bar_phase,
+            is_downbeat,
+            bar_locked: self.bar_locked(),
+            beat_onset,
+            band_onset,
             dominant_band: self.dominant_band,
             last_mark: self.last_mark,
             viz_change_triggered,
@@ -672,9 +1375,13 @@ impl AudioAnalyzer {
             energy_floor,
             rise_rate,
             break_detected,
+            silence_detected,
             instrument_added,
             instrument_removed,
             spectral_centroid,
+            spectral_contrast: self.smoothed_contrast,
+            chroma: self.smoothed_chroma,
+            dominant_pitch_class,
             // Kick detection
             kick_detected,
             kick_confidence,
@@ -686,10 +1393,98 @@ impl AudioAnalyzer {
         self.last_analysis.clone()
     }
 
-    fn detect_transition(&mut self, energy: f32, bands: &[f32; NUM_BANDS]) -> bool {
+    /// Zeroed analysis for frames without samples. Tempo and the beat clock keep
+    /// running from the last real frame; smoothing state is left untouched.
+    fn silent_analysis(&mut self, dt: f32) -> AudioAnalysis {
+        let beat_phase = self.clock_phase();
+        let is_beat = self.tempo() > 0.0 && beat_phase < self.prev_beat_phase;
+        let (bar_phase, is_downbeat) = self.advance_bar(beat_phase, false);
+        self.prev_beat_phase = beat_phase;
+
+        let spectrum_size = self.spectrum.len();
+        self.last_analysis = AudioAnalysis {
+            bpm: self.tempo(),
+            beat_phase,
+            is_beat,
+            bar_phase,
+            is_downbeat,
+            bar_locked: self.bar_locked(),
+            beat_onset: false,
+            silence_detected: self.detect_silence(0.0, dt),
+            dominant_band: self.dominant_band,
+            last_mark: self.last_mark,
+            spectrum: vec![0.0; spectrum_size],
+            spectrum_diff: vec![0.0; spectrum_size],
+            spectrum_left: vec![0.0; spectrum_size],
+            spectrum_right: vec![0.0; spectrum_size],
+            ..AudioAnalysis::with_num_bands(self.band_bins.len())
+        };
+        self.last_analysis.clone()
+    }
+
+    /// Whether the downbeat slot leads every other slot by a clear accent
+    fn bar_locked(&self) -> bool {
+        let downbeat = self.slot_strengths[self.downbeat_slot];
+        self.tempo() > 0.0
+            && (0..BEATS_PER_BAR)
+                .filter(|&slot| slot != self.downbeat_slot)
+                .all(|slot| downbeat > self.slot_strengths[slot] + MIN_DOWNBEAT_ACCENT)
+    }
+
+    /// Advance the bar clock from this frame's beat phase (call before updating
+    /// `prev_beat_phase`). Returns the bar phase and whether a downbeat lands now.
+    ///
+    /// Every beat clock wrap moves one slot along a 4-beat bar. Each onset's peak
+    /// bass level is averaged into the slot it landed in, and the slot with the
+    /// strongest onsets becomes beat one (kicks on the one are usually accented).
+    fn advance_bar(&mut self, beat_phase: f32, beat_onset: bool) -> (f32, bool) {
+        if self.tempo() <= 0.0 {
+            self.prev_bar_phase = 0.0;
+            return (0.0, false);
+        }
+
+        // A real wrap, not a late onset pulling the clock back from just past the beat
+        let wrapped = beat_phase < self.prev_beat_phase - 0.5;
+        if wrapped {
+            self.beat_count += 1;
+        }
+
+        if beat_onset {
+            // The previous beat's peak is final now that the next one arrived
+            if let (Some(slot), [.., previous, _]) = (self.onset_slot, self.beat_strengths.as_slice()) {
+                const SLOT_SMOOTHING: f32 = 0.25;
+                self.slot_strengths[slot] += (previous - self.slot_strengths[slot]) * SLOT_SMOOTHING;
+            }
+            self.onset_slot = Some(self.beat_count % BEATS_PER_BAR);
+
+            // Only move beat one for a clear accent, so equal kicks don't make it wander
+            let (strongest, &strength) = self
+                .slot_strengths
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap_or((0, &0.0));
+            if strength > self.slot_strengths[self.downbeat_slot] + MIN_DOWNBEAT_ACCENT {
+                self.downbeat_slot = strongest;
+            }
+        }
+
+        let beat_in_bar = (self.beat_count + BEATS_PER_BAR - self.downbeat_slot) % BEATS_PER_BAR;
+        let mut bar_phase = (beat_in_bar as f32 + beat_phase) / BEATS_PER_BAR as f32;
+        // A late onset resets the beat clock slightly backwards; hold the bar
+        // position instead (bigger jumps are a new downbeat slot and go through)
+        let backstep = self.prev_bar_phase - bar_phase;
+        if !wrapped && backstep > 0.0 && backstep < 0.5 / BEATS_PER_BAR as f32 {
+            bar_phase = self.prev_bar_phase;
+        }
+        self.prev_bar_phase = bar_phase;
+        (bar_phase, wrapped && beat_in_bar == 0)
+    }
+
+    fn detect_transition(&mut self, energy: f32, bands: &[f32], dt: f32) -> bool {
         // High frequency ratio
-        let low_energy: f32 = bands[0..3].iter().sum();
-        let high_energy: f32 = bands[5..8].iter().sum();
+        let low_energy: f32 = bands[self.transition_low_bands.clone()].iter().sum();
+        let high_energy: f32 = bands[self.treble_bands.clone()].iter().sum();
         let total = low_energy + high_energy;
         let freq_ratio = if total > 0.0 {
             high_energy / total
@@ -697,19 +1492,20 @@ impl AudioAnalyzer {
             0.0
         };
 
-        // Store in history
-        let history_size = self.energy_history.len();
-        self.energy_history[self.history_idx] = energy;
-        self.freq_ratio_history[self.history_idx] = freq_ratio;
-        self.history_idx = (self.history_idx + 1) % history_size;
+        // Store in history, dropping frames older than the long window
+        self.transition_history.push_back((dt, energy, freq_ratio));
+        self.transition_history_time += dt;
+        while let Some(&(oldest_dt, _, _)) = self.transition_history.front() {
+            if self.transition_history_time - oldest_dt < LONG_WINDOW {
+                break;
+            }
+            self.transition_history_time -= oldest_dt;
+            self.transition_history.pop_front();
+        }
 
         // Recent vs long-term averages (increased window for stability)
-        let recent_frames = 60; // ~1 second at 60fps
-        let recent_energy = self.recent_average(&self.energy_history, recent_frames);
-        let recent_freq = self.recent_average(&self.freq_ratio_history, recent_frames);
-
-        let long_energy: f32 = self.energy_history.iter().sum::<f32>() / history_size as f32;
-        let long_freq: f32 = self.freq_ratio_history.iter().sum::<f32>() / history_size as f32;
+        let (recent_energy, recent_freq) = self.window_average(RECENT_WINDOW);
+        let (long_energy, long_freq) = self.window_average(LONG_WINDOW);
 
         // Detect state transitions (lower thresholds = more sensitive)
         let is_high_energy = recent_energy > long_energy * 1.15;
@@ -735,19 +1531,29 @@ impl AudioAnalyzer {
         energy_transition || freq_transition
     }
 
-    fn recent_average(&self, history: &[f32], frames: usize) -> f32 {
-        let history_size = history.len();
-        let mut sum = 0.0;
-        for i in 0..frames {
-            let idx = (self.history_idx + history_size - 1 - i) % history_size;
-            sum += history[idx];
+    /// Time-weighted (energy, freq_ratio) averages over the last `seconds`.
+    /// Time before the history started counts as zero.
+    fn window_average(&self, seconds: f32) -> (f32, f32) {
+        let mut remaining = seconds;
+        let mut energy_sum = 0.0;
+        let mut freq_sum = 0.0;
+        for &(dt, energy, freq_ratio) in self.transition_history.iter().rev() {
+            if remaining <= 0.0 {
+                break;
+            }
+            let weight = dt.min(remaining);
+            energy_sum += energy * weight;
+            freq_sum += freq_ratio * weight;
+            remaining -= weight;
         }
-        sum / frames as f32
+        (energy_sum / seconds, freq_sum / seconds)
     }
 
     /// Detect punch (calm-before-spike): energy was low then suddenly spiked
     /// Returns (punch_detected, energy_floor, rise_rate)
-    fn detect_punch(&mut self, current_energy: f32) -> (bool, f32, f32) {
+    /// `fast_level` is the short path's level relative to its recent peak; it can
+    /// complete the spike a frame or two before the smoothed energy catches up
+    fn detect_punch(&mut self, current_energy: f32, fast_level: f32, dt: f32) -> (bool, f32, f32) {
         const FLOOR_DECAY: f32 = 0.992; // Faster drift toward current (~2 sec at 60fps, was 0.998)
         const FLOOR_ATTACK: f32 = 0.18; // Faster drop on new lows (was 0.1)
         const FLOOR_SPIKE_ATTACK: f32 = 0.12; // Faster rise during sustained loud sections (was 0.05)
@@ -757,7 +1563,7 @@ impl AudioAnalyzer {
         let floor_threshold = self.detection_config.punch_floor_threshold();
         let punch_threshold = self.detection_config.punch_spike_threshold();
         let min_rise_rate = self.detection_config.punch_rise_rate();
-        let cooldown_frames = self.detection_config.punch_cooldown_frames();
+        let cooldown = self.detection_config.punch_cooldown_frames() as f32 / NOMINAL_FRAME_RATE;
 
         // Update energy floor (adaptive minimum tracking)
         let energy_gap = current_energy - self.energy_floor;
@@ -779,52 +1585,88 @@ impl AudioAnalyzer {
         let rise_rate = current_energy - self.lagged_energy;
 
         // Detect punch: floor was calm AND current energy spiked significantly
-        let punch_detected = self.punch_cooldown == 0
+        // (the floor only follows the smoothed energy; the spike may come from either path)
+        let spike = current_energy.max(fast_level);
+        let punch_detected = self.punch_cooldown <= 0.0
             && self.energy_floor < floor_threshold
-            && (current_energy - self.energy_floor) > punch_threshold
-            && rise_rate > min_rise_rate;
+            && (spike - self.energy_floor) > punch_threshold
+            && (spike - self.lagged_energy) > min_rise_rate;
 
         if punch_detected {
-            self.punch_cooldown = cooldown_frames;
-        }
-        if self.punch_cooldown > 0 {
-            self.punch_cooldown -= 1;
+            self.punch_cooldown = cooldown;
         }
+        self.punch_cooldown = (self.punch_cooldown - dt).max(0.0);
 
         (punch_detected, self.energy_floor, rise_rate)
     }
 
+    /// Per-band onsets: a band's amplitude crossing `band_onset_ratio` times its
+    /// running average. The ratio is taken on linear amplitudes, since the adaptive
+    /// normalization of `bands_raw` blows tiny wobbles of a steady band up to full
+    /// swings; `bands_raw` only gates out bands near the bottom of their range.
+    /// True only on the crossing frame, like `beat_onset`
+    fn detect_band_onsets(&mut self, amplitudes: &[f32], bands_raw: &[f32], dt: f32) -> Vec<bool> {
+        const BAND_AVG_DECAY: f32 = 0.98; // ~1 second to adapt at 60fps
+        const MIN_BAND_FOR_ONSET: f32 = 0.5;
+        let ratio = self.detection_config.band_onset_ratio();
+        let decay = frame_decay(BAND_AVG_DECAY, dt);
+
+        let mut onsets = vec![false; amplitudes.len()];
+        for (i, &amplitude) in amplitudes.iter().enumerate() {
+            // Start from the first level heard, so the warm-up isn't one long onset
+            if self.band_averages[i] == 0.0 {
+                self.band_averages[i] = amplitude;
+            }
+            let above = amplitude > self.band_averages[i] * ratio;
+            onsets[i] = above && !self.bands_above[i] && bands_raw[i] > MIN_BAND_FOR_ONSET;
+            self.bands_above[i] = above;
+            self.band_averages[i] = self.band_averages[i] * decay + amplitude * (1.0 - decay);
+        }
+        onsets
+    }
+
+    /// Detect silence (no music, e.g. between tracks): energy below the configured
+    /// threshold for the configured time. Ends on the first louder frame.
+    fn detect_silence(&mut self, current_energy: f32, dt: f32) -> bool {
+        if current_energy < self.detection_config.silence_threshold() {
+            self.quiet_time += dt;
+        } else {
+            self.quiet_time = 0.0;
+        }
+        self.quiet_time >= self.detection_config.silence_frames() as f32 / NOMINAL_FRAME_RATE
+    }
+
     /// Detect break patterns: silence (no beats) for extended period
     /// Returns whether a break was detected
-    fn detect_break(&mut self, is_beat: bool, current_energy: f32) -> bool {
-        // Get thresholds from config
-        let silence_threshold = self.detection_config.break_silence_frames();
-        let cooldown_threshold = self.detection_config.break_cooldown_frames();
+    fn detect_break(&mut self, is_beat: bool, current_energy: f32, dt: f32) -> bool {
+        // Get thresholds from config (frame counts at 60fps)
+        let silence_threshold =
+            self.detection_config.break_silence_frames() as f32 / NOMINAL_FRAME_RATE;
+        let cooldown_threshold =
+            self.detection_config.break_cooldown_frames() as f32 / NOMINAL_FRAME_RATE;
 
         // Minimum energy required to consider break detection
         // If energy is too low, we're in actual silence (no music), not a musical break
         const MIN_ENERGY_FOR_BREAK: f32 = 0.05;
 
-        // Decrement cooldown
-        if self.break_cooldown > 0 {
-            self.break_cooldown -= 1;
-        }
+        // Count down cooldown
+        self.break_cooldown = (self.break_cooldown - dt).max(0.0);
 
-        // Track frames since last beat
+        // Track time since last beat
         if is_beat {
-            self.frames_since_beat = 0;
+            self.time_since_beat = 0.0;
         } else {
-            self.frames_since_beat += 1;
+            self.time_since_beat += dt;
         }
 
         // Break detected when no beat for extended period and not in cooldown
         // Also require some minimum energy to distinguish from actual silence
-        if self.frames_since_beat >= silence_threshold
-            && self.break_cooldown == 0
+        if self.time_since_beat >= silence_threshold
+            && self.break_cooldown <= 0.0
             && current_energy > MIN_ENERGY_FOR_BREAK
         {
             self.break_cooldown = cooldown_threshold;
-            self.frames_since_beat = 0; // Reset to avoid immediate re-trigger
+            self.time_since_beat = 0.0; // Reset to avoid immediate re-trigger
             return true;
         }
 
@@ -833,7 +1675,7 @@ impl AudioAnalyzer {
 
     /// Detect instrument changes via spectral complexity
     /// Returns (instrument_added, instrument_removed, spectral_centroid)
-    fn detect_instrument_changes(&mut self, bands: &[f32; NUM_BANDS]) -> (bool, bool, f32) {
+    fn detect_instrument_changes(&mut self, bands: &[f32]) -> (bool, bool, f32) {
         const SMOOTHING: f32 = 0.95; // Slower smoothing for stability
 
         // Get thresholds from config
@@ -851,7 +1693,7 @@ impl AudioAnalyzer {
             }
             total_energy += band_energy;
             // Spectral centroid: weighted average frequency
-            let band_center_freq = (BAND_EDGES[i] + BAND_EDGES[i + 1]) / 2.0;
+            let band_center_freq = (self.band_edges[i] + self.band_edges[i + 1]) / 2.0;
             weighted_freq_sum += band_center_freq * band_energy;
         }
 
@@ -881,3 +1723,868 @@ impl AudioAnalyzer {
         (instrument_added, instrument_removed, spectral_centroid)
     }
 }
+
+/// Per-frame smoothing factor tuned at 60fps, rescaled for a frame of `dt` seconds
+fn frame_decay(per_frame: f32, dt: f32) -> f32 {
+    per_frame.powf(dt * NOMINAL_FRAME_RATE)
+}
+
+/// `last_mark` steps for the time since a drastic change (1 on the change frame)
+fn mark_steps(elapsed: f32) -> u32 {
+    1 + (elapsed * NOMINAL_FRAME_RATE).round() as u32
+}
+
+/// Band boundaries (Hz) for `num_bands` bands: `BAND_EDGES` for the default
+/// count, otherwise equal steps in log frequency across the same range
+fn band_edges(num_bands: usize) -> Vec<f32> {
+    if num_bands == DEFAULT_NUM_BANDS {
+        return BAND_EDGES.to_vec();
+    }
+    let low = BAND_EDGES[0];
+    let ratio = BAND_EDGES[DEFAULT_NUM_BANDS] / low;
+    (0..=num_bands)
+        .map(|i| low * ratio.powf(i as f32 / num_bands as f32))
+        .collect()
+}
+
+/// FFT bin range [low, high) covering each band between `edges`. Narrow low
+/// bands get at least one bin, even if a neighbour shares it
+fn band_bins(edges: &[f32], sample_rate: f32, fft_size: usize) -> Vec<(usize, usize)> {
+    let bin_width = sample_rate / fft_size as f32;
+    edges
+        .windows(2)
+        .map(|edge| {
+            let low_bin = ((edge[0] / bin_width).floor() as usize).max(1);
+            let high_bin = ((edge[1] / bin_width).ceil() as usize).max(low_bin + 1);
+            (low_bin, high_bin.min(fft_size / 2))
+        })
+        .collect()
+}
+
+/// Bands whose center frequency lies in [min_hz, max_hz)
+fn bands_between(edges: &[f32], min_hz: f32, max_hz: f32) -> Range<usize> {
+    let centers: Vec<f32> = edges.windows(2).map(|edge| (edge[0] + edge[1]) / 2.0).collect();
+    let start = centers.iter().take_while(|&&hz| hz < min_hz).count();
+    let end = centers.iter().take_while(|&&hz| hz < max_hz).count();
+    start..end.max(start)
+}
+
+/// Average level of group `group` when `bands` are split evenly into `groups`
+/// groups, lowest first. With `groups` equal to the band count this is just
+/// `bands[group]`, so visualizations laid out for the default 8 bands can call
+/// it with `DEFAULT_NUM_BANDS` whatever `num_bands` is configured to.
+pub fn band_group(bands: &[f32], group: usize, groups: usize) -> f32 {
+    let groups = groups.max(1);
+    let start = (group.min(groups - 1) * bands.len() / groups).min(bands.len().saturating_sub(1));
+    let end = ((group.min(groups - 1) + 1) * bands.len() / groups).max(start + 1);
+    band_mean(bands, &(start..end.min(bands.len())))
+}
+
+/// Average of `bands[range]` (0 for an empty range)
+fn band_mean(bands: &[f32], range: &Range<usize>) -> f32 {
+    if range.is_empty() {
+        return 0.0;
+    }
+    bands[range.clone()].iter().sum::<f32>() / range.len() as f32
+}
+
+/// Pitch class (C = 0 .. B = 11) of every FFT bin between `CHROMA_MIN_HZ` and
+/// `CHROMA_MAX_HZ`, rounded to the nearest equal-tempered note relative to A440
+fn chroma_bins(sample_rate: f32, fft_size: usize) -> Vec<(usize, usize)> {
+    const A_PITCH_CLASS: f32 = 9.0;
+    let bin_width = sample_rate / fft_size as f32;
+    let low = (CHROMA_MIN_HZ / bin_width).ceil() as usize;
+    let high = ((CHROMA_MAX_HZ / bin_width).floor() as usize).min(fft_size / 2 - 1);
+    (low.max(1)..=high)
+        .map(|bin| {
+            let semitones_from_a = 12.0 * (bin as f32 * bin_width / 440.0).log2();
+            let pitch_class = (A_PITCH_CLASS + semitones_from_a.round()).rem_euclid(12.0);
+            (bin, pitch_class as usize)
+        })
+        .collect()
+}
+
+/// `points` values covering `samples`, each the sample with the largest magnitude
+/// in its stretch (sign kept). Shorter inputs are stretched, empty ones are silent.
+fn downsample_waveform(samples: &[f32], points: usize) -> Vec<f32> {
+    if samples.is_empty() {
+        return vec![0.0; points];
+    }
+    (0..points)
+        .map(|i| {
+            let start = (i * samples.len() / points).min(samples.len() - 1);
+            let end = ((i + 1) * samples.len() / points).max(start + 1);
+            samples[start..end]
+                .iter()
+                .copied()
+                .fold(0.0f32, |peak, s| if s.abs() > peak.abs() { s } else { peak })
+        })
+        .collect()
+}
+
+/// Stereo width from the left/right correlation: (1 - r) / 2.
+/// Identical channels give 0, one silent channel 0.5, inverted channels 1.
+fn stereo_width(left: &[f32], right: &[f32]) -> f32 {
+    let (mut lr, mut ll, mut rr) = (0.0f32, 0.0f32, 0.0f32);
+    for (&l, &r) in left.iter().zip(right) {
+        lr += l * r;
+        ll += l * l;
+        rr += r * r;
+    }
+    if ll + rr < 1e-8 {
+        return 0.0; // Silence has no width
+    }
+    let correlation = lr / (ll * rr).sqrt().max(1e-8);
+    ((1.0 - correlation) / 2.0).clamp(0.0, 1.0)
+}
+
+/// Peak-to-valley spread of dB-scaled band levels (0-1)
+fn spectral_contrast(bands: &[f32]) -> f32 {
+    let peak = bands.iter().cloned().fold(0.0f32, f32::max);
+    let valley = bands.iter().cloned().fold(1.0f32, f32::min);
+    (peak - valley).max(0.0)
+}
+
+/// Fold a BPM reading onto the octave of a reference tempo.
+/// Readings at ~double or ~half the reference are scaled back to it, others pass through.
+fn fold_to_octave(bpm: f32, reference: f32) -> f32 {
+    const OCTAVE_TOLERANCE: f32 = 0.08;
+    if reference <= 0.0 {
+        return bpm;
+    }
+    for factor in [0.5, 2.0] {
+        let folded = bpm * factor;
+        if (folded - reference).abs() / reference < OCTAVE_TOLERANCE {
+            return folded;
+        }
+    }
+    bpm
+}
+
+/// Phase (0-1) within the beat at `now`, counting from the last beat onset.
+/// `compensation` (seconds) advances the clock to offset capture/analysis latency.
+fn beat_phase(now: f32, last_beat_time: f32, bpm: f32, compensation: f32) -> f32 {
+    if bpm <= 0.0 {
+        return 0.0;
+    }
+    let beat_duration = 60.0 / bpm;
+    ((now + compensation - last_beat_time) / beat_duration).rem_euclid(1.0)
+}
+
+/// Whether beat strengths alternate strong/weak (downbeats plus quieter off-beats).
+/// Requires most consecutive differences to flip sign with a clear level gap.
+fn accents_alternate(strengths: &[f32]) -> bool {
+    const MIN_ACCENT: f32 = 0.015; // ~2.4 dB on the rough dB scale
+    if strengths.len() < 8 {
+        return false;
+    }
+
+    let diffs: Vec<f32> = strengths.windows(2).map(|w| w[1] - w[0]).collect();
+    let mean_gap = diffs.iter().map(|d| d.abs()).sum::<f32>() / diffs.len() as f32;
+    if mean_gap < MIN_ACCENT {
+        return false;
+    }
+
+    let flips = diffs
+        .windows(2)
+        .filter(|d| d[0] * d[1] < 0.0 && d[0].abs() > MIN_ACCENT * 0.5)
+        .count();
+    flips * 4 >= (diffs.len() - 1) * 3
+}
+
+/// RMS to dBFS, sine-referenced (AES17): full-scale sine RMS (1/sqrt 2) is 0 dB
+fn rms_to_dbfs(rms: f32) -> f32 {
+    (20.0 * (rms * std::f32::consts::SQRT_2).log10()).max(LOUDNESS_FLOOR_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// Kick pattern with optional off-beat hits, fed frame by frame like `SourcePipe` does.
+    /// Each hit is a 50 Hz tone lasting a third of the beat, over a faint noise floor.
+    fn run_pattern(analyzer: &mut AudioAnalyzer, bpm: f32, offbeat_amp: f32, seconds: f32) -> AudioAnalysis {
+        run_pattern_with_dt(analyzer, bpm, offbeat_amp, seconds, |_| 1.0 / 60.0)
+    }
+
+    /// `run_pattern` with frame intervals from `frame_dt(frame_index)`
+    fn run_pattern_with_dt(
+        analyzer: &mut AudioAnalyzer,
+        bpm: f32,
+        offbeat_amp: f32,
+        seconds: f32,
+        frame_dt: impl FnMut(usize) -> f32,
+    ) -> AudioAnalysis {
+        run_pattern_frames(analyzer, bpm, offbeat_amp, seconds, frame_dt, |_| {})
+    }
+
+    /// `run_pattern_with_dt` that also hands every frame's analysis to `on_frame`
+    fn run_pattern_frames(
+        analyzer: &mut AudioAnalyzer,
+        bpm: f32,
+        offbeat_amp: f32,
+        seconds: f32,
+        mut frame_dt: impl FnMut(usize) -> f32,
+        mut on_frame: impl FnMut(&AudioAnalysis),
+    ) -> AudioAnalysis {
+        let beat = 60.0 / bpm;
+        let hit_len = beat / 3.0;
+        let sample_at = |t: f32| -> f32 {
+            // Deterministic hash noise so the adaptive floor never sees pure silence
+            let n = (t * SAMPLE_RATE) as i64 as u64;
+            let hash = n.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let noise = ((hash >> 40) as f32 / (1u64 << 24) as f32 - 0.5) * 0.002;
+            if t < 0.0 {
+                return noise;
+            }
+            let phase = t % beat;
+            let tone = (t * 50.0 * std::f32::consts::TAU).sin();
+            if phase < hit_len {
+                tone + noise
+            } else if phase >= beat / 2.0 && phase < beat / 2.0 + hit_len {
+                tone * offbeat_amp + noise
+            } else {
+                noise
+            }
+        };
+
+        let mut analysis = AudioAnalysis::default();
+        let mut buffer = vec![0.0f32; 1024];
+        let mut end = 0.0;
+        let mut frame = 0;
+        while end < seconds {
+            let dt = frame_dt(frame);
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                let t = end - (1024 - i) as f32 / SAMPLE_RATE;
+                *sample = sample_at(t);
+            }
+            analysis = analyzer.analyze_with_dt(&buffer, dt);
+            on_frame(&analysis);
+            end += dt;
+            frame += 1;
+        }
+        analysis
+    }
+
+    fn assert_valid(analysis: &AudioAnalysis) {
+        let scalars = [
+            analysis.energy,
+            analysis.energy_raw,
+            analysis.bass,
+            analysis.mids,
+            analysis.treble,
+            analysis.energy_diff,
+            analysis.bpm,
+            analysis.beat_phase,
+            analysis.energy_floor,
+            analysis.rise_rate,
+            analysis.spectral_centroid,
+            analysis.spectral_contrast,
+            analysis.spectral_flux,
+            analysis.stereo_width,
+            analysis.kick_confidence,
+            analysis.kick_time_since,
+        ];
+        let arrays = [
+            &analysis.bands[..],
+            &analysis.bands_raw[..],
+            &analysis.bands_normalized[..],
+            &analysis.spectrum[..],
+            &analysis.spectrum_diff[..],
+            &analysis.spectrum_left[..],
+            &analysis.spectrum_right[..],
+            &analysis.bands_left[..],
+            &analysis.bands_right[..],
+            &analysis.chroma[..],
+            &analysis.kick_envelopes[..],
+            &analysis.kick_flux[..],
+        ];
+        assert!(scalars.iter().all(|v| v.is_finite()));
+        assert!(arrays.iter().all(|a| a.iter().all(|v| v.is_finite())));
+    }
+
+    #[test]
+    fn test_empty_buffer_yields_valid_silence() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        for _ in 0..10 {
+            let analysis = analyzer.analyze(&[]);
+            assert_valid(&analysis);
+            assert_eq!(analysis.energy, 0.0);
+            assert_eq!(analysis.spectrum.len(), DEFAULT_SPECTRUM_SIZE);
+        }
+
+        // A starved frame mid-track keeps the tempo and doesn't disturb smoothing
+        let before = run_pattern(&mut analyzer, 120.0, 0.0, 10.0);
+        let smoothed_energy = analyzer.smoothed_energy;
+        let starved = analyzer.analyze_stereo(&[], &[], &[], 1.0 / 60.0);
+        assert_valid(&starved);
+        assert_eq!(starved.energy, 0.0);
+        assert_eq!(starved.bpm, before.bpm);
+        assert_eq!(analyzer.smoothed_energy, smoothed_energy);
+    }
+
+    #[test]
+    fn test_spectral_contrast_sparse_vs_dense() {
+        // Feed one second of a signal and return the settled contrast
+        fn settled_contrast(mut sample_at: impl FnMut(f32) -> f32) -> f32 {
+            let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+            let mut buffer = vec![0.0f32; 1024];
+            let mut analysis = AudioAnalysis::default();
+            for frame in 0..60 {
+                for (i, sample) in buffer.iter_mut().enumerate() {
+                    *sample = sample_at((frame * 1024 + i) as f32 / SAMPLE_RATE);
+                }
+                analysis = analyzer.analyze(&buffer);
+            }
+            assert_valid(&analysis);
+            analysis.spectral_contrast
+        }
+
+        // A single 1 kHz tone lights up one band
+        let tone = settled_contrast(|t| 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * t).sin());
+
+        // White noise spreads evenly across every band
+        let mut seed: u32 = 12345;
+        let noise = settled_contrast(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+        });
+
+        assert!(
+            tone > noise * 3.0,
+            "expected tone contrast {} well above noise contrast {}",
+            tone,
+            noise
+        );
+    }
+
+    #[test]
+    fn test_band_bins_follow_sample_rate() {
+        const RATE: f32 = 48000.0;
+        for fft_size in [DEFAULT_FFT_SIZE, 4096] {
+            let mut analyzer = AudioAnalyzer::with_fft_size(RATE, fft_size, DetectionConfig::default());
+            let mut buffer = vec![0.0f32; fft_size];
+            let mut analysis = AudioAnalysis::default();
+            for frame in 0..30 {
+                for (i, sample) in buffer.iter_mut().enumerate() {
+                    let t = (frame * fft_size + i) as f32 / RATE;
+                    *sample = 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                }
+                analysis = analyzer.analyze(&buffer);
+            }
+            assert_valid(&analysis);
+            assert_eq!(analysis.spectrum.len(), fft_size / 2);
+
+            // The strongest bin sits at 1 kHz and inside the 500-2000 Hz band
+            let peak = (1..analysis.spectrum.len())
+                .max_by(|&a, &b| analysis.spectrum[a].total_cmp(&analysis.spectrum[b]))
+                .unwrap();
+            let bin_width = RATE / fft_size as f32;
+            assert!((peak as f32 * bin_width - 1000.0).abs() <= bin_width);
+            let (low, high) = analyzer.band_bins[3];
+            assert!((low..high).contains(&peak), "bin {} outside band 3 ({}..{})", peak, low, high);
+        }
+    }
+
+    #[test]
+    fn test_num_bands_resizes_every_band_field() {
+        let config = DetectionConfig {
+            num_bands: Some(16),
+            band_decay_per_band: Some(vec![0.1, 0.4]),
+            ..DetectionConfig::default()
+        };
+        let decay = config.band_decay();
+        assert_eq!(decay.len(), 16);
+        assert_eq!((decay[7], decay[8]), (0.1, 0.4), "short lists stretch across the bands");
+
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+        let analysis = run_pattern(&mut analyzer, 120.0, 0.0, 10.0);
+        assert_valid(&analysis);
+        for bands in [
+            &analysis.bands,
+            &analysis.bands_left,
+            &analysis.bands_right,
+            &analysis.bands_normalized,
+            &analysis.bands_raw,
+        ] {
+            assert_eq!(bands.len(), 16);
+        }
+        // The 50 Hz kick lands in the bass bands, which drive the beat clock as before
+        assert!(
+            analysis.bass > analysis.treble,
+            "bass {} <= treble {}",
+            analysis.bass,
+            analysis.treble
+        );
+        assert!((analysis.bpm - 120.0).abs() < 2.0, "bpm = {}", analysis.bpm);
+
+        // Log-spaced edges over the default range, with every band covering a bin
+        let edges = band_edges(16);
+        assert_eq!((edges[0], edges[16].round()), (BAND_EDGES[0], BAND_EDGES[DEFAULT_NUM_BANDS]));
+        assert!((edges[2] / edges[1] - edges[15] / edges[14]).abs() < 1e-3);
+        assert!(analyzer.band_bins.iter().all(|&(low, high)| high > low));
+    }
+
+    #[test]
+    fn test_band_onset_flags_a_mid_band_spike() {
+        let run = |config: DetectionConfig| {
+            let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+            let mut buffer = vec![0.0f32; 1024];
+            let mut onsets = Vec::new();
+            // Steady bass for two seconds, then a 1 kHz hit (band 3, 500-2000 Hz)
+            for frame in 0..150 {
+                let spike = frame >= 120;
+                for (i, sample) in buffer.iter_mut().enumerate() {
+                    let t = (frame * 1024 + i) as f32 / SAMPLE_RATE;
+                    let tone = |hz: f32| (std::f32::consts::TAU * hz * t).sin();
+                    let hit = if spike { 0.2 * tone(1000.0) } else { 0.0 };
+                    *sample = 0.3 * tone(80.0) + 0.01 * tone(1234.5) + hit;
+                }
+                let analysis = analyzer.analyze(&buffer);
+                assert_eq!(analysis.band_onset.len(), DEFAULT_NUM_BANDS);
+                onsets.push(analysis.band_onset);
+            }
+            onsets
+        };
+
+        let onsets = run(DetectionConfig::default());
+        let mid_frames: Vec<usize> = (0..onsets.len()).filter(|&f| onsets[f][3]).collect();
+        assert_eq!(mid_frames, vec![120], "one onset, on the spike frame");
+        assert!(!onsets[120][0] && !onsets[120][1], "the steady bass doesn't fire");
+
+        // The hit is ~8x the band's usual level: a stricter ratio ignores it
+        let strict = DetectionConfig {
+            band_onset_ratio: Some(10.0),
+            ..DetectionConfig::default()
+        };
+        assert!(run(strict).iter().all(|frame| !frame[3]));
+    }
+
+    #[test]
+    fn test_band_group_pools_finer_bands() {
+        let bands: Vec<f32> = (0..16).map(|i| i as f32).collect();
+        assert_eq!(band_group(&bands, 0, DEFAULT_NUM_BANDS), 0.5);
+        assert_eq!(band_group(&bands, 7, DEFAULT_NUM_BANDS), 14.5);
+        assert_eq!(band_group(&bands[..8], 3, DEFAULT_NUM_BANDS), 3.0);
+        assert_eq!(band_group(&[], 3, DEFAULT_NUM_BANDS), 0.0);
+    }
+
+    #[test]
+    fn test_per_band_decay_sets_release_speed() {
+        let decays = |bass_decay: f32| {
+            let mut decay = vec![0.25; DEFAULT_NUM_BANDS];
+            decay[0] = bass_decay;
+            DetectionConfig {
+                band_decay_per_band: Some(decay),
+                ..DetectionConfig::default()
+            }
+        };
+        let mut fast = AudioAnalyzer::with_config(SAMPLE_RATE, decays(0.6));
+        let mut slow = AudioAnalyzer::with_config(SAMPLE_RATE, decays(0.05));
+
+        // Same kicks into both, stopping just after a hit, then silence
+        run_pattern(&mut fast, 120.0, 0.0, 2.1);
+        run_pattern(&mut slow, 120.0, 0.0, 2.1);
+        let silence = vec![0.0f32; 1024];
+        let (mut fast_bass, mut slow_bass) = (0.0, 0.0);
+        for _ in 0..10 {
+            fast_bass = fast.analyze_with_dt(&silence, 1.0 / 60.0).bands[0];
+            slow_bass = slow.analyze_with_dt(&silence, 1.0 / 60.0).bands[0];
+        }
+        assert!(slow_bass > 0.2, "slow bass = {}", slow_bass);
+        assert!(fast_bass < slow_bass * 0.01, "fast bass = {}", fast_bass);
+
+        // Defaults are the original global attack/decay
+        let defaults = DetectionConfig::default();
+        assert_eq!(
+            (defaults.band_attack(), defaults.band_decay()),
+            (vec![0.7; DEFAULT_NUM_BANDS], vec![0.25; DEFAULT_NUM_BANDS])
+        );
+    }
+
+    #[test]
+    fn test_sustained_silence_sets_flag_after_window() {
+        let config = DetectionConfig {
+            silence_frames: Some(30),
+            ..DetectionConfig::default()
+        };
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+        let playing = run_pattern(&mut analyzer, 120.0, 0.0, 3.0);
+        assert!(!playing.silence_detected);
+
+        // Energy decays below the threshold within a few frames, then the window runs
+        let silence = vec![0.0f32; 1024];
+        let first_silent = (1..=120)
+            .find(|_| analyzer.analyze_with_dt(&silence, 1.0 / 60.0).silence_detected)
+            .expect("silence never detected");
+        assert!((30..45).contains(&first_silent), "first silent frame = {}", first_silent);
+
+        // Starved frames count as silence too, and the music coming back clears it
+        assert!(analyzer.analyze_with_dt(&[], 1.0 / 60.0).silence_detected);
+        let resumed = run_pattern(&mut analyzer, 120.0, 0.0, 0.1);
+        assert!(!resumed.silence_detected);
+    }
+
+    #[test]
+    fn test_hard_panned_left_leaves_right_bands_empty() {
+        let config = DetectionConfig {
+            stereo_spectrum: Some(true),
+            ..DetectionConfig::default()
+        };
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+        let silent = vec![0.0f32; 1024];
+        let mut left = vec![0.0f32; 1024];
+        let mut mono = vec![0.0f32; 1024];
+        let mut analysis = AudioAnalysis::default();
+        for frame in 0..60 {
+            for i in 0..1024 {
+                let t = (frame * 1024 + i) as f32 / SAMPLE_RATE;
+                left[i] = 0.5 * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                mono[i] = left[i] / 2.0;
+            }
+            analysis = analyzer.analyze_stereo(&mono, &left, &silent, 1.0 / 60.0);
+        }
+        assert_valid(&analysis);
+
+        assert!(analysis.bands_left[3] > 0.5, "left band 3 = {}", analysis.bands_left[3]);
+        let right_max = analysis.bands_right.iter().cloned().fold(0.0f32, f32::max);
+        assert!(right_max < 0.01, "right bands should be silent, max {}", right_max);
+        // The panned side carries the whole signal, so it reads at least as loud as mono
+        assert!(analysis.bands_left[3] >= analysis.bands[3]);
+        // One silent channel is uncorrelated, identical channels have no width
+        assert!((analysis.stereo_width - 0.5).abs() < 0.05);
+        assert!(stereo_width(&left, &left) < 0.01);
+    }
+
+    #[test]
+    fn test_spectral_flux_spikes_on_broadband_transient() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let mut buffer = vec![0.0f32; 1024];
+        let tone = |t: f32| 0.3 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+        let mut steady = AudioAnalysis::default();
+        for frame in 0..120 {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                *sample = tone((frame * 1024 + i) as f32 / SAMPLE_RATE);
+            }
+            steady = analyzer.analyze(&buffer);
+        }
+
+        // A noise burst on top of the tone lights up every bin at once
+        let mut seed: u32 = 777;
+        for (i, sample) in buffer.iter_mut().enumerate() {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+            *sample = tone((120 * 1024 + i) as f32 / SAMPLE_RATE) + noise;
+        }
+        let burst = analyzer.analyze(&buffer);
+        assert_valid(&burst);
+
+        assert!(steady.spectral_flux < 0.2, "steady tone flux {}", steady.spectral_flux);
+        assert!(burst.spectral_flux > 0.8, "burst flux {}", burst.spectral_flux);
+    }
+
+    #[test]
+    fn test_loudness_reads_dbfs_for_sines() {
+        let loudness = |amplitude: f32| {
+            let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+            let mut buffer = vec![0.0f32; 1024];
+            let mut analysis = AudioAnalysis::default();
+            for frame in 0..120 {
+                for (i, sample) in buffer.iter_mut().enumerate() {
+                    let t = (frame * 1024 + i) as f32 / SAMPLE_RATE;
+                    *sample = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                }
+                analysis = analyzer.analyze(&buffer);
+            }
+            analysis
+        };
+
+        let full = loudness(1.0);
+        assert!((full.rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01, "rms {}", full.rms);
+        assert!(full.loudness_db.abs() < 0.2, "full scale {} dB", full.loudness_db);
+        let half = loudness(0.5);
+        assert!((half.loudness_db + 6.02).abs() < 0.2, "half scale {} dB", half.loudness_db);
+        assert_eq!(loudness(0.0).loudness_db, LOUDNESS_FLOOR_DB);
+    }
+
+    #[test]
+    fn test_waveform_keeps_peaks_of_the_frame() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let buffer: Vec<f32> = (0..DEFAULT_FFT_SIZE)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 100.0 * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        let analysis = analyzer.analyze(&buffer);
+        assert_eq!(analysis.waveform.len(), WAVEFORM_POINTS);
+        let peak = analysis.waveform.iter().cloned().fold(0.0f32, f32::max);
+        let trough = analysis.waveform.iter().cloned().fold(0.0f32, f32::min);
+        assert!(peak > 0.49 && trough < -0.49, "peak {} trough {}", peak, trough);
+        assert!(analysis.waveform[0].abs() < 0.05, "starts at the sine's zero crossing");
+
+        // Starved frames are flat, short ones still fill every point
+        let silent = analyzer.analyze(&[]);
+        assert!(silent.waveform.iter().all(|&s| s == 0.0));
+        assert_eq!(downsample_waveform(&[0.25, -1.0], 4), vec![0.25, 0.25, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_centroid_hue_runs_red_to_violet() {
+        let hue = |spectral_centroid: f32| {
+            AudioAnalysis {
+                spectral_centroid,
+                ..AudioAnalysis::default()
+            }
+            .hue_from_centroid()
+        };
+        assert_eq!(hue(20.0), 0.0);
+        assert_eq!(hue(0.0), 0.0, "below the range stays red");
+        assert!(hue(16000.0) > 260.0 && hue(16000.0) <= CENTROID_HUE_MAX_DEGREES);
+        assert!(hue(1000.0) > hue(100.0) && hue(10000.0) > hue(1000.0));
+    }
+
+    #[test]
+    fn test_chroma_peaks_at_a_for_440hz() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let mut buffer = vec![0.0f32; 1024];
+        let mut analysis = AudioAnalysis::default();
+        for frame in 0..30 {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                let t = (frame * 1024 + i) as f32 / SAMPLE_RATE;
+                *sample = 0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+            }
+            analysis = analyzer.analyze(&buffer);
+        }
+        assert_valid(&analysis);
+        assert_eq!(analysis.dominant_pitch_class, 9);
+        // Neighbouring semitones pick up window leakage, but well below A
+        assert!(analysis.chroma[8] < analysis.chroma[9] * 0.8);
+        assert!(analysis.chroma[10] < analysis.chroma[9] * 0.8);
+    }
+
+    #[test]
+    fn test_fold_to_octave() {
+        assert!((fold_to_octave(170.0, 85.0) - 85.0).abs() < 0.01);
+        assert!((fold_to_octave(64.0, 128.0) - 128.0).abs() < 0.01);
+        assert!((fold_to_octave(128.0, 128.0) - 128.0).abs() < 0.01);
+        // Unrelated tempos are left alone
+        assert!((fold_to_octave(100.0, 128.0) - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_beat_phase_advanced_by_latency_compensation() {
+        // 120 BPM = 0.5s per beat, 0.1s after the onset
+        assert!((beat_phase(10.1, 10.0, 120.0, 0.0) - 0.2).abs() < 1e-4);
+        // 50ms compensation advances the phase by 0.05 / 0.5 = 0.1 of a beat
+        assert!((beat_phase(10.1, 10.0, 120.0, 0.05) - 0.3).abs() < 1e-4);
+        // Compensation can carry the clock past the next beat
+        assert!((beat_phase(10.45, 10.0, 120.0, 0.1) - 0.1).abs() < 1e-4);
+        // No tempo, no clock
+        assert_eq!(beat_phase(10.1, 10.0, 0.0, 0.05), 0.0);
+    }
+
+    #[test]
+    fn test_accents_alternate() {
+        let accented: Vec<f32> = (0..12).map(|i| if i % 2 == 0 { 0.9 } else { 0.85 }).collect();
+        assert!(accents_alternate(&accented));
+
+        let flat = vec![0.9; 12];
+        assert!(!accents_alternate(&flat));
+    }
+
+    #[test]
+    fn test_strong_offbeats_do_not_double_tempo() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let analysis = run_pattern(&mut analyzer, 85.0, 0.5, 30.0);
+        assert!(
+            (analysis.bpm - 85.0).abs() < 5.0,
+            "expected ~85 BPM, got {}",
+            analysis.bpm
+        );
+    }
+
+    #[test]
+    fn test_irregular_frame_times_recover_tempo() {
+        // Jittery frame pacing between 144 Hz and 30 fps, like a loaded or high-refresh display
+        let intervals = [1.0 / 144.0, 1.0 / 60.0, 1.0 / 30.0, 1.0 / 90.0, 1.0 / 45.0, 1.0 / 120.0];
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let analysis = run_pattern_with_dt(&mut analyzer, 128.0, 0.0, 20.0, |frame| {
+            intervals[(frame * 7 + frame / 3) % intervals.len()]
+        });
+        assert!(
+            (analysis.bpm - 128.0).abs() < 2.0,
+            "expected ~128 BPM, got {}",
+            analysis.bpm
+        );
+    }
+
+    #[test]
+    fn test_manual_bpm_overrides_detection_until_cleared() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        run_pattern(&mut analyzer, 128.0, 0.0, 15.0);
+
+        analyzer.set_manual_bpm(100.0);
+        let analysis = run_pattern(&mut analyzer, 128.0, 0.0, 5.0);
+        assert_eq!(analysis.bpm, 100.0);
+
+        analyzer.clear_manual_bpm();
+        let analysis = run_pattern(&mut analyzer, 128.0, 0.0, 1.0);
+        assert!(
+            (analysis.bpm - 128.0).abs() < 2.0,
+            "expected detection to resume at ~128 BPM, got {}",
+            analysis.bpm
+        );
+    }
+
+    #[test]
+    fn test_steady_kick_drives_beat_and_bar_clocks() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        run_pattern(&mut analyzer, 120.0, 0.0, 10.0);
+
+        let (mut prev_beat, mut prev_bar) = (0.0, 0.0);
+        let (mut beats, mut downbeats) = (0, 0);
+        let last = run_pattern_frames(&mut analyzer, 120.0, 0.0, 8.0, |_| 1.0 / 60.0, |a| {
+            assert!((0.0..1.0).contains(&a.bar_phase), "bar_phase {}", a.bar_phase);
+            if a.is_beat {
+                beats += 1;
+            } else {
+                assert!(a.beat_phase > prev_beat, "beat_phase went back without a beat");
+            }
+            if a.is_downbeat {
+                downbeats += 1;
+                assert!(a.is_beat);
+            } else {
+                assert!(a.bar_phase >= prev_bar, "bar_phase went back without a downbeat");
+            }
+            prev_beat = a.beat_phase;
+            prev_bar = a.bar_phase;
+        });
+        // 8 seconds at 120 BPM: 16 beats, four 4-beat bars
+        assert!((15..=17).contains(&beats), "{} beats", beats);
+        assert!((3..=5).contains(&downbeats), "{} downbeats", downbeats);
+        // Equal kicks never single out beat one, so the bar stays a guess
+        assert!(!last.bar_locked);
+    }
+
+    #[test]
+    fn test_window_coefficients() {
+        let size = 1024;
+
+        // No window: a DC-only frame goes into the FFT untouched
+        let dc = vec![0.25; size];
+        let windowed: Vec<f32> = WindowFn::Rectangular
+            .coefficients(size)
+            .iter()
+            .zip(&dc)
+            .map(|(w, x)| w * x)
+            .collect();
+        assert_eq!(windowed, dc);
+
+        // The cosine terms of a periodic window cancel over a full period,
+        // so the coefficients sum to a0 * size
+        let sum: f32 = WindowFn::BlackmanHarris.coefficients(size).iter().sum();
+        assert!((sum - 0.35875 * size as f32).abs() < 1e-2, "sum {}", sum);
+        let hann: f32 = WindowFn::Hann.coefficients(size).iter().sum();
+        assert!((hann - 0.5 * size as f32).abs() < 1e-2, "sum {}", hann);
+
+        // The default keeps the Hann window
+        let config = DetectionConfig::default();
+        let analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+        assert_eq!(analyzer.fft_window, WindowFn::Hann.coefficients(analyzer.fft_size));
+    }
+
+    #[test]
+    fn test_kick_pattern_queues_one_beat_event_per_onset() {
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, DetectionConfig::default());
+        let mut onsets = 0;
+        // Drained only once at the end, like a consumer that skips frames
+        run_pattern_frames(&mut analyzer, 120.0, 0.0, 8.0, |_| 1.0 / 60.0, |a| {
+            onsets += a.beat_onset as usize;
+        });
+        let events = analyzer.take_beat_events();
+
+        // 16 kicks; the first only starts the interval, so 15 are confirmed
+        assert_eq!(events.len(), 15);
+        assert_eq!(events.len(), onsets);
+        assert!(events.windows(2).all(|w| (w[1].time - w[0].time - 0.5).abs() < 0.05));
+        assert!(events.iter().all(|e| e.strength >= 1.5 && e.dominant_band < 2));
+        assert!(analyzer.take_beat_events().is_empty());
+    }
+
+    #[test]
+    fn test_noise_gate_mutes_steady_hum_but_passes_transients() {
+        let config = DetectionConfig {
+            noise_gate: Some(true),
+            ..DetectionConfig::default()
+        };
+        let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+        // Mains hum with a harmonic, around -57 dBFS (under the -50 dBFS default threshold)
+        let hum = |t: f32| {
+            0.002 * (t * 50.0 * std::f32::consts::TAU).sin()
+                + 0.001 * (t * 150.0 * std::f32::consts::TAU).sin()
+        };
+        let mut buffer = vec![0.0f32; 1024];
+        let mut analyze = |time: f32, kick: f32| {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                let t = time + i as f32 / SAMPLE_RATE;
+                *sample = hum(t) + kick * (t * 60.0 * std::f32::consts::TAU).sin();
+            }
+            analyzer.analyze_with_dt(&buffer, 1.0 / 60.0)
+        };
+
+        let mut time = 0.0;
+        let mut hummed = AudioAnalysis::default();
+        for _ in 0..180 {
+            hummed = analyze(time, 0.0);
+            time += 1.0 / 60.0;
+        }
+        let loudest = hummed.bands.iter().cloned().fold(0.0f32, f32::max);
+        assert!(loudest < 0.05, "hum bands = {:?}", hummed.bands);
+        assert!(hummed.energy < 0.05, "hum energy = {}", hummed.energy);
+
+        // A loud kick on top of the hum still comes through
+        let kicked = analyze(time, 0.8);
+        assert!(kicked.bands[0] > 0.5, "kick bands = {:?}", kicked.bands);
+    }
+
+    #[test]
+    fn test_short_path_flags_kicks_before_the_band_path() {
+        const FRAME: f32 = 1.0 / 60.0;
+        // Kicks start 4 ms before a frame boundary, over a sustained bass line, and
+        // frames carry a full 2048-sample window like a device stream
+        const KICK_START: f32 = 0.0125;
+        let onset_delays = |fast_onsets: bool| {
+            let config = DetectionConfig {
+                fast_onsets: Some(fast_onsets),
+                ..DetectionConfig::default()
+            };
+            let mut analyzer = AudioAnalyzer::with_config(SAMPLE_RATE, config);
+            let mut buffer = vec![0.0f32; 2048];
+            let mut delays = Vec::new();
+            let mut end: f32 = 0.0;
+            while end < 8.0 {
+                for (i, sample) in buffer.iter_mut().enumerate() {
+                    let t = end - (2048 - i) as f32 / SAMPLE_RATE;
+                    let bassline = 0.25 * (t * 80.0 * std::f32::consts::TAU).sin();
+                    let hit = t >= KICK_START && (t - KICK_START) % 0.5 < 0.15;
+                    let kick = if hit { (t * 50.0 * std::f32::consts::TAU).sin() } else { 0.0 };
+                    *sample = bassline + kick;
+                }
+                if analyzer.analyze_with_dt(&buffer, FRAME).beat_onset {
+                    delays.push((end - KICK_START) % 0.5);
+                }
+                end += FRAME;
+            }
+            delays
+        };
+
+        // The band path needs a second frame before the kick outweighs the
+        // window; the short path flags it on the first frame that contains it
+        let fast = onset_delays(true);
+        let slow = onset_delays(false);
+        assert_eq!(fast.len(), slow.len());
+        assert!(fast.len() >= 14, "onsets = {}", fast.len());
+        assert!(fast.iter().all(|&d| d < FRAME), "fast delays = {:?}", fast);
+        assert!(slow.iter().all(|&d| d > FRAME), "slow delays = {:?}", slow);
+    }
+}
@@ -0,0 +1,227 @@
+//! Analysis broadcast over UDP, for lighting software and other external apps.
+//!
+//! Every frame a compact subset of `AudioAnalysis` is sent to one address as a
+//! single datagram, either a JSON object or an OSC bundle (one message per
+//! field under `/dj-viz/`). Nothing is acknowledged: a receiver can come and go
+//! at any time. The packet is encoded into a buffer kept between frames (and
+//! the OSC messages are refilled in place), so steady-state sending doesn't
+//! allocate.
+
+use rosc::{encoder, OscBundle, OscMessage, OscPacket, OscType};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+
+use super::AudioAnalysis;
+use crate::log_error;
+
+/// Wire format of the broadcast packets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BroadcastFormat {
+    /// One JSON object per datagram (field names match `AudioAnalysis`)
+    Json,
+    /// One OSC bundle per datagram, every value a float (flags are 0 or 1)
+    Osc,
+}
+
+/// The fields that are broadcast, borrowed from the analysis
+#[derive(Serialize)]
+struct AnalysisFrame<'a> {
+    bands: &'a [f32],
+    energy: f32,
+    bass: f32,
+    mids: f32,
+    treble: f32,
+    bpm: f32,
+    beat_phase: f32,
+    is_beat: bool,
+    is_downbeat: bool,
+    beat_onset: bool,
+    spectral_centroid: f32,
+}
+
+impl<'a> From<&'a AudioAnalysis> for AnalysisFrame<'a> {
+    fn from(analysis: &'a AudioAnalysis) -> Self {
+        Self {
+            bands: &analysis.bands,
+            energy: analysis.energy,
+            bass: analysis.bass,
+            mids: analysis.mids,
+            treble: analysis.treble,
+            bpm: analysis.bpm,
+            beat_phase: analysis.beat_phase,
+            is_beat: analysis.is_beat,
+            is_downbeat: analysis.is_downbeat,
+            beat_onset: analysis.beat_onset,
+            spectral_centroid: analysis.spectral_centroid,
+        }
+    }
+}
+
+/// Sends the analysis of every frame to a fixed address
+pub struct AnalysisBroadcaster {
+    socket: UdpSocket,
+    format: BroadcastFormat,
+    /// Encoded packet, reused every frame
+    buf: Vec<u8>,
+    /// OSC bundle, refilled every frame
+    osc: OscPacket,
+    /// Set after the first send error, so a failing network logs once
+    send_failed: bool,
+}
+
+impl AnalysisBroadcaster {
+    /// Send to `target` (`host:port`) from an ephemeral local port
+    pub fn start(target: &str, format: BroadcastFormat) -> Result<Self, String> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))
+            .map_err(|e| format!("Failed to open broadcast socket: {}", e))?;
+        socket
+            .connect(target)
+            .map_err(|e| format!("Failed to resolve broadcast address {}: {}", target, e))?;
+        // A slow network must never stall the render loop
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure broadcast socket: {}", e))?;
+        Ok(Self {
+            socket,
+            format,
+            buf: Vec::with_capacity(512),
+            osc: osc_bundle(&AudioAnalysis::default()),
+            send_failed: false,
+        })
+    }
+
+    /// Encode and send one frame of analysis
+    pub fn send(&mut self, analysis: &AudioAnalysis) {
+        self.buf.clear();
+        match self.format {
+            BroadcastFormat::Json => write_json(analysis, &mut self.buf),
+            BroadcastFormat::Osc => write_osc(analysis, &mut self.osc, &mut self.buf),
+        }
+        match self.socket.send(&self.buf) {
+            Ok(_) => self.send_failed = false,
+            // Nobody listening (yet), or the send buffer is full: drop this frame
+            Err(e) if matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::WouldBlock) => {}
+            Err(e) => {
+                if !self.send_failed {
+                    log_error!("Analysis broadcast failed: {}", e);
+                }
+                self.send_failed = true;
+            }
+        }
+    }
+}
+
+/// Append the analysis as a JSON object
+fn write_json(analysis: &AudioAnalysis, buf: &mut Vec<u8>) {
+    // Writing plain numbers and bools into a Vec can't fail
+    let _ = serde_json::to_writer(&mut *buf, &AnalysisFrame::from(analysis));
+}
+
+/// Call `field` with each OSC address and its float values, in bundle order
+fn for_each_osc_field(analysis: &AudioAnalysis, mut field: impl FnMut(&'static str, &[f32])) {
+    let flag = |set: bool| if set { 1.0 } else { 0.0 };
+    field("/dj-viz/bands", &analysis.bands);
+    field("/dj-viz/energy", &[analysis.energy]);
+    field("/dj-viz/bass", &[analysis.bass]);
+    field("/dj-viz/mids", &[analysis.mids]);
+    field("/dj-viz/treble", &[analysis.treble]);
+    field("/dj-viz/bpm", &[analysis.bpm]);
+    field("/dj-viz/beat_phase", &[analysis.beat_phase]);
+    field("/dj-viz/is_beat", &[flag(analysis.is_beat)]);
+    field("/dj-viz/is_downbeat", &[flag(analysis.is_downbeat)]);
+    field("/dj-viz/beat_onset", &[flag(analysis.beat_onset)]);
+    field("/dj-viz/spectral_centroid", &[analysis.spectral_centroid]);
+}
+
+/// Bundle holding one float message per field
+fn osc_bundle(analysis: &AudioAnalysis) -> OscPacket {
+    let mut content = Vec::new();
+    for_each_osc_field(analysis, |addr, values| {
+        content.push(OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: values.iter().map(|&v| OscType::Float(v)).collect(),
+        }));
+    });
+    // Time tag 1 = "immediately"
+    OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content,
+    })
+}
+
+/// Refill `packet` (from `osc_bundle`) with the analysis and append it encoded
+fn write_osc(analysis: &AudioAnalysis, packet: &mut OscPacket, buf: &mut Vec<u8>) {
+    if let OscPacket::Bundle(bundle) = packet {
+        let mut messages = bundle.content.iter_mut();
+        for_each_osc_field(analysis, |_, values| {
+            if let Some(OscPacket::Message(message)) = messages.next() {
+                message.args.clear();
+                message.args.extend(values.iter().map(|&v| OscType::Float(v)));
+            }
+        });
+    }
+    // Encoding into a Vec can't fail
+    let _ = encoder::encode_into(packet, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_analysis_serializes_the_broadcast_fields() {
+        let analysis = AudioAnalysis::default();
+        let mut buf = Vec::new();
+        write_json(&analysis, &mut buf);
+        let value: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        let mut fields: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect();
+        fields.sort_unstable();
+        assert_eq!(
+            fields,
+            [
+                "bands",
+                "bass",
+                "beat_onset",
+                "beat_phase",
+                "bpm",
+                "energy",
+                "is_beat",
+                "is_downbeat",
+                "mids",
+                "spectral_centroid",
+                "treble",
+            ]
+        );
+        assert_eq!(value["bands"].as_array().unwrap().len(), analysis.bands.len());
+
+        // The OSC bundle has one message per field and carries this frame's values
+        let mut packet = osc_bundle(&analysis);
+        let loud = AudioAnalysis {
+            bpm: 128.0,
+            is_beat: true,
+            ..AudioAnalysis::default()
+        };
+        buf.clear();
+        write_osc(&loud, &mut packet, &mut buf);
+        let Ok((rest, OscPacket::Bundle(bundle))) = rosc::decoder::decode_udp(&buf) else {
+            panic!("not an OSC bundle");
+        };
+        assert!(rest.is_empty());
+        assert_eq!(bundle.content.len(), fields.len());
+        let value = |addr: &str| {
+            bundle.content.iter().find_map(|element| match element {
+                OscPacket::Message(m) if m.addr == addr => Some(m.args.clone()),
+                _ => None,
+            })
+        };
+        assert_eq!(value("/dj-viz/bpm"), Some(vec![OscType::Float(128.0)]));
+        assert_eq!(value("/dj-viz/is_beat"), Some(vec![OscType::Float(1.0)]));
+        assert_eq!(value("/dj-viz/bands").unwrap().len(), analysis.bands.len());
+    }
+}
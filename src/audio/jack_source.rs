@@ -0,0 +1,95 @@
+//! JACK capture (built with the `jack` feature).
+//!
+//! Lists the audio output ports of a running JACK server (hardware captures and
+//! other clients' outputs, e.g. a DJ deck) and captures one by connecting it to
+//! an input port dj-viz registers. The process callback pushes into the source
+//! pipe's ring exactly like the cpal callback. A server is never started on
+//! demand: without one (or without the JACK library) there are no ports.
+
+use jack::{
+    AsyncClient, AudioIn, Client, ClientOptions, ClientStatus, ClosureProcessHandler, Control,
+    NotificationHandler, PortFlags, PortId, PortSpec, ProcessScope,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::ring_buffer::SampleRing;
+
+const CLIENT_NAME: &str = "dj-viz";
+/// Short name of the port the captured source is connected to
+const INPUT_PORT: &str = "in";
+
+type ProcessFn = Box<dyn FnMut(&Client, &ProcessScope) -> Control + Send>;
+
+/// Flags the source pipe disconnected when the server goes away or the
+/// captured port is unplugged (JACK keeps calling `process` with silence)
+struct ConnectionWatch {
+    /// Full name of our input port
+    port: String,
+    disconnected: Arc<AtomicBool>,
+}
+
+impl NotificationHandler for ConnectionWatch {
+    fn shutdown(&mut self, _status: ClientStatus, _reason: &str) {
+        self.disconnected.store(true, Ordering::Relaxed);
+    }
+
+    fn ports_connected(&mut self, client: &Client, _: PortId, _: PortId, are_connected: bool) {
+        if are_connected {
+            return;
+        }
+        let connections = client
+            .port_by_name(&self.port)
+            .and_then(|port| port.connected_count().ok());
+        if connections == Some(0) {
+            self.disconnected.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A running capture; dropping it closes the client (and its connection)
+pub struct JackCapture {
+    _client: AsyncClient<ConnectionWatch, ClosureProcessHandler<ProcessFn>>,
+}
+
+/// Audio output ports as (full port name, is a hardware capture port)
+pub fn list_ports() -> Vec<(String, bool)> {
+    let Ok((client, _)) = Client::new(CLIENT_NAME, ClientOptions::NO_START_SERVER) else {
+        return Vec::new();
+    };
+    client
+        .ports(None, Some(AudioIn.jack_port_type()), PortFlags::IS_OUTPUT)
+        .into_iter()
+        .map(|name| {
+            let physical = client
+                .port_by_name(&name)
+                .is_some_and(|port| port.flags().contains(PortFlags::IS_PHYSICAL));
+            (name, physical)
+        })
+        .collect()
+}
+
+/// Capture `port` into `ring`, returning the capture and the server sample rate
+pub fn connect(
+    port: &str,
+    ring: Arc<SampleRing>,
+    disconnected: Arc<AtomicBool>,
+) -> Result<(JackCapture, f32), jack::Error> {
+    let (client, _) = Client::new(CLIENT_NAME, ClientOptions::NO_START_SERVER)?;
+    let input = client.register_port(INPUT_PORT, AudioIn)?;
+    let watch = ConnectionWatch {
+        port: input.name()?,
+        disconnected,
+    };
+    let sample_rate = client.sample_rate() as f32;
+
+    let process: ProcessFn = Box::new(move |_, scope| {
+        // Lock-free: only atomic stores, never waits on the render thread
+        ring.push_interleaved(input.as_slice(scope), 1);
+        Control::Continue
+    });
+    let target = watch.port.clone();
+    let client = client.activate_async(watch, ClosureProcessHandler::new(process))?;
+    client.as_client().connect_ports_by_name(port, &target)?;
+    Ok((JackCapture { _client: client }, sample_rate))
+}
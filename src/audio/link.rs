@@ -0,0 +1,77 @@
+//! Ableton Link tempo sync (built with the `link` feature).
+//!
+//! Wraps the official Link SDK through the `ableton-link` bindings, which
+//! compile it with CMake, so builds without the feature need neither. dj-viz
+//! joins the session as a peer but only reads it: the session tempo and beat
+//! phase drive the beat clock, and nothing is ever committed back.
+
+use ableton_link::Link;
+use std::cell::Cell;
+
+use crate::log_line;
+
+/// Tempo the session starts at before any peer is found (never used, since
+/// the beat is only followed with peers)
+const INITIAL_BPM: f64 = 120.0;
+
+/// Beats per phase cycle: `beat_phase` wraps once per beat
+const QUANTUM: f64 = 1.0;
+
+/// Session tempo and beat phase right now
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkBeat {
+    pub bpm: f32,
+    /// Position within the beat (0-1)
+    pub phase: f32,
+}
+
+/// Connection to the Link session on the local network
+pub struct LinkSession {
+    link: Link,
+    /// Peer count last logged, to report joins and leaves once
+    peers: Cell<usize>,
+}
+
+impl LinkSession {
+    /// Enable Link and start following the session
+    pub fn start() -> Result<Self, String> {
+        let mut link = Link::new(INITIAL_BPM);
+        link.enable(true);
+        if !link.is_enabled() {
+            return Err("Failed to enable Ableton Link".to_string());
+        }
+        log_line!("Link: waiting for peers");
+        Ok(Self {
+            link,
+            peers: Cell::new(0),
+        })
+    }
+
+    /// The session's tempo and phase, or None when there are no peers
+    pub fn beat(&self) -> Option<LinkBeat> {
+        let peers = self.link.num_peers();
+        if peers != self.peers.replace(peers) {
+            if peers == 0 {
+                log_line!("Link: no peers, following the audio tempo");
+            } else {
+                log_line!("Link: {} peer(s)", peers);
+            }
+        }
+        if peers == 0 {
+            return None;
+        }
+
+        let now = self.link.clock().micros();
+        let (mut bpm, mut phase) = (0.0, 0.0);
+        // The bindings hand the closure over as an `Option` of itself, which
+        // only lines up for closures capturing references, like this one
+        self.link.with_app_session_state(|state| {
+            bpm = state.tempo();
+            phase = state.phase_at_time(now, QUANTUM);
+        });
+        Some(LinkBeat {
+            bpm: bpm as f32,
+            phase: (phase / QUANTUM) as f32,
+        })
+    }
+}
@@ -1,8 +1,19 @@
 mod analyzer;
+mod broadcast;
+#[cfg(feature = "jack")]
+mod jack_source;
+#[cfg(feature = "link")]
+pub mod link;
 mod output_capture;
+mod ring_buffer;
 mod source_pipe;
+mod wav;
 pub mod utils;
 
-pub use analyzer::{AudioAnalysis, AudioAnalyzer, NUM_BANDS};
+pub use analyzer::{
+    band_group, AudioAnalysis, AudioAnalyzer, BeatEvent, WindowFn, DEFAULT_NUM_BANDS,
+    MAX_NUM_BANDS, MIN_NUM_BANDS,
+};
+pub use broadcast::{AnalysisBroadcaster, BroadcastFormat};
 pub use output_capture::OutputCapture;
 pub use source_pipe::SourcePipe;
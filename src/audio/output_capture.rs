@@ -3,8 +3,8 @@
 //! Manages the interactive fuzzy-search overlay for selecting audio input/output
 //! devices at runtime.
 
-use cpal::traits::{DeviceTrait, HostTrait};
-use crate::ui::text_picker::{PickerItem, TextPickerState};
+use super::SourcePipe;
+use crate::ui::text_picker::{fuzzy_score, PickerItem, TextPickerState};
 
 /// Represents an audio device entry for search/selection
 #[derive(Clone, Debug)]
@@ -41,41 +41,17 @@ impl OutputCapture {
         }
     }
 
-    /// Collect all audio devices using cpal (inputs first, then outputs)
+    /// Collect all audio devices, indexed as `SourcePipe::select_device` expects
     fn collect_devices() -> Vec<AudioDeviceEntry> {
-        let host = cpal::default_host();
-        let mut devices = Vec::new();
-        let mut idx = 0;
-
-        // Inputs first
-        if let Ok(input_devices) = host.input_devices() {
-            for device in input_devices {
-                if let Ok(name) = device.name() {
-                    devices.push(AudioDeviceEntry {
-                        index: idx,
-                        is_input: true,
-                        name,
-                    });
-                    idx += 1;
-                }
-            }
-        }
-
-        // Then outputs
-        if let Ok(output_devices) = host.output_devices() {
-            for device in output_devices {
-                if let Ok(name) = device.name() {
-                    devices.push(AudioDeviceEntry {
-                        index: idx,
-                        is_input: false,
-                        name,
-                    });
-                    idx += 1;
-                }
-            }
-        }
-
-        devices
+        SourcePipe::collect_devices()
+            .into_iter()
+            .enumerate()
+            .map(|(index, info)| AudioDeviceEntry {
+                index,
+                is_input: info.is_input,
+                name: info.name,
+            })
+            .collect()
     }
 
     /// Start search mode: enumerate audio devices and activate UI
@@ -87,33 +63,33 @@ impl OutputCapture {
         self.search_active = true;
     }
 
-    /// Filter devices by current query (case-insensitive)
+    /// Filter devices by fuzzy-matching the current query (case-insensitive),
+    /// best matches first
     pub fn filter(&mut self) {
-        let query_lower = self.query.to_lowercase();
-        self.filtered = self
+        let mut scored: Vec<(i32, &AudioDeviceEntry)> = self
             .devices
             .iter()
-            .filter(|device| {
-                query_lower.is_empty() || device.name.to_lowercase().contains(&query_lower)
-            })
-            .cloned()
+            .filter_map(|device| fuzzy_score(&self.query, &device.name).map(|score| (score, device)))
             .collect();
+        // Stable sort keeps device order among equal scores
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.filtered = scored.into_iter().map(|(_, device)| device.clone()).collect();
 
-        // Reset selection if out of bounds
-        if self.selected_idx >= self.filtered.len() {
-            self.selected_idx = 0;
-        }
+        // Keep the selection on a visible entry when the list shrinks
+        self.selected_idx = self.selected_idx.min(self.filtered.len().saturating_sub(1));
     }
 
-    /// Append a character to the search query
+    /// Append a character to the search query (the best match becomes selected)
     pub fn append_char(&mut self, c: char) {
         self.query.push(c);
+        self.selected_idx = 0;
         self.filter();
     }
 
-    /// Delete last character from query
+    /// Delete last character from query (the best match becomes selected)
     pub fn backspace(&mut self) {
         self.query.pop();
+        self.selected_idx = 0;
         self.filter();
     }
 
@@ -190,4 +166,103 @@ impl TextPickerState for OutputCapture {
     fn selected_index(&self) -> usize {
         self.selected_idx
     }
+
+    fn empty_message(&self) -> String {
+        if self.devices.is_empty() {
+            "No audio devices found (visuals keep running on silence)".to_string()
+        } else {
+            format!("No device matches \"{}\"", self.query)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture_with(names: &[&str]) -> OutputCapture {
+        let mut capture = OutputCapture::new();
+        capture.devices = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| AudioDeviceEntry {
+                index,
+                is_input: false,
+                name: name.to_string(),
+            })
+            .collect();
+        capture
+    }
+
+    fn filtered_names(capture: &OutputCapture) -> Vec<&str> {
+        capture.filtered.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_filter_ranks_matches() {
+        let mut capture = capture_with(&[
+            "alsa_output.pci-0000_00_1f.3.analog-stereo",
+            "alsa_output.pci-0000_01_00.1.hdmi-stereo",
+            "pipewire",
+            "Pulse Input Pipe",
+            "default",
+        ]);
+
+        for c in "pipe".chars() {
+            capture.append_char(c);
+        }
+        assert_eq!(filtered_names(&capture), vec!["pipewire", "Pulse Input Pipe"]);
+
+        capture.query.clear();
+        for c in "hdmi".chars() {
+            capture.append_char(c);
+        }
+        assert_eq!(
+            capture.selected().map(|d| d.name.as_str()),
+            Some("alsa_output.pci-0000_01_00.1.hdmi-stereo")
+        );
+
+        // Subsequence matches across separators, word starts ranked above scattered letters
+        capture.query = "aos".to_string();
+        capture.filter();
+        assert_eq!(
+            filtered_names(&capture),
+            vec![
+                "alsa_output.pci-0000_00_1f.3.analog-stereo",
+                "alsa_output.pci-0000_01_00.1.hdmi-stereo",
+            ]
+        );
+        assert_eq!(fuzzy_score("xyz", "pipewire"), None);
+    }
+
+    #[test]
+    fn test_selection_clamps_when_list_shrinks() {
+        let mut capture = capture_with(&["pipewire", "pulse", "default"]);
+        capture.filter();
+        capture.move_up();
+        assert_eq!(capture.selected_idx, 2);
+
+        capture.query = "p".to_string();
+        capture.filter();
+        assert_eq!(capture.selected_idx, 1, "clamped to the last remaining entry");
+        capture.query = "nothing".to_string();
+        capture.filter();
+        assert_eq!(capture.selected_idx, 0);
+        assert!(capture.selected().is_none());
+    }
+
+    #[test]
+    fn test_empty_device_list_navigates_without_panicking() {
+        let mut capture = capture_with(&[]);
+        capture.filter();
+        capture.search_active = true;
+        capture.move_up();
+        capture.move_down();
+        capture.append_char('x');
+        capture.backspace();
+        assert!(capture.selected().is_none());
+        assert_eq!(capture.select(), None);
+        assert!(capture.search_active, "stays open to show the message");
+        assert!(capture.empty_message().starts_with("No audio devices found"));
+    }
 }
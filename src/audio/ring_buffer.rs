@@ -0,0 +1,163 @@
+//! Lock-free single-producer sample ring for the audio callback.
+//!
+//! The cpal callback pushes (mono, left, right) frames and publishes them with a
+//! single atomic store, so it never waits on the render thread. The render
+//! thread copies out the most recent N frames whenever it likes. Samples are
+//! stored as atomic bit patterns, so a reader racing the writer can at worst see
+//! a frame that was just overwritten (only possible if the writer laps the whole
+//! ring during one copy), never a data race.
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// Fixed-capacity ring of (mono, left, right) frames, written by one producer
+pub struct SampleRing {
+    mono: Box<[AtomicU32]>,
+    left: Box<[AtomicU32]>,
+    right: Box<[AtomicU32]>,
+    /// Total frames ever written (publishes frames to the reader)
+    written: AtomicUsize,
+    /// `written` at the last `clear`; older frames read as silence
+    cleared_at: AtomicUsize,
+}
+
+/// Which channel to read from the ring
+#[derive(Clone, Copy)]
+pub enum Channel {
+    Mono,
+    Left,
+    Right,
+}
+
+impl SampleRing {
+    pub fn new(capacity: usize) -> Self {
+        let channel = || (0..capacity).map(|_| AtomicU32::new(0)).collect();
+        Self {
+            mono: channel(),
+            left: channel(),
+            right: channel(),
+            written: AtomicUsize::new(0),
+            cleared_at: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.mono.len()
+    }
+
+    /// Append frames from interleaved `data` with `channels` channels.
+    /// Must only be called from a single producer (the audio callback).
+    pub fn push_interleaved(&self, data: &[f32], channels: usize) {
+        let capacity = self.capacity();
+        let mut written = self.written.load(Ordering::Relaxed);
+        for chunk in data.chunks(channels.max(1)) {
+            let mono = chunk.iter().sum::<f32>() / chunk.len() as f32;
+            let left = chunk[0];
+            let right = chunk.get(1).copied().unwrap_or(left);
+            let idx = written % capacity;
+            self.mono[idx].store(mono.to_bits(), Ordering::Relaxed);
+            self.left[idx].store(left.to_bits(), Ordering::Relaxed);
+            self.right[idx].store(right.to_bits(), Ordering::Relaxed);
+            written = written.wrapping_add(1);
+        }
+        self.written.store(written, Ordering::Release);
+    }
+
+    /// Total frames written so far, to pass to `read` so several channels
+    /// can be read at the same position
+    pub fn position(&self) -> usize {
+        self.written.load(Ordering::Acquire)
+    }
+
+    /// Copy the `out.len()` frames ending at `end` (from `position`) into `out`.
+    /// Frames never written, or written before the last `clear`, read as 0.
+    pub fn read(&self, channel: Channel, end: usize, out: &mut [f32]) {
+        let samples = match channel {
+            Channel::Mono => &self.mono,
+            Channel::Left => &self.left,
+            Channel::Right => &self.right,
+        };
+        let capacity = self.capacity();
+        let available = end
+            .wrapping_sub(self.cleared_at.load(Ordering::Relaxed))
+            .min(capacity);
+        let len = out.len();
+        for (i, value) in out.iter_mut().enumerate() {
+            let age = len - i; // 1 = newest frame
+            *value = if age <= available {
+                f32::from_bits(samples[end.wrapping_sub(age) % capacity].load(Ordering::Relaxed))
+            } else {
+                0.0
+            };
+        }
+    }
+
+    /// Forget everything written so far (reader side, e.g. after switching devices)
+    pub fn clear(&self) {
+        self.cleared_at.store(self.position(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_read_latest_frames() {
+        let ring = SampleRing::new(8);
+        ring.push_interleaved(&[1.0, 3.0, 2.0, 4.0], 2);
+
+        let mut out = [9.0; 4];
+        ring.read(Channel::Mono, ring.position(), &mut out);
+        assert_eq!(out, [0.0, 0.0, 2.0, 3.0]);
+        ring.read(Channel::Right, ring.position(), &mut out);
+        assert_eq!(out, [0.0, 0.0, 3.0, 4.0]);
+
+        // Wrapping keeps the newest frames, clearing hides them
+        let data: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        ring.push_interleaved(&data, 1);
+        ring.read(Channel::Left, ring.position(), &mut out);
+        assert_eq!(out, [16.0, 17.0, 18.0, 19.0]);
+        ring.clear();
+        ring.read(Channel::Left, ring.position(), &mut out);
+        assert_eq!(out, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_concurrent_push_and_read() {
+        const FRAMES: usize = 200_000;
+        let ring = Arc::new(SampleRing::new(4096));
+
+        // Producer writes an increasing counter, in callback-sized chunks
+        let producer = {
+            let ring = Arc::clone(&ring);
+            std::thread::spawn(move || {
+                let mut next = 0usize;
+                while next < FRAMES {
+                    let chunk: Vec<f32> = (next..(next + 256).min(FRAMES)).map(|n| n as f32).collect();
+                    next += chunk.len();
+                    ring.push_interleaved(&chunk, 1);
+                }
+            })
+        };
+
+        // Reader drains windows while the producer runs; every window it sees
+        // must be consecutive frames ending at the published position (unless the
+        // producer lapped the ring mid-copy, which only a stalled reader allows)
+        let mut window = vec![0.0f32; 1024];
+        let mut reads = 0;
+        while reads < 10 || !producer.is_finished() {
+            let end = ring.position();
+            ring.read(Channel::Mono, end, &mut window);
+            let lapped = ring.position() - end > ring.capacity() - window.len();
+            if end >= window.len() && !lapped {
+                for (i, &value) in window.iter().enumerate() {
+                    assert_eq!(value as usize, end - window.len() + i);
+                }
+            }
+            reads += 1;
+        }
+        producer.join().unwrap();
+        assert_eq!(ring.position(), FRAMES);
+    }
+}
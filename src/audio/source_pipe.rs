@@ -1,116 +1,362 @@
 //! Audio device capture and stream management.
 //!
-//! Handles audio input from system devices using cpal, managing device enumeration,
-//! stream creation, and a ring buffer for sample storage.
+//! Handles audio input from system devices using cpal (and JACK ports when built
+//! with the `jack` feature), managing device enumeration, stream creation, and a
+//! lock-free ring buffer for sample storage (the audio callback never blocks on
+//! the render thread). If the device goes away (an
+//! error from cpal, or for inputs no samples for `STALL_TIMEOUT`) `stream()`
+//! returns no samples and the stream is rebuilt, preferring the same device,
+//! then the startup fallbacks.
+//! A WAV file can stand in for the device (`from_file`), played back in real time
+//! and looped, or stepped by the caller once to the end (`from_file_stepped`,
+//! for offline rendering).
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Stream, StreamConfig};
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "jack")]
+use super::jack_source::{self, JackCapture};
+use super::ring_buffer::{Channel, SampleRing};
+use super::wav::{read_wav, WavAudio};
 use crate::utils::Config;
+use crate::{log_error, log_line};
+
+/// Samples returned per `stream()` call by default (the analyzer's default FFT size)
+const DEFAULT_WINDOW_SIZE: usize = 2048;
+
+/// Frames kept by the ring; twice the largest FFT size so a window is never
+/// overwritten while it is being copied
+const RING_CAPACITY: usize = 32768;
+
+/// Minimum time between reconnection attempts after a device disappears
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An input stream whose callback delivers nothing for this long is treated as
+/// disconnected. Outputs are exempt: loopback capture (WASAPI in particular)
+/// gets no callbacks while nothing plays, so only cpal's error callback counts
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Assumed sample rate until a stream reports its own
+const DEFAULT_SAMPLE_RATE: f32 = 44100.0;
+
+/// Shown when there is nothing to capture (the analysis runs on silence)
+const NO_DEVICES_NOTICE: &str = "No audio devices found";
+
+/// Auto-gain target peak level range (input sensitivity)
+const MIN_TARGET_LEVEL: f32 = 0.1;
+const MAX_TARGET_LEVEL: f32 = 1.0;
+/// Target for file playback, which ignores the configured sensitivity
+const DEFAULT_TARGET_LEVEL: f32 = 0.5;
+
+/// Auto-gain range
+const MIN_GAIN: f32 = 0.5;
+const MAX_GAIN: f32 = 10.0;
 
-pub const BUFFER_SIZE: usize = 1024;
+/// Smoothed peak below which the input counts as silence: the gain ramps back
+/// toward 1 instead of boosting hiss and hum to full scale
+const NOISE_FLOOR_PEAK: f32 = 0.02;
+
+/// Where a device's samples come from
+pub enum DeviceSource {
+    Cpal(cpal::Device),
+    /// A JACK audio output port, by full name
+    #[cfg(feature = "jack")]
+    Jack(String),
+}
 
 pub struct DeviceInfo {
-    pub device: cpal::Device,
+    pub source: DeviceSource,
     pub name: String,
     pub is_input: bool,
 }
 
+/// Running capture, kept alive until replaced or dropped (never read, only held)
+#[allow(dead_code)]
+enum ActiveStream {
+    Cpal(Stream),
+    #[cfg(feature = "jack")]
+    Jack(JackCapture),
+}
+
+/// Playback of a decoded file: looping and advanced by wall-clock time, or
+/// stepped explicitly and played once
+struct FilePlayback {
+    audio: WavAudio,
+    /// Frames played so far (keeps counting across loops)
+    played: f64,
+    /// Time of the last wall-clock advance; None when stepped with `advance_file`
+    last_advance: Option<Instant>,
+}
+
+impl FilePlayback {
+    /// Push the frames that played since the last call (wrapping at the end of the file)
+    fn fill(&mut self, ring: &SampleRing) {
+        let Some(last_advance) = self.last_advance else {
+            return;
+        };
+        self.last_advance = Some(Instant::now());
+        self.advance(ring, last_advance.elapsed().as_secs_f64());
+    }
+
+    /// Push the next `seconds` of audio. Stepped playback stops at the end of the file
+    fn advance(&mut self, ring: &SampleRing, seconds: f64) {
+        let start = self.played as usize;
+        self.played += seconds * self.audio.sample_rate as f64;
+        if self.last_advance.is_none() {
+            self.played = self.played.min(self.audio.left.len() as f64);
+        }
+        let end = self.played as usize;
+
+        // Anything older than the ring would be overwritten anyway
+        let len = self.audio.left.len();
+        let interleaved: Vec<f32> = (end.saturating_sub(ring.capacity()).max(start)..end)
+            .flat_map(|frame| [self.audio.left[frame % len], self.audio.right[frame % len]])
+            .collect();
+        ring.push_interleaved(&interleaved, 2);
+    }
+}
+
 pub struct SourcePipe {
-    buffer: Arc<Mutex<Vec<f32>>>,
+    /// Samples shared with the audio callback: mono is the channel average,
+    /// left/right the first two channels (both mirror mono on single-channel devices)
+    ring: Arc<SampleRing>,
+    /// Samples returned per `stream()` call
+    window_size: usize,
+    /// Ring position read by the last `stream()`, so `stream_stereo` matches it
+    read_position: usize,
     devices: Vec<DeviceInfo>,
     current_device: usize,
-    _stream: Option<Stream>,
+    _stream: Option<ActiveStream>,
+    /// Sample rate of the running stream
+    sample_rate: f32,
     // Auto-gain normalization state
     smoothed_peak: f32,
     target_level: f32,
+    /// Gain applied by the last `stream()` call, reused for the stereo channels
+    last_gain: f32,
+    /// Set by the stream error callback (or stall detection) when the device is no longer available
+    disconnected: Arc<AtomicBool>,
+    last_reconnect_attempt: Instant,
+    /// Whether the current disconnect has been reported in `notice`
+    disconnect_reported: bool,
+    /// Ring position at the last check, and when it last moved (stall detection)
+    last_seen_position: usize,
+    last_activity: Instant,
+    /// Connection change for the user, taken by `take_notice`
+    notice: Option<String>,
+    /// File standing in for the device, when playing from `from_file`
+    file: Option<FilePlayback>,
 }
 
 impl SourcePipe {
     pub fn new() -> Self {
-        let devices = Self::collect_devices();
-        let buffer = Arc::new(Mutex::new(vec![0.0; BUFFER_SIZE]));
+        Self::with_devices(Self::collect_devices(), &Config::load())
+    }
 
-        // Try to load last used device from config
-        let config = Config::load();
-        let start_index = config
-            .last_device
-            .as_ref()
-            .and_then(|name| {
-                let is_input = config.last_device_is_input.unwrap_or(false);
-                devices
-                    .iter()
-                    .position(|d| d.name == *name && d.is_input == is_input)
-            })
-            .or_else(|| {
-                // Prefer pipewire or pulse input devices (more reliable on Linux)
-                devices
-                    .iter()
-                    .position(|d| d.is_input && d.name == "pipewire")
-            })
-            .or_else(|| devices.iter().position(|d| d.is_input && d.name == "pulse"))
-            .or_else(|| {
-                // Fall back to default output device for loopback capture
-                let host = cpal::default_host();
-                let default_output_name = host.default_output_device().and_then(|d| d.name().ok());
-                default_output_name
-                    .and_then(|name| devices.iter().position(|d| !d.is_input && d.name == name))
-            })
-            .unwrap_or(0);
-
-        let stream = if !devices.is_empty() {
-            Self::build_stream(&devices[start_index], Arc::clone(&buffer))
+    /// Capture from the preferred of `devices`. Without any, the pipe still
+    /// works and streams silence, so the visuals keep running
+    fn with_devices(devices: Vec<DeviceInfo>, config: &Config) -> Self {
+        let ring = Arc::new(SampleRing::new(RING_CAPACITY));
+
+        let disconnected = Arc::new(AtomicBool::new(false));
+
+        let (start_index, stream, notice) = if devices.is_empty() {
+            log_error!("No audio devices found, running on silence");
+            (0, None, Some(NO_DEVICES_NOTICE.to_string()))
         } else {
-            eprintln!("No audio devices found!");
-            None
+            // Try to load last used device from config
+            let start_index = Self::pick_preferred_device(&devices, config);
+            let stream = Self::build_stream(
+                &devices[start_index],
+                Arc::clone(&ring),
+                Arc::clone(&disconnected),
+            );
+            (start_index, stream, None)
         };
 
-        if let Some(ref _s) = stream {
+        if let Some((_, sample_rate)) = stream {
             let info = &devices[start_index];
             let device_type = if info.is_input { "input" } else { "output" };
-            println!(
-                "[{}] Selected: {} ({})",
-                start_index, info.name, device_type
+            log_line!(
+                "[{}] Selected: {} ({}, {} Hz)",
+                start_index, info.name, device_type, sample_rate
             );
         }
 
-        Self {
-            buffer,
+        let sample_rate = stream.as_ref().map_or(DEFAULT_SAMPLE_RATE, |&(_, rate)| rate);
+        let mut source = Self {
+            ring,
+            window_size: DEFAULT_WINDOW_SIZE,
+            read_position: 0,
             devices,
             current_device: start_index,
-            _stream: stream,
+            _stream: stream.map(|(s, _)| s),
+            sample_rate,
             smoothed_peak: 0.1, // Start with a reasonable default
-            target_level: 0.5,  // Target peak level for normalization
+            target_level: DEFAULT_TARGET_LEVEL,
+            last_gain: 1.0,
+            disconnected,
+            last_reconnect_attempt: Instant::now(),
+            disconnect_reported: false,
+            last_seen_position: 0,
+            last_activity: Instant::now(),
+            notice,
+            file: None,
+        };
+        source.set_target_level(config.input_target_level());
+        source
+    }
+
+    /// Play a WAV file in a loop instead of capturing a device.
+    /// No devices are opened; device selection is disabled.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let audio = read_wav(path)?;
+        let sample_rate = audio.sample_rate as f32;
+        log_line!(
+            "Playing file: {} ({} Hz, {:.1}s, looping)",
+            path.display(),
+            audio.sample_rate,
+            audio.left.len() as f32 / sample_rate
+        );
+        Ok(Self::with_file(FilePlayback {
+            audio,
+            played: 0.0,
+            last_advance: Some(Instant::now()),
+        }))
+    }
+
+    /// Play a WAV file once, advanced only by `advance_file`, so every run sees
+    /// exactly the same samples whatever the render speed
+    pub fn from_file_stepped(path: &Path) -> Result<Self, String> {
+        let audio = read_wav(path)?;
+        log_line!(
+            "Rendering file: {} ({} Hz, {:.1}s)",
+            path.display(),
+            audio.sample_rate,
+            audio.left.len() as f32 / audio.sample_rate as f32
+        );
+        Ok(Self::with_file(FilePlayback {
+            audio,
+            played: 0.0,
+            last_advance: None,
+        }))
+    }
+
+    fn with_file(file: FilePlayback) -> Self {
+        Self {
+            ring: Arc::new(SampleRing::new(RING_CAPACITY)),
+            window_size: DEFAULT_WINDOW_SIZE,
+            read_position: 0,
+            devices: Vec::new(),
+            current_device: 0,
+            _stream: None,
+            sample_rate: file.audio.sample_rate as f32,
+            smoothed_peak: 0.1,
+            target_level: DEFAULT_TARGET_LEVEL,
+            last_gain: 1.0,
+            disconnected: Arc::new(AtomicBool::new(false)),
+            last_reconnect_attempt: Instant::now(),
+            disconnect_reported: false,
+            last_seen_position: 0,
+            last_activity: Instant::now(),
+            notice: None,
+            file: Some(file),
         }
     }
 
-    pub fn list_devices() {
-        let host = cpal::default_host();
-        println!("\n=== Audio Devices ===");
+    /// Step a `from_file_stepped` file forward by `seconds` (no-op otherwise)
+    pub fn advance_file(&mut self, seconds: f64) {
+        if let Some(file) = self.file.as_mut().filter(|f| f.last_advance.is_none()) {
+            file.advance(&self.ring, seconds);
+        }
+    }
 
-        let mut idx = 0;
-        if let Ok(inputs) = host.input_devices() {
-            for device in inputs {
-                if let Ok(name) = device.name() {
-                    println!("  [{}] {} (input)", idx, name);
-                    idx += 1;
-                }
-            }
+    /// Whether a `from_file_stepped` file has played to its end
+    pub fn file_finished(&self) -> bool {
+        self.file
+            .as_ref()
+            .is_some_and(|f| f.last_advance.is_none() && f.played >= f.audio.left.len() as f64)
+    }
+
+    /// Index of the device to capture from: the configured last device, then
+    /// pipewire / pulse inputs, then the default output (loopback), else the first.
+    pub fn pick_preferred_device(devices: &[DeviceInfo], config: &Config) -> usize {
+        let names: Vec<(&str, bool)> = devices
+            .iter()
+            .map(|d| (d.name.as_str(), d.is_input))
+            .collect();
+        let last_device = config
+            .last_device
+            .as_deref()
+            .map(|name| (name, config.last_device_is_input.unwrap_or(false)));
+        let default_output_name = cpal::default_host()
+            .default_output_device()
+            .and_then(|d| d.name().ok());
+        preferred_device_index(&names, last_device, default_output_name.as_deref())
+    }
+
+    /// Rebuild the stream after a disconnect, trying the same device name first
+    fn reconnect(&mut self) {
+        self.last_reconnect_attempt = Instant::now();
+        let previous = self
+            .devices
+            .get(self.current_device)
+            .map(|d| (d.name.clone(), d.is_input));
+
+        let devices = Self::collect_devices();
+        if devices.is_empty() {
+            return;
         }
-        if let Ok(outputs) = host.output_devices() {
-            for device in outputs {
-                if let Ok(name) = device.name() {
-                    println!("  [{}] {} (output)", idx, name);
-                    idx += 1;
-                }
+        let preference = Config {
+            last_device: previous.as_ref().map(|(name, _)| name.clone()),
+            last_device_is_input: previous.as_ref().map(|&(_, is_input)| is_input),
+            ..Config::default()
+        };
+        let index = Self::pick_preferred_device(&devices, &preference);
+
+        self.disconnected.store(false, Ordering::Relaxed);
+        self.ring.clear();
+        match Self::build_stream(
+            &devices[index],
+            Arc::clone(&self.ring),
+            Arc::clone(&self.disconnected),
+        ) {
+            Some((stream, sample_rate)) => {
+                log_line!("Reconnected: {}", devices[index].name);
+                let fallback = previous
+                    .as_ref()
+                    .is_some_and(|(name, _)| *name != devices[index].name);
+                self.notice = Some(if fallback {
+                    format!("Audio: fell back to {}", devices[index].name)
+                } else {
+                    format!("Audio reconnected: {}", devices[index].name)
+                });
+                self.disconnect_reported = false;
+                self.last_activity = Instant::now();
+                self._stream = Some(stream);
+                self.sample_rate = sample_rate;
+                self.devices = devices;
+                self.current_device = index;
             }
+            None => self.disconnected.store(true, Ordering::Relaxed),
+        }
+    }
+
+    pub fn list_devices() {
+        println!("\n=== Audio Devices ===");
+        for (idx, info) in Self::collect_devices().iter().enumerate() {
+            let device_type = if info.is_input { "input" } else { "output" };
+            println!("  [{}] {} ({})", idx, info.name, device_type);
         }
         println!("Use 0-9 (Shift for +10) to switch devices\n");
     }
 
-    fn collect_devices() -> Vec<DeviceInfo> {
+    /// Capture devices in selection order: cpal inputs, cpal outputs, then JACK ports
+    pub(super) fn collect_devices() -> Vec<DeviceInfo> {
         let host = cpal::default_host();
         let mut devices = Vec::new();
 
@@ -118,7 +364,7 @@ impl SourcePipe {
             for device in input_devices {
                 if let Ok(name) = device.name() {
                     devices.push(DeviceInfo {
-                        device,
+                        source: DeviceSource::Cpal(device),
                         name,
                         is_input: true,
                     });
@@ -130,7 +376,7 @@ impl SourcePipe {
             for device in output_devices {
                 if let Ok(name) = device.name() {
                     devices.push(DeviceInfo {
-                        device,
+                        source: DeviceSource::Cpal(device),
                         name,
                         is_input: false,
                     });
@@ -138,6 +384,16 @@ impl SourcePipe {
             }
         }
 
+        // Hardware capture ports count as inputs, other clients' outputs as outputs
+        #[cfg(feature = "jack")]
+        for (port, is_physical) in jack_source::list_ports() {
+            devices.push(DeviceInfo {
+                name: format!("JACK {}", port),
+                source: DeviceSource::Jack(port),
+                is_input: is_physical,
+            });
+        }
+
         devices
     }
 
@@ -164,11 +420,11 @@ impl SourcePipe {
         match rx.recv_timeout(timeout) {
             Ok(Ok(config)) => Some(config.into()),
             Ok(Err(e)) => {
-                eprintln!("  Failed to get config: {}", e);
+                log_error!("  Failed to get config: {}", e);
                 None
             }
             Err(_) => {
-                eprintln!("  Device config timed out after {:?}", timeout);
+                log_error!("  Device config timed out after {:?}", timeout);
                 None
             }
         }
@@ -176,23 +432,47 @@ impl SourcePipe {
 
     fn build_stream(
         device_info: &DeviceInfo,
-        audio_buffer: Arc<Mutex<Vec<f32>>>,
-    ) -> Option<Stream> {
-        let stream_config =
-            Self::get_config_with_timeout(&device_info.device, device_info.is_input)?;
+        ring: Arc<SampleRing>,
+        disconnected: Arc<AtomicBool>,
+    ) -> Option<(ActiveStream, f32)> {
+        match &device_info.source {
+            DeviceSource::Cpal(device) => {
+                Self::build_cpal_stream(device, device_info.is_input, ring, disconnected)
+                    .map(|(stream, sample_rate)| (ActiveStream::Cpal(stream), sample_rate))
+            }
+            #[cfg(feature = "jack")]
+            DeviceSource::Jack(port) => match jack_source::connect(port, ring, disconnected) {
+                Ok((capture, sample_rate)) => Some((ActiveStream::Jack(capture), sample_rate)),
+                Err(e) => {
+                    log_error!("  Failed to connect JACK port: {}", e);
+                    None
+                }
+            },
+        }
+    }
+
+    fn build_cpal_stream(
+        device: &Device,
+        is_input: bool,
+        ring: Arc<SampleRing>,
+        disconnected: Arc<AtomicBool>,
+    ) -> Option<(Stream, f32)> {
+        let stream_config = Self::get_config_with_timeout(device, is_input)?;
         let channels = stream_config.channels as usize;
+        let sample_rate = stream_config.sample_rate.0 as f32;
 
-        let err_fn = |err| eprintln!("Audio stream error: {}", err);
+        let err_fn = move |err: cpal::StreamError| {
+            if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                disconnected.store(true, Ordering::Relaxed);
+            }
+            log_error!("Audio stream error: {}", err);
+        };
 
-        let stream = device_info.device.build_input_stream(
+        let stream = device.build_input_stream(
             &stream_config,
             move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                let mut buffer = audio_buffer.lock().unwrap();
-                for chunk in data.chunks(channels) {
-                    let sample: f32 = chunk.iter().sum::<f32>() / channels as f32;
-                    buffer.remove(0);
-                    buffer.push(sample);
-                }
+                // Lock-free: only atomic stores, never waits on the render thread
+                ring.push_interleaved(data, channels);
             },
             err_fn,
             None,
@@ -201,60 +481,135 @@ impl SourcePipe {
         match stream {
             Ok(s) => {
                 if let Err(e) = s.play() {
-                    eprintln!("  Failed to play stream: {}", e);
+                    log_error!("  Failed to play stream: {}", e);
                     return None;
                 }
-                Some(s)
+                Some((s, sample_rate))
             }
             Err(e) => {
-                eprintln!("  Failed to build stream: {}", e);
+                log_error!("  Failed to build stream: {}", e);
                 None
             }
         }
     }
 
-    /// Attempts to select a device.
-    /// Returns Some((device_name, success)) if a switch was attempted, None if index invalid/same.
-    pub fn select_device(&mut self, index: usize) -> Option<(String, bool)> {
-        if index >= self.devices.len() {
-            return None;
+    /// Switch capture to device `index`, returning its name, or why it failed.
+    /// Selecting the running device again is a no-op
+    pub fn select_device(&mut self, index: usize) -> Result<String, String> {
+        if self.file.is_some() {
+            return Err("Playing from a file, device selection is disabled".to_string());
         }
-        if index == self.current_device {
-            let info = &self.devices[index];
-            return Some((info.name.clone(), true));
+        let Some(info) = self.devices.get(index) else {
+            return Err(if self.devices.is_empty() {
+                NO_DEVICES_NOTICE.to_string()
+            } else {
+                format!("No audio device [{}] ({} available)", index, self.devices.len())
+            });
+        };
+        if index == self.current_device && self._stream.is_some() {
+            return Ok(info.name.clone());
         }
 
-        let info = &self.devices[index];
         let device_type = if info.is_input { "input" } else { "output" };
         let device_name = info.name.clone();
         let is_input = info.is_input;
-        println!("[{}] Selecting: {} ({})", index, device_name, device_type);
+        log_line!("[{}] Selecting: {} ({})", index, device_name, device_type);
 
         // Clear the buffer
-        {
-            let mut buf = self.buffer.lock().unwrap();
-            buf.iter_mut().for_each(|x| *x = 0.0);
-        }
+        self.ring.clear();
 
-        if let Some(stream) = Self::build_stream(info, Arc::clone(&self.buffer)) {
-            println!("  -> OK");
+        if let Some((stream, sample_rate)) =
+            Self::build_stream(info, Arc::clone(&self.ring), Arc::clone(&self.disconnected))
+        {
+            self.disconnected.store(false, Ordering::Relaxed);
+            self.disconnect_reported = false;
+            self.last_activity = Instant::now();
+            log_line!("  -> OK ({} Hz)", sample_rate);
             self._stream = Some(stream);
+            self.sample_rate = sample_rate;
             self.current_device = index;
 
             // Save to config
             let mut config = Config::load();
             config.set_device(&device_name, is_input);
 
-            Some((device_name, true))
+            Ok(device_name)
         } else {
-            println!("  -> FAILED");
-            Some((device_name, false))
+            log_line!("  -> FAILED");
+            Err(format!("{} - FAILED", device_name))
+        }
+    }
+
+    /// Number of samples returned per `stream()` call (set to the analyzer's FFT size)
+    pub fn set_window_size(&mut self, window_size: usize) {
+        self.window_size = window_size.clamp(1, RING_CAPACITY / 2);
+    }
+
+    /// Set the auto-gain target peak level (clamped to 0.1-1.0)
+    pub fn set_target_level(&mut self, level: f32) {
+        self.target_level = level.clamp(MIN_TARGET_LEVEL, MAX_TARGET_LEVEL);
+    }
+
+    /// Nudge the auto-gain target by `delta`, returning the new level
+    pub fn adjust_gain(&mut self, delta: f32) -> f32 {
+        self.set_target_level(self.target_level + delta);
+        self.target_level
+    }
+
+    /// Sample rate of the current stream (analysis bin widths depend on it)
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Connection change worth telling the user about (device lost, reconnected
+    /// or fallen back to another), at most once per change
+    pub fn take_notice(&mut self) -> Option<String> {
+        self.notice.take()
+    }
+
+    /// Mark an input stream dead if it stopped delivering samples, and retry dead ones
+    fn check_connection(&mut self) {
+        let position = self.ring.position();
+        if position != self.last_seen_position {
+            self.last_seen_position = position;
+            self.last_activity = Instant::now();
+        } else if self._stream.is_some()
+            && self.devices.get(self.current_device).is_some_and(|d| d.is_input)
+            && self.last_activity.elapsed() >= STALL_TIMEOUT
+            && !self.disconnected.load(Ordering::Relaxed)
+        {
+            log_error!("Audio stream stalled: no samples for {:?}", STALL_TIMEOUT);
+            self.disconnected.store(true, Ordering::Relaxed);
+        }
+
+        if !self.disconnected.load(Ordering::Relaxed) {
+            return;
+        }
+        if !self.disconnect_reported {
+            self.disconnect_reported = true;
+            let name = self.devices.get(self.current_device).map_or("", |d| d.name.as_str());
+            self.notice = Some(format!("Audio device lost: {} (reconnecting)", name));
+        }
+        if self.last_reconnect_attempt.elapsed() >= RECONNECT_INTERVAL {
+            self.reconnect();
         }
     }
 
-    /// Get current audio samples with auto-gain normalization
+    /// Get current audio samples with auto-gain normalization.
+    /// Empty while the device is disconnected, rather than the last samples it delivered
     pub fn stream(&mut self) -> Vec<f32> {
-        let buffer = self.buffer.lock().unwrap().clone();
+        if let Some(file) = self.file.as_mut() {
+            file.fill(&self.ring);
+        } else {
+            self.check_connection();
+        }
+        if self.disconnected.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+
+        self.read_position = self.ring.position();
+        let mut buffer = vec![0.0; self.window_size];
+        self.ring.read(Channel::Mono, self.read_position, &mut buffer);
 
         // Calculate current peak level (absolute max)
         let current_peak = buffer.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
@@ -268,11 +623,178 @@ impl SourcePipe {
             self.smoothed_peak = self.smoothed_peak * 0.995 + current_peak * 0.005;
         }
 
-        // Prevent division by zero and limit gain range
-        let safe_peak = self.smoothed_peak.max(0.001);
-        let gain = (self.target_level / safe_peak).clamp(0.5, 10.0);
+        let gain = auto_gain(self.smoothed_peak, self.target_level);
+        self.last_gain = gain;
 
         // Apply gain normalization
         buffer.iter().map(|s| (s * gain).clamp(-1.0, 1.0)).collect()
     }
+
+    /// Get left/right samples at the position of the last `stream()` call, normalized with its gain.
+    /// Mono devices return the same samples for both channels; both are empty while disconnected.
+    pub fn stream_stereo(&self) -> (Vec<f32>, Vec<f32>) {
+        if self.disconnected.load(Ordering::Relaxed) {
+            return (Vec::new(), Vec::new());
+        }
+        let gain = self.last_gain;
+        let read = |channel: Channel| -> Vec<f32> {
+            let mut samples = vec![0.0; self.window_size];
+            self.ring.read(channel, self.read_position, &mut samples);
+            samples.iter().map(|s| (s * gain).clamp(-1.0, 1.0)).collect()
+        };
+        (read(Channel::Left), read(Channel::Right))
+    }
+}
+
+/// Gain bringing `smoothed_peak` to `target_level`, limited to the auto-gain
+/// range. Under the noise floor the ceiling falls toward unity with the peak, so
+/// silence stays quiet while the gain stays continuous
+fn auto_gain(smoothed_peak: f32, target_level: f32) -> f32 {
+    let max_gain = if smoothed_peak < NOISE_FLOOR_PEAK {
+        1.0 + (MAX_GAIN - 1.0) * smoothed_peak / NOISE_FLOOR_PEAK
+    } else {
+        MAX_GAIN
+    };
+    // Prevent division by zero
+    (target_level / smoothed_peak.max(0.001)).clamp(MIN_GAIN, max_gain)
+}
+
+/// Preference order over (name, is_input) pairs: the last used device, then
+/// pipewire / pulse inputs (more reliable on Linux), then the default output
+/// device for loopback capture, else the first device.
+fn preferred_device_index(
+    devices: &[(&str, bool)],
+    last_device: Option<(&str, bool)>,
+    default_output_name: Option<&str>,
+) -> usize {
+    let find = |name: &str, is_input: bool| devices.iter().position(|&d| d == (name, is_input));
+    last_device
+        .and_then(|(name, is_input)| find(name, is_input))
+        .or_else(|| find("pipewire", true))
+        .or_else(|| find("pulse", true))
+        .or_else(|| default_output_name.and_then(|name| find(name, false)))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferred_device_order() {
+        let devices = [
+            ("hw:0", true),
+            ("Speakers", false),
+            ("pulse", true),
+            ("pipewire", true),
+            ("USB Audio", true),
+        ];
+
+        // Last used device wins, but only with the matching direction
+        assert_eq!(preferred_device_index(&devices, Some(("USB Audio", true)), Some("Speakers")), 4);
+        assert_eq!(preferred_device_index(&devices, Some(("USB Audio", false)), Some("Speakers")), 3);
+        // Then pipewire, then pulse
+        assert_eq!(preferred_device_index(&devices, None, Some("Speakers")), 3);
+        assert_eq!(preferred_device_index(&devices[..3], None, Some("Speakers")), 2);
+        // Then the default output for loopback, else the first device
+        assert_eq!(preferred_device_index(&devices[..2], None, Some("Speakers")), 1);
+        assert_eq!(preferred_device_index(&devices[..2], None, None), 0);
+    }
+
+    #[test]
+    fn test_stepped_file_plays_once_to_the_end() {
+        // 1 second ramp at 1 kHz, left channel only
+        let left: Vec<f32> = (0..1000).map(|i| i as f32 / 1000.0).collect();
+        let mut source = SourcePipe::with_file(FilePlayback {
+            audio: WavAudio {
+                sample_rate: 1000,
+                right: vec![0.0; left.len()],
+                left,
+            },
+            played: 0.0,
+            last_advance: None,
+        });
+        // Latest samples pushed, before auto-gain
+        let latest = |source: &SourcePipe| {
+            let mut samples = [0.0; 4];
+            source.ring.read(Channel::Left, source.ring.position(), &mut samples);
+            samples
+        };
+
+        source.advance_file(0.5);
+        assert!(!source.file_finished());
+        assert_eq!(latest(&source), [0.496, 0.497, 0.498, 0.499]);
+
+        // Stepping past the end stops there instead of looping
+        source.advance_file(0.75);
+        assert!(source.file_finished());
+        assert_eq!(latest(&source), [0.996, 0.997, 0.998, 0.999]);
+    }
+
+    #[test]
+    fn test_disconnected_stream_is_silent_and_reported_once() {
+        let mut source = SourcePipe::with_file(FilePlayback {
+            audio: WavAudio {
+                sample_rate: 1000,
+                left: vec![0.5; 1000],
+                right: vec![0.5; 1000],
+            },
+            played: 0.0,
+            last_advance: None,
+        });
+        source.advance_file(1.0);
+        // A device that delivered samples, then went away (reconnect not due yet)
+        source.file = None;
+        source.disconnected.store(true, Ordering::Relaxed);
+        source.last_reconnect_attempt = Instant::now();
+
+        assert!(source.stream().is_empty(), "no stale samples while disconnected");
+        assert!(source.stream_stereo().0.is_empty());
+        assert!(source.take_notice().is_some_and(|n| n.starts_with("Audio device lost")));
+        source.stream();
+        assert_eq!(source.take_notice(), None);
+    }
+
+    #[test]
+    fn test_no_devices_streams_silence_and_refuses_selection() {
+        let mut source = SourcePipe::with_devices(Vec::new(), &Config::default());
+        assert_eq!(source.take_notice().as_deref(), Some(NO_DEVICES_NOTICE));
+
+        // Keeps feeding the analysis
+        let samples = source.stream();
+        assert_eq!(samples.len(), DEFAULT_WINDOW_SIZE);
+        assert!(samples.iter().all(|&s| s == 0.0));
+        assert_eq!(source.stream_stereo().0.len(), DEFAULT_WINDOW_SIZE);
+
+        for index in [0, 3] {
+            assert_eq!(source.select_device(index), Err(NO_DEVICES_NOTICE.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_auto_gain_boosts_quiet_music_but_not_silence() {
+        // Quiet music is brought up to the target
+        assert!((auto_gain(0.05, 0.5) - 10.0).abs() < 1e-6);
+        assert!((auto_gain(0.25, 0.5) - 2.0).abs() < 1e-6);
+        // Hiss barely gets boosted, digital silence not at all
+        assert!(auto_gain(0.002, 0.5) * 0.002 < 0.01);
+        assert_eq!(auto_gain(0.0, 0.5), 1.0);
+        // No jump at the floor
+        let below = auto_gain(NOISE_FLOOR_PEAK - 1e-4, 1.0);
+        let above = auto_gain(NOISE_FLOOR_PEAK, 1.0);
+        assert!((above - below).abs() < 0.1, "{} -> {}", below, above);
+
+        let mut source = SourcePipe::with_file(FilePlayback {
+            audio: WavAudio {
+                sample_rate: 1000,
+                left: Vec::new(),
+                right: Vec::new(),
+            },
+            played: 0.0,
+            last_advance: None,
+        });
+        assert_eq!(source.adjust_gain(0.1), 0.6);
+        assert_eq!(source.adjust_gain(5.0), MAX_TARGET_LEVEL);
+        assert_eq!(source.adjust_gain(-5.0), MIN_TARGET_LEVEL);
+    }
 }
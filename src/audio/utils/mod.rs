@@ -1,3 +1,7 @@
 mod kick_detector;
+mod onset_envelope;
+mod tap_tempo;
 
 pub use kick_detector::KickDetector;
+pub use onset_envelope::OnsetEnvelope;
+pub use tap_tempo::TapTempo;
@@ -0,0 +1,125 @@
+//! Short-window bass envelope for low-latency onsets.
+//!
+//! The analyzer's FFT spans 2048 samples (~46 ms at 44.1 kHz) for frequency
+//! resolution, so a kick landing at the newest end of the window is averaged
+//! with up to 46 ms of what came before it and takes a frame or two to push the
+//! bass bands over their onset threshold. This tracker only looks at the newest
+//! `WINDOW` samples (~12 ms), low-passed to the kick range, so the same kick
+//! reaches full level on the first frame that contains it. The price is that a
+//! short window can't tell frequencies apart: anything loud below the cutoff
+//! counts, which is why it only times onsets and never feeds the bands.
+
+/// Newest samples measured per frame
+const WINDOW: usize = 512;
+
+/// Low-pass cutoff (two one-pole stages) keeping the kick fundamental and its punch
+const LOWPASS_HZ: f32 = 150.0;
+
+/// Level this many times the previous frame's is an onset
+const RISE_RATIO: f32 = 1.8;
+
+/// RMS below this (about -40 dBFS) never counts, so noise can't fire onsets
+const MIN_LEVEL: f32 = 0.01;
+
+/// Frame rate the per-frame decays are tuned for
+const NOMINAL_FRAME_RATE: f32 = 60.0;
+
+/// Per-frame decay of the running average (~3 seconds to adapt at 60fps)
+const AVERAGE_DECAY: f32 = 0.995;
+
+/// Per-frame decay of the running peak (halves in ~2 seconds at 60fps)
+const PEAK_DECAY: f32 = 0.995;
+
+/// Low-passed RMS of the newest samples, with onsets where it jumps between frames
+pub struct OnsetEnvelope {
+    /// Low-pass coefficient for the sample rate
+    alpha: f32,
+    prev_level: f32,
+    average: f32,
+    peak: f32,
+    /// Above the onset threshold last frame (onsets fire on the rising edge)
+    above: bool,
+}
+
+impl OnsetEnvelope {
+    pub fn new(sample_rate: f32) -> Self {
+        let dt = 1.0 / sample_rate.max(1.0);
+        let rc = 1.0 / (std::f32::consts::TAU * LOWPASS_HZ);
+        Self {
+            alpha: dt / (rc + dt),
+            prev_level: 0.0,
+            average: 0.0,
+            peak: 0.0,
+            above: false,
+        }
+    }
+
+    /// Measure the newest samples (the end of `samples`), `dt` seconds after the
+    /// previous frame. Returns whether an onset starts this frame and the level
+    /// relative to its recent peak (0-1)
+    pub fn process(&mut self, samples: &[f32], dt: f32) -> (bool, f32) {
+        let window = &samples[samples.len().saturating_sub(WINDOW)..];
+        if window.is_empty() {
+            return (false, 0.0);
+        }
+        let (mut stage, mut filtered) = (0.0, 0.0);
+        let mut sum = 0.0;
+        for &sample in window {
+            stage += (sample - stage) * self.alpha;
+            filtered += (stage - filtered) * self.alpha;
+            sum += filtered * filtered;
+        }
+        let level = (sum / window.len() as f32).sqrt();
+
+        // A sharp rise that is also loud for the passage (not a blip in a quiet part)
+        let above = level > MIN_LEVEL
+            && level > self.prev_level * RISE_RATIO
+            && level > self.average;
+        let onset = above && !self.above;
+        self.above = above;
+        self.prev_level = level;
+
+        let frames = dt * NOMINAL_FRAME_RATE;
+        let average_decay = AVERAGE_DECAY.powf(frames);
+        self.average = self.average * average_decay + level * (1.0 - average_decay);
+        self.peak = (self.peak * PEAK_DECAY.powf(frames)).max(level).max(MIN_LEVEL);
+        (onset, level / self.peak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kick_at_the_window_end_fires_once() {
+        let mut envelope = OnsetEnvelope::new(44100.0);
+        let quiet = vec![0.001f32; 2048];
+        for _ in 0..60 {
+            assert!(!envelope.process(&quiet, 1.0 / 60.0).0);
+        }
+
+        // A 60 Hz kick filling only the newest 400 samples
+        let mut kick = quiet.clone();
+        for (i, sample) in kick[2048 - 400..].iter_mut().enumerate() {
+            *sample = 0.8 * (i as f32 * 60.0 * std::f32::consts::TAU / 44100.0).sin();
+        }
+        let (onset, level) = envelope.process(&kick, 1.0 / 60.0);
+        assert!(onset);
+        assert_eq!(level, 1.0);
+
+        // Still loud next frame: no second onset
+        assert!(!envelope.process(&kick, 1.0 / 60.0).0);
+
+        // A hi-hat-like 8 kHz burst is filtered out
+        let mut hat = quiet.clone();
+        for (i, sample) in hat[2048 - 400..].iter_mut().enumerate() {
+            *sample = 0.8 * (i as f32 * 8000.0 * std::f32::consts::TAU / 44100.0).sin();
+        }
+        let mut fresh = OnsetEnvelope::new(44100.0);
+        for _ in 0..60 {
+            fresh.process(&quiet, 1.0 / 60.0);
+        }
+        assert!(!fresh.process(&hat, 1.0 / 60.0).0);
+    }
+}
@@ -0,0 +1,82 @@
+//! Manual tempo from key taps.
+//!
+//! Averages the intervals between recent taps into a BPM. A pause longer than
+//! `MAX_TAP_GAP` starts a new measurement, so a fresh series of taps isn't
+//! skewed by an old one.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Taps kept for averaging (7 intervals)
+const MAX_TAPS: usize = 8;
+
+/// A longer pause between taps starts over (1.5s = 40 BPM)
+const MAX_TAP_GAP: Duration = Duration::from_millis(1500);
+
+/// Collects tap times and turns them into a tempo
+#[derive(Default)]
+pub struct TapTempo {
+    taps: VecDeque<Instant>,
+}
+
+impl TapTempo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tap at `now`, returning the tapped BPM once there are at least two taps
+    pub fn tap(&mut self, now: Instant) -> Option<f32> {
+        if let Some(&last) = self.taps.back() {
+            if now.saturating_duration_since(last) > MAX_TAP_GAP {
+                self.taps.clear();
+            }
+        }
+        self.taps.push_back(now);
+        if self.taps.len() > MAX_TAPS {
+            self.taps.pop_front();
+        }
+        self.bpm()
+    }
+
+    /// Average BPM of the current taps (None with fewer than two)
+    pub fn bpm(&self) -> Option<f32> {
+        let (first, last) = (self.taps.front()?, self.taps.back()?);
+        let intervals = self.taps.len() - 1;
+        if intervals == 0 {
+            return None;
+        }
+        let average = last.duration_since(*first).as_secs_f32() / intervals as f32;
+        (average > 0.0).then(|| 60.0 / average)
+    }
+
+    /// Forget all taps
+    pub fn reset(&mut self) {
+        self.taps.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_averages_taps_and_restarts_after_pause() {
+        let start = Instant::now();
+        let mut tempo = TapTempo::new();
+        assert_eq!(tempo.tap(start), None);
+
+        // 0.5s taps with a little jitter average to 120 BPM
+        let offsets_ms = [490, 1010, 1500];
+        let mut bpm = None;
+        for ms in offsets_ms {
+            bpm = tempo.tap(start + Duration::from_millis(ms));
+        }
+        assert!((bpm.unwrap() - 120.0).abs() < 0.1);
+
+        // After a long pause, the next taps measure a new tempo
+        let later = start + Duration::from_secs(10);
+        assert_eq!(tempo.tap(later), None);
+        let bpm = tempo.tap(later + Duration::from_millis(600)).unwrap();
+        assert!((bpm - 100.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,146 @@
+//! Minimal WAV decoding for file playback.
+//!
+//! Reads RIFF/WAVE files with integer PCM (8/16/24/32-bit) or 32-bit float
+//! samples, including `WAVE_FORMAT_EXTENSIBLE` headers. Everything is decoded
+//! up front into left/right channels; mono files fill both.
+
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Decoded audio, one sample per frame in each channel
+pub struct WavAudio {
+    pub sample_rate: u32,
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+}
+
+/// Read and decode a WAV file
+pub fn read_wav(path: &Path) -> Result<WavAudio, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_wav(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn parse_wav(bytes: &[u8]) -> Result<WavAudio, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".to_string());
+    }
+
+    // (format, channels, sample_rate, bits_per_sample)
+    let mut format: Option<(u16, usize, u32, u16)> = None;
+    let mut data: Option<&[u8]> = None;
+
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let body_start = offset + 8;
+        // Truncated files still play up to where they end
+        let body = &bytes[body_start..(body_start + size).min(bytes.len())];
+
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+                let mut tag = u16_at(0);
+                if tag == FORMAT_EXTENSIBLE && body.len() >= 26 {
+                    // The real format is the first two bytes of the sub-format GUID
+                    tag = u16_at(24);
+                }
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                format = Some((tag, u16_at(2) as usize, sample_rate, u16_at(14)));
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        offset = body_start + size + (size & 1);
+    }
+
+    let (tag, channels, sample_rate, bits) = format.ok_or("missing fmt chunk")?;
+    let data = data.ok_or("missing data chunk")?;
+    if channels == 0 || sample_rate == 0 {
+        return Err("invalid channel count or sample rate".to_string());
+    }
+
+    let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+        (FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+        (FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+        (FORMAT_PCM, 24) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0,
+        (FORMAT_PCM, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+        (FORMAT_FLOAT, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        _ => return Err(format!("unsupported format {} with {} bits per sample", tag, bits)),
+    };
+
+    let sample_bytes = bits as usize / 8;
+    let frame_bytes = sample_bytes * channels;
+    let frames = data.len() / frame_bytes;
+    let mut left = Vec::with_capacity(frames);
+    let mut right = Vec::with_capacity(frames);
+    for frame in data.chunks_exact(frame_bytes) {
+        let l = decode(&frame[..sample_bytes]);
+        let r = if channels > 1 {
+            decode(&frame[sample_bytes..sample_bytes * 2])
+        } else {
+            l
+        };
+        left.push(l);
+        right.push(r);
+    }
+
+    if left.is_empty() {
+        return Err("no audio data".to_string());
+    }
+
+    Ok(WavAudio {
+        sample_rate,
+        left,
+        right,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a WAV file with a single fmt chunk and the given interleaved data
+    fn wav_bytes(tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_stereo_pcm16_and_mono_float() {
+        let samples: [i16; 4] = [16384, -16384, 32767, 0];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let audio = parse_wav(&wav_bytes(FORMAT_PCM, 2, 48000, 16, &data)).unwrap();
+        assert_eq!(audio.sample_rate, 48000);
+        assert_eq!(audio.left, vec![0.5, 32767.0 / 32768.0]);
+        assert_eq!(audio.right, vec![-0.5, 0.0]);
+
+        let data: Vec<u8> = [0.25f32, -1.0].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let audio = parse_wav(&wav_bytes(FORMAT_FLOAT, 1, 44100, 32, &data)).unwrap();
+        assert_eq!(audio.left, vec![0.25, -1.0]);
+        assert_eq!(audio.right, audio.left);
+
+        assert!(parse_wav(b"not a wav file").is_err());
+    }
+}
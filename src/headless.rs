@@ -0,0 +1,313 @@
+//! Headless rendering without a window.
+//!
+//! Drives the same `Renderer` + `FeedbackRenderer` pipeline as the windowed app,
+//! but composites into an offscreen `FrameCapture` target and hands every frame
+//! to a callback as raw RGBA for piping into a streaming/encoding pipeline.
+//!
+//! Frame format: `width * height * 4` bytes per frame, RGBA8 with sRGB-encoded
+//! color, rows top to bottom with no padding. Frames are delivered back to back
+//! with no header, so consumers must be told the size up front, e.g.
+//! `ffmpeg -f rawvideo -pix_fmt rgba -s 1280x720 -r 30 -i <path> ...`.
+//!
+//! Performance: each frame waits for the GPU and copies the whole target back to
+//! system memory, so throughput is bounded by readback bandwidth (~110 MB/s at
+//! 1280x720@30). If rendering can't keep up with `--fps`, frames are produced
+//! late rather than dropped.
+//!
+//! Offline mode (`HeadlessOptions::offline`) renders a file as fast as possible
+//! instead: the audio and every frame advance by exactly `1 / fps`, and the run
+//! ends when the file does, so the same file, seed and fps give the same frames.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use nannou::geom::Rect;
+use nannou::wgpu;
+
+use crate::audio::{AudioAnalyzer, SourcePipe};
+use crate::renderer::effects::capture::CAPTURE_FORMAT;
+use crate::renderer::{energy_ranges, FeedbackRenderer, FrameCapture, Renderer};
+use crate::utils::Config;
+
+/// Headless run settings, parsed from `--width`, `--height`, `--fps`, `--frames`
+/// and `--input-file`
+pub struct HeadlessOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f32,
+    /// Stop after this many frames (runs until the callback stops it if unset)
+    pub frames: Option<u64>,
+    /// WAV file to play instead of capturing a device
+    pub input_file: Option<PathBuf>,
+    /// Seed for visualization selection and everything the visualizations
+    /// scatter at random (random if unset)
+    pub seed: Option<u64>,
+    /// Step `input_file` at a fixed `1 / fps` as fast as possible and stop at its
+    /// end, instead of playing it in real time
+    pub offline: bool,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fps: 30.0,
+            frames: None,
+            input_file: None,
+            seed: None,
+            offline: false,
+        }
+    }
+}
+
+impl HeadlessOptions {
+    pub fn from_args(args: &[String]) -> Self {
+        let defaults = Self::default();
+        Self {
+            width: arg_value(args, "--width").unwrap_or(defaults.width).max(1),
+            height: arg_value(args, "--height").unwrap_or(defaults.height).max(1),
+            fps: arg_value(args, "--fps").unwrap_or(defaults.fps).clamp(1.0, 240.0),
+            frames: arg_value(args, "--frames"),
+            input_file: arg_value(args, "--input-file"),
+            seed: arg_value(args, "--seed"),
+            offline: false,
+        }
+    }
+}
+
+/// One rendered frame, borrowed for the duration of the callback.
+/// Dimensions are those of the `HeadlessOptions` passed to `run`.
+pub struct HeadlessFrame<'a> {
+    /// Zero-based frame counter
+    pub index: u64,
+    /// Tightly packed RGBA8 rows, top row first
+    pub rgba: &'a [u8],
+}
+
+/// Render frames until `options.frames` is reached or `on_frame` returns false
+/// (or, offline, the input file ends).
+///
+/// Captures audio from the configured/default device exactly like the windowed app,
+/// or plays `options.input_file` when set.
+pub fn run<F>(options: &HeadlessOptions, on_frame: F) -> Result<(), String>
+where
+    F: FnMut(&HeadlessFrame) -> bool,
+{
+    run_with_config(options, &Config::load(), on_frame)
+}
+
+/// `run` with an already loaded config
+fn run_with_config<F>(
+    options: &HeadlessOptions,
+    config: &Config,
+    mut on_frame: F,
+) -> Result<(), String>
+where
+    F: FnMut(&HeadlessFrame) -> bool,
+{
+    let instance = wgpu::Instance::default();
+    let adapter = futures::executor::block_on(instance.request_adapter(
+        &wgpu::RequestAdapterOptions {
+            power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        },
+    ))
+    .ok_or("No GPU adapter available for headless rendering")?;
+    let (device, queue) = futures::executor::block_on(
+        adapter.request_device(&wgpu::default_device_descriptor(), None),
+    )
+    .map_err(|e| format!("Failed to create GPU device: {}", e))?;
+
+    let size = [options.width, options.height];
+    let mut feedback = FeedbackRenderer::new(
+        &device,
+        &queue,
+        size,
+        1,
+        CAPTURE_FORMAT,
+        config.detection().max_overlays(),
+    );
+    let capture = FrameCapture::new(&device, size);
+
+    let detection_config = config.detection();
+    let mut source = match (&options.input_file, options.offline) {
+        (Some(path), true) => SourcePipe::from_file_stepped(path)?,
+        (Some(path), false) => SourcePipe::from_file(path)?,
+        (None, true) => return Err("Offline rendering needs an input file".to_string()),
+        (None, false) => SourcePipe::new(),
+    };
+    let mut analyzer = AudioAnalyzer::with_config(source.sample_rate(), detection_config.clone());
+    source.set_window_size(analyzer.fft_size());
+    let mut renderer = match options.seed {
+        Some(seed) => Renderer::with_cycling_seeded(
+            detection_config,
+            energy_ranges(config),
+            config.start_viz.as_deref(),
+            config.start_overlays(),
+            seed,
+        ),
+        None => Renderer::with_cycling(
+            detection_config,
+            energy_ranges(config),
+            config.start_viz.as_deref(),
+            config.start_overlays(),
+        ),
+    };
+    feedback.apply_display_config(&config.display());
+    feedback.apply_feedback_config(&config.feedback());
+
+    let bounds = Rect::from_w_h(options.width as f32, options.height as f32);
+    let frame_duration = Duration::from_secs_f32(1.0 / options.fps);
+    let start = Instant::now();
+    let mut rgba = Vec::new();
+    let mut index = 0u64;
+    let mut previous_frame: Option<Instant> = None;
+
+    while options.frames.is_none_or(|limit| index < limit) && !source.file_finished() {
+        let frame_start = Instant::now();
+        // Real frame interval (renders can run slower than the target fps)
+        let dt = match previous_frame {
+            Some(previous) if !options.offline => frame_start - previous,
+            _ => frame_duration,
+        };
+        previous_frame = Some(frame_start);
+        source.advance_file(dt.as_secs_f64());
+        let time = if options.offline {
+            index as f32 / options.fps
+        } else {
+            start.elapsed().as_secs_f32()
+        };
+
+        let samples = source.stream();
+        analyzer.set_sample_rate(source.sample_rate());
+        let analysis = if analyzer.stereo_enabled() {
+            let (left, right) = source.stream_stereo();
+            analyzer.analyze_stereo(&samples, &left, &right, dt.as_secs_f32())
+        } else {
+            analyzer.analyze_with_dt(&samples, dt.as_secs_f32())
+        };
+
+        renderer.update(&analysis, bounds);
+        feedback.follow_audio(time, &analysis);
+
+        let primary_draw = nannou::Draw::new();
+        renderer.draw_primary(&primary_draw, bounds);
+        let overlay_draws: Vec<nannou::Draw> = (0..renderer.overlay_count())
+            .map(|_| nannou::Draw::new())
+            .collect();
+        let overlay_draw_refs: Vec<&nannou::Draw> = overlay_draws.iter().collect();
+        renderer.draw_overlays(&overlay_draw_refs, bounds);
+
+        let outgoing_draw = nannou::Draw::new();
+        if let Some(progress) = renderer.draw_outgoing(&outgoing_draw, bounds) {
+            feedback.crossfade_from(outgoing_draw, progress);
+        }
+
+        feedback.trails = renderer.primary_uses_feedback();
+        feedback.render_with_overlays(
+            &device,
+            &queue,
+            &primary_draw,
+            &overlay_draw_refs,
+            &renderer.overlay_alphas(),
+            &[],
+            capture.view(),
+            CAPTURE_FORMAT,
+            1,
+        );
+        capture.read_rgba(&device, &queue, &mut rgba);
+
+        let frame = HeadlessFrame { index, rgba: &rgba };
+        if !on_frame(&frame) {
+            break;
+        }
+        index += 1;
+
+        if options.offline {
+            continue;
+        }
+        if let Some(remaining) = frame_duration.checked_sub(frame_start.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    Ok(())
+}
+
+/// Value following `name` in the argument list (e.g. `--fps 30`)
+fn arg_value<T: FromStr>(args: &[String], name: &str) -> Option<T> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    /// Render `path` offline with seed 7 and return every frame back to back
+    fn render_clip(path: &std::path::Path) -> Result<Vec<u8>, String> {
+        let options = HeadlessOptions {
+            width: 64,
+            height: 36,
+            input_file: Some(path.to_path_buf()),
+            seed: Some(7),
+            offline: true,
+            ..HeadlessOptions::default()
+        };
+        let mut frames = Vec::new();
+        run_with_config(&options, &Config::default(), |frame| {
+            frames.extend_from_slice(frame.rgba);
+            true
+        })?;
+        Ok(frames)
+    }
+
+    #[test]
+    fn test_offline_render_repeats_byte_for_byte() {
+        // Half a second of 16-bit mono: a 55 Hz thump every 0.125s over a steady 5 kHz tone
+        let sample_rate = 48000u32;
+        let data: Vec<u8> = (0..sample_rate / 2)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let beat = (-(t % 0.125) * 40.0).exp();
+                let sample = beat * (t * 55.0 * TAU).sin() * 0.8 + (t * 5000.0 * TAU).sin() * 0.1;
+                (sample * i16::MAX as f32) as i16
+            })
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+
+        let path = std::env::temp_dir().join(format!("dj-viz-offline-test-{}.wav", std::process::id()));
+        std::fs::write(&path, wav).unwrap();
+        let first = render_clip(&path);
+        let second = render_clip(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let (first, second) = match (first, second) {
+            (Err(e), _) if e.starts_with("No GPU adapter") => return,
+            (first, second) => (first.unwrap(), second.unwrap()),
+        };
+        assert_eq!(first.len(), 15 * 64 * 36 * 4);
+        assert!(first.iter().any(|&b| b != 0), "the clip renders something");
+        assert!(first == second, "the same clip and seed render the same bytes");
+    }
+}
@@ -1,20 +1,41 @@
 mod audio;
+mod headless;
 mod renderer;
 mod ui;
 mod utils;
 
-use audio::{AudioAnalysis, AudioAnalyzer, OutputCapture, SourcePipe};
+#[cfg(feature = "link")]
+use audio::link::LinkSession;
+use audio::utils::TapTempo;
+use audio::{
+    AnalysisBroadcaster, AudioAnalysis, AudioAnalyzer, BroadcastFormat, OutputCapture, SourcePipe,
+};
 use nannou::prelude::*;
 use nannou::winit::event::WindowEvent;
-use renderer::{FeedbackRenderer, Renderer, Resolution, ScriptManager};
+use nannou::winit::monitor::MonitorHandle;
+use nannou::winit::window::Fullscreen;
+use renderer::scripted::MAX_ACTIVE_SCRIPTS;
+use renderer::{preset, FeedbackRenderer, Renderer, Resolution, ScriptManager};
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::env;
+use std::fs::File;
+use std::io::Write;
 use std::path::PathBuf;
-use ui::bindings::{parse_key, Action};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use ui::bindings::{parse_key, Action, KeyBindings};
 use ui::help_overlay::HelpOverlay;
+use ui::log_overlay::LogOverlay;
+use ui::midi::{MidiCommand, MidiListener};
+use ui::osc::{OscCommand, OscListener};
+use ui::param_panel::ParamPanel;
+use ui::status_hud::StatusHud;
 use ui::text_picker::{draw_text_picker, TextPickerState};
 use ui::viz_picker::{draw_viz_picker, VizPicker};
-use utils::Config;
+use utils::{Config, FrameLimiter, FrameRecorder};
+
+/// Frame rate cap in eco mode (`--eco`)
+const ECO_FPS: f32 = 30.0;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -24,10 +45,197 @@ fn main() {
         return;
     }
 
-    // List all devices at startup
-    SourcePipe::list_devices();
+    if args.contains(&"--print-config-defaults".to_string()) {
+        print!("{}", Config::defaults_toml());
+        return;
+    }
+
+    if args.contains(&"--dump-catalog".to_string()) {
+        println!("{}", renderer::catalog_json(&renderer::energy_ranges(&Config::load())));
+        return;
+    }
+
+    if args.contains(&"--headless".to_string()) {
+        run_headless(&args);
+        return;
+    }
+
+    if let Some(input) = offline_arg(&args) {
+        run_offline(&args, input);
+        return;
+    }
+
+    // List all devices at startup (not needed when playing a file)
+    if input_file_arg(&args).is_none() {
+        SourcePipe::list_devices();
+    }
+
+    nannou::app(model).update(update).exit(exit).run();
+}
+
+/// Path given with `--input-file`, to play instead of capturing a device
+fn input_file_arg(args: &[String]) -> Option<PathBuf> {
+    args.iter()
+        .position(|a| a == "--input-file")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
+
+/// Directory given with `--record`, to save every frame as a PNG
+fn record_arg(args: &[String]) -> Option<PathBuf> {
+    args.iter()
+        .position(|a| a == "--record")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
+
+/// UDP port given with `--osc`, to accept OSC remote control
+fn osc_arg(args: &[String]) -> Option<u16> {
+    args.iter()
+        .position(|a| a == "--osc")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+}
+
+/// `host:port` given with `--broadcast`, to stream the analysis to
+fn broadcast_arg(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|a| a == "--broadcast")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Monitor index given with `--monitor`
+fn monitor_arg(args: &[String]) -> Option<usize> {
+    args.iter()
+        .position(|a| a == "--monitor")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+}
+
+/// Print the detected monitors with their resolution, marking the primary one
+fn list_monitors(monitors: &[MonitorHandle], primary: Option<&MonitorHandle>) {
+    println!("\n=== Monitors ===");
+    for (idx, monitor) in monitors.iter().enumerate() {
+        let size = monitor.size();
+        let name = monitor.name().unwrap_or_else(|| "Unknown".to_string());
+        let tag = if Some(monitor) == primary { " (primary)" } else { "" };
+        println!("  [{}] {} {}x{}{}", idx, name, size.width, size.height, tag);
+    }
+    println!("Use --monitor <index> (or `monitor` in the config) to pick one\n");
+}
+
+/// Seed given with `--seed`, to replay the same sequence of visualizations
+fn seed_arg(args: &[String]) -> Option<u64> {
+    args.iter()
+        .position(|a| a == "--seed")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+}
+
+/// Render without a window, writing raw RGBA frames to `--output` (file or FIFO).
+/// Without `--output`, frames are discarded and throughput is reported instead.
+fn run_headless(args: &[String]) {
+    let options = headless::HeadlessOptions::from_args(args);
+    let output_path = args
+        .iter()
+        .position(|a| a == "--output")
+        .and_then(|i| args.get(i + 1));
+    let mut output = match output_path.map(File::create).transpose() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to open headless output: {}", e);
+            return;
+        }
+    };
+
+    eprintln!(
+        "Headless: {}x{} @ {} fps, RGBA8 frames to {}",
+        options.width,
+        options.height,
+        options.fps,
+        output_path.map_or("nowhere (benchmark)", |p| p.as_str())
+    );
+
+    let start = std::time::Instant::now();
+    let result = headless::run(&options, |frame| {
+        if let Some(out) = output.as_mut() {
+            if let Err(e) = out.write_all(frame.rgba) {
+                eprintln!("Headless output closed: {}", e);
+                return false;
+            }
+        } else if frame.index % 300 == 299 {
+            eprintln!(
+                "Rendered {} frames ({:.1} fps)",
+                frame.index + 1,
+                (frame.index + 1) as f32 / start.elapsed().as_secs_f32()
+            );
+        }
+        true
+    });
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+}
+
+/// WAV file given with `--offline`, to render to PNGs without a window
+fn offline_arg(args: &[String]) -> Option<PathBuf> {
+    args.iter()
+        .position(|a| a == "--offline")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
 
-    nannou::app(model).update(update).run();
+/// Render `input` start to end at a fixed `--fps` into PNGs in `--out`, as fast
+/// as the GPU allows. Everything random is seeded (`--seed`, 0 by default), so
+/// reruns produce the same frames.
+fn run_offline(args: &[String], input: PathBuf) {
+    let Some(out_dir) = args
+        .iter()
+        .position(|a| a == "--out")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+    else {
+        eprintln!("--offline needs --out <dir> for the frames");
+        return;
+    };
+    let mut options = headless::HeadlessOptions::from_args(args);
+    options.input_file = Some(input);
+    options.seed = options.seed.or(Some(0));
+    options.offline = true;
+
+    let mut recorder = match FrameRecorder::new(&out_dir) {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    eprintln!(
+        "Offline: {}x{} @ {} fps to {}",
+        options.width,
+        options.height,
+        options.fps,
+        out_dir.display()
+    );
+
+    let start = std::time::Instant::now();
+    let mut rendered = 0;
+    let result = headless::run(&options, |frame| {
+        rendered = frame.index + 1;
+        recorder.push_blocking(options.width, options.height, frame.rgba.to_vec())
+    });
+    recorder.finish();
+
+    match result {
+        Ok(()) => eprintln!(
+            "Rendered {} frames in {:.1}s",
+            rendered,
+            start.elapsed().as_secs_f32()
+        ),
+        Err(e) => eprintln!("{}", e),
+    }
 }
 
 struct Model {
@@ -36,30 +244,82 @@ struct Model {
     renderer: Renderer,
     output_capture: OutputCapture,
     viz_picker: VizPicker,
+    /// Sliders for the running script's `param(...)` values
+    param_panel: ParamPanel,
     help_overlay: HelpOverlay,
+    log_overlay: LogOverlay,
+    /// FPS/BPM/viz name corner HUD (lighter than the debug overlay)
+    status_hud: StatusHud,
     feedback: RefCell<FeedbackRenderer>,
     #[allow(dead_code)]
     screensaver_inhibitor: Option<utils::ScreensaverInhibitor>,
-    phase_offset: f32,
-    prev_energy: f32,
     last_analysis: AudioAnalysis,
     /// Track shift key state from raw events (more reliable than app.keys.mods)
     shift_held: bool,
+    /// Keys currently held, so auto-repeat can be told apart from a new press
+    keys_down: HashSet<Key>,
+    /// Output a black frame instead of anything else (the analysis keeps running)
+    blackout: bool,
     /// Manages Rhai scripted visualizations
     script_manager: ScriptManager,
+    /// Collects tap-tempo key presses
+    tap_tempo: TapTempo,
+    /// Writes the output to a PNG sequence when started with `--record`
+    recorder: Option<RefCell<FrameRecorder>>,
+    /// Key remappings from the config
+    key_bindings: KeyBindings,
+    /// OSC remote control, when started with `--osc <port>`
+    osc: Option<OscListener>,
+    /// MIDI controller input, when started with `--midi`
+    midi: Option<MidiListener>,
+    /// Streams the analysis every frame, when started with `--broadcast <host:port>`
+    broadcaster: Option<AnalysisBroadcaster>,
+    /// Caps the frame rate, when started with `--eco`
+    frame_limiter: Option<FrameLimiter>,
+    /// Ableton Link session, when started with `--link` (needs the `link` feature)
+    #[cfg(feature = "link")]
+    link: Option<LinkSession>,
 }
 
 fn model(app: &App) -> Model {
     let args: Vec<String> = env::args().collect();
     let windowed = args.contains(&"--windowed".to_string()) || args.contains(&"-w".to_string());
-    let resolution = Resolution::current(windowed);
+    let eco = args.contains(&"--eco".to_string());
+    let resolution = if eco {
+        Resolution::current(windowed).eco()
+    } else {
+        Resolution::current(windowed)
+    };
     app.set_exit_on_escape(false);
+    let config = Config::load_checked().unwrap_or_else(|problems| {
+        for problem in &problems {
+            log_error!("Config: {}", problem);
+        }
+        Config::load()
+    });
+
+    // Pick the output monitor (None = let the platform decide, normally the primary)
+    let monitors = app.available_monitors();
+    list_monitors(&monitors, app.primary_monitor().as_ref());
+    let monitor = monitor_arg(&args).or(config.monitor).and_then(|idx| {
+        let monitor = monitors.get(idx).cloned();
+        if monitor.is_none() {
+            log_error!(
+                "Monitor {} not found ({} detected), using the primary display",
+                idx,
+                monitors.len()
+            );
+        }
+        monitor
+    });
 
     let mut win = app
         .new_window()
         .view(view)
         .key_pressed(key_pressed)
+        .key_released(key_released)
         .mouse_pressed(mouse_pressed)
+        .mouse_moved(mouse_moved)
         .mouse_wheel(mouse_wheel)
         .raw_event(raw_event)
         .resized(resized)
@@ -67,7 +327,8 @@ fn model(app: &App) -> Model {
         .min_size(400, 400);
 
     if resolution.fullscreen {
-        win = win.fullscreen();
+        let on = monitor.clone().or_else(|| app.primary_monitor());
+        win = win.fullscreen_with(Some(Fullscreen::Borderless(on)));
     }
 
     let window_id = win.build().unwrap();
@@ -78,6 +339,10 @@ fn model(app: &App) -> Model {
     // Hide cursor in fullscreen mode
     if resolution.fullscreen {
         window.set_cursor_visible(false);
+    } else if let Some(monitor) = &monitor {
+        // Windowed: open in the top-left corner of the chosen monitor
+        let position = monitor.position();
+        window.set_outer_position_pixels(position.x, position.y);
     }
     let device = window.device();
     let queue = window.queue();
@@ -91,13 +356,15 @@ fn model(app: &App) -> Model {
     );
 
     // Create feedback renderer
-    let feedback = FeedbackRenderer::new(
+    let mut feedback = FeedbackRenderer::new(
         device,
         queue,
         [size.0, size.1],
         sample_count,
         Frame::TEXTURE_FORMAT,
+        config.detection().max_overlays() + MAX_ACTIVE_SCRIPTS,
     );
+    feedback.set_render_scale(device, resolution.render_scale, [size.0, size.1], sample_count);
 
     // Inhibit screensaver in release mode
     let screensaver_inhibitor = if !cfg!(debug_assertions) {
@@ -106,31 +373,147 @@ fn model(app: &App) -> Model {
         None
     };
 
-    // Load config and extract values for audio analyzer and renderer
-    let config = Config::load();
-    let detection_config = config.detection();
-    let viz_energy_ranges = config.viz_energy_ranges();
+    // Extract config values for audio analyzer and renderer
+    let key_bindings = KeyBindings::from_names(
+        config
+            .keybindings
+            .iter()
+            .flatten()
+            .map(|(action, key)| (action.as_str(), key.as_str())),
+    );
+    let mut detection_config = config.detection();
+    if eco {
+        // The per-channel passes are two extra FFTs every frame
+        detection_config.stereo_spectrum = Some(false);
+        println!(
+            "Eco mode: {} fps, {}% resolution, stereo analysis off",
+            ECO_FPS,
+            resolution.render_scale * 100.0
+        );
+    }
+    let viz_energy_ranges = renderer::energy_ranges(&config);
+    feedback.apply_display_config(&config.display());
+    feedback.apply_feedback_config(&config.feedback());
+
+    let recorder = record_arg(&args).map(|dir| {
+        let recorder = FrameRecorder::new(&dir).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        feedback.set_recording(device, true);
+        println!("Recording frames to {}", dir.display());
+        RefCell::new(recorder)
+    });
+
+    let osc = osc_arg(&args).and_then(|port| match OscListener::start(port) {
+        Ok(listener) => {
+            println!("Listening for OSC on UDP port {}", port);
+            Some(listener)
+        }
+        Err(e) => {
+            log_error!("{}", e);
+            None
+        }
+    });
+
+    let broadcaster = broadcast_arg(&args).and_then(|target| {
+        let format = if args.contains(&"--broadcast-osc".to_string()) {
+            BroadcastFormat::Osc
+        } else {
+            BroadcastFormat::Json
+        };
+        match AnalysisBroadcaster::start(target, format) {
+            Ok(broadcaster) => {
+                println!("Broadcasting analysis to {} ({:?})", target, format);
+                Some(broadcaster)
+            }
+            Err(e) => {
+                log_error!("{}", e);
+                None
+            }
+        }
+    });
+
+    let midi = if args.contains(&"--midi".to_string()) {
+        MidiListener::start(&config.midi())
+            .map_err(|e| log_error!("{}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    #[cfg(feature = "link")]
+    let link = if args.contains(&"--link".to_string()) {
+        LinkSession::start().map_err(|e| log_error!("{}", e)).ok()
+    } else {
+        None
+    };
+    #[cfg(not(feature = "link"))]
+    if args.contains(&"--link".to_string()) {
+        log_error!("Built without Link support, rebuild with `--features link` to use --link");
+    }
 
     // Initialize script manager with scripts directory
     let scripts_dir = PathBuf::from("scripts");
     let script_manager = ScriptManager::new(scripts_dir);
 
+    let mut source = match input_file_arg(&args) {
+        Some(path) => SourcePipe::from_file(&path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => SourcePipe::new(),
+    };
+    let analyzer = AudioAnalyzer::with_config(source.sample_rate(), detection_config.clone());
+    source.set_window_size(analyzer.fft_size());
+
     let mut model = Model {
-        source: SourcePipe::new(),
-        analyzer: AudioAnalyzer::with_config(44100.0, detection_config.clone()),
-        renderer: Renderer::with_cycling(detection_config, viz_energy_ranges),
+        source,
+        analyzer,
+        renderer: match seed_arg(&args) {
+            Some(seed) => Renderer::with_cycling_seeded(
+                detection_config,
+                viz_energy_ranges,
+                config.start_viz.as_deref(),
+                config.start_overlays(),
+                seed,
+            ),
+            None => Renderer::with_cycling(
+                detection_config,
+                viz_energy_ranges,
+                config.start_viz.as_deref(),
+                config.start_overlays(),
+            ),
+        },
         output_capture: OutputCapture::new(),
         viz_picker: VizPicker::new(),
+        param_panel: ParamPanel::new(),
         help_overlay: HelpOverlay::new(),
+        log_overlay: LogOverlay::new(),
+        status_hud: StatusHud::new(),
         feedback: RefCell::new(feedback),
         screensaver_inhibitor,
-        phase_offset: 0.0,
-        prev_energy: 0.0,
         last_analysis: AudioAnalysis::default(),
         shift_held: false,
+        keys_down: HashSet::new(),
+        blackout: false,
         script_manager,
+        tap_tempo: TapTempo::new(),
+        recorder,
+        key_bindings,
+        osc,
+        midi,
+        broadcaster,
+        frame_limiter: eco.then(|| FrameLimiter::new(ECO_FPS)),
+        #[cfg(feature = "link")]
+        link,
     };
 
+    // Animate at full tempo under eco mode's frame cap
+    if eco {
+        model.renderer.set_frame_rate(ECO_FPS);
+    }
+
     // Enable debug visualization if --debug or -d flag was passed
     let debug_enabled = args.contains(&"--debug".to_string()) || args.contains(&"-d".to_string());
     if debug_enabled {
@@ -146,15 +529,133 @@ fn model(app: &App) -> Model {
     model
 }
 
-fn update(app: &App, model: &mut Model, _update: Update) {
+/// Switch to a visualization and lock, leaving any script, and report it
+fn select_visualization(model: &mut Model, idx: usize) {
+    model.script_manager.deactivate();
+    if let Some(name) = model.renderer.set_visualization(idx) {
+        model
+            .renderer
+            .show_notification(format!("[{}] {}", idx, name));
+    }
+    model.viz_picker.update_active_states(
+        model.renderer.current_idx(),
+        model.renderer.overlay_indices(),
+    );
+}
+
+/// Apply MIDI controller commands received since the last frame
+fn apply_midi_commands(app: &App, model: &mut Model) {
+    let Some(midi) = &mut model.midi else {
+        return;
+    };
+    for command in midi.drain() {
+        match command {
+            MidiCommand::Action(action) => apply_action(app, model, action),
+            MidiCommand::SelectViz(idx) if idx < renderer::VIZ_NAMES.len() => {
+                select_visualization(model, idx)
+            }
+            MidiCommand::SelectViz(_) => {}
+            MidiCommand::Zoom(amount) => model.feedback.borrow_mut().zoom_scrub = amount,
+        }
+    }
+}
+
+/// Apply remote control commands received since the last frame, the same way
+/// the matching keyboard/picker actions do
+fn apply_osc_commands(model: &mut Model) {
+    let Some(osc) = &model.osc else {
+        return;
+    };
+    for command in osc.drain() {
+        match command {
+            OscCommand::SelectViz(name) => {
+                model.script_manager.deactivate();
+                match model.renderer.set_visualization_by_name(&name) {
+                    Some(idx) => model
+                        .renderer
+                        .show_notification(format!("[{}] {}", idx, model.renderer.current_name())),
+                    None => log_error!("OSC: unknown visualization {:?}", name),
+                }
+            }
+            OscCommand::ToggleOverlay(name) => {
+                if model.renderer.toggle_overlay_by_name(&name).is_none() {
+                    log_error!("OSC: unknown visualization {:?}", name);
+                }
+            }
+            OscCommand::Lock(locked) => {
+                if model.renderer.is_locked() != locked {
+                    model.renderer.toggle_lock();
+                }
+            }
+            OscCommand::Fade(fade) => model.feedback.borrow_mut().fade = fade,
+        }
+        model.viz_picker.update_active_states(
+            model.renderer.current_idx(),
+            model.renderer.overlay_indices(),
+        );
+    }
+}
+
+/// Finish writing queued recording frames before quitting
+fn exit(_app: &App, model: Model) {
+    if let Some(recorder) = model.recorder {
+        let mut recorder = recorder.into_inner();
+        recorder.finish();
+        if recorder.dropped() > 0 {
+            log_error!(
+                "Recording dropped {} frames (PNG writing couldn't keep up)",
+                recorder.dropped()
+            );
+        }
+    }
+}
+
+/// Lock the beat clock to the Link session, or release it when there are no peers
+#[cfg(feature = "link")]
+fn follow_link(model: &mut Model) {
+    let Some(link) = &model.link else {
+        return;
+    };
+    match link.beat() {
+        Some(beat) => model.analyzer.set_external_beat(beat.bpm, Some(beat.phase)),
+        None => model.analyzer.clear_external_beat(),
+    }
+}
+
+fn update(app: &App, model: &mut Model, update: Update) {
+    if let Some(limiter) = &mut model.frame_limiter {
+        limiter.wait();
+    }
     let samples = model.source.stream();
+    if let Some(notice) = model.source.take_notice() {
+        model.renderer.show_notification(notice);
+    }
+    // Device switches and reconnects can change the rate
+    model.analyzer.set_sample_rate(model.source.sample_rate());
+    let dt = update.since_last.as_secs_f32();
+    model.status_hud.tick(dt);
 
-    // Analyze audio (single FFT for all visualizations)
-    let analysis = model.analyzer.analyze(&samples);
+    #[cfg(feature = "link")]
+    follow_link(model);
+
+    // Analyze audio (single FFT for all visualizations, plus one per channel in stereo mode)
+    let analysis = if model.analyzer.stereo_enabled() {
+        let (left, right) = model.source.stream_stereo();
+        model.analyzer.analyze_stereo(&samples, &left, &right, dt)
+    } else {
+        model.analyzer.analyze_with_dt(&samples, dt)
+    };
 
     // Store for use in key handlers
     model.last_analysis = analysis.clone();
 
+    if let Some(broadcaster) = &mut model.broadcaster {
+        broadcaster.send(&analysis);
+    }
+
+    apply_osc_commands(model);
+    apply_midi_commands(app, model);
+
     // // Debug: print energy every second
     // if app.elapsed_frames().is_multiple_of(60) {
     //     println!(
@@ -168,26 +669,26 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 
     model.renderer.update(&analysis, bounds);
     let viz_info = model.renderer.viz_info();
-    model.script_manager.update(&analysis, bounds, &viz_info);
-
-    // Detect energy peak and flip zoom direction
-    if analysis.energy >= 0.95 && model.prev_energy < 0.95 {
-        model.phase_offset += std::f32::consts::PI; // Add 180 degrees to reverse direction
-    }
-    model.prev_energy = analysis.energy;
+    let beat_events = model.analyzer.take_beat_events();
+    model
+        .script_manager
+        .update(&analysis, &beat_events, bounds, &viz_info);
+    let window = app.main_window();
+    model
+        .script_manager
+        .upload_images(window.device(), window.queue());
+    let renderer = &model.renderer;
+    model
+        .viz_picker
+        .update_thumbnails(window.device(), window.queue(), bounds, |idx, draw| {
+            renderer.draw_visualization(idx, draw, bounds)
+        });
 
     // Update feedback zoom based on beat intensity (bass + energy peaks)
-    {
-        let mut feedback = model.feedback.borrow_mut();
-        // Sine wave oscillation over 30 seconds: zooms in and out
-        let phase = app.time * std::f32::consts::TAU / 30.0 + model.phase_offset;
-        let direction = phase.sin(); // -1 to 1
-                                     // Base zoom follows sine wave
-        let base_offset = 0.006 * direction;
-        // Bass amplifies the current direction (zoom in faster or out faster)
-        let bass_boost = analysis.bass * 0.012 * direction;
-        feedback.scale = 1.0 + base_offset + bass_boost;
-    }
+    model
+        .feedback
+        .borrow_mut()
+        .follow_audio(app.time, &analysis);
 }
 
 fn view(app: &App, model: &Model, frame: Frame) {
@@ -196,30 +697,90 @@ fn view(app: &App, model: &Model, frame: Frame) {
     let queue = window.queue();
     let bounds = app.window_rect();
 
+    // Catch up with size changes `resized` hasn't delivered yet (e.g. right after
+    // toggling fullscreen), so the feedback textures always match the frame
+    model.feedback.borrow_mut().resize(
+        device,
+        frame.texture_size(),
+        window.msaa_samples(),
+        Frame::TEXTURE_FORMAT,
+    );
+    let safe_mode = model.feedback.borrow().safe_mode;
+
+    // Blackout overrides everything, UI layers included. Skipping the feedback
+    // pass leaves its trails as they were, to pick up from on the way back
+    if model.blackout {
+        frame.clear(BLACK);
+        if let Some(recorder) = &model.recorder {
+            let [width, height] = model.feedback.borrow().size();
+            let black = [0, 0, 0, 255].repeat((width * height) as usize);
+            recorder.borrow_mut().push(width, height, black);
+        }
+        return;
+    }
+
     // If a script is active, render it directly (no feedback effects)
-    if model.script_manager.is_active() {
+    if script_draws_to_frame(model) {
         let script_draw = app.draw();
         model.script_manager.draw(&script_draw, bounds);
         script_draw.to_frame(app, &frame).unwrap();
+    } else if model.script_manager.is_active() {
+        // Safe mode limits scripts too, and recording needs the output texture:
+        // route them through the output passes, without trails
+        let script_draw = app.draw();
+        model.script_manager.draw(&script_draw, bounds);
+        let mut feedback = model.feedback.borrow_mut();
+        feedback.trails = false;
+        feedback.render_with_overlays(
+            device,
+            queue,
+            &script_draw,
+            &[],
+            &[],
+            &[],
+            frame.texture_view(),
+            Frame::TEXTURE_FORMAT,
+            window.msaa_samples(),
+        );
     } else {
         // Create draw context for primary visualization
         let primary_draw = app.draw();
         model.renderer.draw_primary(&primary_draw, bounds);
 
-        // Create draw contexts for overlay visualizations
+        // Create draw contexts for overlay visualizations (built-ins first, then scripts)
         let overlay_count = model.renderer.overlay_count();
-        let overlay_draws: Vec<nannou::Draw> = (0..overlay_count).map(|_| app.draw()).collect();
+        let script_overlay_count = model.script_manager.overlay_count();
+        let overlay_draws: Vec<nannou::Draw> = (0..overlay_count + script_overlay_count)
+            .map(|_| app.draw())
+            .collect();
         let overlay_draw_refs: Vec<&nannou::Draw> = overlay_draws.iter().collect();
-        model.renderer.draw_overlays(&overlay_draw_refs, bounds);
+        let (builtin_draws, script_draws) = overlay_draw_refs.split_at(overlay_count);
+        model.renderer.draw_overlays(builtin_draws, bounds);
+        model.script_manager.draw_overlays(script_draws);
+
+        // Script overlays blend at full opacity
+        let mut overlay_alphas = model.renderer.overlay_alphas();
+        overlay_alphas.resize(overlay_count + script_overlay_count, 1.0);
+
+        // The primary being switched out, mixed with the new one in the feedback pass.
+        // Its own Draw: app.draw() hands out the one shared Draw, reset each call
+        let outgoing_draw = nannou::Draw::new();
+        let crossfade = model.renderer.draw_outgoing(&outgoing_draw, bounds);
 
         // Render through feedback buffer with burn blending and output to frame
         {
             let mut feedback = model.feedback.borrow_mut();
+            if let Some(progress) = crossfade {
+                feedback.crossfade_from(outgoing_draw, progress);
+            }
+            feedback.trails = model.renderer.primary_uses_feedback();
             feedback.render_with_overlays(
                 device,
                 queue,
                 &primary_draw,
                 &overlay_draw_refs,
+                &overlay_alphas,
+                &[],
                 frame.texture_view(),
                 Frame::TEXTURE_FORMAT,
                 window.msaa_samples(),
@@ -227,6 +788,19 @@ fn view(app: &App, model: &Model, frame: Frame) {
         }
     }
 
+    // Queue the composited output for recording (UI layers below are left out)
+    if let Some(recorder) = &model.recorder {
+        let feedback = model.feedback.borrow();
+        let [width, height] = feedback.size();
+        let rgba = feedback.read_back_rgba(device, queue);
+        recorder.borrow_mut().push(width, height, rgba);
+    }
+
+    // Draw script errors directly to frame (not through feedback)
+    let error_draw = app.draw();
+    model.script_manager.draw_errors(&error_draw, bounds);
+    error_draw.to_frame(app, &frame).unwrap();
+
     // Draw debug visualization directly to frame (not through feedback)
     let debug_draw = app.draw();
     model.renderer.draw_debug_viz(&debug_draw, bounds);
@@ -237,6 +811,38 @@ fn view(app: &App, model: &Model, frame: Frame) {
     model.renderer.draw_notification(&notification_draw, bounds);
     notification_draw.to_frame(app, &frame).unwrap();
 
+    // Safe mode indicator, always visible while it's on
+    if safe_mode {
+        let safe_draw = app.draw();
+        safe_draw
+            .text("SAFE MODE")
+            .x_y(bounds.right() - 70.0, bounds.top() - 20.0)
+            .w_h(120.0, 20.0)
+            .right_justify()
+            .color(rgba(1.0, 0.75, 0.2, 0.9))
+            .font_size(14);
+        safe_draw.to_frame(app, &frame).unwrap();
+    }
+
+    // Draw the status HUD directly to frame
+    if model.status_hud.visible {
+        let hud_draw = app.draw();
+        model.status_hud.draw(
+            &hud_draw,
+            bounds,
+            &model.last_analysis,
+            model.renderer.current_name(),
+        );
+        hud_draw.to_frame(app, &frame).unwrap();
+    }
+
+    // Draw on-screen log directly to frame
+    if model.log_overlay.visible {
+        let log_draw = app.draw();
+        model.log_overlay.draw(&log_draw, bounds);
+        log_draw.to_frame(app, &frame).unwrap();
+    }
+
     // Draw search overlay directly to frame (not through feedback)
     if model.output_capture.is_active() {
         let search_draw = app.draw();
@@ -251,6 +857,15 @@ fn view(app: &App, model: &Model, frame: Frame) {
         picker_draw.to_frame(app, &frame).unwrap();
     }
 
+    // Draw script parameter panel directly to frame
+    if model.param_panel.active {
+        let param_draw = app.draw();
+        model
+            .param_panel
+            .draw(&param_draw, bounds, model.script_manager.params().as_ref());
+        param_draw.to_frame(app, &frame).unwrap();
+    }
+
     // Draw help overlay directly to frame
     if model.help_overlay.visible {
         let help_draw = app.draw();
@@ -261,15 +876,14 @@ fn view(app: &App, model: &Model, frame: Frame) {
     }
 }
 
-fn resized(app: &App, model: &mut Model, size: Vec2) {
+fn resized(app: &App, model: &mut Model, _size: Vec2) {
+    // The reported size is in points; the feedback textures must match the frame in pixels
     let window = app.main_window();
-    let device = window.device();
-    let sample_count = window.msaa_samples();
-
+    let (width, height) = window.inner_size_pixels();
     model.feedback.borrow_mut().resize(
-        device,
-        [size.x as u32, size.y as u32],
-        sample_count,
+        window.device(),
+        [width, height],
+        window.msaa_samples(),
         Frame::TEXTURE_FORMAT,
     );
 }
@@ -279,39 +893,188 @@ fn raw_event(_app: &App, model: &mut Model, event: &WindowEvent) {
     if let WindowEvent::ModifiersChanged(mods) = event {
         model.shift_held = mods.shift();
     }
+    // Releases while unfocused never arrive; forget held keys so the next press counts
+    if let WindowEvent::Focused(false) = event {
+        model.keys_down.clear();
+    }
+}
+
+/// Overlay opacity change per Left/Right press in the picker
+const OPACITY_STEP: f32 = 0.1;
+
+/// Burn strength change per [ / ] press
+const BURN_STRENGTH_STEP: f32 = 0.1;
+
+/// Adjust the overlay burn strength and report it
+fn nudge_burn_strength(model: &mut Model, delta: f32) {
+    let strength = model.feedback.borrow_mut().nudge_burn_strength(delta);
+    model
+        .renderer
+        .show_notification(format!("Burn strength: {:.0}%", strength * 100.0));
+}
+
+/// Input sensitivity change per - / = press
+const INPUT_GAIN_STEP: f32 = 0.05;
+
+/// Adjust the auto-gain target, report it and remember it for the next start
+fn nudge_input_gain(model: &mut Model, delta: f32) {
+    let level = model.source.adjust_gain(delta);
+    model
+        .renderer
+        .show_notification(format!("Input sensitivity: {:.0}%", level * 100.0));
+    Config::load().set_input_target_level(level);
+}
+
+/// Adjust the picker-selected visualization's overlay opacity and report it
+fn nudge_overlay_opacity(model: &mut Model, delta: f32) {
+    let Some(idx) = model.viz_picker.selected_viz_index() else {
+        return;
+    };
+    if let Some(alpha) = model.renderer.nudge_overlay_alpha(idx, delta) {
+        model.viz_picker.set_opacity(idx, alpha);
+        model.renderer.show_notification(format!(
+            "Overlay opacity [{}] {}: {:.0}%",
+            idx,
+            renderer::VIZ_NAMES[idx],
+            alpha * 100.0
+        ));
+    }
+}
+
+/// Whether a fullscreen script draws straight to the frame, skipping the output
+/// passes (safe mode and recording route it through them)
+fn script_draws_to_frame(model: &Model) -> bool {
+    model.script_manager.is_active() && !model.feedback.borrow().safe_mode && model.recorder.is_none()
+}
+
+/// Save the last composited frame as `dj-viz-<unixtime>.png` in the working directory
+fn save_screenshot(app: &App, model: &mut Model) {
+    if script_draws_to_frame(model) {
+        model
+            .renderer
+            .show_notification("Screenshot: not available for fullscreen scripts".to_string());
+        return;
+    }
+    let unix_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = PathBuf::from(format!("dj-viz-{}.png", unix_time));
+    let window = app.main_window();
+    let result = model
+        .feedback
+        .borrow_mut()
+        .capture_png(window.device(), window.queue(), &path);
+    let text = match result {
+        Ok(()) => {
+            log_line!("Saved screenshot {}", path.display());
+            format!("Screenshot: {}", path.display())
+        }
+        Err(e) => {
+            log_error!("Screenshot failed: {}", e);
+            format!("Screenshot failed: {}", e)
+        }
+    };
+    model.renderer.show_notification(text);
+}
+
+/// Number of parameters the edited script declares (for panel navigation)
+fn script_param_count(model: &Model) -> usize {
+    model
+        .script_manager
+        .params()
+        .map_or(0, |(_, params)| params.len())
+}
+
+/// Save the current arrangement (and blend mode) to a numbered preset slot
+fn save_preset_slot(model: &mut Model, slot: u8) {
+    let Some(path) = preset::slot_path(slot) else {
+        return;
+    };
+    let mut preset = model.renderer.export_preset();
+    preset.blend_mode = model.feedback.borrow().blend_mode;
+    match preset::save_preset(&preset, &path) {
+        Ok(()) => model
+            .renderer
+            .show_notification(format!("Saved preset {}: {}", slot, preset.primary)),
+        Err(e) => log_error!("Failed to save preset {}: {}", slot, e),
+    }
+}
+
+/// Recall a numbered preset slot saved with `save_preset_slot`
+fn recall_preset_slot(model: &mut Model, slot: u8) {
+    let Some(path) = preset::slot_path(slot) else {
+        return;
+    };
+    let preset = match preset::load_preset(&path) {
+        Ok(preset) => preset,
+        Err(_) if !path.exists() => {
+            model
+                .renderer
+                .show_notification(format!("Preset {} is empty (Shift+{} saves)", slot, slot));
+            return;
+        }
+        Err(e) => {
+            log_error!("Failed to load preset {}: {}", slot, e);
+            return;
+        }
+    };
+    if model.renderer.apply_preset(&preset) {
+        model.feedback.borrow_mut().blend_mode = preset.blend_mode;
+        model
+            .renderer
+            .show_notification(format!("Preset {}: {}", slot, preset.primary));
+    } else {
+        log_error!("Preset {} has unknown primary {:?}", slot, preset.primary);
+    }
 }
 
 fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    let repeat = !model.keys_down.insert(key);
     let action = parse_key(
         key,
         app.keys.mods.shift(),
         model.output_capture.search_active,
         model.viz_picker.active,
+        model.param_panel.active,
+        &model.key_bindings,
     );
+    // A held key auto-repeats: blackout flips once per physical press
+    if repeat && action == Some(Action::Blackout) {
+        return;
+    }
+    if let Some(action) = action {
+        apply_action(app, model, action);
+    }
+}
+
+fn key_released(_app: &App, model: &mut Model, key: Key) {
+    model.keys_down.remove(&key);
+}
 
+/// Perform an action from a key press (or another controller mapped to actions)
+fn apply_action(app: &App, model: &mut Model, action: Action) {
     match action {
-        Some(Action::Quit) => app.quit(),
-        Some(Action::ShowHelp) => {
+        Action::Quit => app.quit(),
+        Action::ShowHelp => {
             model.help_overlay.toggle();
             model.viz_picker.hide(); // Close picker when showing help
         }
+        Action::Blackout => {
+            model.blackout = !model.blackout;
+            log_line!("Blackout {}", if model.blackout { "on" } else { "off" });
+        }
 
         // Search mode actions (audio device search)
-        Some(Action::SearchCancel) => model.output_capture.cancel(),
-        Some(Action::SearchMoveUp) => model.output_capture.move_up(),
-        Some(Action::SearchMoveDown) => model.output_capture.move_down(),
-        Some(Action::SearchBackspace) => model.output_capture.backspace(),
-        Some(Action::SearchInput(c)) => model.output_capture.append_char(c),
-        Some(Action::SearchConfirm) => {
+        Action::SearchCancel => model.output_capture.cancel(),
+        Action::SearchMoveUp => model.output_capture.move_up(),
+        Action::SearchMoveDown => model.output_capture.move_down(),
+        Action::SearchBackspace => model.output_capture.backspace(),
+        Action::SearchInput(c) => model.output_capture.append_char(c),
+        Action::SearchConfirm => {
             if let Some((name, idx)) = model.output_capture.select() {
-                let msg = if let Some((_, success)) = model.source.select_device(idx) {
-                    if success {
-                        format!("[{}] {}", idx, name)
-                    } else {
-                        format!("[{}] {} - FAILED", idx, name)
-                    }
-                } else {
-                    format!("[{}] {} - INVALID", idx, name)
+                let msg = match model.source.select_device(idx) {
+                    Ok(_) => format!("[{}] {}", idx, name),
+                    Err(e) => format!("[{}] {}", idx, e),
                 };
                 model.renderer.show_notification(msg);
             }
@@ -319,7 +1082,7 @@ fn key_pressed(app: &App, model: &mut Model, key: Key) {
         }
 
         // Viz picker mode actions
-        Some(Action::VizPickerShow) => {
+        Action::VizPickerShow => {
             model.help_overlay.hide();
             model.viz_picker.update_active_states(
                 model.renderer.current_idx(),
@@ -327,25 +1090,16 @@ fn key_pressed(app: &App, model: &mut Model, key: Key) {
             );
             model.viz_picker.show();
         }
-        Some(Action::VizPickerHide) => model.viz_picker.hide(),
-        Some(Action::VizPickerMoveUp) => model.viz_picker.move_up(),
-        Some(Action::VizPickerMoveDown) => model.viz_picker.move_down(),
-        Some(Action::VizPickerSelect) => {
+        Action::VizPickerHide => model.viz_picker.hide(),
+        Action::VizPickerMoveUp => model.viz_picker.move_up(),
+        Action::VizPickerMoveDown => model.viz_picker.move_down(),
+        Action::VizPickerSelect => {
             if let Some(idx) = model.viz_picker.selected_viz_index() {
-                model.script_manager.deactivate();
-                if let Some(name) = model.renderer.set_visualization(idx) {
-                    model
-                        .renderer
-                        .show_notification(format!("[{}] {}", idx, name));
-                }
-                model.viz_picker.update_active_states(
-                    model.renderer.current_idx(),
-                    model.renderer.overlay_indices(),
-                );
+                select_visualization(model, idx);
             }
             model.viz_picker.hide();
         }
-        Some(Action::VizPickerToggle) => {
+        Action::VizPickerToggle => {
             if let Some(idx) = model.viz_picker.selected_viz_index() {
                 model.renderer.toggle_overlay(idx);
                 model.viz_picker.update_active_states(
@@ -354,11 +1108,37 @@ fn key_pressed(app: &App, model: &mut Model, key: Key) {
                 );
             }
         }
+        Action::VizPickerOpacityDown => nudge_overlay_opacity(model, -OPACITY_STEP),
+        Action::VizPickerOpacityUp => nudge_overlay_opacity(model, OPACITY_STEP),
+
+        // Script parameter panel actions
+        Action::ParamPanelHide => model.param_panel.hide(),
+        Action::ParamPanelMoveUp => model.param_panel.move_up(script_param_count(model)),
+        Action::ParamPanelMoveDown => model.param_panel.move_down(script_param_count(model)),
+        Action::ParamPanelDecrease => {
+            let idx = model.param_panel.selected(script_param_count(model));
+            model.script_manager.adjust_param(idx, -1.0);
+        }
+        Action::ParamPanelIncrease => {
+            let idx = model.param_panel.selected(script_param_count(model));
+            model.script_manager.adjust_param(idx, 1.0);
+        }
+        Action::ParamPanelReset => {
+            let idx = model.param_panel.selected(script_param_count(model));
+            if let Some((name, value)) = model.script_manager.reset_param(idx) {
+                model
+                    .renderer
+                    .show_notification(format!("{} reset to {:.3}", name, value));
+            }
+        }
 
         // Normal mode actions
-        Some(Action::StartSearch) => model.output_capture.start_search(),
-        Some(Action::ToggleDebugViz) => model.renderer.toggle_debug_viz(),
-        Some(Action::ToggleLock) => {
+        Action::StartSearch => model.output_capture.start_search(),
+        Action::ToggleDebugViz => model.renderer.toggle_debug_viz(),
+        Action::ToggleLog => model.log_overlay.toggle(),
+        Action::ToggleHud => model.status_hud.toggle(),
+        Action::ToggleParams => model.param_panel.toggle(),
+        Action::ToggleLock => {
             model.renderer.toggle_lock();
             let status = if model.renderer.is_locked() {
                 "LOCKED"
@@ -369,11 +1149,21 @@ fn key_pressed(app: &App, model: &mut Model, key: Key) {
                 .renderer
                 .show_notification(format!("Auto-cycling: {}", status));
         }
-        Some(Action::CycleNext) => {
+        Action::CycleNext => {
             model.script_manager.deactivate();
             model.renderer.cycle_next(&model.last_analysis);
         }
-        Some(Action::CycleScript) => {
+        Action::CyclePrevious => {
+            let text = match model.renderer.cycle_previous() {
+                Some(name) => {
+                    model.script_manager.deactivate();
+                    format!("Back to: {}", name)
+                }
+                None => "No previous visualization".to_string(),
+            };
+            model.renderer.show_notification(text);
+        }
+        Action::CycleScript => {
             if let Some(name) = model.script_manager.cycle_next() {
                 model
                     .renderer
@@ -384,13 +1174,127 @@ fn key_pressed(app: &App, model: &mut Model, key: Key) {
                     .show_notification("No scripts found in scripts/".to_string());
             }
         }
-
-        None => {} // Unhandled key
+        Action::ClearOverlays => {
+            let cleared = model.renderer.clear_overlays() + model.script_manager.clear_overlays();
+            let text = match cleared {
+                0 => "No overlays to clear".to_string(),
+                n => format!("Cleared {} overlay(s)", n),
+            };
+            model.renderer.show_notification(text);
+        }
+        Action::AddScriptOverlay => {
+            if let Some(name) = model.script_manager.add_overlay() {
+                model
+                    .renderer
+                    .show_notification(format!("Script overlay: {}", name));
+            } else {
+                model
+                    .renderer
+                    .show_notification("No more scripts can be added".to_string());
+            }
+        }
+        Action::ToggleScriptOverlay => {
+            let text = match model.script_manager.toggle_overlay_mode() {
+                Some(name) if model.script_manager.is_active() => {
+                    format!("Script fullscreen: {}", name)
+                }
+                Some(name) => format!("Script as overlay: {}", name),
+                None => "No script running".to_string(),
+            };
+            model.renderer.show_notification(text);
+        }
+        Action::CycleSymmetry => {
+            let folds = model.feedback.borrow_mut().cycle_symmetry();
+            let msg = if folds > 1 {
+                format!("Symmetry: {}-fold", folds)
+            } else {
+                "Symmetry: OFF".to_string()
+            };
+            model.renderer.show_notification(msg);
+        }
+        Action::BurnStrengthDown => nudge_burn_strength(model, -BURN_STRENGTH_STEP),
+        Action::BurnStrengthUp => nudge_burn_strength(model, BURN_STRENGTH_STEP),
+        Action::InputGainDown => nudge_input_gain(model, -INPUT_GAIN_STEP),
+        Action::InputGainUp => nudge_input_gain(model, INPUT_GAIN_STEP),
+        Action::TapTempo => {
+            match model.tap_tempo.tap(Instant::now()) {
+                Some(bpm) => {
+                    model.analyzer.set_manual_bpm(bpm);
+                    model
+                        .renderer
+                        .show_notification(format!("Tap tempo: {:.1} BPM", bpm));
+                }
+                None => model.renderer.show_notification("Tap tempo: keep tapping".to_string()),
+            }
+        }
+        Action::ClearTapTempo => {
+            model.tap_tempo.reset();
+            model.analyzer.clear_manual_bpm();
+            model
+                .renderer
+                .show_notification("Tap tempo cleared: auto BPM".to_string());
+        }
+        Action::CycleBlendMode => {
+            let mode = model.feedback.borrow_mut().cycle_blend_mode();
+            model
+                .renderer
+                .show_notification(format!("Blend mode: {}", mode.name()));
+        }
+        Action::CycleMirror => {
+            let mode = model.feedback.borrow_mut().cycle_mirror();
+            model
+                .renderer
+                .show_notification(format!("Mirror: {}", mode.name()));
+        }
+        Action::SavePreset(slot) => save_preset_slot(model, slot),
+        Action::RecallPreset(slot) => recall_preset_slot(model, slot),
+        Action::ToggleFullscreen => {
+            let window = app.main_window();
+            let fullscreen = !window.is_fullscreen();
+            window.set_fullscreen(fullscreen);
+            window.set_cursor_visible(!fullscreen);
+            let status = if fullscreen { "fullscreen" } else { "windowed" };
+            model
+                .renderer
+                .show_notification(format!("Display: {}", status));
+        }
+        Action::Screenshot => save_screenshot(app, model),
+        Action::ToggleSafeMode => {
+            let on = model.feedback.borrow_mut().toggle_safe_mode();
+            let status = if on { "ON" } else { "OFF" };
+            model
+                .renderer
+                .show_notification(format!("Safe mode: {}", status));
+        }
+        Action::ToggleAberration => {
+            let on = model.feedback.borrow_mut().toggle_chromatic_aberration();
+            let status = if on { "ON" } else { "OFF" };
+            model
+                .renderer
+                .show_notification(format!("Chromatic aberration: {}", status));
+        }
     }
 }
 
-fn mouse_pressed(_app: &App, model: &mut Model, button: MouseButton) {
+/// Feedback texture UV (0-1, y down) under a window point
+fn point_to_uv(bounds: Rect, point: Point2) -> [f32; 2] {
+    [
+        (point.x - bounds.left()) / bounds.w(),
+        (bounds.top() - point.y) / bounds.h(),
+    ]
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    // Outside the picker the mouse steers the zoom: left moves the focal point, right centers it
     if !model.viz_picker.active {
+        let mut feedback = model.feedback.borrow_mut();
+        match button {
+            MouseButton::Left => {
+                feedback.set_focal_target(point_to_uv(app.window_rect(), app.mouse.position()))
+            }
+            MouseButton::Right => feedback.reset_focal(),
+            _ => {}
+        }
         return;
     }
 
@@ -425,6 +1329,14 @@ fn mouse_pressed(_app: &App, model: &mut Model, button: MouseButton) {
     }
 }
 
+/// Dragging with the left button held drags the zoom focal point along
+fn mouse_moved(app: &App, model: &mut Model, position: Point2) {
+    if !model.viz_picker.active && app.mouse.buttons.left().is_down() {
+        let uv = point_to_uv(app.window_rect(), position);
+        model.feedback.borrow_mut().set_focal_target(uv);
+    }
+}
+
 fn mouse_wheel(_app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
     // Close help when scrolling
     model.help_overlay.hide();
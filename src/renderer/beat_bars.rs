@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::{AudioAnalysis, NUM_BANDS};
+use crate::audio::{AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgb;
 
 /// Number of scanlines
 const NUM_SCANLINES: usize = if cfg!(debug_assertions) { 30 } else { 60 };
@@ -40,11 +42,15 @@ pub struct BeatBars {
     /// Frame counter
     frame_count: u32,
     /// Band values from analyzer (no additional smoothing needed)
-    bands: [f32; NUM_BANDS],
+    bands: Vec<f32>,
     /// Head switch noise (bottom of frame distortion)
     head_switch_intensity: f32,
     /// Color shift amount
     color_shift: f32,
+    /// Glitch timing, seeded by `reset`
+    rng: StdRng,
+    /// Seeds the noise `draw` scatters, rolled once per update
+    noise_seed: u64,
 }
 
 impl Default for BeatBars {
@@ -60,9 +66,11 @@ impl Default for BeatBars {
             treble: 0.0,
             energy: 0.0,
             frame_count: 0,
-            bands: [0.0; NUM_BANDS],
+            bands: vec![0.0; DEFAULT_NUM_BANDS],
             head_switch_intensity: 0.0,
             color_shift: 0.0,
+            rng: StdRng::from_os_rng(),
+            noise_seed: 0,
         }
     }
 }
@@ -75,37 +83,15 @@ impl BeatBars {
         let sat = saturation * 0.7; // Washed out look
         let val = value * 0.9 + 0.1; // Slightly lifted blacks
 
-        Self::hsv_to_rgb(hue, sat, val)
-    }
-
-    fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        (r1 + m, g1 + m, b1 + m)
+        hsv_to_rgb(hue, sat, val)
     }
 }
 
 impl Visualization for BeatBars {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
-        let mut rng = rand::rng();
+        self.noise_seed = self.rng.random();
+        let rng = &mut self.rng;
 
         // Smooth audio tracking
         self.bass = self.bass * 0.7 + analysis.bass * 0.3;
@@ -113,7 +99,7 @@ impl Visualization for BeatBars {
         self.energy = self.energy * 0.9 + analysis.energy * 0.1;
 
         // Use analyzer's already-smoothed bands directly (no additional smoothing needed)
-        self.bands = analysis.bands;
+        self.bands.clone_from(&analysis.bands);
 
         // Tracking error triggered by bass hits
         if analysis.bass > 0.6 && rng.random::<f32>() < 0.3 {
@@ -156,15 +142,16 @@ impl Visualization for BeatBars {
         let h = bounds.h();
         let left = bounds.left();
         let bottom = bounds.bottom();
-        let mut rng = rand::rng();
+        let mut rng = StdRng::seed_from_u64(self.noise_seed);
 
         // Draw frequency bars with VHS color palette
         let bar_width = w / NUM_BARS as f32;
         for i in 0..NUM_BARS {
             // Map bar index to band with interpolation
-            let band_pos = (i as f32 / NUM_BARS as f32) * (NUM_BANDS - 1) as f32;
+            let last_band = self.bands.len().saturating_sub(1);
+            let band_pos = (i as f32 / NUM_BARS as f32) * last_band as f32;
             let low_band = band_pos as usize;
-            let high_band = (low_band + 1).min(NUM_BANDS - 1);
+            let high_band = (low_band + 1).min(last_band);
             let t = band_pos - low_band as f32;
             let magnitude =
                 self.bands[low_band] * (1.0 - t) + self.bands[high_band] * t;
@@ -307,7 +294,7 @@ impl Visualization for BeatBars {
         if self.bass > 0.7 && rng.random::<f32>() < 0.1 {
             let flash_alpha = (self.bass - 0.7) * 0.3;
             let hue = rng.random_range(0.0..360.0);
-            let (r, g, b) = Self::hsv_to_rgb(hue, 0.3, 0.9);
+            let (r, g, b) = hsv_to_rgb(hue, 0.3, 0.9);
 
             draw.rect().xy(bounds.xy()).wh(bounds.wh()).color(srgba(
                 (r * 255.0) as u8,
@@ -317,4 +304,8 @@ impl Visualization for BeatBars {
             ));
         }
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.rng = rng;
+    }
 }
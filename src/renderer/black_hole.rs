@@ -5,9 +5,10 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
 
 /// Number of particles in the accretion disk
 const NUM_PARTICLES: usize = if cfg!(debug_assertions) { 200 } else { 500 };
@@ -65,11 +66,19 @@ pub struct BlackHole {
     frame_count: u32,
     /// Hue shift for disk colors
     hue_shift: f32,
+    /// Disk layout and respawns, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for BlackHole {
     fn default() -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(StdRng::from_os_rng())
+    }
+}
+
+impl BlackHole {
+    /// Fresh disk and star field laid out from `rng`
+    fn with_rng(mut rng: StdRng) -> Self {
 
         // Initialize particles in disk
         let particles: Vec<Particle> = (0..NUM_PARTICLES)
@@ -109,11 +118,10 @@ impl Default for BlackHole {
             energy: 0.0,
             frame_count: 0,
             hue_shift: 0.0,
+            rng,
         }
     }
-}
 
-impl BlackHole {
     /// Convert temperature (0-1) to blackbody-ish color
     fn temperature_to_color(&self, temp: f32, brightness: f32) -> Srgba<u8> {
         // Hot (1.0) = white/blue, Cool (0.0) = red/orange
@@ -173,7 +181,7 @@ impl BlackHole {
 impl Visualization for BlackHole {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         // Smooth audio tracking
         self.bass = self.bass * 0.7 + analysis.bass * 0.3;
@@ -228,7 +236,8 @@ impl Visualization for BlackHole {
 
             // Size variation with audio
             let band_idx = ((particle.angle / std::f32::consts::TAU) * 8.0) as usize % 8;
-            particle.size = 1.0 + analysis.bands_normalized[band_idx] * 3.0;
+            particle.size =
+                1.0 + band_group(&analysis.bands_normalized, band_idx, DEFAULT_NUM_BANDS) * 3.0;
         }
 
         // Update star twinkle
@@ -379,4 +388,8 @@ impl Visualization for BlackHole {
                 .stroke(srgba(200, 180, 255, (alpha * 255.0) as u8));
         }
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        *self = Self::with_rng(rng);
+    }
 }
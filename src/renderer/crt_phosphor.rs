@@ -5,8 +5,10 @@
 
 use super::Visualization;
 use nannou::prelude::*;
+use rand::rngs::StdRng;
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgb;
 
 /// Number of points in the waveform
 const WAVEFORM_POINTS: usize = if cfg!(debug_assertions) { 128 } else { 256 };
@@ -69,29 +71,6 @@ impl Default for CrtPhosphor {
 }
 
 impl CrtPhosphor {
-    fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        (r1 + m, g1 + m, b1 + m)
-    }
-
     /// Get phosphor color with proper CRT phosphor characteristics
     fn phosphor_color(&self, brightness: f32, age: f32) -> Srgba<u8> {
         // Phosphor color shifts slightly as it decays (green -> yellow-green for P31)
@@ -104,7 +83,7 @@ impl CrtPhosphor {
         // Value based on brightness and decay
         let value = brightness * (1.0 - age * 0.5);
 
-        let (r, g, b) = Self::hsv_to_rgb(hue, saturation, value);
+        let (r, g, b) = hsv_to_rgb(hue, saturation, value);
 
         // Alpha for bloom blending
         let alpha = brightness * (1.0 - age * 0.7) * 0.8;
@@ -193,7 +172,8 @@ impl Visualization for CrtPhosphor {
 
                 // Modulate with audio bands
                 let band_idx = (t * 7.0) as usize;
-                let modulation = analysis.bands_normalized[band_idx.min(7)];
+                let modulation =
+                    band_group(&analysis.bands_normalized, band_idx, DEFAULT_NUM_BANDS);
 
                 self.current_waveform.push(TrailPoint {
                     x: x * (0.7 + modulation * 0.3),
@@ -207,7 +187,8 @@ impl Visualization for CrtPhosphor {
 
                 // Y is audio waveform (synthesized from bands)
                 let mut y = 0.0;
-                for (band_idx, &band) in analysis.bands_normalized.iter().enumerate() {
+                for band_idx in 0..DEFAULT_NUM_BANDS {
+                    let band = band_group(&analysis.bands_normalized, band_idx, DEFAULT_NUM_BANDS);
                     let freq = (band_idx + 1) as f32;
                     let phase = self.sweep_phase * freq;
                     y += band * (t * std::f32::consts::TAU * freq + phase).sin() * 0.3;
@@ -344,4 +325,11 @@ impl Visualization for CrtPhosphor {
                 .stroke(srgba(0, 0, 0, (alpha * 255.0) as u8));
         }
     }
+
+    fn reset(&mut self, _rng: StdRng) {
+        for trail in &mut self.waveform_history {
+            trail.clear();
+        }
+        self.current_waveform.clear();
+    }
 }
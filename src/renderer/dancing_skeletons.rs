@@ -1,10 +1,10 @@
 //! Dancing skeletons visualization.
 //!
 //! Animated skeletons traverse the screen diagonally with the following behaviors:
-//! - Maximum of 6 skeletons on screen at any time
+//! - Maximum of 6 skeletons on screen by default (configurable, can rise with energy)
 //! - Spawn at screen edges and move in random diagonal directions
 //! - Removed when they exit the viewport
-//! - Random scales (0.6-2.5x) for variety in size
+//! - Random scales (0.6-2.5x by default) for variety in size
 //! - Random rotation (-30° to +30°) for tilted poses
 //! - Facial features: two eyes, upside-down heart nose, optional smile (50% chance)
 //! - Bone color assigned based on dominant frequency band at spawn time:
@@ -17,16 +17,17 @@
 //!   - Dougie: Front view with side-to-side sway
 //! - Dance style is randomly assigned at spawn
 //! - Animation speed is affected by energy_diff (higher energy = faster dancing)
-//! - Hue-shifted outlines (180° shift) create complementary color pairs
+//! - Hue-shifted outlines (180° shift by default) create complementary color pairs
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
-use crate::utils::get_crossing_path;
+use crate::audio::{AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::{Config, SkeletonConfig};
+use crate::utils::{get_crossing_path, shift_hue};
 
-const MAX_SKELETONS: usize = 6;
 const SPAWN_AREA_WIDTH: f32 = 800.0;
 const SPAWN_AREA_HEIGHT: f32 = 600.0;
 const BASE_EDGE_OFFSET: f32 = 20.0;
@@ -48,6 +49,9 @@ struct Skeleton {
     animation_phase: f32,
     has_smile: bool,
     bone_color: Rgb<u8>,
+    face_color: Rgb<u8>,
+    /// Outline hue offset from the bone color (degrees)
+    outline_hue_shift: f32,
 }
 
 impl Skeleton {
@@ -58,10 +62,10 @@ impl Skeleton {
         dominant_band: usize,
         rotation: f32,
         dance_style: DanceStyle,
+        config: &SkeletonConfig,
     ) -> Self {
-        let mut rng = rand::rng();
-        let has_smile = rng.random();
         let bone_color = Self::band_to_color(dominant_band);
+        let [face_r, face_g, face_b] = config.face_color();
 
         Self {
             position,
@@ -69,9 +73,11 @@ impl Skeleton {
             scale,
             rotation,
             dance_style,
-            animation_phase: rng.random_range(0.0..std::f32::consts::TAU),
-            has_smile,
+            animation_phase: 0.0,
+            has_smile: false,
             bone_color,
+            face_color: rgb(face_r, face_g, face_b),
+            outline_hue_shift: config.outline_hue_shift(),
         }
     }
 
@@ -89,61 +95,8 @@ impl Skeleton {
         }
     }
 
-    fn shift_hue(color: Rgb<u8>, shift: f32) -> Rgb<u8> {
-        let r = color.red as f32 / 255.0;
-        let g = color.green as f32 / 255.0;
-        let b = color.blue as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let mut h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        if h < 0.0 {
-            h += 360.0;
-        }
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        h = (h + shift * 360.0) % 360.0;
-
-        let c = v * s;
-        let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
-        let m = v - c;
-
-        let (r_prime, g_prime, b_prime) = if h < 60.0 {
-            (c, x, 0.0)
-        } else if h < 120.0 {
-            (x, c, 0.0)
-        } else if h < 180.0 {
-            (0.0, c, x)
-        } else if h < 240.0 {
-            (0.0, x, c)
-        } else if h < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        rgb(
-            ((r_prime + m) * 255.0) as u8,
-            ((g_prime + m) * 255.0) as u8,
-            ((b_prime + m) * 255.0) as u8,
-        )
-    }
-
     fn get_outline_color(&self) -> Rgb<u8> {
-        Self::shift_hue(self.bone_color, 0.5)
+        shift_hue(self.bone_color, self.outline_hue_shift)
     }
 
     fn update(&mut self, analysis: &AudioAnalysis) {
@@ -502,12 +455,12 @@ impl Skeleton {
         draw.ellipse()
             .x_y(x - eye_spacing, head_y + 2.0 * scale)
             .radius(eye_size)
-            .color(BLACK);
+            .color(self.face_color);
 
         draw.ellipse()
             .x_y(x + eye_spacing, head_y + 2.0 * scale)
             .radius(eye_size)
-            .color(BLACK);
+            .color(self.face_color);
 
         self.draw_upside_down_heart_nose(draw, x, head_y, scale);
 
@@ -535,9 +488,9 @@ impl Skeleton {
         draw.polyline()
             .weight(2.0 * scale)
             .points(points.clone())
-            .color(BLACK);
+            .color(self.face_color);
 
-        draw.polygon().points(points).color(BLACK);
+        draw.polygon().points(points).color(self.face_color);
     }
 
     fn draw_smile(&self, draw: &Draw, x: f32, head_y: f32, scale: f32) {
@@ -558,30 +511,36 @@ impl Skeleton {
         draw.polyline()
             .weight(1.5 * scale)
             .points(points)
-            .color(BLACK);
+            .color(self.face_color);
     }
 }
 
 pub struct DancingSkeletons {
     skeletons: Vec<Skeleton>,
+    config: SkeletonConfig,
+    /// Spawns and their looks, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for DancingSkeletons {
     fn default() -> Self {
         Self {
             skeletons: Vec::new(),
+            config: Config::load().skeletons(),
+            rng: StdRng::from_os_rng(),
         }
     }
 }
 
 impl DancingSkeletons {
     fn try_spawn_skeleton(&mut self, analysis: &AudioAnalysis) {
-        if self.skeletons.len() >= MAX_SKELETONS {
+        if self.skeletons.len() >= self.config.cap_for_energy(analysis.energy) {
             return;
         }
 
-        let mut rng = rand::rng();
-        let scale = rng.random_range(0.6..2.5);
+        let rng = &mut self.rng;
+        let (scale_min, scale_max) = self.config.scale_range();
+        let scale = rng.random_range(scale_min..scale_max);
         let skeleton_offset = BASE_EDGE_OFFSET + (SKELETON_HEIGHT_FACTOR * scale);
 
         let dance_style = match rng.random_range(0..3) {
@@ -591,7 +550,7 @@ impl DancingSkeletons {
         };
 
         let (start_pos, end_pos) =
-            get_crossing_path(SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT, skeleton_offset);
+            get_crossing_path(rng, SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT, skeleton_offset);
         let rotation = rng.random_range(-30.0_f32..30.0_f32).to_radians();
 
         let crossing_distance = start_pos.distance(end_pos);
@@ -599,22 +558,28 @@ impl DancingSkeletons {
         let speed = crossing_distance / crossing_frames;
         let velocity = (end_pos - start_pos).normalize() * speed;
 
+        // Bone colors follow the default band split, whatever num_bands is
         let dominant_band = analysis
             .bands
             .iter()
             .enumerate()
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(idx, _)| idx)
+            .map(|(idx, _)| idx * DEFAULT_NUM_BANDS / analysis.bands.len())
             .unwrap_or(0);
 
-        let skeleton = Skeleton::new(
-            start_pos,
-            velocity,
-            scale,
-            dominant_band,
-            rotation,
-            dance_style,
-        );
+        let skeleton = Skeleton {
+            animation_phase: rng.random_range(0.0..std::f32::consts::TAU),
+            has_smile: rng.random(),
+            ..Skeleton::new(
+                start_pos,
+                velocity,
+                scale,
+                dominant_band,
+                rotation,
+                dance_style,
+                &self.config,
+            )
+        };
         self.skeletons.push(skeleton);
     }
 }
@@ -628,8 +593,10 @@ impl Visualization for DancingSkeletons {
         let bounds = Rect::from_w_h(SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT);
         self.skeletons.retain(|s| s.is_in_bounds(bounds));
 
-        let mut rng = rand::rng();
-        if self.skeletons.len() < MAX_SKELETONS && rng.random::<f32>() < 0.05 {
+        // Busier tracks spawn more often and allow more dancers (up to the configured cap)
+        let cap = self.config.cap_for_energy(analysis.energy);
+        let chance = self.config.spawn_chance_for_energy(analysis.energy);
+        if self.skeletons.len() < cap && self.rng.random::<f32>() < chance {
             self.try_spawn_skeleton(analysis);
         }
     }
@@ -639,4 +606,26 @@ impl Visualization for DancingSkeletons {
             skeleton.draw(draw);
         }
     }
+
+    fn warm_up(&mut self, analysis: &AudioAnalysis, frames: u32) {
+        // Fill up to the cap at once, each newcomer already partway along its path
+        let existing = self.skeletons.len();
+        while self.skeletons.len() < self.config.cap_for_energy(analysis.energy) {
+            self.try_spawn_skeleton(analysis);
+        }
+
+        for skeleton in &mut self.skeletons[existing..] {
+            for _ in 0..self.rng.random_range(0..frames.max(1)) {
+                skeleton.update(analysis);
+            }
+        }
+
+        let bounds = Rect::from_w_h(SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT);
+        self.skeletons.retain(|s| s.is_in_bounds(bounds));
+    }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.skeletons.clear();
+        self.rng = rng;
+    }
 }
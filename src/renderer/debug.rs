@@ -18,6 +18,8 @@ pub struct DebugViz {
     display_fps: f32,
     /// The debug.rhai script visualization (RefCell for interior mutability in draw)
     debug_script: RefCell<Option<ScriptedVisualization>>,
+    /// Configured beat clock latency compensation, shown next to the beat phase
+    pub latency_compensation_ms: f32,
 }
 
 impl DebugViz {
@@ -40,6 +42,7 @@ impl DebugViz {
             last_frame_time: Instant::now(),
             display_fps: 0.0,
             debug_script: RefCell::new(debug_script),
+            latency_compensation_ms: 0.0,
         }
     }
 
@@ -57,7 +60,12 @@ impl DebugViz {
         if let Some(ref mut script) = *self.debug_script.borrow_mut() {
             // Pass FPS to the script
             script.set_var("fps", self.display_fps as f64);
-            script.update(analysis, bounds, viz_info);
+            script.set_var(
+                "latency_compensation_ms",
+                self.latency_compensation_ms as f64,
+            );
+            // Beat events go to the user's scripts; the overlay shows the flags
+            script.update(analysis, &[], bounds, viz_info);
         }
     }
 
@@ -0,0 +1,208 @@
+//! Auto-brightness for the final output.
+//!
+//! Each frame the composited image is averaged into a 1x1 luminance probe
+//! and copied back to the CPU without blocking: the copy is only issued when
+//! the previous readback has landed, so measurements trail the picture by a
+//! frame or two. The brightness gain then drifts toward whatever would put
+//! the average luminance at the target, over a configurable number of seconds.
+
+use std::sync::mpsc;
+use std::time::Instant;
+
+use nannou::wgpu;
+
+use super::post::PostPass;
+
+/// Format of the probe: sRGB so the stored byte is perceptual luminance
+const PROBE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Taps per axis averaged by the probe shader
+const PROBE_GRID: f32 = 16.0;
+
+/// Gain limits so black or white-out scenes don't swing the output wildly
+const MIN_GAIN: f32 = 0.25;
+const MAX_GAIN: f32 = 3.0;
+
+/// Uniform buffer for the luminance probe
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ProbeUniforms {
+    grid: f32,
+    _padding: [f32; 3],
+}
+
+/// Measures output luminance and tracks the brightness gain that corrects it.
+pub(super) struct AutoBrightness {
+    probe_pass: PostPass,
+    probe_texture: wgpu::Texture,
+    probe_view: wgpu::TextureView,
+    readback: wgpu::Buffer,
+    /// Set when this frame's commands include a probe copy
+    copy_encoded: bool,
+    /// Outstanding readback, if one is in flight
+    pending: Option<mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    last_update: Instant,
+    /// Linear multiplier applied to the output
+    gain: f32,
+}
+
+impl AutoBrightness {
+    pub(super) fn new(device: &wgpu::Device) -> Self {
+        let probe_pass = PostPass::new(
+            device,
+            "Luma Probe Pass",
+            include_str!("../shaders/luma.wgsl"),
+            1,
+            std::mem::size_of::<ProbeUniforms>() as u64,
+            PROBE_FORMAT,
+        );
+        let probe_texture = wgpu::TextureBuilder::new()
+            .size([1, 1])
+            // A 1x1 size would otherwise be inferred as a 1D texture
+            .dimension(wgpu::TextureDimension::D2)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+            .sample_count(1)
+            .format(PROBE_FORMAT)
+            .build(device);
+        let probe_view = probe_texture.view().build();
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Luma Probe Readback"),
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            probe_pass,
+            probe_texture,
+            probe_view,
+            readback,
+            copy_encoded: false,
+            pending: None,
+            last_update: Instant::now(),
+            gain: 1.0,
+        }
+    }
+
+    /// Current linear brightness multiplier
+    pub(super) fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Drop any correction (used when auto-brightness is switched off)
+    pub(super) fn reset(&mut self) {
+        self.gain = 1.0;
+        self.last_update = Instant::now();
+    }
+
+    /// Collect a finished readback, if any, and move the gain toward the target.
+    ///
+    /// # Arguments
+    /// * `target` - desired average perceptual luminance (0-1)
+    /// * `rate_secs` - time constant of the adjustment
+    pub(super) fn poll(&mut self, device: &wgpu::Device, target: f32, rate_secs: f32) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        device.poll(wgpu::Maintain::Poll);
+        let Ok(result) = rx.try_recv() else {
+            return;
+        };
+        self.pending = None;
+        if result.is_err() {
+            return;
+        }
+
+        let measured = {
+            let mapped = self.readback.slice(..).get_mapped_range();
+            mapped[0] as f32 / 255.0
+        };
+        self.readback.unmap();
+
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+        let step = 1.0 - (-dt / rate_secs.max(0.1)).exp();
+        self.gain += (target_gain(measured, target) - self.gain) * step;
+    }
+
+    /// Average `input` into the probe and queue its copy, unless a readback is still in flight
+    pub(super) fn encode_probe(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        fullscreen_quad: &wgpu::Buffer,
+        sampler: &wgpu::Sampler,
+        input: &wgpu::TextureView,
+    ) {
+        if self.pending.is_some() {
+            return;
+        }
+
+        self.probe_pass.write_uniforms(
+            queue,
+            &ProbeUniforms {
+                grid: PROBE_GRID,
+                _padding: [0.0; 3],
+            },
+        );
+        self.probe_pass.encode(
+            device,
+            encoder,
+            fullscreen_quad,
+            sampler,
+            &[input],
+            &self.probe_view,
+        );
+        encoder.copy_texture_to_buffer(
+            self.probe_texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            self.probe_texture.extent(),
+        );
+        self.copy_encoded = true;
+    }
+
+    /// Start mapping the probe copy; call once the frame's commands are submitted
+    pub(super) fn after_submit(&mut self) {
+        if !self.copy_encoded {
+            return;
+        }
+        self.copy_encoded = false;
+
+        let (tx, rx) = mpsc::channel();
+        self.readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+        self.pending = Some(rx);
+    }
+}
+
+/// Linear gain that brings a measured perceptual luminance to `target`.
+/// Perceptual values are roughly linear^(1/2.2), so the ratio is raised back to 2.2.
+fn target_gain(measured: f32, target: f32) -> f32 {
+    (target / measured.max(0.01)).powf(2.2).clamp(MIN_GAIN, MAX_GAIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_gain_pulls_toward_target_within_limits() {
+        assert!((target_gain(0.35, 0.35) - 1.0).abs() < 1e-5);
+        assert!(target_gain(0.7, 0.35) < 1.0);
+        assert!(target_gain(0.2, 0.35) > 1.0);
+        assert_eq!(target_gain(0.0, 0.35), MAX_GAIN);
+        assert_eq!(target_gain(1.0, 0.05), MIN_GAIN);
+    }
+}
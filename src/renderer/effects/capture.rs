@@ -0,0 +1,144 @@
+//! Offscreen render target with CPU readback.
+//!
+//! Used when frames need to leave the GPU (headless streaming, recording and
+//! screenshots), so the feedback renderer can composite into a plain texture
+//! instead of a window.
+
+use nannou::wgpu;
+
+/// Format of captured frames: 8-bit RGBA, sRGB-encoded, no row padding
+pub const CAPTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// An RGBA render target that can be copied back to system memory.
+pub struct FrameCapture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    readback: wgpu::Buffer,
+    /// Bytes per row in the readback buffer (aligned to COPY_BYTES_PER_ROW_ALIGNMENT)
+    padded_row_bytes: u32,
+    size: [u32; 2],
+    format: wgpu::TextureFormat,
+}
+
+impl FrameCapture {
+    pub fn new(device: &wgpu::Device, size: [u32; 2]) -> Self {
+        Self::with_format(device, size, CAPTURE_FORMAT)
+    }
+
+    /// Capture target in another 4-byte RGBA or BGRA format (read back as RGBA all the same)
+    pub fn with_format(device: &wgpu::Device, size: [u32; 2], format: wgpu::TextureFormat) -> Self {
+        let texture = wgpu::TextureBuilder::new()
+            .size(size)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+            .sample_count(1)
+            .format(format)
+            .build(device);
+        let view = texture.view().build();
+
+        let row_bytes = size[0] * 4;
+        let padded_row_bytes = row_bytes + wgpu::compute_row_padding(row_bytes);
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Capture Readback"),
+            size: (padded_row_bytes * size[1]) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            texture,
+            view,
+            readback,
+            padded_row_bytes,
+            size,
+            format,
+        }
+    }
+
+    /// View to render into (single-sampled, `CAPTURE_FORMAT`)
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Copy the target back to the CPU as tightly packed RGBA rows, top row first.
+    ///
+    /// Blocks until the GPU has finished all submitted work, so call it after
+    /// the frame's commands have been submitted.
+    pub fn read_rgba(&self, device: &wgpu::Device, queue: &wgpu::Queue, out: &mut Vec<u8>) {
+        let [width, height] = self.size;
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Capture Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            self.texture.extent(),
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        out.clear();
+        if let Ok(Ok(())) = rx.recv() {
+            let mapped = slice.get_mapped_range();
+            unpad_rows_to_rgba(&mapped, self.padded_row_bytes, width, self.format, out);
+            drop(mapped);
+            self.readback.unmap();
+        }
+    }
+}
+
+/// Strip the row padding wgpu requires for buffer copies, appending tightly packed
+/// RGBA rows to `out`. BGRA formats get their red and blue channels swapped.
+fn unpad_rows_to_rgba(
+    padded: &[u8],
+    padded_row_bytes: u32,
+    width: u32,
+    format: wgpu::TextureFormat,
+    out: &mut Vec<u8>,
+) {
+    let row_bytes = (width * 4) as usize;
+    let bgra = matches!(
+        format,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+    out.reserve(row_bytes * (padded.len() / padded_row_bytes.max(1) as usize));
+    for row in padded.chunks(padded_row_bytes as usize) {
+        let start = out.len();
+        out.extend_from_slice(&row[..row_bytes]);
+        if bgra {
+            for pixel in out[start..].chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpad_rows_swaps_bgra() {
+        // Two 1-pixel rows, each padded to 8 bytes
+        let padded = [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+        let mut rgba = Vec::new();
+        unpad_rows_to_rgba(&padded, 8, 1, wgpu::TextureFormat::Rgba8UnormSrgb, &mut rgba);
+        assert_eq!(rgba, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        rgba.clear();
+        unpad_rows_to_rgba(&padded, 8, 1, wgpu::TextureFormat::Bgra8UnormSrgb, &mut rgba);
+        assert_eq!(rgba, [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+}
@@ -1,5 +1,10 @@
 //! Visual effects for post-processing and blending.
 
+mod brightness;
+pub mod capture;
+mod post;
+mod safe_mode;
 pub mod zoom;
 
+pub use capture::FrameCapture;
 pub use zoom::FeedbackRenderer;
@@ -0,0 +1,198 @@
+//! Photosensitivity safe mode for the final output.
+//!
+//! Caps how much the average luminance of the whole picture may change from
+//! one frame to the next, whatever is being drawn (built-in viz, overlays or
+//! scripts). The cap is a rate per second scaled by the frame time, so flashes
+//! per second stay the same at any refresh rate. Both the new frame and the last shown frame are averaged into
+//! 1x1 luminance probes on the GPU; when the jump exceeds the cap, the output
+//! is blended back toward the last shown frame. Everything stays on the GPU,
+//! so there is no readback latency.
+
+use nannou::wgpu;
+
+use super::post::PostPass;
+use super::zoom::FeedbackRenderer;
+
+/// Default maximum change in average linear luminance per second. A 10% swing
+/// then takes 1/6 s at any frame rate, keeping full-screen flashes under 3 per second.
+pub(super) const DEFAULT_MAX_LUMA_RATE: f32 = 0.6;
+
+/// Probes hold linear luminance, so a float format keeps small steps exact
+const PROBE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Taps per axis averaged by the probe shader
+const PROBE_GRID: f32 = 16.0;
+
+/// Uniform buffer for the luminance probes
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ProbeUniforms {
+    grid: f32,
+    _padding: [f32; 3],
+}
+
+/// Uniform buffer for the limiter pass
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LimitUniforms {
+    max_delta: f32,
+    has_previous: f32,
+    _padding: [f32; 2],
+}
+
+/// Limits frame-to-frame brightness changes of the output.
+pub(super) struct SafeMode {
+    probe_pass: PostPass,
+    limit_pass: PostPass,
+    /// Probe targets: [new frame, last shown frame]
+    luma_views: [wgpu::TextureView; 2],
+    /// Ping-pong of limited output frames; the last written one was shown
+    history_views: [wgpu::TextureView; 2],
+    write_idx: usize,
+    /// False until a frame has been shown since safe mode was (re)enabled
+    has_previous: bool,
+    /// Largest average luminance change allowed this frame (the per-second
+    /// rate times the frame time)
+    pub(super) max_delta: f32,
+    /// Output format (matches the post-processing targets)
+    format: wgpu::TextureFormat,
+}
+
+impl SafeMode {
+    pub(super) fn new(device: &wgpu::Device, size: [u32; 2], format: wgpu::TextureFormat) -> Self {
+        let probe_pass = PostPass::new(
+            device,
+            "Safe Mode Probe Pass",
+            include_str!("../shaders/luma.wgsl"),
+            1,
+            std::mem::size_of::<ProbeUniforms>() as u64,
+            PROBE_FORMAT,
+        );
+        let limit_pass = PostPass::new(
+            device,
+            "Safe Mode Limit Pass",
+            include_str!("../shaders/safe_limit.wgsl"),
+            4,
+            std::mem::size_of::<LimitUniforms>() as u64,
+            format,
+        );
+        let luma_views = [Self::create_probe(device), Self::create_probe(device)];
+
+        Self {
+            probe_pass,
+            limit_pass,
+            luma_views,
+            history_views: Self::create_history(device, size, format),
+            write_idx: 0,
+            has_previous: false,
+            max_delta: DEFAULT_MAX_LUMA_RATE / 60.0,
+            format,
+        }
+    }
+
+    fn create_probe(device: &wgpu::Device) -> wgpu::TextureView {
+        wgpu::TextureBuilder::new()
+            .size([1, 1])
+            // A 1x1 size would otherwise be inferred as a 1D texture
+            .dimension(wgpu::TextureDimension::D2)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(PROBE_FORMAT)
+            .build(device)
+            .view()
+            .build()
+    }
+
+    fn create_history(
+        device: &wgpu::Device,
+        size: [u32; 2],
+        format: wgpu::TextureFormat,
+    ) -> [wgpu::TextureView; 2] {
+        [
+            FeedbackRenderer::create_texture(device, size, format)
+                .view()
+                .build(),
+            FeedbackRenderer::create_texture(device, size, format)
+                .view()
+                .build(),
+        ]
+    }
+
+    /// Recreate the frame history for a new output size
+    pub(super) fn resize(&mut self, device: &wgpu::Device, size: [u32; 2]) {
+        self.history_views = Self::create_history(device, size, self.format);
+        self.has_previous = false;
+    }
+
+    /// Views `encode` can return (for finding the last output again)
+    pub(super) fn output_views(&self) -> &[wgpu::TextureView] {
+        &self.history_views
+    }
+
+    /// Forget the last shown frame (used when safe mode is switched off)
+    pub(super) fn reset(&mut self) {
+        self.has_previous = false;
+    }
+
+    /// Limit `input` against the last shown frame, returning the view to show
+    pub(super) fn encode(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        fullscreen_quad: &wgpu::Buffer,
+        sampler: &wgpu::Sampler,
+        input: &wgpu::TextureView,
+    ) -> &wgpu::TextureView {
+        let read_idx = 1 - self.write_idx;
+        let previous = &self.history_views[read_idx];
+
+        self.probe_pass.write_uniforms(
+            queue,
+            &ProbeUniforms {
+                grid: PROBE_GRID,
+                _padding: [0.0; 3],
+            },
+        );
+        self.probe_pass.encode(
+            device,
+            encoder,
+            fullscreen_quad,
+            sampler,
+            &[input],
+            &self.luma_views[0],
+        );
+        if self.has_previous {
+            self.probe_pass.encode(
+                device,
+                encoder,
+                fullscreen_quad,
+                sampler,
+                &[previous],
+                &self.luma_views[1],
+            );
+        }
+
+        self.limit_pass.write_uniforms(
+            queue,
+            &LimitUniforms {
+                max_delta: self.max_delta,
+                has_previous: if self.has_previous { 1.0 } else { 0.0 },
+                _padding: [0.0; 2],
+            },
+        );
+        self.limit_pass.encode(
+            device,
+            encoder,
+            fullscreen_quad,
+            sampler,
+            &[input, previous, &self.luma_views[0], &self.luma_views[1]],
+            &self.history_views[self.write_idx],
+        );
+
+        let shown = self.write_idx;
+        self.write_idx = read_idx;
+        self.has_previous = true;
+        &self.history_views[shown]
+    }
+}
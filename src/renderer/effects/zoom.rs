@@ -5,27 +5,217 @@
 //! overlay visualizations on top.
 
 use nannou::prelude::*;
+use nannou::image;
 use nannou::wgpu;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 
-const MAX_OVERLAYS: usize = 9;
+use super::brightness::AutoBrightness;
+use super::capture::{FrameCapture, CAPTURE_FORMAT};
+use super::post::PostPass;
+use super::safe_mode::{SafeMode, DEFAULT_MAX_LUMA_RATE};
+use crate::audio::AudioAnalysis;
+use crate::utils::{DisplayConfig, FeedbackConfig};
+
+/// Zoom per frame at full controller scrub
+const MAX_ZOOM_SCRUB: f32 = 0.02;
+
+/// Per-frame easing of the zoom focal point toward its target (~0.5s to settle at 60fps)
+const FOCAL_SMOOTHING: f32 = 0.08;
+
+/// Texture center, the default zoom focal point
+const FOCAL_CENTER: [f32; 2] = [0.5, 0.5];
+
+/// Lowest internal resolution relative to the window
+const MIN_RENDER_SCALE: f32 = 0.25;
+
+/// Frame time assumed until `follow_audio` has seen two frames
+const DEFAULT_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Longest frame time per-second rates are scaled by, so a hitch can't allow a big jump
+const MAX_FRAME_TIME: f32 = 0.1;
+
+/// Texture size for a window of `size` at `scale` (never zero)
+fn scaled_size(size: [u32; 2], scale: f32) -> [u32; 2] {
+    size.map(|s| ((s as f32 * scale).round() as u32).max(1))
+}
+
+/// Uniform buffer for per-overlay blend opacity and mode, and the shared burn strength
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct BurnUniforms {
+    overlay_alpha: f32,
+    burn_strength: f32,
+    mode: u32,
+    _padding: f32,
+}
+
+/// How an overlay is composited onto the layers below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Screen blend shaped by the burn strength (only highlights burn through when low)
+    #[default]
+    Burn,
+    /// Sum of both layers, clipped at white
+    Add,
+    /// Plain screen blend: brightens without clipping
+    Screen,
+    /// Darkens the layers below wherever the overlay is dark
+    Multiply,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 4] = [
+        BlendMode::Burn,
+        BlendMode::Add,
+        BlendMode::Screen,
+        BlendMode::Multiply,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BlendMode::Burn => "Burn",
+            BlendMode::Add => "Add",
+            BlendMode::Screen => "Screen",
+            BlendMode::Multiply => "Multiply",
+        }
+    }
+
+    pub fn next(self) -> BlendMode {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Mode index understood by the blend shader
+    fn shader_mode(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Mirror fold applied to the feedback image (primary and trails, before overlays)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MirrorMode {
+    #[default]
+    None,
+    /// Left half reflected onto the right
+    Horizontal,
+    /// Top half reflected onto the bottom
+    Vertical,
+    /// Top-left quarter reflected into all four corners
+    Quad,
+}
+
+impl MirrorMode {
+    pub const ALL: [MirrorMode; 4] = [
+        MirrorMode::None,
+        MirrorMode::Horizontal,
+        MirrorMode::Vertical,
+        MirrorMode::Quad,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MirrorMode::None => "Off",
+            MirrorMode::Horizontal => "Horizontal",
+            MirrorMode::Vertical => "Vertical",
+            MirrorMode::Quad => "Quad",
+        }
+    }
+
+    pub fn next(self) -> MirrorMode {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Mode index understood by the mirror shader
+    fn shader_mode(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Weakest burn strength (the shader curve degenerates at 0)
+const MIN_BURN_STRENGTH: f32 = 0.05;
+
+/// Radial symmetry modes cycled at runtime (1 = off)
+const SYMMETRY_MODES: [u32; 5] = [1, 2, 4, 6, 8];
 
 /// Vertex for fullscreen quad
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-struct FeedbackVertex {
+pub(super) struct FeedbackVertex {
     position: [f32; 2],
     tex_coords: [f32; 2],
 }
 
-/// Uniform buffer for fade/scale parameters
+/// Uniform buffer for fade/scale parameters (the scale is about `focal`, in UV)
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct Uniforms {
     fade: f32,
     scale: f32,
+    focal: [f32; 2],
+}
+
+/// Uniform buffer for the symmetry post-process
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SymmetryUniforms {
+    folds: f32,
+    aspect: f32,
     _padding: [f32; 2],
 }
 
+/// Uniform buffer for the crossfade between two primaries
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct CrossfadeUniforms {
+    progress: f32,
+    _padding: [f32; 3],
+}
+
+/// Uniform buffer for the mirror fold
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct MirrorUniforms {
+    mode: u32,
+    _padding: [u32; 3],
+}
+
+/// Uniform buffer for the chromatic aberration pass (offsets in UV units)
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct AberrationUniforms {
+    red_offset: [f32; 2],
+    blue_offset: [f32; 2],
+}
+
+/// Red/blue channel split in pixels at full bass
+const MAX_ABERRATION_PX: f32 = 6.0;
+
+/// Uniform buffer for the output grade pass
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradeUniforms {
+    brightness: f32,
+    temperature: f32,
+    /// Dither amplitude in 8-bit steps (0 = off)
+    dither: f32,
+    _padding: f32,
+}
+
+/// Spectral centroids at or below this read as fully warm
+const WARM_CENTROID_HZ: f32 = 1500.0;
+/// Spectral centroids at or above this read as fully cool
+const COOL_CENTROID_HZ: f32 = 8000.0;
+/// Per-frame smoothing of the temperature shift (~1 second to settle at 60fps)
+const TEMPERATURE_SMOOTHING: f32 = 0.03;
+
+/// Map a spectral centroid to a temperature in -1 (cool) ..= 1 (warm), log-scaled
+fn centroid_temperature(centroid_hz: f32) -> f32 {
+    let t = (centroid_hz.max(1.0) / WARM_CENTROID_HZ).ln()
+        / (COOL_CENTROID_HZ / WARM_CENTROID_HZ).ln();
+    1.0 - 2.0 * t.clamp(0.0, 1.0)
+}
+
 const FULLSCREEN_QUAD: [FeedbackVertex; 6] = [
     FeedbackVertex {
         position: [-1.0, -1.0],
@@ -53,6 +243,47 @@ const FULLSCREEN_QUAD: [FeedbackVertex; 6] = [
     },
 ];
 
+/// Reshapers copying the final output to a frame, by (source view, target
+/// format, target sample count). The output alternates between a few textures
+/// (feedback ping-pong, post-processing and safe mode targets), so one reshaper
+/// is kept per source instead of building a new one every frame.
+#[derive(Default)]
+struct ReshaperCache {
+    reshapers: HashMap<(wgpu::TextureViewId, wgpu::TextureFormat, u32), wgpu::TextureReshaper>,
+    /// Reshapers built so far
+    builds: usize,
+}
+
+impl ReshaperCache {
+    fn get(
+        &mut self,
+        device: &wgpu::Device,
+        source: &wgpu::TextureView,
+        sample_count: u32,
+        format: wgpu::TextureFormat,
+    ) -> &wgpu::TextureReshaper {
+        self.reshapers
+            .entry((source.id(), format, sample_count))
+            .or_insert_with(|| {
+                self.builds += 1;
+                // 8-bit formats are float filterable
+                wgpu::TextureReshaper::new(
+                    device,
+                    source,
+                    1,
+                    wgpu::TextureSampleType::Float { filterable: true },
+                    sample_count,
+                    format,
+                )
+            })
+    }
+
+    /// Forget all reshapers (their source textures are being replaced)
+    fn clear(&mut self) {
+        self.reshapers.clear();
+    }
+}
+
 /// Feedback renderer using ping-pong textures for trail effects.
 pub struct FeedbackRenderer {
     // Ping-pong textures
@@ -63,6 +294,12 @@ pub struct FeedbackRenderer {
     // For drawing visualizations to texture
     draw_renderer: nannou::draw::Renderer,
 
+    // Crossfade between primaries: the outgoing one's renderer and the mix pass
+    crossfade_draw_renderer: nannou::draw::Renderer,
+    crossfade_pass: PostPass,
+    /// Outgoing primary and progress for the next render (`crossfade_from`)
+    crossfade: Option<(nannou::Draw, f32)>,
+
     // For fade/scale pass
     fade_pipeline: wgpu::RenderPipeline,
     bind_group_layout: wgpu::BindGroupLayout,
@@ -79,16 +316,86 @@ pub struct FeedbackRenderer {
     // Burn blend pipeline
     burn_pipeline: wgpu::RenderPipeline,
     burn_bind_group_layout: wgpu::BindGroupLayout,
+    /// One opacity uniform per overlay slot (all blend passes share one submit)
+    burn_uniform_buffers: Vec<wgpu::Buffer>,
+
+    // For displaying result to screen (and the recording target)
+    output_reshapers: ReshaperCache,
 
-    // For displaying result to screen
-    reshaper: wgpu::TextureReshaper,
+    // Post-processing (output only, not fed back into trails)
+    post_textures: [wgpu::Texture; 2],
+    post_texture_views: [wgpu::TextureView; 2],
+    mirror_pass: PostPass,
+    symmetry_pass: PostPass,
+    aberration_pass: PostPass,
+    grade_pass: PostPass,
+    brightness: AutoBrightness,
+    limiter: SafeMode,
+    /// CPU-readable copy of the output, while recording (`set_recording`)
+    record_target: Option<FrameCapture>,
+    /// View holding the last rendered output, for `capture_png`
+    last_output: Option<wgpu::TextureViewId>,
 
     // Parameters
     pub fade: f32,
     pub scale: f32,
+    /// Zoom per frame at the peak of the in/out oscillation
+    pub base_zoom_amount: f32,
+    /// Extra zoom per frame at full bass
+    pub bass_zoom_gain: f32,
+    /// Period of the zoom in/out oscillation in seconds
+    pub oscillation_seconds: f32,
+    /// Manual zoom from a controller, -1 (out) to 1 (in), added on top of the oscillation
+    pub zoom_scrub: f32,
+    /// Point the zoom scales about, in texture UV (0-1, y down); eases toward `focal_target`
+    pub focal: [f32; 2],
+    focal_target: [f32; 2],
+    /// Mirror fold applied before overlays blend (so trails mirror too)
+    pub mirror: MirrorMode,
+    /// Radial symmetry folds applied to the output (1 = off)
+    pub symmetry: u32,
+    /// Split the output's red and blue channels (off by default)
+    pub chromatic_aberration: bool,
+    /// Aberration intensity (0-1), following the bass
+    pub aberration: f32,
+    /// When false, the previous frame is not faded back in (crisp, trail-free primary)
+    pub trails: bool,
+    /// Scale output brightness toward `brightness_target` (off by default)
+    pub auto_brightness: bool,
+    /// Desired average perceptual luminance of the output (0-1)
+    pub brightness_target: f32,
+    /// Seconds for auto-brightness to settle
+    pub brightness_rate_secs: f32,
+    /// Tint the output warm/cool from the spectral centroid (off by default)
+    pub color_temperature: bool,
+    /// Maximum tint applied at extreme centroids (0-0.5)
+    pub temperature_strength: f32,
+    /// Smoothed temperature shift, -1 (cool) to 1 (warm)
+    temperature: f32,
+    /// Ordered dither on the output to hide banding in dark gradients (off by default)
+    pub dither: bool,
+    /// Cap frame-to-frame brightness changes of the whole output (photosensitivity safeguard)
+    pub safe_mode: bool,
+    /// Largest average luminance change per second while safe mode is on
+    pub safe_max_luma_rate: f32,
+    /// Burn blend curve for overlays (1 = full screen blend, lower = only highlights burn)
+    burn_strength: f32,
+    /// Blend mode for overlays without a mode of their own
+    pub blend_mode: BlendMode,
 
-    // Texture size
+    // Zoom oscillation state
+    zoom_phase_offset: f32,
+    prev_energy: f32,
+
+    // Seconds between the last two `follow_audio` calls, for per-second rates
+    frame_time: f32,
+    last_time: Option<f32>,
+
+    // Texture size (the window size times `render_scale`) and format (matches the window)
     size: [u32; 2],
+    format: wgpu::TextureFormat,
+    /// Internal resolution relative to the window; below 1 the output is upscaled
+    render_scale: f32,
 }
 
 impl FeedbackRenderer {
@@ -100,27 +407,32 @@ impl FeedbackRenderer {
     /// * `size` - texture dimensions [width, height]
     /// * `window_sample_count` - MSAA sample count of the window
     /// * `window_format` - texture format of the window
+    /// * `max_overlays` - overlay layers to allocate (built-in and script overlays together)
     pub fn new(
         device: &wgpu::Device,
         _queue: &wgpu::Queue,
         size: [u32; 2],
         window_sample_count: u32,
         window_format: wgpu::TextureFormat,
+        max_overlays: usize,
     ) -> Self {
-        // Default parameters - can be tuned
-        let fade = 0.97; // 3% fade per frame
+        // Default parameters (overridden by `apply_feedback_config`)
+        let defaults = FeedbackConfig::default();
+        let fade = defaults.fade();
         let scale = 1.003; // Slight zoom out for spiral effect
 
         // Create two textures for ping-pong
         let textures = [
-            Self::create_texture(device, size),
-            Self::create_texture(device, size),
+            Self::create_texture(device, size, window_format),
+            Self::create_texture(device, size, window_format),
         ];
         let texture_views = [textures[0].view().build(), textures[1].view().build()];
 
         // Create draw renderer for rendering nannou Draw to texture
         let draw_renderer = nannou::draw::RendererBuilder::new()
             .build_from_texture_descriptor(device, textures[0].descriptor());
+        let crossfade_draw_renderer = nannou::draw::RendererBuilder::new()
+            .build_from_texture_descriptor(device, textures[0].descriptor());
 
         // Create sampler
         let sampler_desc = wgpu::SamplerBuilder::new()
@@ -134,7 +446,7 @@ impl FeedbackRenderer {
         let uniforms = Uniforms {
             fade,
             scale,
-            _padding: [0.0; 2],
+            focal: FOCAL_CENTER,
         };
         let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
             label: Some("Feedback Uniforms"),
@@ -203,74 +515,17 @@ impl FeedbackRenderer {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
-        // Load and create shader
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Feedback Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/feedback.wgsl").into()),
-        });
-
-        // Create pipeline layout
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Feedback Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout],
-            push_constant_ranges: &[],
-        });
-
-        // Create render pipeline
-        let fade_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Feedback Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: std::mem::size_of::<FeedbackVertex>() as wgpu::BufferAddress,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    attributes: &[
-                        wgpu::VertexAttribute {
-                            offset: 0,
-                            shader_location: 0,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                        wgpu::VertexAttribute {
-                            offset: 8,
-                            shader_location: 1,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                    ],
-                }],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    blend: None,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        });
+        let fade_pipeline = Self::create_fade_pipeline(device, &bind_group_layout, window_format);
 
-        // Create reshaper for final output (Bgra8UnormSrgb requires float filterable)
-        let reshaper = wgpu::TextureReshaper::new(
-            device,
-            &texture_views[0],
-            1,
-            wgpu::TextureSampleType::Float { filterable: true },
-            window_sample_count,
-            window_format,
-        );
+        // Window reshapers for the plain ping-pong output, built up front
+        let mut output_reshapers = ReshaperCache::default();
+        for view in &texture_views {
+            output_reshapers.get(device, view, window_sample_count, window_format);
+        }
 
         // Create overlay textures
-        let overlay_textures: Vec<wgpu::Texture> = (0..MAX_OVERLAYS)
-            .map(|_| Self::create_texture(device, size))
+        let overlay_textures: Vec<wgpu::Texture> = (0..max_overlays)
+            .map(|_| Self::create_texture(device, size, window_format))
             .collect();
         let overlay_texture_views: Vec<wgpu::TextureView> =
             overlay_textures.iter().map(|t| t.view().build()).collect();
@@ -282,12 +537,6 @@ impl FeedbackRenderer {
             })
             .collect();
 
-        // Create burn blend shader and pipeline
-        let burn_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Burn Blend Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/burn_blend.wgsl").into()),
-        });
-
         let burn_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Burn Blend Bind Group Layout"),
@@ -321,20 +570,185 @@ impl FeedbackRenderer {
                         ty: wgpu::BindingType::Sampler(wgpu_types::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    // Overlay opacity
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
-        let burn_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Burn Blend Pipeline Layout"),
-            bind_group_layouts: &[&burn_bind_group_layout],
+        let burn_uniform_buffers: Vec<wgpu::Buffer> = (0..max_overlays)
+            .map(|_| {
+                device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                    label: Some("Burn Blend Uniforms"),
+                    contents: bytemuck::cast_slice(&[BurnUniforms {
+                        overlay_alpha: 1.0,
+                        burn_strength: 1.0,
+                        mode: BlendMode::Burn.shader_mode(),
+                        _padding: 0.0,
+                    }]),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                })
+            })
+            .collect();
+
+        let burn_pipeline =
+            Self::create_burn_pipeline(device, &burn_bind_group_layout, window_format);
+
+        // Post-processing targets and passes
+        let post_textures = [
+            Self::create_texture(device, size, window_format),
+            Self::create_texture(device, size, window_format),
+        ];
+        let post_texture_views = [
+            post_textures[0].view().build(),
+            post_textures[1].view().build(),
+        ];
+        let crossfade_pass = Self::create_crossfade_pass(device, window_format);
+        let mirror_pass = Self::create_mirror_pass(device, window_format);
+        let symmetry_pass = Self::create_symmetry_pass(device, window_format);
+        let aberration_pass = Self::create_aberration_pass(device, window_format);
+        let grade_pass = Self::create_grade_pass(device, window_format);
+        let brightness = AutoBrightness::new(device);
+        let limiter = SafeMode::new(device, size, window_format);
+
+        Self {
+            textures,
+            texture_views,
+            current_idx: 0,
+            draw_renderer,
+            crossfade_draw_renderer,
+            crossfade_pass,
+            crossfade: None,
+            fade_pipeline,
+            bind_group_layout,
+            bind_groups,
+            fullscreen_quad,
+            sampler,
+            uniform_buffer,
+            overlay_textures,
+            overlay_texture_views,
+            overlay_draw_renderers,
+            burn_pipeline,
+            burn_bind_group_layout,
+            burn_uniform_buffers,
+            output_reshapers,
+            post_textures,
+            post_texture_views,
+            mirror_pass,
+            symmetry_pass,
+            aberration_pass,
+            grade_pass,
+            brightness,
+            limiter,
+            record_target: None,
+            last_output: None,
+            fade,
+            scale,
+            base_zoom_amount: defaults.base_zoom_amount(),
+            bass_zoom_gain: defaults.bass_zoom_gain(),
+            oscillation_seconds: defaults.oscillation_seconds(),
+            zoom_scrub: 0.0,
+            focal: FOCAL_CENTER,
+            focal_target: FOCAL_CENTER,
+            mirror: MirrorMode::default(),
+            symmetry: 1,
+            chromatic_aberration: false,
+            aberration: 0.0,
+            trails: true,
+            auto_brightness: false,
+            brightness_target: 0.35,
+            brightness_rate_secs: 3.0,
+            color_temperature: false,
+            temperature_strength: 0.15,
+            dither: false,
+            safe_mode: false,
+            safe_max_luma_rate: DEFAULT_MAX_LUMA_RATE,
+            burn_strength: 1.0,
+            blend_mode: BlendMode::default(),
+            temperature: 0.0,
+            zoom_phase_offset: 0.0,
+            prev_energy: 0.0,
+            frame_time: DEFAULT_FRAME_TIME,
+            last_time: None,
+            size,
+            format: window_format,
+            render_scale: 1.0,
+        }
+    }
+
+    pub(super) fn create_texture(
+        device: &wgpu::Device,
+        size: [u32; 2],
+        format: wgpu::TextureFormat,
+    ) -> wgpu::Texture {
+        wgpu::TextureBuilder::new()
+            .size(size)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(format)
+            .build(device)
+    }
+
+    fn create_fade_pipeline(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        Self::create_quad_pipeline(
+            device,
+            "Feedback",
+            include_str!("../shaders/feedback.wgsl"),
+            bind_group_layout,
+            format,
+        )
+    }
+
+    fn create_burn_pipeline(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        Self::create_quad_pipeline(
+            device,
+            "Burn Blend",
+            include_str!("../shaders/burn_blend.wgsl"),
+            bind_group_layout,
+            format,
+        )
+    }
+
+    /// Fullscreen-quad pipeline writing unblended to a single `format` target
+    fn create_quad_pipeline(
+        device: &wgpu::Device,
+        label: &str,
+        shader_source: &str,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(&format!("{} Shader", label)),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(&format!("{} Pipeline Layout", label)),
+            bind_group_layouts: &[bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        let burn_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Burn Blend Pipeline"),
-            layout: Some(&burn_pipeline_layout),
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("{} Pipeline", label)),
+            layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &burn_shader,
+                module: &shader,
                 entry_point: "vs_main",
                 buffers: &[wgpu::VertexBufferLayout {
                     array_stride: std::mem::size_of::<FeedbackVertex>() as wgpu::BufferAddress,
@@ -354,10 +768,10 @@ impl FeedbackRenderer {
                 }],
             },
             fragment: Some(wgpu::FragmentState {
-                module: &burn_shader,
+                module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                    format,
                     blend: None,
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -369,38 +783,62 @@ impl FeedbackRenderer {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
-        });
+        })
+    }
 
-        Self {
-            textures,
-            texture_views,
-            current_idx: 0,
-            draw_renderer,
-            fade_pipeline,
-            bind_group_layout,
-            bind_groups,
-            fullscreen_quad,
-            sampler,
-            uniform_buffer,
-            overlay_textures,
-            overlay_texture_views,
-            overlay_draw_renderers,
-            burn_pipeline,
-            burn_bind_group_layout,
-            reshaper,
-            fade,
-            scale,
-            size,
-        }
+    fn create_crossfade_pass(device: &wgpu::Device, format: wgpu::TextureFormat) -> PostPass {
+        PostPass::new(
+            device,
+            "Crossfade Pass",
+            include_str!("../shaders/crossfade.wgsl"),
+            3,
+            std::mem::size_of::<CrossfadeUniforms>() as u64,
+            format,
+        )
     }
 
-    fn create_texture(device: &wgpu::Device, size: [u32; 2]) -> wgpu::Texture {
-        wgpu::TextureBuilder::new()
-            .size(size)
-            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
-            .sample_count(1)
-            .format(wgpu::TextureFormat::Bgra8UnormSrgb)
-            .build(device)
+    fn create_mirror_pass(device: &wgpu::Device, format: wgpu::TextureFormat) -> PostPass {
+        PostPass::new(
+            device,
+            "Mirror Pass",
+            include_str!("../shaders/mirror.wgsl"),
+            1,
+            std::mem::size_of::<MirrorUniforms>() as u64,
+            format,
+        )
+    }
+
+    fn create_symmetry_pass(device: &wgpu::Device, format: wgpu::TextureFormat) -> PostPass {
+        PostPass::new(
+            device,
+            "Symmetry Pass",
+            include_str!("../shaders/symmetry.wgsl"),
+            1,
+            std::mem::size_of::<SymmetryUniforms>() as u64,
+            format,
+        )
+    }
+
+    fn create_aberration_pass(device: &wgpu::Device, format: wgpu::TextureFormat) -> PostPass {
+        PostPass::new(
+            device,
+            "Aberration Pass",
+            include_str!("../shaders/aberration.wgsl"),
+            1,
+            std::mem::size_of::<AberrationUniforms>() as u64,
+            format,
+        )
+    }
+
+    fn create_grade_pass(device: &wgpu::Device, format: wgpu::TextureFormat) -> PostPass {
+        PostPass::new(
+            device,
+            "Grade Pass",
+            include_str!("../shaders/grade.wgsl"),
+            1,
+            std::mem::size_of::<GradeUniforms>() as u64,
+            format,
+        )
     }
 
     fn create_bind_group(
@@ -430,17 +868,17 @@ impl FeedbackRenderer {
         })
     }
 
-    /// Update uniform buffer with current fade/scale values
+    /// Update uniform buffer with current fade/scale/focal values
     fn update_uniforms(&self, queue: &wgpu::Queue) {
         let uniforms = Uniforms {
             fade: self.fade,
             scale: self.scale,
-            _padding: [0.0; 2],
+            focal: self.focal,
         };
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
     }
 
-    /// Handle window resize by recreating textures.
+    /// Handle window resize by recreating textures (and pipelines if the format changed).
     pub fn resize(
         &mut self,
         device: &wgpu::Device,
@@ -448,24 +886,46 @@ impl FeedbackRenderer {
         window_sample_count: u32,
         window_format: wgpu::TextureFormat,
     ) {
-        if size == self.size {
+        // Minimized windows report a zero size; keep the old textures until restored
+        if size[0] == 0 || size[1] == 0 {
+            return;
+        }
+        let size = scaled_size(size, self.render_scale);
+        if size == self.size && window_format == self.format {
             return;
         }
         self.size = size;
 
+        // Pipelines are tied to the texture format
+        if window_format != self.format {
+            self.format = window_format;
+            self.fade_pipeline =
+                Self::create_fade_pipeline(device, &self.bind_group_layout, window_format);
+            self.burn_pipeline =
+                Self::create_burn_pipeline(device, &self.burn_bind_group_layout, window_format);
+            self.crossfade_pass = Self::create_crossfade_pass(device, window_format);
+            self.mirror_pass = Self::create_mirror_pass(device, window_format);
+            self.symmetry_pass = Self::create_symmetry_pass(device, window_format);
+            self.aberration_pass = Self::create_aberration_pass(device, window_format);
+            self.grade_pass = Self::create_grade_pass(device, window_format);
+            self.limiter = SafeMode::new(device, size, window_format);
+        }
+
         // Recreate textures
         self.textures = [
-            Self::create_texture(device, size),
-            Self::create_texture(device, size),
+            Self::create_texture(device, size, window_format),
+            Self::create_texture(device, size, window_format),
         ];
         self.texture_views = [
             self.textures[0].view().build(),
             self.textures[1].view().build(),
         ];
 
-        // Recreate draw renderer
+        // Recreate draw renderers
         self.draw_renderer = nannou::draw::RendererBuilder::new()
             .build_from_texture_descriptor(device, self.textures[0].descriptor());
+        self.crossfade_draw_renderer = nannou::draw::RendererBuilder::new()
+            .build_from_texture_descriptor(device, self.textures[0].descriptor());
 
         // Recreate bind groups
         self.bind_groups = [
@@ -485,19 +945,16 @@ impl FeedbackRenderer {
             ),
         ];
 
-        // Recreate reshaper
-        self.reshaper = wgpu::TextureReshaper::new(
-            device,
-            &self.texture_views[0],
-            1,
-            wgpu::TextureSampleType::Float { filterable: true },
-            window_sample_count,
-            window_format,
-        );
+        // Output reshapers point at the old textures
+        self.output_reshapers.clear();
+        for view in &self.texture_views {
+            self.output_reshapers
+                .get(device, view, window_sample_count, window_format);
+        }
 
         // Recreate overlay textures
-        self.overlay_textures = (0..MAX_OVERLAYS)
-            .map(|_| Self::create_texture(device, size))
+        self.overlay_textures = (0..self.overlay_textures.len())
+            .map(|_| Self::create_texture(device, size, window_format))
             .collect();
         self.overlay_texture_views = self
             .overlay_textures
@@ -513,7 +970,211 @@ impl FeedbackRenderer {
             })
             .collect();
 
+        // Recreate post-processing targets
+        self.post_textures = [
+            Self::create_texture(device, size, window_format),
+            Self::create_texture(device, size, window_format),
+        ];
+        self.post_texture_views = [
+            self.post_textures[0].view().build(),
+            self.post_textures[1].view().build(),
+        ];
+        self.limiter.resize(device, size);
+        if self.record_target.is_some() {
+            self.record_target = Some(FrameCapture::new(device, size));
+        }
+
         self.current_idx = 0;
+        self.last_output = None;
+    }
+
+    /// Render at `scale` times the window size (0.25-1) and upscale the output,
+    /// trading sharpness for fill rate (eco mode). Rebuilds the textures
+    pub fn set_render_scale(
+        &mut self,
+        device: &wgpu::Device,
+        scale: f32,
+        window_size: [u32; 2],
+        window_sample_count: u32,
+    ) {
+        self.render_scale = scale.clamp(MIN_RENDER_SCALE, 1.0);
+        self.resize(device, window_size, window_sample_count, self.format);
+    }
+
+    /// Start or stop keeping a CPU-readable copy of every output frame
+    pub fn set_recording(&mut self, device: &wgpu::Device, enabled: bool) {
+        self.record_target = enabled.then(|| FrameCapture::new(device, self.size));
+    }
+
+    /// Output texture size [width, height] (the window size times the render scale)
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// Read the last rendered output back as tightly packed RGBA8 rows (top row
+    /// first, `size()` pixels). Empty unless recording is enabled.
+    ///
+    /// Blocks until the GPU has finished the frame.
+    pub fn read_back_rgba(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Vec<u8> {
+        let mut rgba = Vec::new();
+        if let Some(target) = &self.record_target {
+            target.read_rgba(device, queue, &mut rgba);
+        }
+        rgba
+    }
+
+    /// Save the last rendered output (post-processing included, UI layers not) as a PNG.
+    ///
+    /// Blocks until the GPU has copied the frame back.
+    pub fn capture_png(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &Path,
+    ) -> Result<(), String> {
+        let id = self.last_output.ok_or("nothing rendered yet")?;
+        let source = self
+            .texture_views
+            .iter()
+            .chain(&self.post_texture_views)
+            .chain(self.limiter.output_views())
+            .find(|view| view.id() == id)
+            .ok_or("nothing rendered yet")?;
+
+        let capture = FrameCapture::new(device, self.size);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Screenshot Encoder"),
+        });
+        self.output_reshapers
+            .get(device, source, 1, CAPTURE_FORMAT)
+            .encode_render_pass(capture.view(), &mut encoder);
+        queue.submit(Some(encoder.finish()));
+
+        let mut rgba = Vec::new();
+        capture.read_rgba(device, queue, &mut rgba);
+        let [width, height] = self.size;
+        if rgba.len() != (width * height * 4) as usize {
+            return Err("frame readback failed".to_string());
+        }
+        image::save_buffer(path, &rgba, width, height, image::ColorType::Rgba8)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Update zoom scale from the beat: a slow in/out oscillation (30s by default) that bass
+    /// amplifies, reversing direction on energy peaks.
+    ///
+    /// # Arguments
+    /// * `time` - seconds since start
+    /// * `analysis` - current frame's audio analysis
+    pub fn follow_audio(&mut self, time: f32, analysis: &AudioAnalysis) {
+        if let Some(last_time) = self.last_time {
+            self.frame_time = (time - last_time).clamp(0.0, MAX_FRAME_TIME);
+        }
+        self.last_time = Some(time);
+
+        // Detect energy peak and flip zoom direction
+        if analysis.energy >= 0.95 && self.prev_energy < 0.95 {
+            self.zoom_phase_offset += std::f32::consts::PI; // Add 180 degrees to reverse direction
+        }
+        self.prev_energy = analysis.energy;
+
+        // Sine wave oscillation: zooms in and out
+        let phase =
+            time * std::f32::consts::TAU / self.oscillation_seconds + self.zoom_phase_offset;
+        let direction = phase.sin(); // -1 to 1
+        // Base zoom follows sine wave
+        let base_offset = self.base_zoom_amount * direction;
+        // Bass amplifies the current direction (zoom in faster or out faster)
+        let bass_boost = analysis.bass * self.bass_zoom_gain * direction;
+        self.scale = 1.0 + base_offset + bass_boost + self.zoom_scrub * MAX_ZOOM_SCRUB;
+
+        // Glide the focal point, so moving it drifts the tunnel instead of jumping
+        for (focal, target) in self.focal.iter_mut().zip(self.focal_target) {
+            *focal += (target - *focal) * FOCAL_SMOOTHING;
+        }
+
+        // Drift the color temperature toward the current spectral balance
+        let target = centroid_temperature(analysis.spectral_centroid);
+        self.temperature += (target - self.temperature) * TEMPERATURE_SMOOTHING;
+
+        self.aberration = analysis.bass.clamp(0.0, 1.0);
+    }
+
+    /// Apply output settings from the config file
+    pub fn apply_display_config(&mut self, config: &DisplayConfig) {
+        self.auto_brightness = config.auto_brightness();
+        self.brightness_target = config.auto_brightness_target();
+        self.brightness_rate_secs = config.auto_brightness_rate_secs();
+        self.color_temperature = config.color_temperature();
+        self.temperature_strength = config.color_temperature_strength();
+        self.dither = config.dither();
+        self.chromatic_aberration = config.chromatic_aberration();
+        self.safe_mode = config.safe_mode();
+        self.safe_max_luma_rate = config.safe_max_luma_rate();
+        self.burn_strength = config.burn_strength().max(MIN_BURN_STRENGTH);
+    }
+
+    /// Apply trail and zoom settings from the `[feedback]` config table
+    pub fn apply_feedback_config(&mut self, config: &FeedbackConfig) {
+        self.fade = config.fade();
+        self.base_zoom_amount = config.base_zoom_amount();
+        self.bass_zoom_gain = config.bass_zoom_gain();
+        self.oscillation_seconds = config.oscillation_seconds();
+    }
+
+    /// Move the zoom focal point to `point` (texture UV, clamped to 0-1), gliding there
+    pub fn set_focal_target(&mut self, point: [f32; 2]) {
+        self.focal_target = point.map(|v| v.clamp(0.0, 1.0));
+    }
+
+    /// Zoom about the texture center again
+    pub fn reset_focal(&mut self) {
+        self.set_focal_target(FOCAL_CENTER);
+    }
+
+    /// Overlay layers allocated at construction
+    pub fn max_overlays(&self) -> usize {
+        self.overlay_textures.len()
+    }
+
+    /// Advance to the next radial symmetry mode, returning the new fold count
+    pub fn cycle_symmetry(&mut self) -> u32 {
+        let pos = SYMMETRY_MODES
+            .iter()
+            .position(|&m| m == self.symmetry)
+            .unwrap_or(0);
+        self.symmetry = SYMMETRY_MODES[(pos + 1) % SYMMETRY_MODES.len()];
+        self.symmetry
+    }
+
+    /// Advance to the next mirror mode, returning it
+    pub fn cycle_mirror(&mut self) -> MirrorMode {
+        self.mirror = self.mirror.next();
+        self.mirror
+    }
+
+    /// Advance to the next overlay blend mode, returning it
+    pub fn cycle_blend_mode(&mut self) -> BlendMode {
+        self.blend_mode = self.blend_mode.next();
+        self.blend_mode
+    }
+
+    /// Toggle the chromatic aberration pass, returning the new state
+    pub fn toggle_chromatic_aberration(&mut self) -> bool {
+        self.chromatic_aberration = !self.chromatic_aberration;
+        self.chromatic_aberration
+    }
+
+    /// Toggle photosensitivity safe mode, returning the new state
+    pub fn toggle_safe_mode(&mut self) -> bool {
+        self.safe_mode = !self.safe_mode;
+        self.safe_mode
+    }
+
+    /// Adjust the overlay burn strength by `delta`, returning the new value
+    pub fn nudge_burn_strength(&mut self, delta: f32) -> f32 {
+        self.burn_strength = (self.burn_strength + delta).clamp(MIN_BURN_STRENGTH, 1.0);
+        self.burn_strength
     }
 
     /// Create a bind group for burn blending two textures
@@ -522,6 +1183,7 @@ impl FeedbackRenderer {
         device: &wgpu::Device,
         base_view: &wgpu::TextureView,
         overlay_view: &wgpu::TextureView,
+        uniform_buffer: &wgpu::Buffer,
     ) -> wgpu::BindGroup {
         device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Burn Blend Bind Group"),
@@ -539,17 +1201,29 @@ impl FeedbackRenderer {
                     binding: 2,
                     resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
             ],
         })
     }
 
+    /// Crossfade from `outgoing` to the primary in the next render: the outgoing
+    /// drawing blends in at `1 - progress` and the primary at `progress`
+    pub fn crossfade_from(&mut self, outgoing: nannou::Draw, progress: f32) {
+        self.crossfade = Some((outgoing, progress));
+    }
+
     /// Render a frame with feedback effect and overlay burn blending.
     ///
     /// # Arguments
     /// * `device` - wgpu device
     /// * `queue` - wgpu queue
     /// * `primary_draw` - nannou Draw with primary visualization
-    /// * `overlay_draws` - nannou Draws with overlay visualizations (up to 3)
+    /// * `overlay_draws` - nannou Draws with overlay visualizations (extras past `max_overlays` are skipped)
+    /// * `overlay_alphas` - blend opacity per overlay (missing entries blend at 1.0)
+    /// * `overlay_modes` - blend mode per overlay (missing entries use `blend_mode`)
     /// * `frame_view` - texture view of the output frame
     /// * `frame_format` - format of the output frame
     /// * `frame_sample_count` - MSAA sample count of the output frame
@@ -559,6 +1233,8 @@ impl FeedbackRenderer {
         queue: &wgpu::Queue,
         primary_draw: &nannou::Draw,
         overlay_draws: &[&nannou::Draw],
+        overlay_alphas: &[f32],
+        overlay_modes: &[BlendMode],
         frame_view: &wgpu::TextureView,
         frame_format: wgpu::TextureFormat,
         frame_sample_count: u32,
@@ -566,14 +1242,26 @@ impl FeedbackRenderer {
         // Update uniforms in case fade/scale changed
         self.update_uniforms(queue);
 
+        // Pick up the latest luminance measurement (non-blocking)
+        if self.auto_brightness {
+            self.brightness
+                .poll(device, self.brightness_target, self.brightness_rate_secs);
+        } else {
+            self.brightness.reset();
+        }
+        if !self.safe_mode {
+            self.limiter.reset();
+        }
+
         let prev_idx = self.current_idx;
-        let curr_idx = 1 - prev_idx;
+        let mut curr_idx = 1 - prev_idx;
 
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Feedback Encoder"),
         });
 
         // Pass 1: Render previous frame with fade/scale to current texture
+        // (or just clear it when trails are disabled)
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Feedback Pass"),
@@ -588,22 +1276,107 @@ impl FeedbackRenderer {
                 depth_stencil_attachment: None,
             });
 
-            render_pass.set_pipeline(&self.fade_pipeline);
-            render_pass.set_bind_group(0, &self.bind_groups[prev_idx], &[]);
-            render_pass.set_vertex_buffer(0, self.fullscreen_quad.slice(..));
-            render_pass.draw(0..6, 0..1);
+            if self.trails {
+                render_pass.set_pipeline(&self.fade_pipeline);
+                render_pass.set_bind_group(0, &self.bind_groups[prev_idx], &[]);
+                render_pass.set_vertex_buffer(0, self.fullscreen_quad.slice(..));
+                render_pass.draw(0..6, 0..1);
+            }
         }
 
         // Pass 2: Draw current primary visualization on top
-        self.draw_renderer.render_to_texture(
-            device,
-            &mut encoder,
-            primary_draw,
-            &self.textures[curr_idx],
-        );
+        // Drawings are in window points; the render scale maps them onto the textures
+        if let Some((outgoing_draw, progress)) = self.crossfade.take() {
+            // Crossfading: each primary draws into its own transparent layer (the
+            // post-processing targets are free until pass 4); the layers mix by
+            // progress and go over the trails into the previous-frame texture
+            for view in &self.post_texture_views {
+                let _render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Crossfade Clear Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                });
+            }
+            self.draw_renderer.encode_render_pass(
+                device,
+                &mut encoder,
+                primary_draw,
+                self.render_scale,
+                self.size,
+                &self.post_texture_views[0],
+                None,
+            );
+            self.crossfade_draw_renderer.encode_render_pass(
+                device,
+                &mut encoder,
+                &outgoing_draw,
+                self.render_scale,
+                self.size,
+                &self.post_texture_views[1],
+                None,
+            );
+            self.crossfade_pass.write_uniforms(
+                queue,
+                &CrossfadeUniforms {
+                    progress: progress.clamp(0.0, 1.0),
+                    _padding: [0.0; 3],
+                },
+            );
+            self.crossfade_pass.encode(
+                device,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                &[
+                    &self.texture_views[curr_idx],
+                    &self.post_texture_views[0],
+                    &self.post_texture_views[1],
+                ],
+                &self.texture_views[1 - curr_idx],
+            );
+            curr_idx = 1 - curr_idx;
+        } else {
+            self.draw_renderer.encode_render_pass(
+                device,
+                &mut encoder,
+                primary_draw,
+                self.render_scale,
+                self.size,
+                &self.texture_views[curr_idx],
+                None,
+            );
+        }
+
+        // Mirror the primary and its trails into the other ping-pong texture,
+        // before overlays blend; the result feeds back, so trails stay mirrored
+        if self.mirror != MirrorMode::None {
+            self.mirror_pass.write_uniforms(
+                queue,
+                &MirrorUniforms {
+                    mode: self.mirror.shader_mode(),
+                    _padding: [0; 3],
+                },
+            );
+            self.mirror_pass.encode(
+                device,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                &[&self.texture_views[curr_idx]],
+                &self.texture_views[1 - curr_idx],
+            );
+            curr_idx = 1 - curr_idx;
+        }
 
         // Pass 3: Render each overlay and blend onto the result using ping-pong
-        let num_overlays = overlay_draws.len().min(MAX_OVERLAYS);
+        let num_overlays = overlay_draws.len().min(self.max_overlays());
         let mut read_idx = curr_idx;
         let mut write_idx = 1 - curr_idx;
 
@@ -624,18 +1397,37 @@ impl FeedbackRenderer {
                 });
             }
 
-            self.overlay_draw_renderers[i].render_to_texture(
+            self.overlay_draw_renderers[i].encode_render_pass(
                 device,
                 &mut encoder,
                 overlay_draws[i],
-                &self.overlay_textures[i],
+                self.render_scale,
+                self.size,
+                &self.overlay_texture_views[i],
+                None,
             );
 
             // Blend the overlay onto the current texture, output to the other texture
+            let burn_uniforms = BurnUniforms {
+                overlay_alpha: overlay_alphas.get(i).copied().unwrap_or(1.0).clamp(0.0, 1.0),
+                burn_strength: self.burn_strength,
+                mode: overlay_modes
+                    .get(i)
+                    .copied()
+                    .unwrap_or(self.blend_mode)
+                    .shader_mode(),
+                _padding: 0.0,
+            };
+            queue.write_buffer(
+                &self.burn_uniform_buffers[i],
+                0,
+                bytemuck::cast_slice(&[burn_uniforms]),
+            );
             let blend_bind_group = self.create_burn_bind_group(
                 device,
                 &self.texture_views[read_idx],
                 &self.overlay_texture_views[i],
+                &self.burn_uniform_buffers[i],
             );
 
             {
@@ -666,20 +1458,444 @@ impl FeedbackRenderer {
         // If no overlays, final result is still in curr_idx
         let final_idx = if num_overlays > 0 { read_idx } else { curr_idx };
 
-        // Pass 4: Copy final feedback result to frame
-        let reshaper = wgpu::TextureReshaper::new(
-            device,
-            &self.texture_views[final_idx],
-            1,
-            wgpu::TextureSampleType::Float { filterable: true },
-            frame_sample_count,
-            frame_format,
-        );
-        reshaper.encode_render_pass(frame_view, &mut encoder);
+        // Pass 4: Output-only post-processing (doesn't feed back into trails)
+        let mut output_view = &self.texture_views[final_idx];
+        let mut post_idx = 0;
+        if self.symmetry > 1 {
+            self.symmetry_pass.write_uniforms(
+                queue,
+                &SymmetryUniforms {
+                    folds: self.symmetry as f32,
+                    aspect: self.size[0] as f32 / self.size[1].max(1) as f32,
+                    _padding: [0.0; 2],
+                },
+            );
+            self.symmetry_pass.encode(
+                device,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                &[output_view],
+                &self.post_texture_views[post_idx],
+            );
+            output_view = &self.post_texture_views[post_idx];
+            post_idx = 1 - post_idx;
+        }
+
+        // Chromatic aberration: red and blue split apart with the bass
+        if self.chromatic_aberration {
+            let split_px = self.aberration * MAX_ABERRATION_PX;
+            let red_offset = [
+                split_px / self.size[0].max(1) as f32,
+                0.3 * split_px / self.size[1].max(1) as f32,
+            ];
+            self.aberration_pass.write_uniforms(
+                queue,
+                &AberrationUniforms {
+                    red_offset,
+                    blue_offset: [-red_offset[0], -red_offset[1]],
+                },
+            );
+            self.aberration_pass.encode(
+                device,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                &[output_view],
+                &self.post_texture_views[post_idx],
+            );
+            output_view = &self.post_texture_views[post_idx];
+            post_idx = 1 - post_idx;
+        }
+
+        // Auto-brightness: measure the ungraded image before grading
+        if self.auto_brightness {
+            self.brightness.encode_probe(
+                device,
+                queue,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                output_view,
+            );
+        }
+
+        // Grade: brightness gain, color temperature and dither
+        if self.auto_brightness || self.color_temperature || self.dither {
+            let temperature = if self.color_temperature {
+                self.temperature * self.temperature_strength
+            } else {
+                0.0
+            };
+            self.grade_pass.write_uniforms(
+                queue,
+                &GradeUniforms {
+                    brightness: self.brightness.gain(),
+                    temperature,
+                    dither: if self.dither { 1.0 } else { 0.0 },
+                    _padding: 0.0,
+                },
+            );
+            self.grade_pass.encode(
+                device,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                &[output_view],
+                &self.post_texture_views[post_idx],
+            );
+            output_view = &self.post_texture_views[post_idx];
+        }
+
+        // Safe mode: limit the brightness jump against the last shown frame, by
+        // how much this frame's share of a second allows
+        if self.safe_mode {
+            self.limiter.max_delta = self.safe_max_luma_rate * self.frame_time;
+            output_view = self.limiter.encode(
+                device,
+                queue,
+                &mut encoder,
+                &self.fullscreen_quad,
+                &self.sampler,
+                output_view,
+            );
+        }
+
+        // Pass 5: Copy final result to frame
+        self.output_reshapers
+            .get(device, output_view, frame_sample_count, frame_format)
+            .encode_render_pass(frame_view, &mut encoder);
+        if let Some(target) = &self.record_target {
+            self.output_reshapers
+                .get(device, output_view, 1, CAPTURE_FORMAT)
+                .encode_render_pass(target.view(), &mut encoder);
+        }
 
         queue.submit(Some(encoder.finish()));
+        self.brightness.after_submit();
+        self.last_output = Some(output_view.id());
 
         // Set current_idx for next frame's feedback (should read from final result)
         self.current_idx = final_idx;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GPU device to test against, or None when the machine has no adapter
+    fn test_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        let instance = wgpu::Instance::default();
+        let adapter = futures::executor::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+        )?;
+        futures::executor::block_on(
+            adapter.request_device(&wgpu::default_device_descriptor(), None),
+        )
+        .ok()
+    }
+
+    #[test]
+    fn test_feedback_config_sets_fade() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let config: crate::utils::Config = toml::from_str("[feedback]\nfade = 0.8\n").unwrap();
+
+        let mut feedback = FeedbackRenderer::new(
+            &device,
+            &queue,
+            [16, 16],
+            1,
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+            3,
+        );
+        feedback.apply_feedback_config(&config.feedback());
+        assert_eq!(feedback.fade, 0.8);
+        assert_eq!(feedback.oscillation_seconds, 30.0);
+    }
+
+    #[test]
+    fn test_focal_point_glides_to_clamped_target() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let mut feedback = FeedbackRenderer::new(
+            &device,
+            &queue,
+            [16, 16],
+            1,
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+            3,
+        );
+        assert_eq!(feedback.focal, FOCAL_CENTER, "centered by default");
+
+        feedback.set_focal_target([1.5, 0.25]);
+        assert_eq!(feedback.focal_target, [1.0, 0.25]);
+        feedback.follow_audio(0.0, &AudioAnalysis::default());
+        assert!(feedback.focal[0] > 0.5 && feedback.focal[0] < 0.6, "eases, doesn't jump");
+        for frame in 1..120 {
+            feedback.follow_audio(frame as f32 / 60.0, &AudioAnalysis::default());
+        }
+        assert!((feedback.focal[0] - 1.0).abs() < 1e-3 && (feedback.focal[1] - 0.25).abs() < 1e-3);
+
+        feedback.reset_focal();
+        assert_eq!(feedback.focal_target, FOCAL_CENTER);
+    }
+
+    #[test]
+    fn test_renders_with_window_format() {
+        // Uncaptured wgpu validation errors panic, so rendering cleanly is the check
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+
+        for format in [
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+        ] {
+            let mut feedback = FeedbackRenderer::new(&device, &queue, [32, 24], 1, format, 3);
+            // Exercise every pass that writes to the internal textures
+            feedback.mirror = MirrorMode::Quad;
+            feedback.symmetry = 2;
+            feedback.chromatic_aberration = true;
+            feedback.aberration = 1.0;
+            feedback.dither = true;
+            feedback.safe_mode = true;
+            feedback.resize(&device, [16, 16], 1, format);
+
+            let frame = FeedbackRenderer::create_texture(&device, [16, 16], format);
+            let frame_view = frame.view().build();
+            let draw = nannou::Draw::new();
+            draw.ellipse().w_h(8.0, 8.0).color(WHITE);
+            feedback.render_with_overlays(
+                &device,
+                &queue,
+                &draw,
+                &[&draw],
+                &[0.5],
+                &[BlendMode::Multiply],
+                &frame_view,
+                format,
+                1,
+            );
+            device.poll(wgpu::Maintain::Wait);
+        }
+    }
+
+    #[test]
+    fn test_allocates_requested_overlay_layers() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [16, 16], 1, format, 5);
+        let counts = |feedback: &FeedbackRenderer| {
+            [
+                feedback.overlay_textures.len(),
+                feedback.overlay_texture_views.len(),
+                feedback.overlay_draw_renderers.len(),
+                feedback.burn_uniform_buffers.len(),
+            ]
+        };
+        assert_eq!(feedback.max_overlays(), 5);
+        assert_eq!(counts(&feedback), [5; 4]);
+
+        // Resizing reallocates the same number of layers
+        feedback.resize(&device, [8, 8], 1, format);
+        assert_eq!(counts(&feedback), [5; 4]);
+
+        // Draws past the allocation are skipped rather than indexing out of bounds
+        let frame = FeedbackRenderer::create_texture(&device, [8, 8], format);
+        let frame_view = frame.view().build();
+        let draw = nannou::Draw::new();
+        let overlays = [&draw; 7];
+        feedback.render_with_overlays(&device, &queue, &draw, &overlays, &[], &[], &frame_view, format, 1);
+        device.poll(wgpu::Maintain::Wait);
+    }
+
+    #[test]
+    fn test_output_reshapers_reused_at_steady_size() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [16, 16], 1, format, 3);
+        let frame = FeedbackRenderer::create_texture(&device, [16, 16], format);
+        let frame_view = frame.view().build();
+        let draw = nannou::Draw::new();
+        let render = |feedback: &mut FeedbackRenderer| {
+            feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        };
+
+        // Both ping-pong textures are covered up front
+        for _ in 0..4 {
+            render(&mut feedback);
+        }
+        assert_eq!(feedback.output_reshapers.builds, 2);
+
+        // Post-processing adds its own source once, then frames reuse it
+        feedback.symmetry = 2;
+        for _ in 0..4 {
+            render(&mut feedback);
+        }
+        assert_eq!(feedback.output_reshapers.builds, 3);
+
+        // Same-size resizes keep the cache; real ones rebuild it
+        feedback.resize(&device, [16, 16], 1, format);
+        assert_eq!(feedback.output_reshapers.builds, 3);
+        feedback.resize(&device, [8, 8], 1, format);
+        assert_eq!(feedback.output_reshapers.builds, 5);
+    }
+
+    #[test]
+    fn test_render_scale_keeps_drawings_in_window_points() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [40, 20], 1, format, 3);
+        feedback.set_render_scale(&device, 0.5, [40, 20], 1);
+        assert_eq!(feedback.size(), [20, 10]);
+        // Window resizes keep the scale
+        feedback.resize(&device, [80, 40], 1, format);
+        assert_eq!(feedback.size(), [40, 20]);
+        feedback.resize(&device, [40, 20], 1, format);
+
+        // The middle half of a 40-point-wide window
+        let frame = FeedbackRenderer::create_texture(&device, [40, 20], format);
+        let frame_view = frame.view().build();
+        let draw = nannou::Draw::new();
+        draw.rect().w_h(20.0, 40.0).color(WHITE);
+        feedback.set_recording(&device, true);
+        feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        let rgba = feedback.read_back_rgba(&device, &queue);
+        assert_eq!(rgba.len(), 20 * 10 * 4);
+        let row: Vec<u8> = rgba[..20 * 4].chunks(4).map(|pixel| pixel[0]).collect();
+        assert!(row[..4].iter().all(|&v| v < 50), "{:?}", row);
+        assert!(row[6..14].iter().all(|&v| v > 200), "{:?}", row);
+        assert!(row[16..].iter().all(|&v| v < 50), "{:?}", row);
+    }
+
+    #[test]
+    fn test_read_back_recorded_output() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [20, 10], 1, format, 3);
+        let frame = FeedbackRenderer::create_texture(&device, [20, 10], format);
+        let frame_view = frame.view().build();
+        let draw = nannou::Draw::new();
+        draw.rect().w_h(100.0, 100.0).color(WHITE);
+
+        feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        assert!(feedback.read_back_rgba(&device, &queue).is_empty());
+
+        // 20px rows (80 bytes) need padding in the staging buffer, which must not leak out
+        feedback.set_recording(&device, true);
+        feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        let rgba = feedback.read_back_rgba(&device, &queue);
+        assert_eq!(rgba.len(), 20 * 10 * 4);
+        assert!(rgba[0] > 200);
+        assert!(rgba.chunks(4).all(|pixel| pixel == &rgba[..4]));
+    }
+
+    #[test]
+    fn test_crossfade_weights_outgoing_and_incoming_by_progress() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [8, 8], 1, format, 3);
+        let frame = FeedbackRenderer::create_texture(&device, [8, 8], format);
+        let frame_view = frame.view().build();
+        feedback.trails = false;
+        feedback.set_recording(&device, true);
+
+        // Red fading out, blue fading in; returns the output's center (r, b)
+        let mut render = |progress: f32| {
+            let outgoing = nannou::Draw::new();
+            outgoing.rect().w_h(100.0, 100.0).color(RED);
+            let incoming = nannou::Draw::new();
+            incoming.rect().w_h(100.0, 100.0).color(BLUE);
+            feedback.crossfade_from(outgoing, progress);
+            feedback.render_with_overlays(&device, &queue, &incoming, &[], &[], &[], &frame_view, format, 1);
+            let rgba = feedback.read_back_rgba(&device, &queue);
+            let center = (4 * 8 + 4) * 4;
+            (rgba[center], rgba[center + 2])
+        };
+
+        let (r, b) = render(0.0);
+        assert!(r > 250 && b < 5, "progress 0: ({}, {})", r, b);
+        // Half of each in linear light, about 188 once sRGB-encoded
+        let (r, b) = render(0.5);
+        assert!((180..=196).contains(&r) && (180..=196).contains(&b), "progress 0.5: ({}, {})", r, b);
+        let (r, b) = render(1.0);
+        assert!(r < 5 && b > 250, "progress 1: ({}, {})", r, b);
+
+        // Without a crossfade the primary draws as is
+        let draw = nannou::Draw::new();
+        draw.rect().w_h(100.0, 100.0).color(RED);
+        feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        assert!(feedback.read_back_rgba(&device, &queue)[0] > 250);
+    }
+
+    #[test]
+    fn test_safe_mode_caps_brightness_per_second() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let frame = FeedbackRenderer::create_texture(&device, [8, 8], format);
+        let frame_view = frame.view().build();
+
+        // Black, then white for 1/6 s at `fps`; returns the output's red level
+        let ramp = |fps: u32| {
+            let mut feedback = FeedbackRenderer::new(&device, &queue, [8, 8], 1, format, 3);
+            feedback.trails = false;
+            feedback.safe_mode = true;
+            feedback.set_recording(&device, true);
+            for i in 0..=fps / 6 {
+                feedback.follow_audio(i as f32 / fps as f32, &AudioAnalysis::default());
+                let draw = nannou::Draw::new();
+                let color = if i == 0 { BLACK } else { WHITE };
+                draw.rect().w_h(100.0, 100.0).color(color);
+                feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+            }
+            feedback.read_back_rgba(&device, &queue)[0]
+        };
+
+        // A 10% luminance swing either way, about 89 once sRGB-encoded
+        let (at_60, at_144) = (ramp(60), ramp(144));
+        assert!((80..=98).contains(&at_60), "60fps: {}", at_60);
+        assert!(at_60.abs_diff(at_144) <= 6, "60fps: {}, 144fps: {}", at_60, at_144);
+    }
+
+    #[test]
+    fn test_capture_png_saves_last_output() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let mut feedback = FeedbackRenderer::new(&device, &queue, [20, 10], 1, format, 3);
+        let frame = FeedbackRenderer::create_texture(&device, [20, 10], format);
+        let frame_view = frame.view().build();
+        let path = std::env::temp_dir().join(format!("dj-viz-screenshot-{}.png", std::process::id()));
+        assert!(feedback.capture_png(&device, &queue, &path).is_err());
+
+        // Red stays red through the BGRA window format and the post passes
+        let draw = nannou::Draw::new();
+        draw.rect().w_h(100.0, 100.0).color(RED);
+        feedback.symmetry = 2;
+        feedback.render_with_overlays(&device, &queue, &draw, &[], &[], &[], &frame_view, format, 1);
+        feedback.capture_png(&device, &queue, &path).unwrap();
+
+        let image = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (20, 10));
+        let [r, g, b, _] = image.get_pixel(10, 5).0;
+        assert!(r > 200 && g < 50 && b < 50);
+        let _ = std::fs::remove_file(&path);
+    }
+}
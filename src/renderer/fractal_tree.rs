@@ -5,11 +5,12 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::cell::Cell;
 
-use crate::audio::AudioAnalysis;
-use crate::utils::get_random_edge_coord;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::{get_random_edge_coord, Config};
 
 const MAX_BRANCHES: usize = 5;
 const MIN_BRANCHES: usize = 1;
@@ -32,8 +33,8 @@ enum LeafColor {
 }
 
 impl LeafColor {
-    fn random() -> Self {
-        let roll = rand::rng().random_range(0.0..1.0);
+    fn random(rng: &mut impl Rng) -> Self {
+        let roll = rng.random_range(0.0..1.0);
         if roll < 0.04 {
             LeafColor::Yellow
         } else if roll < 0.19 {
@@ -103,6 +104,10 @@ pub struct FractalTree {
     next_branch_id: usize,
     last_energy_turn_triggered: bool,
     bounds: Cell<Rect>,
+    /// Color branches by `hue_from_centroid` instead of the dominant band
+    centroid_hue: bool,
+    /// Growth, turns and spawns, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for FractalTree {
@@ -117,19 +122,41 @@ impl Default for FractalTree {
             next_branch_id: 0,
             last_energy_turn_triggered: false,
             bounds: Cell::new(default_bounds),
+            centroid_hue: Config::load().fractal_tree_centroid_hue(),
+            rng: StdRng::from_os_rng(),
         };
 
-        // Start with one branch from random edge (default color)
-        tree.spawn_main_branch_with_color(rgba(0.95, 0.92, 0.85, 0.5));
-
+        tree.start_over();
         tree
     }
 }
 
 impl FractalTree {
+    /// Color for a new branch from the current audio
+    fn branch_color(&mut self, analysis: &AudioAnalysis) -> Rgba {
+        let hue = self.centroid_hue.then(|| analysis.hue_from_centroid());
+        let bands = std::array::from_fn(|i| {
+            band_group(&analysis.bands_normalized, i, DEFAULT_NUM_BANDS)
+        });
+        Self::color_from_bands(&mut self.rng, &bands, hue)
+    }
+
     /// Get color based on the dominant frequency band
-    /// Maps frequency bands to colors: low=warm, mid=green/yellow, high=cool
-    fn color_from_bands(bands: &[f32; 8]) -> Rgba {
+    /// Maps frequency bands to colors: low=warm, mid=green/yellow, high=cool.
+    /// With `centroid_hue` (degrees) that hue is used instead
+    fn color_from_bands(
+        rng: &mut impl Rng,
+        bands: &[f32; DEFAULT_NUM_BANDS],
+        centroid_hue: Option<f32>,
+    ) -> Rgba {
+        // Add some randomness to the color
+        let variation = rng.random_range(-0.1..0.1);
+
+        if let Some(hue) = centroid_hue {
+            let color: Rgb = hsv(hue / 360.0 + variation * 0.1, 0.85, 0.95).into();
+            return rgba(color.red, color.green, color.blue, 0.5);
+        }
+
         // Find the dominant band (highest energy)
         let mut max_idx = 0;
         let mut max_val = bands[0];
@@ -140,10 +167,6 @@ impl FractalTree {
             }
         }
 
-        // Add some randomness to the color
-        let mut rng = rand::rng();
-        let variation = rng.random_range(-0.1..0.1);
-
         // Map bands to colors with 50% opacity
         match max_idx {
             0 => rgba(0.9 + variation, 0.1, 0.1, 0.5), // Sub-bass: Deep red
@@ -158,6 +181,16 @@ impl FractalTree {
         }
     }
 
+    /// Start over with one branch from a random edge (default color)
+    fn start_over(&mut self) {
+        self.branches.clear();
+        self.leaves.clear();
+        self.next_branch_id = 0;
+        self.last_energy_diff = 0.0;
+        self.last_energy_turn_triggered = false;
+        self.spawn_main_branch_with_color(rgba(0.95, 0.92, 0.85, 0.5));
+    }
+
     /// Calculate spawn radius (corner-to-center distance) from bounds
     fn calculate_spawn_radius(&self) -> f32 {
         let bounds = self.bounds.get();
@@ -181,17 +214,16 @@ impl FractalTree {
         let bounds = self.bounds.get();
 
         // Use viewport utility to get random edge position outside bounds
-        let start_pos = get_random_edge_coord(bounds.w(), bounds.h(), SPAWN_OFFSET);
+        let start_pos = get_random_edge_coord(&mut self.rng, bounds.w(), bounds.h(), SPAWN_OFFSET);
 
         let center = vec2(0.0, 0.0);
 
         // Calculate initial angle towards center with some randomness
         let to_center = (center - start_pos).normalize();
-        let mut rng = rand::rng();
-        let angle_variation = rng.random_range(-0.5..0.5);
+        let angle_variation = self.rng.random_range(-0.5..0.5);
         let initial_angle = to_center.y.atan2(to_center.x) + angle_variation;
 
-        let thickness = rng.random_range(8.0..15.0);
+        let thickness = self.rng.random_range(8.0..15.0);
         let branch_id = self.next_branch_id;
         self.next_branch_id += 1;
 
@@ -234,7 +266,7 @@ impl FractalTree {
         let parent_max_length = parent.max_length;
         let parent_color = parent.color;
 
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         // Fork angle diverges from parent by 30-60 degrees
         let angle_divergence = rng.random_range(0.5..1.0);
@@ -273,12 +305,12 @@ impl FractalTree {
     /// Spawn a leaf at a given position
     fn spawn_leaf(&mut self, position: Vec2, angle: f32, branch_id: usize) {
         if self.leaves.len() < 300 {
-            let mut rng = rand::rng();
+            let rng = &mut self.rng;
             self.leaves.push(Leaf {
                 position,
                 angle: angle + rng.random_range(-0.5..0.5),
                 size: rng.random_range(0.8..1.2),
-                color: LeafColor::random(),
+                color: LeafColor::random(rng),
                 branch_id,
             });
         }
@@ -413,7 +445,7 @@ impl Visualization for FractalTree {
             let proximity_multiplier = 1.0 + proximity * 3.0; // Up to 4x twist near center
             let base_twist = TWIST_AMOUNT * length_multiplier * proximity_multiplier;
 
-            let mut rng = rand::rng();
+            let rng = &mut self.rng;
 
             // Check if we can make a major turn
             if branch.distance_since_turn >= min_turn_distance {
@@ -534,14 +566,14 @@ impl Visualization for FractalTree {
             && self.last_energy_diff.abs() < ENERGY_THRESHOLD
         {
             if main_branch_count < MAX_BRANCHES {
-                let color = Self::color_from_bands(&analysis.bands_normalized);
+                let color = self.branch_color(analysis);
                 self.spawn_main_branch_with_color(color);
             }
         }
 
         // Ensure minimum main branches
         if main_branch_count < MIN_BRANCHES {
-            let color = Self::color_from_bands(&analysis.bands_normalized);
+            let color = self.branch_color(analysis);
             self.spawn_main_branch_with_color(color);
         }
 
@@ -563,4 +595,20 @@ impl Visualization for FractalTree {
             self.draw_leaf(draw, leaf);
         }
     }
+    fn warm_up(&mut self, analysis: &AudioAnalysis, frames: u32) {
+        // Grow a full set of branches (topping up as they finish) so the tree is already spread out
+        const RESPAWN_INTERVAL: u32 = 20;
+        for frame in 0..frames {
+            if frame % RESPAWN_INTERVAL == 0 {
+                let color = self.branch_color(analysis);
+                self.spawn_main_branch_with_color(color);
+            }
+            self.update(analysis);
+        }
+    }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.rng = rng;
+        self.start_over();
+    }
 }
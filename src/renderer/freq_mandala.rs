@@ -6,10 +6,11 @@
 use super::Visualization;
 use nannou::prelude::*;
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgba;
 
-/// Number of symmetry segments (matches 8 frequency bands)
-const NUM_SEGMENTS: usize = 8;
+/// Number of symmetry segments (one per default frequency band)
+const NUM_SEGMENTS: usize = DEFAULT_NUM_BANDS;
 /// Number of concentric rings
 const NUM_RINGS: usize = if cfg!(debug_assertions) { 12 } else { 20 };
 
@@ -17,7 +18,7 @@ pub struct FreqMandala {
     /// Current rotation
     rotation: f32,
     /// Per-band smoothed values
-    bands: [f32; 8],
+    bands: [f32; NUM_SEGMENTS],
     /// Hue offset for color cycling
     hue_offset: f32,
     /// Smoothed bass
@@ -34,7 +35,7 @@ impl Default for FreqMandala {
     fn default() -> Self {
         Self {
             rotation: 0.0,
-            bands: [0.0; 8],
+            bands: [0.0; NUM_SEGMENTS],
             hue_offset: 0.0,
             bass: 0.0,
             energy: 0.0,
@@ -44,36 +45,6 @@ impl Default for FreqMandala {
     }
 }
 
-impl FreqMandala {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for FreqMandala {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
@@ -82,8 +53,8 @@ impl Visualization for FreqMandala {
         let attack = 0.6;
         let decay = 0.12;
 
-        for i in 0..8 {
-            let target = analysis.bands_normalized[i];
+        for i in 0..NUM_SEGMENTS {
+            let target = band_group(&analysis.bands_normalized, i, NUM_SEGMENTS);
             if target > self.bands[i] {
                 self.bands[i] = self.bands[i] * (1.0 - attack) + target * attack;
             } else {
@@ -146,7 +117,7 @@ impl Visualization for FreqMandala {
                 let value = 0.3 + band_value * 0.5 + ring_t * 0.2;
                 let alpha = 0.2 + band_value * 0.4;
 
-                let color = Self::hsv_to_rgba(hue, saturation, value, alpha);
+                let color = hsv_to_rgba(hue, saturation, value, alpha);
 
                 // Draw segment as a curved quad (approximated with triangles)
                 let num_arc_points = 8;
@@ -179,7 +150,7 @@ impl Visualization for FreqMandala {
                 // Add highlight lines on active bands
                 if band_value > 0.3 {
                     let line_alpha = (band_value - 0.3) * 0.8;
-                    let line_color = Self::hsv_to_rgba(hue, 0.3, 0.95, line_alpha);
+                    let line_color = hsv_to_rgba(hue, 0.3, 0.95, line_alpha);
 
                     let angle = seg_angle;
                     let start = pt2(
@@ -207,7 +178,7 @@ impl Visualization for FreqMandala {
             let r = rim_radius + t * max_radius * 0.05 * (1.0 + self.bloom);
             let alpha = (1.0 - t) * 0.2 * (0.5 + self.energy);
             let hue = (self.hue_offset + t * 30.0) % 360.0;
-            let color = Self::hsv_to_rgba(hue, 0.5, 0.8, alpha);
+            let color = hsv_to_rgba(hue, 0.5, 0.8, alpha);
 
             draw.ellipse()
                 .xy(center)
@@ -226,7 +197,7 @@ impl Visualization for FreqMandala {
             let r = core_radius * (1.0 - t * 0.7);
             let hue = (self.hue_offset + t * 45.0 + 180.0) % 360.0;
             let alpha = (1.0 - t) * 0.5;
-            let color = Self::hsv_to_rgba(hue, 0.7, 0.9, alpha);
+            let color = hsv_to_rgba(hue, 0.7, 0.9, alpha);
 
             draw.ellipse().xy(center).radius(r).color(color);
         }
@@ -249,7 +220,7 @@ impl Visualization for FreqMandala {
 
             let hue = (seg as f32 / NUM_SEGMENTS as f32 * 360.0 + self.hue_offset + 60.0) % 360.0;
             let alpha = 0.4 + band_value * 0.4;
-            let color = Self::hsv_to_rgba(hue, 0.8, 0.8, alpha);
+            let color = hsv_to_rgba(hue, 0.8, 0.8, alpha);
 
             let points = vec![
                 pt2(center.x + base_offset_x, center.y + base_offset_y),
@@ -260,4 +231,9 @@ impl Visualization for FreqMandala {
             draw.polygon().points(points).color(color);
         }
     }
+
+    /// Mirror seams stay crisp: drawn fresh each frame without fade/zoom trails
+    fn uses_feedback(&self) -> bool {
+        false
+    }
 }
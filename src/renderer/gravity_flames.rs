@@ -6,9 +6,10 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
 
 /// Number of flame particles
 const NUM_PARTICLES: usize = if cfg!(debug_assertions) { 200 } else { 500 };
@@ -53,11 +54,13 @@ pub struct GravityFlames {
     /// Current rotation offset for base angles
     base_angle_rotation: f32,
     /// Smoothed frequency band values
-    smoothed_bands: [f32; 8],
+    smoothed_bands: [f32; DEFAULT_NUM_BANDS],
     /// Smoothed overall energy
     energy: f32,
     /// Frame counter for timing effects
     frame_count: u32,
+    /// Spawns and turbulence, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for GravityFlames {
@@ -65,9 +68,10 @@ impl Default for GravityFlames {
         Self {
             particles: Vec::with_capacity(NUM_PARTICLES),
             base_angle_rotation: 0.0,
-            smoothed_bands: [0.0; 8],
+            smoothed_bands: [0.0; DEFAULT_NUM_BANDS],
             energy: 0.0,
             frame_count: 0,
+            rng: StdRng::from_os_rng(),
         }
     }
 }
@@ -113,7 +117,7 @@ impl GravityFlames {
 
     /// Spawn a new particle
     fn spawn_particle(&mut self, band_idx: usize) {
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         // Calculate base angle for this frequency band
         let angle = (band_idx as f32 / 8.0) * TAU + self.base_angle_rotation;
@@ -143,13 +147,12 @@ impl Visualization for GravityFlames {
         self.frame_count += 1;
 
         // Smooth frequency bands and energy
-        for i in 0..8 {
-            if analysis.bands_normalized[i] > self.smoothed_bands[i] {
-                self.smoothed_bands[i] =
-                    self.smoothed_bands[i] * (1.0 - ATTACK) + analysis.bands_normalized[i] * ATTACK;
+        for i in 0..DEFAULT_NUM_BANDS {
+            let target = band_group(&analysis.bands_normalized, i, DEFAULT_NUM_BANDS);
+            if target > self.smoothed_bands[i] {
+                self.smoothed_bands[i] = self.smoothed_bands[i] * (1.0 - ATTACK) + target * ATTACK;
             } else {
-                self.smoothed_bands[i] =
-                    self.smoothed_bands[i] * (1.0 - DECAY) + analysis.bands_normalized[i] * DECAY;
+                self.smoothed_bands[i] = self.smoothed_bands[i] * (1.0 - DECAY) + target * DECAY;
             }
         }
 
@@ -167,7 +170,7 @@ impl Visualization for GravityFlames {
         }
 
         // Update existing particles
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
         for particle in &mut self.particles {
             particle.age += 1.0;
 
@@ -198,19 +201,17 @@ impl Visualization for GravityFlames {
         // Spawn new particles based on energy, burst on punch detection
         if analysis.punch_detected {
             // Big burst on punch - spawn particles in all directions
-            let mut rng = rand::rng();
             let burst_count = (NUM_PARTICLES / 4).min(NUM_PARTICLES - self.particles.len());
             for _ in 0..burst_count {
-                let band_idx = rng.random_range(0..8);
+                let band_idx = self.rng.random_range(0..8);
                 self.spawn_particle(band_idx);
             }
         } else if self.energy > 0.3 {
-            let mut rng = rand::rng();
             let mut spawn_chance = self.energy * SPAWN_RATE;
 
-            while rng.random::<f32>() < spawn_chance && self.particles.len() < NUM_PARTICLES {
+            while self.rng.random::<f32>() < spawn_chance && self.particles.len() < NUM_PARTICLES {
                 // Pick random frequency band
-                let band_idx = rng.random_range(0..8);
+                let band_idx = self.rng.random_range(0..8);
                 self.spawn_particle(band_idx);
 
                 // Reduce spawn chance to prevent too many spawns per frame
@@ -266,4 +267,9 @@ impl Visualization for GravityFlames {
             .radius(2.0 + pulse * 3.0)
             .color(srgba(255, 255, 255, center_alpha));
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.particles.clear();
+        self.rng = rng;
+    }
 }
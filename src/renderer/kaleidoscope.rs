@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgba;
 
 /// Number of mirror segments in the kaleidoscope
 const NUM_SEGMENTS: usize = 6;
@@ -45,11 +47,19 @@ pub struct Kaleidoscope {
     treble: f32,
     /// Frame counter
     frame_count: u32,
+    /// Particle layout and respawns, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for Kaleidoscope {
     fn default() -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(StdRng::from_os_rng())
+    }
+}
+
+impl Kaleidoscope {
+    /// Fresh particles scattered by `rng`
+    fn with_rng(mut rng: StdRng) -> Self {
         let segment_angle = std::f32::consts::TAU / NUM_SEGMENTS as f32;
 
         let particles: Vec<Particle> = (0..NUM_PARTICLES)
@@ -71,40 +81,11 @@ impl Default for Kaleidoscope {
             bass: 0.0,
             treble: 0.0,
             frame_count: 0,
+            rng,
         }
     }
 }
 
-impl Kaleidoscope {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for Kaleidoscope {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
@@ -136,7 +117,7 @@ impl Visualization for Kaleidoscope {
 
         // Update particles
         let segment_angle = std::f32::consts::TAU / NUM_SEGMENTS as f32;
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         for (i, particle) in self.particles.iter_mut().enumerate() {
             // Move outward/inward based on velocity
@@ -151,14 +132,15 @@ impl Visualization for Kaleidoscope {
             }
 
             // Swirl within segment based on bands
-            let band_idx = i % 8;
-            particle.local_angle += analysis.bands_normalized[band_idx] * 0.02;
+            let band_idx = i % DEFAULT_NUM_BANDS;
+            let band_energy = band_group(&analysis.bands_normalized, band_idx, DEFAULT_NUM_BANDS);
+            particle.local_angle += band_energy * 0.02;
             if particle.local_angle > segment_angle {
                 particle.local_angle -= segment_angle;
             }
 
             // Size pulses with corresponding band
-            particle.size = 0.5 + analysis.bands_normalized[band_idx] * 1.5;
+            particle.size = 0.5 + band_energy * 1.5;
         }
     }
 
@@ -174,7 +156,7 @@ impl Visualization for Kaleidoscope {
             let value = 0.5 + particle.radius * 0.3;
             let alpha = 0.2 + self.bass * 0.25;
 
-            let color = Self::hsv_to_rgba(hue, saturation, value, alpha);
+            let color = hsv_to_rgba(hue, saturation, value, alpha);
             let particle_size = 10.0 + particle.size * 15.0 * (1.0 + self.bass * 0.3);
             let r = particle.radius * max_radius;
 
@@ -230,7 +212,7 @@ impl Visualization for Kaleidoscope {
                     let y2 = center.y + r2 * angle2.sin();
 
                     let hue = (p1.hue + self.hue_offset) % 360.0;
-                    let color = Self::hsv_to_rgba(hue, 0.8, 0.9, line_alpha);
+                    let color = hsv_to_rgba(hue, 0.8, 0.9, line_alpha);
 
                     draw.line()
                         .start(pt2(x1, y1))
@@ -248,9 +230,18 @@ impl Visualization for Kaleidoscope {
             let r = glow_radius * (1.0 - t);
             let hue = (self.hue_offset + t * 60.0) % 360.0;
             let alpha = t * 0.2 * (0.5 + self.bass * 0.3);
-            let color = Self::hsv_to_rgba(hue, 0.6, 0.9, alpha);
+            let color = hsv_to_rgba(hue, 0.6, 0.9, alpha);
 
             draw.ellipse().xy(center).radius(r).color(color);
         }
     }
+
+    /// Mirror seams stay crisp: drawn fresh each frame without fade/zoom trails
+    fn uses_feedback(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self, rng: StdRng) {
+        *self = Self::with_rng(rng);
+    }
 }
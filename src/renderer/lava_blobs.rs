@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
+use rand::rngs::StdRng;
 use rand::Rng;
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgb;
 
 /// Number of metaballs in the simulation
 const NUM_BLOBS: usize = if cfg!(debug_assertions) { 8 } else { 16 };
@@ -50,7 +52,13 @@ pub struct LavaBlobs {
 
 impl Default for LavaBlobs {
     fn default() -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(&mut rand::rng())
+    }
+}
+
+impl LavaBlobs {
+    /// Fresh blobs scattered by `rng`
+    fn with_rng(rng: &mut impl Rng) -> Self {
 
         let blobs: Vec<Blob> = (0..NUM_BLOBS)
             .map(|i| {
@@ -116,35 +124,12 @@ impl LavaBlobs {
             let hue = (weighted_hue / total_weight) % 360.0;
             let saturation = 0.7 + self.energy * 0.3;
             let value = 0.8 + self.bass * 0.2;
-            Self::hsv_to_rgb(hue, saturation, value)
+            hsv_to_rgb(hue, saturation, value)
         } else {
             (0.0, 0.0, 0.0)
         }
     }
 
-    fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        (r1 + m, g1 + m, b1 + m)
-    }
-
     /// Simple 2D noise function
     fn noise(x: f32, y: f32, t: f32) -> f32 {
         ((x * 0.1 + t).sin() * (y * 0.1 + t * 0.7).cos() + 1.0) * 0.5
@@ -177,8 +162,9 @@ impl Visualization for LavaBlobs {
             let noise_x = Self::noise(blob.x * 0.01, blob.y * 0.01, t) - 0.5;
             let noise_y = Self::noise(blob.y * 0.01, blob.x * 0.01 + 100.0, t) - 0.5;
 
-            blob.vx += noise_x * 0.3 + analysis.bands_normalized[i % 8] * noise_x * 2.0;
-            blob.vy += noise_y * 0.3 + analysis.bands_normalized[i % 8] * noise_y * 2.0;
+            let band_value = band_group(&analysis.bands_normalized, i % 8, DEFAULT_NUM_BANDS);
+            blob.vx += noise_x * 0.3 + band_value * noise_x * 2.0;
+            blob.vy += noise_y * 0.3 + band_value * noise_y * 2.0;
 
             // Damping
             blob.vx *= 0.98;
@@ -214,7 +200,7 @@ impl Visualization for LavaBlobs {
             }
 
             // Radius pulses with corresponding frequency band
-            let band_value = analysis.bands_normalized[i % 8];
+            let band_value = band_group(&analysis.bands_normalized, i % 8, DEFAULT_NUM_BANDS);
             blob.target_radius = 35.0 + band_value * 50.0;
             blob.radius = blob.radius * 0.9 + blob.target_radius * 0.1;
         }
@@ -303,7 +289,7 @@ impl Visualization for LavaBlobs {
         // Draw bright cores at blob centers
         for blob in &self.blobs {
             let hue = (blob.hue + self.hue_offset) % 360.0;
-            let (r, g, b) = Self::hsv_to_rgb(hue, 0.3, 1.0);
+            let (r, g, b) = hsv_to_rgb(hue, 0.3, 1.0);
 
             // Glow rings
             for i in 0..5 {
@@ -323,6 +309,10 @@ impl Visualization for LavaBlobs {
             }
         }
     }
+
+    fn reset(&mut self, mut rng: StdRng) {
+        *self = Self::with_rng(&mut rng);
+    }
 }
 
 // Store bounds for physics on next frame
@@ -14,7 +14,9 @@ pub mod freq_mandala;
 pub mod gravity_flames;
 pub mod kaleidoscope;
 pub mod lava_blobs;
+pub mod oscilloscope;
 pub mod particle_nebula;
+pub mod preset;
 pub mod psychedelic_spiral;
 pub mod scripted;
 pub mod shuffling_skeletons;
@@ -27,10 +29,14 @@ pub mod tesla_coil;
 
 use enum_dispatch::enum_dispatch;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::VecDeque;
 
 use crate::audio::AudioAnalysis;
-use crate::utils::DetectionConfig;
+use crate::utils::{Config, DetectionConfig};
+use crate::{log_error, log_line};
 
 /// Visualization info for debug display / scripts
 #[derive(Clone, Debug)]
@@ -40,7 +46,7 @@ pub struct VizInfo {
 }
 
 /// Labels for categorizing visualizations that can be layered together
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub enum VisLabel {
     Organic,
     Geometric,
@@ -70,8 +76,25 @@ const VIZ_LABELS: &[&[VisLabel]] = &[
     &[VisLabel::Organic],                      // 15: ParticleNebula
     &[VisLabel::Geometric],                    // 16: FreqMandala
     &[VisLabel::Glitchy, VisLabel::Intense],   // 17: StrobeGrid
+    &[VisLabel::Geometric, VisLabel::Retro],   // 18: Oscilloscope
 ];
 
+/// Spectral contrast above which geometric viz are preferred (sparse, punchy mixes)
+const HIGH_CONTRAST: f32 = 0.35;
+/// Spectral contrast below which organic viz are preferred (dense walls of sound)
+const LOW_CONTRAST: f32 = 0.15;
+
+/// Labels suited to the current spectral contrast, if it leans either way
+fn labels_for_contrast(contrast: f32) -> Option<&'static [VisLabel]> {
+    if contrast >= HIGH_CONTRAST {
+        Some(&[VisLabel::Geometric])
+    } else if contrast <= LOW_CONTRAST {
+        Some(&[VisLabel::Organic])
+    } else {
+        None
+    }
+}
+
 const ALL_LABELS: &[VisLabel] = &[
     VisLabel::Organic,
     VisLabel::Geometric,
@@ -81,18 +104,175 @@ const ALL_LABELS: &[VisLabel] = &[
     VisLabel::Retro,
 ];
 
+/// A label and the visualizations carrying it (`--dump-catalog`)
+#[derive(Serialize)]
+struct CatalogLabel {
+    label: VisLabel,
+    visualizations: Vec<&'static str>,
+}
+
+/// One visualization with its labels and energy range (`--dump-catalog`)
+#[derive(Serialize)]
+struct CatalogViz {
+    index: usize,
+    name: &'static str,
+    labels: &'static [VisLabel],
+    /// None when the configured ranges don't cover this visualization
+    energy_range: Option<[f32; 2]>,
+}
+
+#[derive(Serialize)]
+struct Catalog {
+    labels: Vec<CatalogLabel>,
+    visualizations: Vec<CatalogViz>,
+}
+
+/// Look up a visualization index by name (case-insensitive)
+fn viz_index(name: &str) -> Option<usize> {
+    VIZ_NAMES.iter().position(|n| n.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Resolve the configured start scene into (primary, overlays).
+/// Returns None unless the primary resolves.
+fn resolve_start_scene(
+    start_viz: Option<&str>,
+    start_overlays: &[String],
+    max_overlays: usize,
+) -> Option<(usize, Vec<usize>)> {
+    let name = start_viz?;
+    let Some(primary) = viz_index(name) else {
+        log_error!("Unknown start_viz {:?}, starting with a random scene", name);
+        return None;
+    };
+    Some((primary, resolve_overlays(primary, start_overlays, max_overlays)))
+}
+
+/// Resolve overlay names to indices. Unknown names are skipped with a warning;
+/// overlays are deduplicated (never the primary) and capped at `max_overlays`.
+fn resolve_overlays(primary: usize, names: &[String], max_overlays: usize) -> Vec<usize> {
+    let mut overlays = Vec::new();
+    for name in names {
+        match viz_index(name) {
+            Some(idx) if idx == primary || overlays.contains(&idx) => {}
+            Some(idx) if overlays.len() >= max_overlays => {
+                log_error!(
+                    "Ignoring overlay {} (at most {} overlays)",
+                    Viz::name(idx),
+                    max_overlays
+                );
+            }
+            Some(idx) => overlays.push(idx),
+            None => log_error!("Unknown overlay {:?}, skipping", name),
+        }
+    }
+    overlays
+}
+
+/// Auto-cycle weight per visualization index, from the configured names
+fn resolve_viz_weights(config: &DetectionConfig) -> Vec<f32> {
+    for name in config.viz_weights.iter().flatten().map(|(name, _)| name) {
+        if viz_index(name).is_none() {
+            log_error!("Unknown visualization {:?} in [viz_weights], ignoring", name);
+        }
+    }
+    VIZ_NAMES.iter().map(|name| config.viz_weight(name)).collect()
+}
+
+/// Energy range per visualization index, looked up by name so the config
+/// doesn't depend on the order of `viz_enum!`
+pub fn energy_ranges(config: &Config) -> Vec<[f32; 2]> {
+    resolve_energy_ranges(VIZ_NAMES, config)
+}
+
+fn resolve_energy_ranges(names: &[&str], config: &Config) -> Vec<[f32; 2]> {
+    for name in config.energy_ranges.iter().flatten().map(|(name, _)| name) {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            log_error!("Unknown visualization {:?} in [energy_ranges], ignoring", name);
+        }
+    }
+    names.iter().map(|name| config.energy_range(name)).collect()
+}
+
+/// Position of a random entry of `pool`, each as likely as its weight
+/// (None when no entry has a positive weight)
+fn pick_weighted(rng: &mut impl rand::Rng, pool: &[usize], weights: &[f32]) -> Option<usize> {
+    let weight = |idx: usize| weights.get(idx).copied().unwrap_or(1.0);
+    let total: f32 = pool.iter().map(|&idx| weight(idx)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.random_range(0.0..total);
+    for (pos, &idx) in pool.iter().enumerate() {
+        if target < weight(idx) {
+            return Some(pos);
+        }
+        target -= weight(idx);
+    }
+    // Float rounding can leave a sliver past the last entry
+    pool.iter().rposition(|&idx| weight(idx) > 0.0)
+}
+
+/// Up to `count` distinct entries of `pool`, drawn by weight
+fn pick_weighted_distinct(
+    rng: &mut impl rand::Rng,
+    mut pool: Vec<usize>,
+    weights: &[f32],
+    count: usize,
+) -> Vec<usize> {
+    let mut picked = Vec::with_capacity(count);
+    while picked.len() < count {
+        let Some(pos) = pick_weighted(rng, &pool, weights) else {
+            break;
+        };
+        picked.push(pool.swap_remove(pos));
+    }
+    picked
+}
+
+/// Machine-readable label taxonomy: every label with its visualizations, and
+/// every visualization with its labels and (configured) energy range.
+pub fn catalog_json(viz_energy_ranges: &[[f32; 2]]) -> String {
+    let catalog = Catalog {
+        labels: ALL_LABELS
+            .iter()
+            .map(|&label| CatalogLabel {
+                label,
+                visualizations: VIZ_NAMES
+                    .iter()
+                    .zip(VIZ_LABELS)
+                    .filter(|(_, labels)| labels.contains(&label))
+                    .map(|(&name, _)| name)
+                    .collect(),
+            })
+            .collect(),
+        visualizations: VIZ_NAMES
+            .iter()
+            .enumerate()
+            .map(|(index, &name)| CatalogViz {
+                index,
+                name,
+                labels: VIZ_LABELS.get(index).copied().unwrap_or(&[]),
+                energy_range: viz_energy_ranges.get(index).copied(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&catalog).expect("catalog serializes to JSON")
+}
+
 pub use beat_bars::BeatBars;
 pub use black_hole::BlackHole;
 pub use crt_phosphor::CrtPhosphor;
 pub use dancing_skeletons::DancingSkeletons;
 pub use debug::DebugViz;
-pub use effects::FeedbackRenderer;
+pub use effects::{FeedbackRenderer, FrameCapture};
 pub use fractal_tree::FractalTree;
 pub use freq_mandala::FreqMandala;
 pub use gravity_flames::GravityFlames;
 pub use kaleidoscope::Kaleidoscope;
 pub use lava_blobs::LavaBlobs;
+pub use oscilloscope::Oscilloscope;
 pub use particle_nebula::ParticleNebula;
+pub use preset::Preset;
 pub use psychedelic_spiral::PsychedelicSpiral;
 pub use scripted::ScriptManager;
 pub use shuffling_skeletons::ShufflingSkeletons;
@@ -147,6 +327,7 @@ viz_enum! {
     ParticleNebula,
     FreqMandala,
     StrobeGrid,
+    Oscilloscope,
 }
 
 /// Trait that all visualizations must implement
@@ -157,13 +338,33 @@ pub trait Visualization {
 
     /// Draw the visualization
     fn draw(&self, draw: &Draw, bounds: Rect);
+
+    /// Whether trails from the feedback buffer (fade/zoom) apply when this is the primary.
+    /// Geometric viz can return false so symmetry seams aren't smeared.
+    fn uses_feedback(&self) -> bool {
+        true
+    }
+
+    /// Pre-advance state by `frames` simulated frames when the viz becomes active,
+    /// so viz that build up over time don't start out empty.
+    fn warm_up(&mut self, _analysis: &AudioAnalysis, _frames: u32) {}
+
+    /// Clear state accumulated while last active (particles, history...), called
+    /// when the viz becomes active again, before `warm_up`. All of the viz's
+    /// randomness from then on comes from `rng`, so seeded renders repeat.
+    fn reset(&mut self, _rng: StdRng) {}
 }
 
+/// Internal render resolution in eco mode, relative to the window
+const ECO_RENDER_SCALE: f32 = 0.5;
+
 /// Resolution settings for renderers
 pub struct Resolution {
     pub width: u32,
     pub height: u32,
     pub fullscreen: bool,
+    /// Internal resolution relative to the window (upscaled on output)
+    pub render_scale: f32,
 }
 
 impl Resolution {
@@ -172,6 +373,7 @@ impl Resolution {
             width: 640,
             height: 480,
             fullscreen: false,
+            render_scale: 1.0,
         }
     }
 
@@ -180,6 +382,15 @@ impl Resolution {
             width: 1280,
             height: 720,
             fullscreen: true,
+            render_scale: 1.0,
+        }
+    }
+
+    /// Same window, rendered at a lower internal resolution (`--eco`)
+    pub fn eco(self) -> Self {
+        Self {
+            render_scale: ECO_RENDER_SCALE,
+            ..self
         }
     }
 
@@ -193,12 +404,34 @@ impl Resolution {
 }
 
 const NOTIFICATION_FRAMES: u32 = 180; // ~3 seconds at 60fps
+/// Apply a queued switch anyway if no downbeat arrives in time (~5 seconds at
+/// 60fps, longer than a 4-beat bar at 60 BPM)
+const PENDING_SWITCH_MAX_FRAMES: u32 = 300;
+/// Simulated frames a newly activated viz is warmed up by (~5 seconds at 60fps)
+const WARM_UP_FRAMES: u32 = 300;
+/// Frames to fade to black once silence is detected, and back in when audio returns
+const SILENCE_FADE_OUT_FRAMES: f32 = 60.0;
+const SILENCE_FADE_IN_FRAMES: f32 = 10.0;
+/// Recent arrangements kept for `cycle_previous`
+const HISTORY_LEN: usize = 16;
+/// Mixed into a renderer seed for the visualizations' own randomness, so it
+/// doesn't repeat the selection sequence
+const VIZ_SEED_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+/// Frame rate visualizations are tuned for: each `update` is one step at this rate
+const NOMINAL_FRAME_RATE: f32 = 60.0;
+
+/// Primary visualization being faded out after a switch
+struct Transition {
+    outgoing: usize,
+    /// 0.0 = only the outgoing viz, 1.0 = fully switched
+    progress: f32,
+}
 
 /// Main renderer that manages the visualization pipeline and cycling
 pub struct Renderer {
     visualizations: Vec<Viz>,
     current_idx: usize,
-    /// Indices of overlay visualizations to blend with burn effect (0-3)
+    /// Indices of overlay visualizations to blend with burn effect (up to `max_overlays`)
     overlay_indices: Vec<usize>,
     cooldown: u32,
     notification_text: Option<String>,
@@ -210,24 +443,100 @@ pub struct Renderer {
     pub debug_viz_visible: bool,
     /// Smoothed energy level for selection decisions
     tracked_energy: f32,
+    /// Smoothed spectral contrast for selection decisions
+    tracked_contrast: f32,
     /// Detection configuration (from config file)
     detection_config: DetectionConfig,
     /// Energy ranges for visualizations (from config file)
     viz_energy_ranges: Vec<[f32; 2]>,
+    /// Auto-cycle weight per visualization (0 = never picked automatically)
+    viz_weights: Vec<f32>,
+    /// Switch (primary, overlays) waiting for the next downbeat when quantized switching is on
+    pending_switch: Option<(usize, Vec<usize>)>,
+    /// Frames the pending switch has been waiting
+    pending_frames: u32,
+    /// Burn blend opacity per visualization when used as an overlay (0-1)
+    overlay_alphas: Vec<f32>,
+    /// Visualizations active last frame (newly active ones get warmed up)
+    prev_active: Vec<usize>,
+    /// Arrangements shown before the current one as (primary, overlays), newest last
+    history: VecDeque<(usize, Vec<usize>)>,
+    /// The current arrangement came from `history`, so leaving the last one isn't recorded
+    restored_from_history: bool,
+    /// Crossfade from the previous primary, while one is running
+    transition: Option<Transition>,
+    /// How far the visuals are dimmed while no audio plays (0 = not at all, 1 = black)
+    silence_dim: f32,
+    /// Animation steps per frame, so a capped frame rate keeps the animation tempo
+    steps_per_frame: u32,
+    /// Drives every random selection, so a seeded renderer replays the same sequence
+    rng: StdRng,
+    /// Seeds each visualization as it becomes active, kept apart from `rng` so
+    /// what a viz draws doesn't change which viz comes next
+    viz_rng: StdRng,
 }
 
 impl Renderer {
     /// Creates a renderer that cycles between visualizations
-    /// when audio transitions are detected, starting with a random one
+    /// when audio transitions are detected, starting with a random one.
+    /// A configured start scene (`start_viz` + `start_overlays`) is used instead
+    /// when it resolves, and locks auto-cycling.
     pub fn with_cycling(
         detection_config: DetectionConfig,
         viz_energy_ranges: Vec<[f32; 2]>,
+        start_viz: Option<&str>,
+        start_overlays: &[String],
+    ) -> Self {
+        Self::with_rng(
+            detection_config,
+            viz_energy_ranges,
+            start_viz,
+            start_overlays,
+            StdRng::from_os_rng(),
+            StdRng::from_os_rng(),
+        )
+    }
+
+    /// Like `with_cycling`, but every random choice comes from `seed`, so the
+    /// same seed and the same audio replay the same frames
+    pub fn with_cycling_seeded(
+        detection_config: DetectionConfig,
+        viz_energy_ranges: Vec<[f32; 2]>,
+        start_viz: Option<&str>,
+        start_overlays: &[String],
+        seed: u64,
+    ) -> Self {
+        Self::with_rng(
+            detection_config,
+            viz_energy_ranges,
+            start_viz,
+            start_overlays,
+            StdRng::seed_from_u64(seed),
+            StdRng::seed_from_u64(seed ^ VIZ_SEED_MIX),
+        )
+    }
+
+    fn with_rng(
+        detection_config: DetectionConfig,
+        viz_energy_ranges: Vec<[f32; 2]>,
+        start_viz: Option<&str>,
+        start_overlays: &[String],
+        mut rng: StdRng,
+        viz_rng: StdRng,
     ) -> Self {
         let visualizations = Viz::all();
+        let overlay_alphas = vec![1.0; visualizations.len()];
 
-        let mut rng = rand::rng();
+        let max_overlays = detection_config.max_overlays();
+        let viz_weights = resolve_viz_weights(&detection_config);
+        let start_scene = resolve_start_scene(start_viz, start_overlays, max_overlays);
+        let locked = start_scene.is_some();
         // Select initial visualizations by matching labels
-        let (current_idx, overlay_indices) = Self::select_by_labels(&mut rng);
+        let (current_idx, overlay_indices) = start_scene
+            .unwrap_or_else(|| Self::select_by_labels(&mut rng, max_overlays, &viz_weights));
+
+        let mut debug_viz = DebugViz::new();
+        debug_viz.latency_compensation_ms = detection_config.latency_compensation_ms();
 
         Self {
             visualizations,
@@ -236,18 +545,36 @@ impl Renderer {
             cooldown: 0,
             notification_text: None,
             notification_frames: 0,
-            locked: false,
-            debug_viz: DebugViz::new(),
+            locked,
+            debug_viz,
             debug_viz_visible: false,
             tracked_energy: 0.5,
+            tracked_contrast: 0.25,
             detection_config,
             viz_energy_ranges,
+            viz_weights,
+            pending_switch: None,
+            pending_frames: 0,
+            overlay_alphas,
+            prev_active: Vec::new(),
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            restored_from_history: false,
+            transition: None,
+            silence_dim: 0.0,
+            steps_per_frame: 1,
+            rng,
+            viz_rng,
         }
     }
 
-    /// Selects 1-4 visualizations by picking 1-2 random labels and finding matches
+    /// Selects a primary and up to `max_overlays` overlays by picking 1-2 random
+    /// labels and finding matches, favoring visualizations by their weight
     /// Returns (primary_idx, overlay_indices)
-    fn select_by_labels(rng: &mut impl rand::Rng) -> (usize, Vec<usize>) {
+    fn select_by_labels(
+        rng: &mut impl rand::Rng,
+        max_overlays: usize,
+        weights: &[f32],
+    ) -> (usize, Vec<usize>) {
         // Pick 1 or 2 random labels
         let num_labels = rng.random_range(1..=2);
         let mut selected_labels = Vec::with_capacity(num_labels);
@@ -259,12 +586,13 @@ impl Renderer {
             }
         }
 
-        // Find all visualizations matching ANY of the selected labels
+        // Find all visualizations matching ANY of the selected labels (weight 0 never does)
         let matching: Vec<usize> = VIZ_LABELS
             .iter()
             .enumerate()
             .filter(|(_, labels)| selected_labels.iter().any(|l| labels.contains(l)))
             .map(|(i, _)| i)
+            .filter(|&i| weights.get(i).is_none_or(|&w| w > 0.0))
             .collect();
 
         if matching.is_empty() {
@@ -272,16 +600,9 @@ impl Renderer {
             return (0, Vec::new());
         }
 
-        // Select 1-4 from matching
-        let count = rng.random_range(1..=4).min(matching.len());
-        let mut selected: Vec<usize> = Vec::with_capacity(count);
-
-        while selected.len() < count {
-            let idx = matching[rng.random_range(0..matching.len())];
-            if !selected.contains(&idx) {
-                selected.push(idx);
-            }
-        }
+        // Select the primary and overlays from matching
+        let count = rng.random_range(1..=max_overlays + 1).min(matching.len());
+        let selected = pick_weighted_distinct(rng, matching, weights, count);
 
         // First one is primary, rest are overlays
         let primary = selected[0];
@@ -303,30 +624,78 @@ impl Renderer {
 
     /// Selects new visualizations based on matching labels
     fn select_new_visualizations(&mut self) {
-        let mut rng = rand::rng();
-        let (primary, overlays) = Self::select_by_labels(&mut rng);
-        self.current_idx = primary;
-        self.overlay_indices = overlays;
+        let (primary, overlays) = Self::select_by_labels(
+            &mut self.rng,
+            self.detection_config.max_overlays(),
+            &self.viz_weights,
+        );
+        self.switch_to(primary, overlays);
     }
 
-    /// Returns indices of visualizations suitable for the given energy level
+    /// Switch to the given set now, or queue it for the next downbeat when
+    /// `quantize_switches` is enabled (a newer switch replaces a queued one)
+    fn switch_to(&mut self, primary: usize, overlays: Vec<usize>) {
+        if self.detection_config.quantize_switches() {
+            if self.pending_switch.is_none() {
+                self.pending_frames = 0;
+            }
+            self.pending_switch = Some((primary, overlays));
+        } else {
+            self.current_idx = primary;
+            self.overlay_indices = overlays;
+        }
+    }
+
+    /// Apply a queued switch on the downbeat (or after waiting too long without one).
+    /// Until the bar is locked the downbeat is a guess, so any predicted beat will do
+    fn apply_pending_switch(&mut self, analysis: &AudioAnalysis) {
+        if self.pending_switch.is_none() {
+            return;
+        }
+        self.pending_frames += 1;
+        let on_grid = if analysis.bar_locked {
+            analysis.is_downbeat
+        } else {
+            analysis.is_beat
+        };
+        if on_grid || self.pending_frames >= PENDING_SWITCH_MAX_FRAMES {
+            if let Some((primary, overlays)) = self.pending_switch.take() {
+                self.current_idx = primary;
+                self.overlay_indices = overlays;
+            }
+        }
+    }
+
+    /// Whether auto-cycling may pick this visualization (weight above 0)
+    fn auto_selectable(&self, idx: usize) -> bool {
+        self.viz_weights.get(idx).is_none_or(|&w| w > 0.0)
+    }
+
+    /// Returns indices of auto-selectable visualizations suitable for the given energy level
     fn vizs_for_energy(&self, energy: f32) -> Vec<usize> {
         self.viz_energy_ranges
             .iter()
             .enumerate()
-            .filter(|(_, range)| energy >= range[0] && energy <= range[1])
+            .filter(|(i, range)| {
+                energy >= range[0] && energy <= range[1] && self.auto_selectable(*i)
+            })
             .map(|(i, _)| i)
             .collect()
     }
 
-    /// Select visualizations matching both energy level and optionally labels
+    /// Select visualizations matching both energy level and optionally labels.
+    /// Without explicit labels, a spectral contrast (if given) picks geometric or organic ones.
+    /// Picks are weighted by `viz_weights`; weight 0 visualizations are never chosen.
     /// Returns (primary_idx, overlay_indices)
     fn select_for_energy_and_labels(
         &self,
         rng: &mut impl rand::Rng,
         energy: f32,
+        contrast: Option<f32>,
         preferred_labels: Option<&[VisLabel]>,
     ) -> (usize, Vec<usize>) {
+        let preferred_labels = preferred_labels.or_else(|| contrast.and_then(labels_for_contrast));
+
         // First filter by energy
         let energy_matches = self.vizs_for_energy(energy);
 
@@ -336,6 +705,7 @@ impl Renderer {
                 .viz_energy_ranges
                 .iter()
                 .enumerate()
+                .filter(|(i, _)| self.auto_selectable(*i))
                 .min_by(|(_, a), (_, b)| {
                     let mid_a = (a[0] + a[1]) / 2.0;
                     let mid_b = (b[0] + b[1]) / 2.0;
@@ -370,16 +740,10 @@ impl Renderer {
             candidates
         };
 
-        // Select 1-4 from candidates
-        let count = rng.random_range(1..=4).min(final_candidates.len());
-        let mut selected: Vec<usize> = Vec::with_capacity(count);
-
-        while selected.len() < count {
-            let idx = final_candidates[rng.random_range(0..final_candidates.len())];
-            if !selected.contains(&idx) {
-                selected.push(idx);
-            }
-        }
+        // Select the primary and overlays from candidates
+        let max_overlays = self.detection_config.max_overlays();
+        let count = rng.random_range(1..=max_overlays + 1).min(final_candidates.len());
+        let selected = pick_weighted_distinct(rng, final_candidates, &self.viz_weights, count);
 
         let primary = selected[0];
         let overlays = selected[1..].to_vec();
@@ -414,6 +778,43 @@ impl Renderer {
         }
     }
 
+    /// Go back to the arrangement shown before the current one, locking like a
+    /// manual selection. Returns the restored primary's name, or None when the
+    /// history is empty.
+    pub fn cycle_previous(&mut self) -> Option<&'static str> {
+        let current = (self.current_idx, self.overlay_indices.clone());
+        let (primary, overlays) = loop {
+            let entry = self.history.pop_back()?;
+            if entry != current {
+                break entry;
+            }
+        };
+        self.current_idx = primary;
+        self.overlay_indices = overlays;
+        self.restored_from_history = true;
+        self.pending_switch = None; // Explicit selection overrides a queued switch
+        self.cooldown = self.detection_config.cooldown_frames();
+        self.locked = true;
+
+        let name = Self::visualization_name(primary);
+        log_line!("Back to visualization: {}", name);
+        Some(name)
+    }
+
+    /// Record the arrangement shown last frame, unless it was left by going back
+    fn remember_arrangement(&mut self) {
+        if std::mem::take(&mut self.restored_from_history) {
+            return;
+        }
+        let Some((&primary, overlays)) = self.prev_active.split_first() else {
+            return;
+        };
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back((primary, overlays.to_vec()));
+    }
+
     /// Set a specific visualization by index and lock (disable auto-cycling)
     /// Returns the visualization name if successful
     pub fn set_visualization(&mut self, idx: usize) -> Option<&'static str> {
@@ -423,19 +824,76 @@ impl Renderer {
         }
         self.current_idx = idx;
         self.overlay_indices.clear(); // No overlays when locked to single viz
+        self.pending_switch = None; // Explicit selection overrides a queued switch
         self.cooldown = self.detection_config.cooldown_frames();
         self.locked = true;
 
         let name = Self::visualization_name(idx);
-        println!("Locked to visualization {}: {}", idx, name);
+        log_line!("Locked to visualization {}: {}", idx, name);
         Some(name)
     }
 
+    /// Current arrangement as a preset (blend mode left at its default, since
+    /// the feedback renderer owns it)
+    pub fn export_preset(&self) -> Preset {
+        Preset {
+            primary: self.current_name().to_string(),
+            overlays: self
+                .overlay_indices
+                .iter()
+                .map(|&idx| Self::visualization_name(idx).to_string())
+                .collect(),
+            locked: self.locked,
+            blend_mode: Default::default(),
+        }
+    }
+
+    /// Switch to a preset's arrangement and lock state. Unknown overlay names are
+    /// skipped; returns false (nothing changes) if the primary is unknown.
+    pub fn apply_preset(&mut self, preset: &Preset) -> bool {
+        let Some(primary) = viz_index(&preset.primary) else {
+            return false;
+        };
+        self.current_idx = primary;
+        self.overlay_indices =
+            resolve_overlays(primary, &preset.overlays, self.detection_config.max_overlays());
+        self.pending_switch = None; // Explicit selection overrides a queued switch
+        self.cooldown = self.detection_config.cooldown_frames();
+        self.locked = preset.locked;
+        true
+    }
+
+    /// Select a visualization by name (case-insensitive) and lock, like `set_visualization`.
+    /// Returns its index, or None for an unknown name (nothing changes).
+    pub fn set_visualization_by_name(&mut self, name: &str) -> Option<usize> {
+        let idx = viz_index(name)?;
+        self.set_visualization(idx);
+        Some(idx)
+    }
+
+    /// Toggle a visualization as overlay by name (case-insensitive), like `toggle_overlay`.
+    /// Returns the new overlay state, or None for an unknown name.
+    pub fn toggle_overlay_by_name(&mut self, name: &str) -> Option<bool> {
+        viz_index(name).map(|idx| self.toggle_overlay(idx))
+    }
+
+    /// Name of the current primary visualization
+    pub fn current_name(&self) -> &'static str {
+        Self::visualization_name(self.current_idx)
+    }
+
     /// Get visualization name by index
     fn visualization_name(idx: usize) -> &'static str {
         Viz::name(idx)
     }
 
+    /// Advance cycling and the active visualizations by one frame.
+    ///
+    /// Auto-switching normally follows a priority ladder of musical events
+    /// (punch, instrument added/removed, break, transition) that grows and shrinks
+    /// the overlay set gradually. With `switch_on_viz_change` the analyzer's own
+    /// `viz_change_triggered` (a drastic band change at high energy) comes first
+    /// and always replaces the primary, for fewer but more decisive cuts.
     pub fn update(&mut self, analysis: &AudioAnalysis, bounds: Rect) {
         // Update cooldowns
         if self.cooldown > 0 {
@@ -448,44 +906,80 @@ impl Renderer {
             }
         }
 
+        // Fade toward black while silent, back in quickly when audio returns
+        if analysis.silence_detected && self.detection_config.dim_on_silence() {
+            self.silence_dim = (self.silence_dim + 1.0 / SILENCE_FADE_OUT_FRAMES).min(1.0);
+        } else {
+            self.silence_dim = (self.silence_dim - 1.0 / SILENCE_FADE_IN_FRAMES).max(0.0);
+        }
+
         // Track energy smoothly for selection decisions
         self.tracked_energy = self.tracked_energy * 0.9 + analysis.energy * 0.1;
+        self.tracked_contrast = self.tracked_contrast * 0.9 + analysis.spectral_contrast * 0.1;
+
+        // Land queued switches on the beat grid
+        self.apply_pending_switch(analysis);
 
         // Skip auto-switching if locked or in cooldown
         if !self.locked && self.cooldown == 0 && self.visualizations.len() > 1 {
-            let mut rng = rand::rng();
+            // Work on a copy so selection helpers can borrow self (written back below)
+            let mut rng = self.rng.clone();
             let cooldown_frames = self.detection_config.cooldown_frames();
             let energy_drop_rate = self.detection_config.energy_drop_rate();
+            let contrast = self
+                .detection_config
+                .contrast_selection()
+                .then_some(self.tracked_contrast);
 
             // Require bass to be high for viz changes (sync with kick drums)
             const BASS_PEAK_THRESHOLD: f32 = 0.6;
             let bass_is_peak = analysis.bass > BASS_PEAK_THRESHOLD;
 
+            // Priority 0 (opt-in): analyzer-driven switch forces a new primary
+            if analysis.viz_change_triggered && self.detection_config.switch_on_viz_change() {
+                let (mut primary, mut overlays) =
+                    self.select_for_energy_and_labels(&mut rng, analysis.energy, contrast, None);
+                if primary == self.current_idx {
+                    let others: Vec<usize> = (0..self.visualizations.len())
+                        .filter(|&i| i != self.current_idx)
+                        .collect();
+                    if let Some(pos) = pick_weighted(&mut rng, &others, &self.viz_weights) {
+                        primary = others[pos];
+                        overlays.retain(|&i| i != primary);
+                    }
+                }
+                self.switch_to(primary, overlays);
+                self.cooldown = cooldown_frames;
+                log_line!("Viz change! Switched to {}", Self::visualization_name(primary));
+            }
             // Priority 1: Punch detection - major visual change
-            if analysis.punch_detected && bass_is_peak {
+            else if analysis.punch_detected && bass_is_peak {
                 let (primary, overlays) =
-                    self.select_for_energy_and_labels(&mut rng, analysis.energy, None);
+                    self.select_for_energy_and_labels(&mut rng, analysis.energy, contrast, None);
                 let overlay_count = overlays.len();
-                self.current_idx = primary;
-                self.overlay_indices = overlays;
+                self.switch_to(primary, overlays);
                 self.cooldown = cooldown_frames * 2; // Longer cooldown for punches
-                println!(
+                log_line!(
                     "PUNCH! Switched to {} with {} overlays",
                     Self::visualization_name(primary),
                     overlay_count
                 );
             }
             // Priority 2: Instrument added - add overlay
-            else if analysis.instrument_added && self.overlay_indices.len() < 3 {
+            else if analysis.instrument_added
+                && self.overlay_indices.len() < self.detection_config.max_overlays()
+            {
                 let candidates = self.vizs_for_energy(analysis.energy);
-                if !candidates.is_empty() {
-                    let new_overlay = candidates[rng.random_range(0..candidates.len())];
+                if let Some(pos) = pick_weighted(&mut rng, &candidates, &self.viz_weights) {
+                    let new_overlay = candidates[pos];
                     if !self.overlay_indices.contains(&new_overlay)
                         && new_overlay != self.current_idx
                     {
-                        self.overlay_indices.push(new_overlay);
+                        let mut overlays = self.overlay_indices.clone();
+                        overlays.push(new_overlay);
+                        self.switch_to(self.current_idx, overlays);
                         self.cooldown = cooldown_frames / 2;
-                        println!(
+                        log_line!(
                             "Instrument added: +overlay {}",
                             Self::visualization_name(new_overlay)
                         );
@@ -496,9 +990,11 @@ impl Renderer {
             else if analysis.instrument_removed
                 || (analysis.rise_rate < energy_drop_rate && self.tracked_energy < 0.4)
             {
-                if !self.overlay_indices.is_empty() {
-                    let removed = self.overlay_indices.pop();
-                    println!(
+                let mut primary = self.current_idx;
+                let mut overlays = self.overlay_indices.clone();
+                if !overlays.is_empty() {
+                    let removed = overlays.pop();
+                    log_line!(
                         "Energy/instrument drop: -overlay {:?}",
                         removed.map(Self::visualization_name)
                     );
@@ -507,51 +1003,148 @@ impl Renderer {
                 // If energy is low, also switch primary to calmer viz
                 if self.tracked_energy < 0.3 {
                     let calm_vizs = self.vizs_for_energy(self.tracked_energy);
-                    if !calm_vizs.is_empty() && !calm_vizs.contains(&self.current_idx) {
-                        self.current_idx = calm_vizs[rng.random_range(0..calm_vizs.len())];
+                    let pick = pick_weighted(&mut rng, &calm_vizs, &self.viz_weights)
+                        .filter(|_| !calm_vizs.contains(&self.current_idx));
+                    if let Some(pos) = pick {
+                        primary = calm_vizs[pos];
                         self.cooldown = cooldown_frames;
-                        println!(
+                        log_line!(
                             "Energy low: switched to calmer {}",
-                            Self::visualization_name(self.current_idx)
+                            Self::visualization_name(primary)
                         );
                     }
                 }
+
+                if primary != self.current_idx || overlays != self.overlay_indices {
+                    self.switch_to(primary, overlays);
+                }
             }
             // Priority 4: Break detected - dramatic change (requires bass peak)
             else if analysis.break_detected && bass_is_peak {
                 let (primary, overlays) =
-                    self.select_for_energy_and_labels(&mut rng, self.tracked_energy, None);
-                self.current_idx = primary;
-                self.overlay_indices = overlays;
+                    self.select_for_energy_and_labels(&mut rng, self.tracked_energy, contrast, None);
+                self.switch_to(primary, overlays);
                 self.cooldown = cooldown_frames;
                 // println!("Break! Switched to {}", Self::visualization_name(primary));
             }
             // Priority 5: Regular transition - existing behavior but energy-aware (requires bass peak)
             else if analysis.transition_detected && bass_is_peak {
                 let (primary, overlays) =
-                    self.select_for_energy_and_labels(&mut rng, self.tracked_energy, None);
-                self.current_idx = primary;
-                self.overlay_indices = overlays;
+                    self.select_for_energy_and_labels(&mut rng, self.tracked_energy, contrast, None);
+                self.switch_to(primary, overlays);
                 self.cooldown = cooldown_frames;
             }
+
+            self.rng = rng;
         }
 
-        // Update the active visualization
-        self.visualizations[self.current_idx].update(analysis);
+        // Crossfade out of the previous primary, however it got switched out
+        let crossfade_frames = self.detection_config.crossfade_frames();
+        match self.prev_active.first() {
+            Some(&previous) if previous != self.current_idx && crossfade_frames > 0 => {
+                self.transition = Some(Transition {
+                    outgoing: previous,
+                    progress: 0.0,
+                });
+            }
+            _ => {}
+        }
+        if let Some(transition) = &mut self.transition {
+            transition.progress += 1.0 / crossfade_frames.max(1) as f32;
+            if transition.progress >= 1.0 || transition.outgoing == self.current_idx {
+                self.transition = None;
+            }
+        }
 
-        // Update overlay visualizations
-        for &idx in &self.overlay_indices {
-            self.visualizations[idx].update(analysis);
+        // Start fresh and warm up anything that just became active, however it got switched in
+        let active: Vec<usize> = std::iter::once(self.current_idx)
+            .chain(self.overlay_indices.iter().copied())
+            .collect();
+        if active != self.prev_active {
+            self.remember_arrangement();
+        }
+        for &idx in &active {
+            if !self.prev_active.contains(&idx) {
+                let seed = self.viz_rng.random();
+                self.visualizations[idx].reset(StdRng::seed_from_u64(seed));
+                self.visualizations[idx].warm_up(analysis, WARM_UP_FRAMES);
+            }
+        }
+        self.prev_active = active;
+
+        // Step everything on screen. Below the nominal rate the frame takes extra
+        // steps first, without its events, so only the last one fires them
+        if self.steps_per_frame > 1 {
+            let between = analysis.without_events();
+            for _ in 1..self.steps_per_frame {
+                self.step_visualizations(&between);
+            }
         }
+        self.step_visualizations(analysis);
 
         // Always update debug viz (even if not visible, so it's ready when toggled)
         let viz_info = self.viz_info();
         self.debug_viz.update(analysis, bounds, &viz_info);
     }
 
-    /// Draw the primary visualization
+    /// Advance the primary, its overlays and a fading-out primary by one step
+    fn step_visualizations(&mut self, analysis: &AudioAnalysis) {
+        self.visualizations[self.current_idx].update(analysis);
+        for &idx in &self.overlay_indices {
+            self.visualizations[idx].update(analysis);
+        }
+        // Keep the outgoing primary moving while it fades (unless it's still an overlay)
+        if let Some(transition) = &self.transition {
+            if !self.overlay_indices.contains(&transition.outgoing) {
+                self.visualizations[transition.outgoing].update(analysis);
+            }
+        }
+    }
+
+    /// Match the animation tempo to a frame rate below the nominal 60fps (eco
+    /// mode's cap) by stepping the visualizations more than once per frame.
+    /// Switching and fade timings stay counted in frames
+    pub fn set_frame_rate(&mut self, fps: f32) {
+        self.steps_per_frame = (NOMINAL_FRAME_RATE / fps.max(1.0)).round().max(1.0) as u32;
+    }
+
+    /// Whether the primary visualization wants feedback trails
+    pub fn primary_uses_feedback(&self) -> bool {
+        self.visualizations[self.current_idx].uses_feedback()
+    }
+
+    /// Draw the primary visualization. While no audio plays it is dimmed by a
+    /// black veil. During a crossfade the outgoing one draws separately
+    /// (`draw_outgoing`) and the feedback pass mixes the two
     pub fn draw_primary(&self, draw: &Draw, bounds: Rect) {
         self.visualizations[self.current_idx].draw(draw, bounds);
+        self.draw_silence_dim(draw, bounds);
+    }
+
+    /// Draw the primary being faded out, returning the crossfade progress
+    /// (the outgoing drawing's weight is `1 - progress`, the primary's is
+    /// `progress`), or None when no crossfade is running
+    pub fn draw_outgoing(&self, draw: &Draw, bounds: Rect) -> Option<f32> {
+        let transition = self.transition.as_ref()?;
+        self.visualizations[transition.outgoing].draw(draw, bounds);
+        self.draw_silence_dim(draw, bounds);
+        Some(transition.progress)
+    }
+
+    fn draw_silence_dim(&self, draw: &Draw, bounds: Rect) {
+        if self.silence_dim > 0.0 {
+            draw.rect()
+                .xy(bounds.xy())
+                .wh(bounds.wh())
+                .color(srgba(0.0, 0.0, 0.0, self.silence_dim));
+        }
+    }
+
+    /// Draw one visualization by index in its current state (picker thumbnails)
+    pub fn draw_visualization(&self, idx: usize, draw: &Draw, bounds: Rect) {
+        if let Some(viz) = self.visualizations.get(idx) {
+            viz.draw(draw, bounds);
+        }
     }
 
     /// Draw overlay visualizations (to be blended with burn effect)
@@ -568,6 +1161,21 @@ impl Renderer {
         self.overlay_indices.len()
     }
 
+    /// Blend opacities of the active overlays, in overlay order (dimmed while silent)
+    pub fn overlay_alphas(&self) -> Vec<f32> {
+        self.overlay_indices
+            .iter()
+            .map(|&idx| self.overlay_alphas[idx] * (1.0 - self.silence_dim))
+            .collect()
+    }
+
+    /// Adjust a visualization's overlay opacity by `delta`, returning the new value
+    pub fn nudge_overlay_alpha(&mut self, idx: usize, delta: f32) -> Option<f32> {
+        let alpha = self.overlay_alphas.get_mut(idx)?;
+        *alpha = (*alpha + delta).clamp(0.0, 1.0);
+        Some(*alpha)
+    }
+
     /// Draw notification overlay (should be drawn after all visualizations)
     pub fn draw_notification(&self, draw: &Draw, bounds: Rect) {
         if let Some(ref text) = self.notification_text {
@@ -617,32 +1225,47 @@ impl Renderer {
             .collect();
 
         VizInfo {
-            primary_name: Self::visualization_name(self.current_idx).to_string(),
+            primary_name: self.current_name().to_string(),
             overlay_names,
         }
     }
 
+    /// Drop all overlays, keeping the primary and the lock state.
+    /// Auto-cycling can add overlays again afterwards.
+    /// Returns how many overlays were removed.
+    pub fn clear_overlays(&mut self) -> usize {
+        let cleared = self.overlay_indices.len();
+        self.overlay_indices.clear();
+        if cleared > 0 {
+            log_line!("Cleared {} overlay(s)", cleared);
+        }
+        cleared
+    }
+
     /// Toggle a visualization as overlay (or remove if already overlay)
     /// If it's the primary, this does nothing.
     /// Returns the new overlay state.
     pub fn toggle_overlay(&mut self, idx: usize) -> bool {
+        let max_overlays = self.detection_config.max_overlays();
         if idx >= self.visualizations.len() || idx == self.current_idx {
             return false;
         }
 
         if let Some(pos) = self.overlay_indices.iter().position(|&i| i == idx) {
             self.overlay_indices.remove(pos);
-            println!("Removed overlay: {}", Self::visualization_name(idx));
+            log_line!("Removed overlay: {}", Self::visualization_name(idx));
             false
-        } else if self.overlay_indices.len() < 3 {
+        } else if self.overlay_indices.len() < max_overlays {
             self.overlay_indices.push(idx);
-            println!("Added overlay: {}", Self::visualization_name(idx));
+            log_line!("Added overlay: {}", Self::visualization_name(idx));
             true
+        } else if max_overlays == 0 {
+            false
         } else {
-            // Max 3 overlays, replace the oldest one
+            // At the overlay cap, replace the oldest one
             self.overlay_indices.remove(0);
             self.overlay_indices.push(idx);
-            println!("Replaced overlay with: {}", Self::visualization_name(idx));
+            log_line!("Replaced overlay with: {}", Self::visualization_name(idx));
             true
         }
     }
@@ -660,3 +1283,310 @@ impl Renderer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_resolve_start_scene() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(resolve_start_scene(None, &names(&["Squares"]), 3), None);
+        assert_eq!(resolve_start_scene(Some("NotAViz"), &[], 3), None);
+
+        // Case-insensitive, unknowns / duplicates / the primary skipped, capped
+        let (primary, overlays) = resolve_start_scene(
+            Some("lavablobs"),
+            &names(&["Squares", "Bogus", "squares", "LavaBlobs", "BeatBars", "TeslaCoil", "FractalTree"]),
+            3,
+        )
+        .unwrap();
+        assert_eq!(VIZ_NAMES[primary], "LavaBlobs");
+        let overlay_names: Vec<_> = overlays.iter().map(|&i| VIZ_NAMES[i]).collect();
+        assert_eq!(overlay_names, ["Squares", "BeatBars", "TeslaCoil"]);
+
+        // A larger configured cap lets more through
+        let (_, overlays) =
+            resolve_start_scene(Some("LavaBlobs"), &names(&["Squares", "BeatBars", "TeslaCoil", "FractalTree"]), 5)
+                .unwrap();
+        assert_eq!(overlays.len(), 4);
+    }
+
+    #[test]
+    fn test_named_energy_ranges_survive_reordering() {
+        let config: Config =
+            toml::from_str("[energy_ranges]\nlavablobs = [0.2, 0.3]\nNoSuchViz = [0.0, 0.1]\n").unwrap();
+        let mut reordered = VIZ_NAMES.to_vec();
+        reordered.reverse();
+        reordered.push("NewViz");
+
+        let ranges = energy_ranges(&config);
+        let reordered_ranges = resolve_energy_ranges(&reordered, &config);
+        for (idx, name) in VIZ_NAMES.iter().enumerate() {
+            let moved = reordered.iter().position(|n| n == name).unwrap();
+            assert_eq!(ranges[idx], reordered_ranges[moved], "{}", name);
+        }
+
+        // Configured by name, defaults for the rest, the fallback for unknown vizs
+        assert_eq!(ranges[viz_index("LavaBlobs").unwrap()], [0.2, 0.3]);
+        assert_eq!(ranges[viz_index("SolarBeat").unwrap()], [0.5, 0.9]);
+        assert_eq!(reordered_ranges.last(), Some(&[0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_select_and_toggle_by_name() {
+        let mut renderer =
+            Renderer::with_cycling(DetectionConfig::default(), energy_ranges(&Config::default()), None, &[]);
+
+        let idx = renderer.set_visualization_by_name("kaleidoscope").unwrap();
+        assert_eq!(VIZ_NAMES[idx], "Kaleidoscope");
+        assert_eq!(renderer.current_idx(), idx);
+        assert_eq!(renderer.current_name(), "Kaleidoscope");
+        assert!(renderer.is_locked());
+
+        assert_eq!(renderer.toggle_overlay_by_name("LAVABLOBS"), Some(true));
+        assert_eq!(renderer.viz_info().overlay_names, ["LavaBlobs"]);
+
+        assert_eq!(renderer.set_visualization_by_name("NoSuchViz"), None);
+        assert_eq!(renderer.toggle_overlay_by_name("NoSuchViz"), None);
+        assert_eq!(renderer.current_name(), "Kaleidoscope");
+    }
+
+    #[test]
+    fn test_switch_crossfades_from_previous_primary() {
+        let config = DetectionConfig {
+            crossfade_frames: Some(4),
+            ..DetectionConfig::default()
+        };
+        let mut renderer = Renderer::with_cycling(config, energy_ranges(&Config::default()), None, &[]);
+        let bounds = Rect::from_w_h(640.0, 480.0);
+        let analysis = AudioAnalysis::default();
+
+        renderer.set_visualization(0);
+        renderer.update(&analysis, bounds);
+
+        renderer.set_visualization(1);
+        renderer.update(&analysis, bounds);
+        let transition = renderer.transition.as_ref().unwrap();
+        assert_eq!(transition.outgoing, 0);
+        assert_eq!(renderer.draw_outgoing(&Draw::new(), bounds), Some(0.25));
+
+        for _ in 0..3 {
+            renderer.update(&analysis, bounds);
+        }
+        assert!(renderer.transition.is_none());
+        assert_eq!(renderer.draw_outgoing(&Draw::new(), bounds), None);
+    }
+
+    #[test]
+    fn test_quantized_switch_waits_for_the_downbeat() {
+        let config = DetectionConfig {
+            quantize_switches: Some(true),
+            ..DetectionConfig::default()
+        };
+        let mut renderer = Renderer::with_cycling(config, energy_ranges(&Config::default()), None, &[]);
+        let bounds = Rect::from_w_h(640.0, 480.0);
+        let quiet = AudioAnalysis::default();
+        let kick = AudioAnalysis {
+            kick_detected: true,
+            ..AudioAnalysis::default()
+        };
+        let beat = AudioAnalysis {
+            is_beat: true,
+            ..AudioAnalysis::default()
+        };
+        let locked_kick = AudioAnalysis {
+            bar_locked: true,
+            ..kick.clone()
+        };
+        let locked_beat = AudioAnalysis {
+            bar_locked: true,
+            ..beat.clone()
+        };
+        let locked_downbeat = AudioAnalysis {
+            is_downbeat: true,
+            ..locked_beat.clone()
+        };
+        renderer.set_visualization(0);
+
+        // Bar locked: a raw kick or an ordinary beat doesn't apply it, beat one does
+        renderer.switch_to(1, Vec::new());
+        renderer.update(&locked_kick, bounds);
+        renderer.update(&locked_beat, bounds);
+        assert_eq!(renderer.current_idx(), 0);
+        renderer.update(&locked_downbeat, bounds);
+        assert_eq!(renderer.current_idx(), 1);
+
+        // No bar lock yet: the next predicted beat is the fallback
+        renderer.switch_to(2, Vec::new());
+        renderer.update(&kick, bounds);
+        assert_eq!(renderer.current_idx(), 1);
+        renderer.update(&beat, bounds);
+        assert_eq!(renderer.current_idx(), 2);
+
+        // Without any beat it still goes through eventually
+        renderer.switch_to(3, Vec::new());
+        for _ in 1..PENDING_SWITCH_MAX_FRAMES {
+            renderer.update(&quiet, bounds);
+        }
+        assert_eq!(renderer.current_idx(), 2);
+        renderer.update(&quiet, bounds);
+        assert_eq!(renderer.current_idx(), 3);
+    }
+
+    #[test]
+    fn test_frame_rate_sets_animation_steps() {
+        let mut renderer =
+            Renderer::with_cycling(DetectionConfig::default(), energy_ranges(&Config::default()), None, &[]);
+        for (fps, steps) in [(60.0, 1), (144.0, 1), (30.0, 2), (20.0, 3)] {
+            renderer.set_frame_rate(fps);
+            assert_eq!(renderer.steps_per_frame, steps, "{} fps", fps);
+        }
+
+        // Extra steps replay the frame's levels without its events
+        let analysis = AudioAnalysis {
+            bpm: 128.0,
+            is_beat: true,
+            kick_detected: true,
+            band_onset: vec![true; 8],
+            ..AudioAnalysis::default()
+        };
+        let between = analysis.without_events();
+        assert_eq!(between.bpm, 128.0);
+        assert!(!between.is_beat && !between.kick_detected);
+        assert_eq!(between.band_onset, [false; 8]);
+        renderer.update(&analysis, Rect::from_w_h(640.0, 480.0));
+    }
+
+    #[test]
+    fn test_seeded_renderers_cycle_identically() {
+        let seeded = || {
+            Renderer::with_cycling_seeded(
+                DetectionConfig::default(),
+                energy_ranges(&Config::default()),
+                None,
+                &[],
+                42,
+            )
+        };
+        let (mut a, mut b) = (seeded(), seeded());
+        let analysis = AudioAnalysis::default();
+        for _ in 0..20 {
+            assert_eq!(a.current_idx(), b.current_idx());
+            assert_eq!(a.overlay_indices(), b.overlay_indices());
+            a.cycle_next(&analysis);
+            b.cycle_next(&analysis);
+        }
+        assert_eq!(a.current_idx(), b.current_idx());
+    }
+
+    #[test]
+    fn test_zero_weight_viz_is_never_auto_selected() {
+        let config = DetectionConfig {
+            viz_weights: Some(std::collections::BTreeMap::from([
+                ("lavablobs".to_string(), 0.0),
+                ("Squares".to_string(), 4.0),
+            ])),
+            ..DetectionConfig::default()
+        };
+        let excluded = viz_index("LavaBlobs").unwrap();
+        let weights = resolve_viz_weights(&config);
+        assert_eq!(weights[viz_index("Squares").unwrap()], 4.0);
+        let mut renderer =
+            Renderer::with_cycling_seeded(config, energy_ranges(&Config::default()), None, &[], 7);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let (primary, overlays) = Renderer::select_by_labels(&mut rng, 3, &weights);
+            assert!(primary != excluded && !overlays.contains(&excluded));
+
+            let energy = rng.random_range(0.0..1.0);
+            let (primary, overlays) =
+                renderer.select_for_energy_and_labels(&mut rng, energy, None, None);
+            assert!(primary != excluded && !overlays.contains(&excluded));
+        }
+
+        // Manual selection still works
+        renderer.set_visualization(excluded);
+        assert_eq!(renderer.current_idx(), excluded);
+    }
+
+    #[test]
+    fn test_cycle_previous_walks_back_through_history() {
+        let mut renderer =
+            Renderer::with_cycling(DetectionConfig::default(), energy_ranges(&Config::default()), None, &[]);
+        let bounds = Rect::from_w_h(640.0, 480.0);
+        let analysis = AudioAnalysis::default();
+
+        for idx in 0..3 {
+            renderer.set_visualization(idx);
+            renderer.update(&analysis, bounds);
+        }
+        renderer.toggle_overlay(5);
+        renderer.update(&analysis, bounds);
+
+        renderer.locked = false;
+        assert_eq!(renderer.cycle_previous(), Some(VIZ_NAMES[2]));
+        assert!(renderer.overlay_indices().is_empty());
+        assert!(renderer.is_locked());
+        renderer.update(&analysis, bounds);
+
+        // Going back doesn't record the arrangement it left
+        assert_eq!(renderer.cycle_previous(), Some(VIZ_NAMES[1]));
+        renderer.update(&analysis, bounds);
+        assert_eq!(renderer.cycle_previous(), Some(VIZ_NAMES[0]));
+        renderer.update(&analysis, bounds);
+        assert_eq!(renderer.current_idx(), 0);
+
+        // The history is capped
+        for idx in 0..HISTORY_LEN * 2 {
+            renderer.set_visualization(idx % VIZ_NAMES.len());
+            renderer.update(&analysis, bounds);
+        }
+        assert_eq!(renderer.history.len(), HISTORY_LEN);
+    }
+
+    #[test]
+    fn test_viz_change_trigger_forces_switch() {
+        let config = DetectionConfig {
+            switch_on_viz_change: Some(true),
+            ..DetectionConfig::default()
+        };
+        let mut renderer = Renderer::with_cycling(config, energy_ranges(&Config::default()), None, &[]);
+        let bounds = Rect::from_w_h(640.0, 480.0);
+        let analysis = AudioAnalysis {
+            viz_change_triggered: true,
+            energy: 0.5,
+            ..AudioAnalysis::default()
+        };
+
+        for _ in 0..5 {
+            let before = renderer.current_idx();
+            renderer.update(&analysis, bounds);
+            assert_ne!(renderer.current_idx(), before);
+            renderer.cooldown = 0;
+        }
+    }
+
+    #[test]
+    fn test_catalog_lists_every_viz_and_label() {
+        let ranges = [[0.5, 1.0]];
+        let catalog: serde_json::Value =
+            serde_json::from_str(&catalog_json(&ranges)).expect("catalog is valid JSON");
+
+        let vizs = catalog["visualizations"].as_array().unwrap();
+        assert_eq!(vizs.len(), VIZ_NAMES.len());
+        assert_eq!(vizs[0]["energy_range"], serde_json::json!([0.5, 1.0]));
+        assert!(vizs[1]["energy_range"].is_null());
+
+        let labels = catalog["labels"].as_array().unwrap();
+        assert_eq!(labels.len(), ALL_LABELS.len());
+        let cartoon = labels.iter().find(|l| l["label"] == "Cartoon").unwrap();
+        assert_eq!(
+            cartoon["visualizations"],
+            serde_json::json!(["DancingSkeletons", "ShufflingSkeletons"])
+        );
+    }
+}
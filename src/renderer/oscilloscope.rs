@@ -0,0 +1,176 @@
+//! Oscilloscope visualization.
+//!
+//! Draws the raw waveform as a glowing trace across the screen, triggered on a
+//! rising zero crossing so periodic sounds stand still. The trace shifts from
+//! cool green to hot magenta as the energy rises.
+
+use super::Visualization;
+use nannou::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::audio::AudioAnalysis;
+
+/// Fraction of the waveform searched for the trigger; the rest is displayed
+const TRIGGER_WINDOW: f32 = 0.25;
+/// Weight of the previous trace when blending in a new frame (phosphor persistence)
+const PERSISTENCE: f32 = 0.35;
+/// Auto-gain target: the smoothed peak fills this fraction of the half-height
+const TARGET_PEAK: f32 = 0.8;
+/// Most amplification applied to quiet signals (keeps the noise floor flat)
+const MAX_GAIN: f32 = 8.0;
+
+pub struct Oscilloscope {
+    /// Triggered, persistence-blended trace (-1 to 1)
+    trace: Vec<f32>,
+    /// Smoothed waveform peak for auto-gain
+    peak: f32,
+    /// Smoothed energy (drives color and glow)
+    energy: f32,
+    /// Smoothed bass (thickens the trace)
+    bass: f32,
+}
+
+impl Default for Oscilloscope {
+    fn default() -> Self {
+        Self {
+            trace: Vec::new(),
+            peak: 0.0,
+            energy: 0.0,
+            bass: 0.0,
+        }
+    }
+}
+
+impl Oscilloscope {
+    /// Index of the first rising zero crossing in the trigger window (0 if none)
+    fn trigger(waveform: &[f32]) -> usize {
+        let window = (waveform.len() as f32 * TRIGGER_WINDOW) as usize;
+        (1..window.min(waveform.len()))
+            .find(|&i| waveform[i - 1] < 0.0 && waveform[i] >= 0.0)
+            .unwrap_or(0)
+    }
+
+    /// Amplification applied to the trace
+    fn gain(&self) -> f32 {
+        (TARGET_PEAK / self.peak.max(1e-3)).min(MAX_GAIN)
+    }
+}
+
+impl Visualization for Oscilloscope {
+    fn update(&mut self, analysis: &AudioAnalysis) {
+        self.energy = self.energy * 0.85 + analysis.energy * 0.15;
+        self.bass = self.bass * 0.7 + analysis.bass * 0.3;
+
+        let waveform = &analysis.waveform;
+        let shown = waveform.len() - (waveform.len() as f32 * TRIGGER_WINDOW) as usize;
+        let start = Self::trigger(waveform);
+        let frame = &waveform[start..start + shown];
+
+        // Peak rises instantly and falls slowly, so gain doesn't pump on every beat
+        let frame_peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        self.peak = if frame_peak > self.peak {
+            frame_peak
+        } else {
+            self.peak * 0.98 + frame_peak * 0.02
+        };
+
+        if self.trace.len() != frame.len() {
+            self.trace = frame.to_vec();
+        } else {
+            for (point, &sample) in self.trace.iter_mut().zip(frame) {
+                *point = *point * PERSISTENCE + sample * (1.0 - PERSISTENCE);
+            }
+        }
+    }
+
+    fn draw(&self, draw: &Draw, bounds: Rect) {
+        if self.trace.len() < 2 {
+            return;
+        }
+
+        let half_height = bounds.h() / 2.0;
+        let gain = self.gain();
+        let last = (self.trace.len() - 1) as f32;
+        let points: Vec<Point2> = self
+            .trace
+            .iter()
+            .enumerate()
+            .map(|(i, &sample)| {
+                let x = bounds.left() + bounds.w() * i as f32 / last;
+                let y = bounds.y() + (sample * gain).clamp(-1.0, 1.0) * half_height;
+                pt2(x, y)
+            })
+            .collect();
+
+        // Faint graticule: center line and quarter marks
+        let grid = rgba(0.3, 0.5, 0.4, 0.15);
+        draw.line()
+            .start(pt2(bounds.left(), bounds.y()))
+            .end(pt2(bounds.right(), bounds.y()))
+            .weight(1.0)
+            .color(grid);
+        for quarter in [-0.5, 0.5] {
+            let y = bounds.y() + quarter * half_height;
+            draw.line()
+                .start(pt2(bounds.left(), y))
+                .end(pt2(bounds.right(), y))
+                .weight(1.0)
+                .color(grid);
+        }
+
+        // Green at rest, through cyan and blue to magenta at full energy
+        let energy = self.energy.clamp(0.0, 1.0);
+        let hue = 0.33 + energy * 0.5;
+        let weight = 2.0 + self.bass * 4.0;
+
+        // Wide translucent glow under a bright core
+        draw.polyline()
+            .weight(weight * 4.0)
+            .points(points.iter().copied())
+            .color(hsva(hue, 0.9, 0.8, 0.15 + energy * 0.15));
+        draw.polyline()
+            .weight(weight)
+            .points(points)
+            .color(hsva(hue, 0.6, 1.0, 0.9));
+    }
+
+    fn reset(&mut self, _rng: StdRng) {
+        self.trace.clear();
+        self.peak = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_scope_triggers_and_draws_waveform() {
+        // Two cycles of a sine starting mid-way down, so the trigger has to skip ahead
+        let waveform: Vec<f32> = (0..512)
+            .map(|i| 0.25 * (i as f32 / 256.0 * TAU + PI * 1.25).sin())
+            .collect();
+        let start = Oscilloscope::trigger(&waveform);
+        assert!(start > 0 && waveform[start] >= 0.0 && waveform[start - 1] < 0.0);
+
+        let analysis = AudioAnalysis {
+            waveform,
+            energy: 0.5,
+            ..AudioAnalysis::default()
+        };
+        let mut scope = Oscilloscope::default();
+        scope.update(&analysis);
+        assert_eq!(scope.trace.len(), 384);
+        assert!(scope.trace[0] >= 0.0, "trace starts on the rising edge");
+        assert!((scope.gain() * 0.25 - TARGET_PEAK).abs() < 0.01);
+        scope.draw(&Draw::new(), Rect::from_w_h(640.0, 480.0));
+
+        // Silence (and a default analysis) draws a flat line without blowing up the gain
+        scope.reset(StdRng::seed_from_u64(0));
+        scope.update(&AudioAnalysis::default());
+        assert!(scope.trace.iter().all(|&s| s == 0.0));
+        assert_eq!(scope.gain(), MAX_GAIN);
+        scope.draw(&Draw::new(), Rect::from_w_h(640.0, 480.0));
+    }
+}
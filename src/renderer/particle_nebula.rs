@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
+use rand::rngs::StdRng;
 use rand::Rng;
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgba;
 
 /// Number of particles
 const NUM_PARTICLES: usize = if cfg!(debug_assertions) { 300 } else { 800 };
@@ -51,7 +53,13 @@ pub struct ParticleNebula {
 
 impl Default for ParticleNebula {
     fn default() -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(&mut rand::rng())
+    }
+}
+
+impl ParticleNebula {
+    /// Fresh particles scattered by `rng`
+    fn with_rng(rng: &mut impl Rng) -> Self {
 
         let particles: Vec<Particle> = (0..NUM_PARTICLES)
             .map(|i| {
@@ -85,36 +93,6 @@ impl Default for ParticleNebula {
     }
 }
 
-impl ParticleNebula {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for ParticleNebula {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
@@ -148,7 +126,8 @@ impl Visualization for ParticleNebula {
         // Update particles
         for particle in &mut self.particles {
             // Orbital motion - speed affected by corresponding band
-            let band_energy = analysis.bands_normalized[particle.band_idx];
+            let band_energy =
+                band_group(&analysis.bands_normalized, particle.band_idx, DEFAULT_NUM_BANDS);
             particle.angle += particle.speed * (1.0 + band_energy * 2.0);
 
             if particle.angle > std::f32::consts::TAU {
@@ -194,7 +173,7 @@ impl Visualization for ParticleNebula {
                     let y2 = center.y + r2 * angle2.sin() * 0.6 + p2.z_offset * max_radius * 0.3;
 
                     let hue = (p1.hue + self.hue_offset) % 360.0;
-                    let color = Self::hsv_to_rgba(hue, 0.5, 0.6, line_alpha);
+                    let color = hsv_to_rgba(hue, 0.5, 0.6, line_alpha);
 
                     draw.line()
                         .start(pt2(x1, y1))
@@ -223,14 +202,14 @@ impl Visualization for ParticleNebula {
             let value = 0.4 + depth_factor * 0.3 + self.bass * 0.3;
             let alpha = 0.4 + depth_factor * 0.3;
 
-            let color = Self::hsv_to_rgba(hue, saturation, value, alpha);
+            let color = hsv_to_rgba(hue, saturation, value, alpha);
 
             // Draw particle with glow
             let glow_size = particle.size * 2.0;
             draw.ellipse()
                 .x_y(x, y)
                 .w_h(glow_size, glow_size)
-                .color(Self::hsv_to_rgba(hue, saturation * 0.5, value * 0.5, alpha * 0.3));
+                .color(hsv_to_rgba(hue, saturation * 0.5, value * 0.5, alpha * 0.3));
 
             draw.ellipse()
                 .x_y(x, y)
@@ -245,7 +224,7 @@ impl Visualization for ParticleNebula {
             let r = core_radius * (1.0 - t * 0.8);
             let hue = (self.hue_offset + t * 30.0) % 360.0;
             let alpha = (1.0 - t) * 0.2;
-            let color = Self::hsv_to_rgba(hue, 0.4, 0.8, alpha);
+            let color = hsv_to_rgba(hue, 0.4, 0.8, alpha);
 
             draw.ellipse()
                 .xy(center)
@@ -253,4 +232,8 @@ impl Visualization for ParticleNebula {
                 .color(color);
         }
     }
+
+    fn reset(&mut self, mut rng: StdRng) {
+        *self = Self::with_rng(&mut rng);
+    }
 }
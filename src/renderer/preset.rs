@@ -0,0 +1,84 @@
+//! Saved visualization arrangements.
+//!
+//! A preset records the primary and overlay visualizations by name, whether
+//! auto-cycling is locked, and the overlay blend mode, so a combination built
+//! for a track can be recalled later. Presets are stored as TOML, or JSON when
+//! the file name ends in `.json`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::effects::zoom::BlendMode;
+
+/// A primary + overlays arrangement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub primary: String,
+    #[serde(default)]
+    pub overlays: Vec<String>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub blend_mode: BlendMode,
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Write a preset to `path`, creating its directory if needed
+pub fn save_preset(preset: &Preset, path: &Path) -> Result<(), String> {
+    let content = if is_json(path) {
+        serde_json::to_string_pretty(preset).map_err(|e| e.to_string())?
+    } else {
+        toml::to_string(preset).map_err(|e| e.to_string())?
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Read a preset from `path`
+pub fn load_preset(path: &Path) -> Result<Preset, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if is_json(path) {
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    } else {
+        toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// File for a numbered preset slot (`~/.dj-viz-presets/slot-N.toml`)
+pub fn slot_path(slot: u8) -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".dj-viz-presets").join(format!("slot-{}.toml", slot)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_round_trips_through_toml_and_json() {
+        let preset = Preset {
+            primary: "LavaBlobs".to_string(),
+            overlays: vec!["Squares".to_string(), "BeatBars".to_string()],
+            locked: true,
+            blend_mode: BlendMode::Screen,
+        };
+        let dir = std::env::temp_dir().join(format!("dj-viz-preset-test-{}", std::process::id()));
+        for name in ["preset.toml", "preset.json"] {
+            let path = dir.join(name);
+            save_preset(&preset, &path).unwrap();
+            assert_eq!(load_preset(&path).unwrap(), preset);
+        }
+        let _ = fs::remove_dir_all(&dir);
+
+        // Only the primary is required
+        let minimal: Preset = toml::from_str("primary = \"Squares\"").unwrap();
+        assert!(minimal.overlays.is_empty() && !minimal.locked);
+        assert_eq!(minimal.blend_mode, BlendMode::Burn);
+    }
+}
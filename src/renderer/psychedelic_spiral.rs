@@ -7,6 +7,7 @@ use super::Visualization;
 use nannou::prelude::*;
 
 use crate::audio::AudioAnalysis;
+use crate::utils::hsv_to_rgba;
 
 /// Number of spiral arms
 const NUM_ARMS: usize = 5;
@@ -52,36 +53,6 @@ impl Default for PsychedelicSpiral {
     }
 }
 
-impl PsychedelicSpiral {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for PsychedelicSpiral {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
@@ -149,7 +120,7 @@ impl Visualization for PsychedelicSpiral {
                 let hue = (self.hue_offset + angle.to_degrees() + ring_t * 120.0) % 360.0;
                 let saturation = 0.7 + self.mids * 0.3;
                 let value = 0.5 + ring_t * 0.4;
-                let color = Self::hsv_to_rgba(hue, saturation, value, depth_alpha * 0.6);
+                let color = hsv_to_rgba(hue, saturation, value, depth_alpha * 0.6);
 
                 let x1 = center.x + r1 * (angle + self.rotation).cos();
                 let y1 = center.y + r1 * (angle + self.rotation).sin();
@@ -194,7 +165,7 @@ impl Visualization for PsychedelicSpiral {
                 let value = 0.6 + t * 0.3;
                 let alpha = 0.4 + t * 0.4;
 
-                let color = Self::hsv_to_rgba(hue, saturation, value, alpha);
+                let color = hsv_to_rgba(hue, saturation, value, alpha);
                 let weight = 2.0 + t * 6.0 * (1.0 + self.bass * 0.3);
 
                 draw.line()
@@ -212,7 +183,7 @@ impl Visualization for PsychedelicSpiral {
             let radius = max_radius * 0.15 * (1.0 - t) * (1.0 + self.bass * 0.5);
             let hue = (self.hue_offset + t * 90.0) % 360.0;
             let alpha = t * 0.3;
-            let color = Self::hsv_to_rgba(hue, 0.9, 1.0, alpha);
+            let color = hsv_to_rgba(hue, 0.9, 1.0, alpha);
 
             draw.ellipse()
                 .xy(center)
@@ -225,7 +196,7 @@ impl Visualization for PsychedelicSpiral {
             let pulse_alpha = (self.bass - 0.5) * 0.6;
             let pulse_radius = max_radius * (0.95 + self.bass * 0.1);
             let hue = (self.hue_offset + 180.0) % 360.0;
-            let color = Self::hsv_to_rgba(hue, 1.0, 1.0, pulse_alpha);
+            let color = hsv_to_rgba(hue, 1.0, 1.0, pulse_alpha);
 
             draw.ellipse()
                 .xy(center)
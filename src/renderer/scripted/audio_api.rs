@@ -2,10 +2,36 @@
 //!
 //! Updates audio analysis data in the Rhai scope each frame.
 //! Uses set_or_push to update existing vars or create them on first frame.
+//! The full-resolution spectrum is too large to copy into the scope every
+//! frame, so it is read through the `spectrum(i)` function instead.
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{AudioAnalysis, BeatEvent};
 use nannou::geom::Rect;
-use rhai::{Dynamic, Scope};
+use rhai::{Dynamic, Engine, Scope};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Full-resolution spectrum shared with the `spectrum(i)` script function
+pub type SpectrumStore = Rc<RefCell<Vec<f32>>>;
+
+/// Register audio accessor functions on the engine
+pub fn register_audio_api(engine: &mut Engine, store: SpectrumStore) {
+    // spectrum(i) - normalized magnitude (0-1) of FFT bin i, 0.0 when out of range.
+    // Bin i is centered on i * sample_rate / fft_size Hz: ~21.5 Hz per bin at
+    // 44.1 kHz with the default 2048 FFT, so bin 10 is ~215 Hz and the last bin
+    // (spectrum_len() - 1) sits just below the Nyquist frequency.
+    let s = store.clone();
+    engine.register_fn("spectrum", move |i: i64| -> f64 {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| s.borrow().get(i).copied())
+            .unwrap_or(0.0) as f64
+    });
+
+    // spectrum_len() - number of bins readable with spectrum(i)
+    let s = store.clone();
+    engine.register_fn("spectrum_len", move || -> i64 { s.borrow().len() as i64 });
+}
 
 /// Update all AudioAnalysis fields in the scope.
 /// Uses set_or_push so variables are created on first frame, updated on subsequent frames.
@@ -33,45 +59,78 @@ pub fn update_audio_in_scope(scope: &mut Scope, analysis: &AudioAnalysis, bounds
         .collect();
     scope.set_or_push("bands_normalized", bands_normalized);
 
+    // Unsmoothed bands/energy (instant response for precise triggers)
+    let bands_raw: rhai::Array = analysis
+        .bands_raw
+        .iter()
+        .map(|&b| Dynamic::from(b as f64))
+        .collect();
+    scope.set_or_push("bands_raw", bands_raw);
+    scope.set_or_push("energy_raw", analysis.energy_raw as f64);
+
+    // Perceived volume
+    scope.set_or_push("rms", analysis.rms as f64);
+    scope.set_or_push("loudness_db", analysis.loudness_db as f64);
+
+    // Per-channel bands (mirror `bands` unless stereo_spectrum is enabled)
+    let to_array = |values: &[f32]| -> rhai::Array {
+        values.iter().map(|&b| Dynamic::from(b as f64)).collect()
+    };
+    scope.set_or_push("bands_left", to_array(&analysis.bands_left));
+    scope.set_or_push("bands_right", to_array(&analysis.bands_right));
+    scope.set_or_push("stereo_width", analysis.stereo_width as f64);
+
     // Downsampled spectrum for Rhai (256 bins to reduce overhead)
-    // Each output bin averages 4 input bins (1024 / 256 = 4)
+    // Each output bin averages len / 256 input bins (4 with the default 2048 FFT)
     const RHAI_SPECTRUM_SIZE: usize = 256;
-    const DOWNSAMPLE_FACTOR: usize = 4;
-
-    let spectrum: rhai::Array = (0..RHAI_SPECTRUM_SIZE)
-        .map(|i| {
-            let start = i * DOWNSAMPLE_FACTOR;
-            let end = (start + DOWNSAMPLE_FACTOR).min(analysis.spectrum.len());
-            let sum: f32 = analysis.spectrum[start..end].iter().sum();
-            let avg = sum / (end - start) as f32;
-            Dynamic::from(avg as f64)
-        })
-        .collect();
-    scope.set_or_push("spectrum", spectrum);
+
+    let downsample = |values: &[f32]| -> rhai::Array {
+        let factor = (values.len() / RHAI_SPECTRUM_SIZE).max(1);
+        (0..RHAI_SPECTRUM_SIZE)
+            .map(|i| {
+                let start = (i * factor).min(values.len());
+                let end = (start + factor).min(values.len());
+                let sum: f32 = values[start..end].iter().sum();
+                let avg = sum / (end - start).max(1) as f32;
+                Dynamic::from(avg as f64)
+            })
+            .collect()
+    };
+
+    scope.set_or_push("spectrum", downsample(&analysis.spectrum));
+
+    // Per-channel spectrum (mirrors `spectrum` unless stereo_spectrum is enabled)
+    scope.set_or_push("spectrum_left", downsample(&analysis.spectrum_left));
+    scope.set_or_push("spectrum_right", downsample(&analysis.spectrum_right));
 
     // Spectrum diff (velocity/change from previous frame), also downsampled
-    let spectrum_diff: rhai::Array = (0..RHAI_SPECTRUM_SIZE)
-        .map(|i| {
-            let start = i * DOWNSAMPLE_FACTOR;
-            let end = (start + DOWNSAMPLE_FACTOR).min(analysis.spectrum_diff.len());
-            let sum: f32 = analysis.spectrum_diff[start..end].iter().sum();
-            let avg = sum / (end - start) as f32;
-            Dynamic::from(avg as f64)
-        })
-        .collect();
-    scope.set_or_push("spectrum_diff", spectrum_diff);
+    scope.set_or_push("spectrum_diff", downsample(&analysis.spectrum_diff));
 
     // Temporal metrics
     scope.set_or_push("bpm", analysis.bpm as f64);
+    scope.set_or_push("beat_phase", analysis.beat_phase as f64);
+    scope.set_or_push("is_beat", analysis.is_beat);
+    scope.set_or_push("bar_phase", analysis.bar_phase as f64);
+    scope.set_or_push("is_downbeat", analysis.is_downbeat);
+    scope.set_or_push("beat", analysis.beat_onset);
+    let band_onset: rhai::Array = analysis.band_onset.iter().map(|&o| Dynamic::from(o)).collect();
+    scope.set_or_push("band_onset", band_onset);
     scope.set_or_push("dominant_band", analysis.dominant_band as i64);
     scope.set_or_push("energy_diff", analysis.energy_diff as f64);
     scope.set_or_push("rise_rate", analysis.rise_rate as f64);
     scope.set_or_push("spectral_centroid", analysis.spectral_centroid as f64);
+    scope.set_or_push("spectral_contrast", analysis.spectral_contrast as f64);
+    scope.set_or_push("spectral_flux", analysis.spectral_flux as f64);
+
+    // Chromagram (pitch classes C = 0 .. B = 11) for key-following palettes
+    scope.set_or_push("chroma", to_array(&analysis.chroma));
+    scope.set_or_push("dominant_pitch_class", analysis.dominant_pitch_class as i64);
 
     // Event flags
     scope.set_or_push("transition_detected", analysis.transition_detected);
     scope.set_or_push("punch_detected", analysis.punch_detected);
     scope.set_or_push("break_detected", analysis.break_detected);
+    scope.set_or_push("silence_detected", analysis.silence_detected);
     scope.set_or_push("instrument_added", analysis.instrument_added);
     scope.set_or_push("instrument_removed", analysis.instrument_removed);
     scope.set_or_push("viz_change_triggered", analysis.viz_change_triggered);
@@ -113,3 +172,59 @@ pub fn update_audio_in_scope(scope: &mut Scope, analysis: &AudioAnalysis, bounds
     // Frame counter
     scope.set_or_push("frame", frame);
 }
+
+/// Beats confirmed since the previous frame as `beat_events`: an array of maps
+/// with `time` (seconds), `strength` (bass over its average) and `band`.
+/// Usually empty; unlike `beat`, a beat is never missed or seen twice
+pub fn update_beat_events_in_scope(scope: &mut Scope, events: &[BeatEvent]) {
+    let events: rhai::Array = events
+        .iter()
+        .map(|event| {
+            let mut map = rhai::Map::new();
+            map.insert("time".into(), Dynamic::from(event.time as f64));
+            map.insert("strength".into(), Dynamic::from(event.strength as f64));
+            map.insert("band".into(), Dynamic::from(event.dominant_band as i64));
+            Dynamic::from_map(map)
+        })
+        .collect();
+    scope.set_or_push("beat_events", events);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_reads_spectrum_and_bpm() {
+        let store: SpectrumStore = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+        register_audio_api(&mut engine, store.clone());
+
+        let mut analysis = AudioAnalysis {
+            bpm: 120.0,
+            ..Default::default()
+        };
+        analysis.spectrum[10] = 0.5;
+        store.borrow_mut().clone_from(&analysis.spectrum);
+        let mut scope = Scope::new();
+        update_audio_in_scope(&mut scope, &analysis, Rect::from_w_h(640.0, 480.0), 1);
+
+        let value: f64 = engine
+            .eval_with_scope(&mut scope, "spectrum(10) + bpm + spectrum(-1) + spectrum(1000000)")
+            .unwrap();
+        assert_eq!(value, 120.5);
+        let beat: bool = engine.eval_with_scope(&mut scope, "beat").unwrap();
+        assert!(!beat);
+
+        let event = BeatEvent {
+            time: 2.0,
+            strength: 3.0,
+            dominant_band: 1,
+        };
+        update_beat_events_in_scope(&mut scope, &[event]);
+        let value: f64 = engine
+            .eval_with_scope(&mut scope, "beat_events[0].strength + beat_events[0].band")
+            .unwrap();
+        assert_eq!(value, 4.0);
+    }
+}
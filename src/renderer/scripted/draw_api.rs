@@ -2,11 +2,27 @@
 //!
 //! Provides the bridge between Rhai scripts and nannou's Draw API.
 
+use super::image_api::ImageCache;
 use nannou::prelude::*;
 use rhai::{Dynamic, Engine};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// Target length of one polyline segment when tessellating curves, in pixels
+const CURVE_SEGMENT_PX: f32 = 4.0;
+
+/// Upper bound on segments per curve, so one `arc`/`bezier` call costs a bounded
+/// amount of work no matter its size (the operation limit only counts script steps)
+const MAX_CURVE_SEGMENTS: usize = 128;
+
+/// Segment count for a curve of roughly `length` pixels
+fn curve_segments(length: f32) -> usize {
+    if !length.is_finite() {
+        return MAX_CURVE_SEGMENTS;
+    }
+    ((length / CURVE_SEGMENT_PX).ceil() as usize).clamp(2, MAX_CURVE_SEGMENTS)
+}
+
 /// A queued drawing command from a script
 #[derive(Clone, Debug)]
 pub enum DrawCommand {
@@ -39,11 +55,40 @@ pub enum DrawCommand {
         size: i64,
         color: [f32; 4],
     },
+    /// Circular arc, angles in radians counter-clockwise from +x
+    Arc {
+        x: f32,
+        y: f32,
+        radius: f32,
+        start: f32,
+        end: f32,
+        stroke: f32,
+        color: [f32; 4],
+    },
+    /// Quadratic bezier from (x0, y0) to (x1, y1) bent towards (cx, cy)
+    Bezier {
+        x0: f32,
+        y0: f32,
+        cx: f32,
+        cy: f32,
+        x1: f32,
+        y1: f32,
+        stroke: f32,
+        color: [f32; 4],
+    },
+    /// Textured quad from a `load_image` handle
+    Image {
+        handle: i64,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
 }
 
 impl DrawCommand {
-    /// Execute this command using nannou's Draw API
-    pub fn execute(&self, draw: &Draw) {
+    /// Execute this command using nannou's Draw API (`images` resolves image handles)
+    pub fn execute(&self, draw: &Draw, images: &ImageCache) {
         match self {
             DrawCommand::Rect { x, y, w, h, color } => {
                 draw.rect()
@@ -83,6 +128,53 @@ impl DrawCommand {
                     .font_size(*size as u32)
                     .rgba(color[0], color[1], color[2], color[3]);
             }
+            DrawCommand::Arc {
+                x,
+                y,
+                radius,
+                start,
+                end,
+                stroke,
+                color,
+            } => {
+                let segments = curve_segments((end - start).abs() * radius.abs());
+                let points = (0..=segments).map(|i| {
+                    let angle = start + (end - start) * i as f32 / segments as f32;
+                    pt2(x + radius * angle.cos(), y + radius * angle.sin())
+                });
+                draw.polyline()
+                    .weight(*stroke)
+                    .points(points)
+                    .rgba(color[0], color[1], color[2], color[3]);
+            }
+            DrawCommand::Bezier {
+                x0,
+                y0,
+                cx,
+                cy,
+                x1,
+                y1,
+                stroke,
+                color,
+            } => {
+                let (p0, c, p1) = (pt2(*x0, *y0), pt2(*cx, *cy), pt2(*x1, *y1));
+                // The control polygon is never shorter than the curve
+                let segments = curve_segments(p0.distance(c) + c.distance(p1));
+                let points = (0..=segments).map(|i| {
+                    let t = i as f32 / segments as f32;
+                    let u = 1.0 - t;
+                    p0 * (u * u) + c * (2.0 * u * t) + p1 * (t * t)
+                });
+                draw.polyline()
+                    .weight(*stroke)
+                    .points(points)
+                    .rgba(color[0], color[1], color[2], color[3]);
+            }
+            DrawCommand::Image { handle, x, y, w, h } => {
+                if let Some(texture) = images.texture(*handle) {
+                    draw.texture(texture).x_y(*x, *y).w_h(*w, *h);
+                }
+            }
         }
     }
 }
@@ -153,6 +245,60 @@ pub fn register_draw_api(engine: &mut Engine, commands: CommandQueue) {
         },
     );
 
+    // arc(x, y, radius, start_angle, end_angle, stroke, r, g, b, a)
+    let cmds = commands.clone();
+    engine.register_fn(
+        "arc",
+        move |x: f64,
+              y: f64,
+              radius: f64,
+              start_angle: f64,
+              end_angle: f64,
+              stroke: f64,
+              r: f64,
+              g: f64,
+              b: f64,
+              a: f64| {
+            cmds.borrow_mut().push(DrawCommand::Arc {
+                x: x as f32,
+                y: y as f32,
+                radius: radius as f32,
+                start: start_angle as f32,
+                end: end_angle as f32,
+                stroke: stroke as f32,
+                color: [r as f32, g as f32, b as f32, a as f32],
+            });
+        },
+    );
+
+    // bezier(x0, y0, cx, cy, x1, y1, stroke, r, g, b, a)
+    let cmds = commands.clone();
+    engine.register_fn(
+        "bezier",
+        move |x0: f64,
+              y0: f64,
+              cx: f64,
+              cy: f64,
+              x1: f64,
+              y1: f64,
+              stroke: f64,
+              r: f64,
+              g: f64,
+              b: f64,
+              a: f64| {
+            cmds.borrow_mut().push(DrawCommand::Bezier {
+                x0: x0 as f32,
+                y0: y0 as f32,
+                cx: cx as f32,
+                cy: cy as f32,
+                x1: x1 as f32,
+                y1: y1 as f32,
+                stroke: stroke as f32,
+                color: [r as f32, g as f32, b as f32, a as f32],
+            });
+        },
+    );
+
     // hsla(h, s, l, a) -> [r, g, b, a]
     // Manual HSL to RGB conversion
     engine.register_fn("hsla", |h: f64, s: f64, l: f64, a: f64| -> rhai::Array {
@@ -226,3 +372,38 @@ pub fn register_math_api(engine: &mut Engine) {
     engine.register_fn("pi", || std::f64::consts::PI);
     engine.register_fn("tau", || std::f64::consts::TAU);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_and_bezier_run_and_draw() {
+        let commands: CommandQueue = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(10_000);
+        register_draw_api(&mut engine, commands.clone());
+        register_math_api(&mut engine);
+
+        engine
+            .run(
+                "arc(0.0, 0.0, 100.0, 0.0, pi(), 2.0, 1.0, 0.5, 0.0, 1.0);
+                 arc(10.0, 10.0, 1.0e9, 0.0, tau(), 1.0, 1.0, 1.0, 1.0, 1.0);
+                 bezier(-50.0, 0.0, 0.0, 80.0, 50.0, 0.0, 3.0, 0.0, 1.0, 1.0, 0.8);",
+            )
+            .unwrap();
+
+        let queued = commands.borrow();
+        assert_eq!(queued.len(), 3);
+        assert!(matches!(queued[0], DrawCommand::Arc { .. }));
+        assert!(matches!(queued[2], DrawCommand::Bezier { .. }));
+
+        let draw = Draw::new();
+        let images = ImageCache::default();
+        for cmd in queued.iter() {
+            cmd.execute(&draw, &images);
+        }
+        assert_eq!(curve_segments(1.0e9 * std::f32::consts::TAU), MAX_CURVE_SEGMENTS);
+        assert_eq!(curve_segments(0.0), 2);
+    }
+}
@@ -0,0 +1,169 @@
+//! Image loading for Rhai scripts.
+//!
+//! `load_image(path)` decodes a file the first time a script asks for it and
+//! returns an integer handle; later calls with the same path hit the cache.
+//! Scripts run without access to the GPU, so decoded images are uploaded as
+//! textures by `upload` before the frame is drawn. The cache belongs to the
+//! `ScriptedVisualization` and is kept across hot-reloads.
+
+use super::draw_api::{CommandQueue, DrawCommand};
+use crate::log_error;
+use nannou::image::{self, RgbaImage};
+use nannou::wgpu;
+use rhai::Engine;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Handle returned for images that failed to load (draws nothing)
+pub const INVALID_IMAGE: i64 = -1;
+
+enum ImageState {
+    /// Decoded on the CPU, waiting for `upload`
+    Decoded(RgbaImage),
+    Ready(wgpu::Texture),
+}
+
+/// Images loaded by one script, indexed by handle
+#[derive(Default)]
+pub struct ImageCache {
+    /// Resolved path to handle, including failures (so missing files aren't retried every frame)
+    handles: HashMap<PathBuf, i64>,
+    images: Vec<ImageState>,
+    /// Latest load failure, shown on screen until the script is reloaded
+    error: Option<String>,
+}
+
+impl ImageCache {
+    /// Handle for `path`, decoding the file on first use
+    fn load(&mut self, path: PathBuf) -> i64 {
+        if let Some(handle) = self.handles.get(&path) {
+            return *handle;
+        }
+        let handle = match image::open(&path) {
+            Ok(decoded) => {
+                self.images.push(ImageState::Decoded(decoded.to_rgba8()));
+                self.images.len() as i64 - 1
+            }
+            Err(e) => {
+                let message = format!("Failed to load image {}: {}", path.display(), e);
+                log_error!("{}", message);
+                self.error = Some(message);
+                INVALID_IMAGE
+            }
+        };
+        self.handles.insert(path, handle);
+        handle
+    }
+
+    /// Create textures for images decoded since the last call
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let usage = wgpu::TextureBuilder::default_image_texture_usage();
+        for state in &mut self.images {
+            if let ImageState::Decoded(pixels) = state {
+                let texture = wgpu::Texture::load_from_image_buffer(device, queue, usage, pixels);
+                *state = ImageState::Ready(texture);
+            }
+        }
+    }
+
+    /// Texture for `handle`, if it loaded and has been uploaded
+    pub fn texture(&self, handle: i64) -> Option<&wgpu::Texture> {
+        match self.images.get(usize::try_from(handle).ok()?)? {
+            ImageState::Ready(texture) => Some(texture),
+            ImageState::Decoded(_) => None,
+        }
+    }
+
+    /// Latest load failure, if any
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Forget failed loads so a reloaded script retries them (loaded images are kept)
+    pub fn forget_failures(&mut self) {
+        self.handles.retain(|_, handle| *handle != INVALID_IMAGE);
+        self.error = None;
+    }
+}
+
+/// Shared image cache for the script functions
+pub type ImageStore = Rc<RefCell<ImageCache>>;
+
+/// Register image functions on the engine. Relative paths resolve against `base_dir`
+/// (the script's own directory).
+pub fn register_image_api(
+    engine: &mut Engine,
+    images: ImageStore,
+    commands: CommandQueue,
+    base_dir: &Path,
+) {
+    // load_image(path) -> handle, INVALID_IMAGE (-1) if the file can't be decoded
+    let store = images.clone();
+    let base_dir = base_dir.to_path_buf();
+    engine.register_fn("load_image", move |path: &str| -> i64 {
+        store.borrow_mut().load(base_dir.join(path))
+    });
+
+    // draw_image(handle, x, y, w, h)
+    engine.register_fn(
+        "draw_image",
+        move |handle: i64, x: f64, y: f64, w: f64, h: f64| {
+            commands.borrow_mut().push(DrawCommand::Image {
+                handle,
+                x: x as f32,
+                y: y as f32,
+                w: w as f32,
+                h: h as f32,
+            });
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_image_caches_and_survives_missing_files() {
+        let dir = std::env::temp_dir().join(format!("dj-viz-image-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        image::save_buffer(
+            dir.join("logo.png"),
+            &[255u8, 255, 255, 255].repeat(4),
+            2,
+            2,
+            image::ColorType::Rgba8,
+        )
+        .unwrap();
+
+        let images: ImageStore = Rc::new(RefCell::new(ImageCache::default()));
+        let commands: CommandQueue = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+        register_image_api(&mut engine, images.clone(), commands.clone(), &dir);
+
+        let handles: rhai::Array = engine
+            .eval(
+                r#"let logo = load_image("logo.png");
+                   let missing = load_image("missing.png");
+                   draw_image(logo, 0.0, 0.0, 64.0, 64.0);
+                   draw_image(missing, 0.0, 0.0, 64.0, 64.0);
+                   [logo, missing, load_image("logo.png")]"#,
+            )
+            .unwrap();
+        let handles: Vec<i64> = handles.into_iter().map(|h| h.as_int().unwrap()).collect();
+        assert_eq!(handles, vec![0, INVALID_IMAGE, 0]);
+        assert_eq!(commands.borrow().len(), 2);
+        assert!(images.borrow().error().unwrap().contains("missing.png"));
+        // Not uploaded yet, and invalid handles never resolve
+        assert!(images.borrow().texture(0).is_none());
+        assert!(images.borrow().texture(INVALID_IMAGE).is_none());
+
+        // A reload clears the failure but keeps the decoded image under its handle
+        images.borrow_mut().forget_failures();
+        assert!(images.borrow().error().is_none());
+        assert_eq!(images.borrow_mut().load(dir.join("logo.png")), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
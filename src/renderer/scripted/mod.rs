@@ -5,17 +5,26 @@
 
 mod audio_api;
 mod draw_api;
+mod image_api;
+mod param_api;
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{AudioAnalysis, BeatEvent};
 use crate::renderer::VizInfo;
-use audio_api::update_audio_in_scope;
+use crate::utils::{Config, SCRIPT_MAX_OPERATIONS_CAP};
+use crate::{log_error, log_line};
+use audio_api::{
+    register_audio_api, update_audio_in_scope, update_beat_events_in_scope, SpectrumStore,
+};
 use draw_api::{register_draw_api, register_math_api, CommandQueue};
+use image_api::{register_image_api, ImageCache, ImageStore};
+use param_api::{param_key, param_value, register_param_api, ParamStore, PARAM_KEY_PREFIX};
+pub use param_api::ScriptParam;
 use nannou::prelude::*;
-use rhai::{Dynamic, Engine, Scope, AST};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::time::SystemTime;
 
@@ -58,15 +67,51 @@ fn register_var_api(engine: &mut Engine, store: VarStore) {
 /// Check interval for file modifications (in frames, ~0.5 sec at 60fps)
 const RELOAD_CHECK_INTERVAL: u32 = 30;
 
-/// Maximum operations per script execution (prevents infinite loops)
-const MAX_OPERATIONS: u64 = 200_000;
+/// Parse a per-script operation limit from a `#[ops = N]` directive line.
+///
+/// The directive may sit in a comment (`// #[ops = 500_000]`) so the script
+/// still compiles; the first matching line wins.
+fn parse_ops_directive(source: &str) -> Option<u64> {
+    source.lines().find_map(|line| {
+        let line = line.trim();
+        let line = line.strip_prefix("//").unwrap_or(line).trim_start();
+        let value = line
+            .strip_prefix("#[ops")?
+            .trim_start()
+            .strip_prefix('=')?
+            .trim()
+            .strip_suffix(']')?
+            .trim();
+        value.replace('_', "").parse().ok()
+    })
+}
+
+/// Maximum scripts running at once (each has its own engine and runs every frame)
+pub const MAX_ACTIVE_SCRIPTS: usize = 3;
+
+/// How an active script is composited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptRole {
+    /// Replaces the built-in pipeline and draws straight to the frame
+    Primary,
+    /// Burn-blended on top of the primary like a built-in overlay
+    Overlay,
+}
+
+/// A loaded script and the role it plays
+struct ActiveScript {
+    /// Index into `script_paths`
+    index: usize,
+    role: ScriptRole,
+    visualization: ScriptedVisualization,
+}
 
-/// Manages Rhai script discovery and cycling
+/// Manages Rhai script discovery, cycling and the set of running scripts
 pub struct ScriptManager {
     scripts_dir: PathBuf,
     script_paths: Vec<PathBuf>,
-    current_index: Option<usize>,
-    visualization: Option<ScriptedVisualization>,
+    /// Running scripts, primary (if any) first
+    active: Vec<ActiveScript>,
 }
 
 impl ScriptManager {
@@ -75,8 +120,7 @@ impl ScriptManager {
         let mut manager = Self {
             scripts_dir,
             script_paths: Vec::new(),
-            current_index: None,
-            visualization: None,
+            active: Vec::new(),
         };
         manager.scan_scripts();
         manager
@@ -108,71 +152,220 @@ impl ScriptManager {
         }
     }
 
-    /// Cycle to the next script, returns the script name if successful
+    /// Cycle the primary script to the next one, returns the script name if successful
     pub fn cycle_next(&mut self) -> Option<String> {
-        if self.script_paths.is_empty() {
-            self.scan_scripts(); // Try rescanning
-            if self.script_paths.is_empty() {
-                return None;
-            }
+        if !self.ensure_scripts() {
+            return None;
         }
 
-        let next_index = match self.current_index {
-            Some(idx) => (idx + 1) % self.script_paths.len(),
+        let next_index = match self.primary_position() {
+            Some(pos) => (self.active[pos].index + 1) % self.script_paths.len(),
             None => 0,
         };
 
-        self.load_script_at(next_index)
+        let visualization = self.load_script_at(next_index)?;
+        let script = ActiveScript {
+            index: next_index,
+            role: ScriptRole::Primary,
+            visualization,
+        };
+        match self.primary_position() {
+            Some(pos) => self.active[pos] = script,
+            None => {
+                self.active.insert(0, script);
+                self.active.truncate(MAX_ACTIVE_SCRIPTS);
+            }
+        }
+        Some(self.script_name(next_index))
     }
 
-    /// Load a script by index
-    fn load_script_at(&mut self, index: usize) -> Option<String> {
-        if index >= self.script_paths.len() {
+    /// Add the next script that isn't already running as an overlay.
+    /// Returns None when no script could be added (none found or at the cap).
+    pub fn add_overlay(&mut self) -> Option<String> {
+        if !self.ensure_scripts() || self.active.len() >= MAX_ACTIVE_SCRIPTS {
             return None;
         }
 
-        let path = &self.script_paths[index];
+        // Continue after the most recently added script
+        let start = self.active.last().map_or(0, |s| s.index + 1);
+        let count = self.script_paths.len();
+        let index = (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|idx| !self.active.iter().any(|s| s.index == *idx))?;
+
+        let visualization = self.load_script_at(index)?;
+        self.active.push(ActiveScript {
+            index,
+            role: ScriptRole::Overlay,
+            visualization,
+        });
+        Some(self.script_name(index))
+    }
+
+    /// Rescan if no scripts are known yet, returns whether any exist
+    fn ensure_scripts(&mut self) -> bool {
+        if self.script_paths.is_empty() {
+            self.scan_scripts(); // Try rescanning
+        }
+        !self.script_paths.is_empty()
+    }
+
+    /// Load a script by index
+    fn load_script_at(&self, index: usize) -> Option<ScriptedVisualization> {
+        let path = self.script_paths.get(index)?;
         match ScriptedVisualization::new(path.clone()) {
             Ok(viz) => {
-                let name = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                self.visualization = Some(viz);
-                self.current_index = Some(index);
-                println!("Loaded script: {}", name);
-                Some(name)
+                log_line!("Loaded script: {}", self.script_name(index));
+                Some(viz)
             }
             Err(e) => {
-                eprintln!("Failed to load script {:?}: {}", path, e);
+                log_error!("Failed to load script {:?}: {}", path, e);
                 None
             }
         }
     }
 
-    /// Check if a script is currently active
+    fn script_name(&self, index: usize) -> String {
+        self.script_paths[index]
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    fn primary_position(&self) -> Option<usize> {
+        self.active
+            .iter()
+            .position(|s| s.role == ScriptRole::Primary)
+    }
+
+    fn overlays(&self) -> impl Iterator<Item = &ScriptedVisualization> {
+        self.active
+            .iter()
+            .filter(|s| s.role == ScriptRole::Overlay)
+            .map(|s| &s.visualization)
+    }
+
+    /// Move a script between fullscreen and overlay: the primary script becomes an
+    /// overlay (the built-in pipeline shows underneath), otherwise the most recently
+    /// added overlay becomes the primary. Returns the moved script's name.
+    pub fn toggle_overlay_mode(&mut self) -> Option<String> {
+        let pos = match self.primary_position() {
+            Some(pos) => {
+                let mut script = self.active.remove(pos);
+                script.role = ScriptRole::Overlay;
+                self.active.push(script);
+                self.active.len() - 1
+            }
+            None => {
+                let pos = self
+                    .active
+                    .iter()
+                    .rposition(|s| s.role == ScriptRole::Overlay)?;
+                let mut script = self.active.remove(pos);
+                script.role = ScriptRole::Primary;
+                self.active.insert(0, script);
+                0
+            }
+        };
+        Some(self.script_name(self.active[pos].index))
+    }
+
+    /// Stop all script overlays, returning how many were running
+    pub fn clear_overlays(&mut self) -> usize {
+        let before = self.active.len();
+        self.active.retain(|s| s.role != ScriptRole::Overlay);
+        before - self.active.len()
+    }
+
+    /// Check if a script is currently running as the primary visualization
     pub fn is_active(&self) -> bool {
-        self.visualization.is_some()
+        self.primary_position().is_some()
     }
 
-    /// Deactivate scripted visualization (return to built-in)
+    /// Number of scripts running as overlays
+    pub fn overlay_count(&self) -> usize {
+        self.overlays().count()
+    }
+
+    /// Stop the primary script (return to built-in); script overlays keep running
     pub fn deactivate(&mut self) {
-        self.visualization = None;
-        self.current_index = None;
+        self.active.retain(|s| s.role != ScriptRole::Primary);
     }
 
-    /// Update the current script visualization
-    pub fn update(&mut self, analysis: &AudioAnalysis, bounds: Rect, viz_info: &VizInfo) {
-        if let Some(ref mut viz) = self.visualization {
-            viz.update(analysis, bounds, viz_info);
+    /// Update all running scripts
+    pub fn update(
+        &mut self,
+        analysis: &AudioAnalysis,
+        beat_events: &[BeatEvent],
+        bounds: Rect,
+        viz_info: &VizInfo,
+    ) {
+        for script in &mut self.active {
+            script.visualization.update(analysis, beat_events, bounds, viz_info);
         }
     }
 
-    /// Draw the current script visualization
+    /// Name and parameters of the script the parameter panel edits: the primary,
+    /// else the first overlay
+    pub fn params(&self) -> Option<(String, Vec<(ScriptParam, f64)>)> {
+        let script = self.active.first()?;
+        Some((self.script_name(script.index), script.visualization.params()))
+    }
+
+    /// Nudge a parameter of the edited script, returning its name and new value
+    pub fn adjust_param(&mut self, index: usize, steps: f64) -> Option<(String, f64)> {
+        self.active.first_mut()?.visualization.adjust_param(index, steps)
+    }
+
+    /// Reset a parameter of the edited script to its default
+    pub fn reset_param(&mut self, index: usize) -> Option<(String, f64)> {
+        self.active.first_mut()?.visualization.reset_param(index)
+    }
+
+    /// Upload images newly loaded by running scripts
+    pub fn upload_images(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        for script in &self.active {
+            script.visualization.upload_images(device, queue);
+        }
+    }
+
+    /// Draw the primary script with any script overlays directly on top
     pub fn draw(&self, draw: &Draw, bounds: Rect) {
-        if let Some(ref viz) = self.visualization {
-            viz.draw(draw, bounds);
+        if let Some(pos) = self.primary_position() {
+            self.active[pos].visualization.draw(draw, bounds);
+            for viz in self.overlays() {
+                viz.draw_overlay(draw);
+            }
+        }
+    }
+
+    /// Draw script overlays, one per Draw (for burn blending over a built-in primary)
+    pub fn draw_overlays(&self, draws: &[&Draw]) {
+        for (viz, draw) in self.overlays().zip(draws.iter()) {
+            viz.draw_overlay(draw);
+        }
+    }
+
+    /// Draw the latest script error, if any, in the bottom-left corner
+    pub fn draw_errors(&self, draw: &Draw, bounds: Rect) {
+        let error = self.active.iter().find_map(|s| {
+            let viz = &s.visualization;
+            viz.error_message().map(|message| (viz.file_name(), message))
+        });
+        if let Some((file_name, message)) = error {
+            let text = format!("{}: {}", file_name, message);
+            let (w, h) = (600.0, 60.0);
+            let (x, y) = (bounds.left() + 10.0 + w / 2.0, bounds.bottom() + 10.0 + h / 2.0);
+            // Dark backing so the message stays readable over bright visuals
+            draw.rect().x_y(x, y).w_h(w, h).color(rgba(0.0, 0.0, 0.0, 0.75));
+            draw.text(&text)
+                .x_y(x, y)
+                .w_h(w - 20.0, h - 10.0)
+                .left_justify()
+                .align_text_middle_y()
+                .font_size(14)
+                .color(RED);
         }
     }
 }
@@ -184,11 +377,25 @@ pub struct ScriptedVisualization {
     scope: Scope<'static>,
     commands: CommandQueue,
     vars: VarStore,
+    /// Latest full-resolution spectrum, read by `spectrum(i)`
+    spectrum: SpectrumStore,
+    /// Images from `load_image`, kept across hot-reloads
+    images: ImageStore,
+    /// Parameters declared with `param(...)`, values kept in `vars`
+    params: ParamStore,
     script_path: PathBuf,
     last_modified: SystemTime,
     frame_counter: u32,
     check_counter: u32,
     last_error_frame: u32,
+    /// Operation limit from config, used when the script has no `#[ops = N]` directive
+    default_max_operations: u64,
+    /// Effective operation limit for the loaded script
+    max_operations: u64,
+    /// Latest runtime error to show on screen (cleared after a successful run)
+    error_message: Option<String>,
+    /// Failed hot-reload, shown until the script compiles again (the previous AST keeps running)
+    reload_error: Option<String>,
     bounds: Rect,
     /// True on first frame after script load/reload
     script_init: bool,
@@ -199,16 +406,24 @@ impl ScriptedVisualization {
     pub fn new(script_path: PathBuf) -> Result<Self, String> {
         let commands: CommandQueue = Rc::new(RefCell::new(Vec::new()));
         let vars: VarStore = Rc::new(RefCell::new(HashMap::new()));
+        let spectrum: SpectrumStore = Rc::new(RefCell::new(Vec::new()));
+        let images: ImageStore = Rc::new(RefCell::new(ImageCache::default()));
+        let params: ParamStore = Rc::new(RefCell::new(Vec::new()));
 
         let mut engine = Engine::new();
 
         // Set operation limit to prevent infinite loops
-        engine.set_max_operations(MAX_OPERATIONS);
+        let max_operations = Config::load().script_max_operations();
+        engine.set_max_operations(max_operations);
 
         // Register APIs
         register_draw_api(&mut engine, commands.clone());
         register_math_api(&mut engine);
         register_var_api(&mut engine, vars.clone());
+        register_audio_api(&mut engine, spectrum.clone());
+        let script_dir = script_path.parent().unwrap_or(Path::new("."));
+        register_image_api(&mut engine, images.clone(), commands.clone(), script_dir);
+        register_param_api(&mut engine, params.clone(), vars.clone());
 
         // Get initial modification time
         let last_modified = fs::metadata(&script_path)
@@ -221,11 +436,18 @@ impl ScriptedVisualization {
             scope: Scope::new(),
             commands,
             vars,
+            spectrum,
+            images,
+            params,
             script_path,
             last_modified,
             frame_counter: 0,
             check_counter: 0,
             last_error_frame: 0,
+            default_max_operations: max_operations,
+            max_operations,
+            error_message: None,
+            reload_error: None,
             bounds: Rect::from_w_h(640.0, 480.0),
             script_init: true,
         };
@@ -247,12 +469,33 @@ impl ScriptedVisualization {
             .map_err(|e| format!("Compile error: {}", e))?;
 
         self.ast = Some(ast);
-        // Clear scope and persistent vars so script can reinitialize
+
+        // Per-script operation limit override, still bounded by the global cap
+        self.max_operations = match parse_ops_directive(&source) {
+            Some(ops) if ops > SCRIPT_MAX_OPERATIONS_CAP => {
+                log_error!(
+                    "Script #[ops = {}] exceeds the maximum, using {}",
+                    ops, SCRIPT_MAX_OPERATIONS_CAP
+                );
+                SCRIPT_MAX_OPERATIONS_CAP
+            }
+            Some(ops) => ops.max(1),
+            None => self.default_max_operations,
+        };
+        self.engine.set_max_operations(self.max_operations);
+        self.error_message = None;
+        self.reload_error = None;
+        // Clear scope and persistent vars so script can reinitialize (tuned params stay)
         self.scope.clear();
-        self.vars.borrow_mut().clear();
+        self.vars
+            .borrow_mut()
+            .retain(|name, _| name.starts_with(PARAM_KEY_PREFIX));
+        self.params.borrow_mut().clear();
+        // Decoded images stay cached, but missing ones get another try
+        self.images.borrow_mut().forget_failures();
         // Signal first frame after reload
         self.script_init = true;
-        println!("Script compiled: {:?}", self.script_path.file_name());
+        log_line!("Script compiled: {:?}", self.script_path.file_name());
 
         Ok(())
     }
@@ -269,19 +512,26 @@ impl ScriptedVisualization {
             if let Ok(modified) = metadata.modified() {
                 if modified > self.last_modified {
                     self.last_modified = modified;
-                    println!("Script modified, reloading...");
+                    log_line!("Script modified, reloading...");
 
                     if let Err(e) = self.reload_script() {
-                        eprintln!("Reload failed: {}", e);
-                        // Keep using the previous AST
+                        log_error!("Reload failed: {}", e);
+                        // Keep using the previous AST, but make the failure visible
+                        self.reload_error = Some(e);
                     }
                 }
             }
         }
     }
 
-    /// Update the visualization with audio analysis
-    pub fn update(&mut self, analysis: &AudioAnalysis, bounds: Rect, viz_info: &VizInfo) {
+    /// Update the visualization with audio analysis and the beats since the last frame
+    pub fn update(
+        &mut self,
+        analysis: &AudioAnalysis,
+        beat_events: &[BeatEvent],
+        bounds: Rect,
+        viz_info: &VizInfo,
+    ) {
         self.frame_counter += 1;
         self.bounds = bounds;
 
@@ -298,6 +548,8 @@ impl ScriptedVisualization {
             bounds,
             self.frame_counter as i64,
         );
+        update_beat_events_in_scope(&mut self.scope, beat_events);
+        self.spectrum.borrow_mut().clone_from(&analysis.spectrum);
 
         // Update visualization info in scope
         self.scope
@@ -322,11 +574,26 @@ impl ScriptedVisualization {
 
         // Run the script
         if let Some(ref ast) = self.ast {
-            if let Err(e) = self.engine.run_ast_with_scope(&mut self.scope, ast) {
-                // Throttle error messages (once per second)
-                if self.frame_counter - self.last_error_frame > 60 {
-                    eprintln!("Script error: {}", e);
-                    self.last_error_frame = self.frame_counter;
+            match self.engine.run_ast_with_scope(&mut self.scope, ast) {
+                // A missing image doesn't stop the script, but still shows on screen
+                Ok(()) => self.error_message = self.images.borrow().error().map(str::to_string),
+                Err(e) => {
+                    // Overruns are reported distinctly: the script is valid but too slow
+                    let message = match *e {
+                        EvalAltResult::ErrorTooManyOperations(_) => format!(
+                            "script exceeded operation limit ({} ops)",
+                            self.max_operations
+                        ),
+                        _ => format!("Script error: {}", e),
+                    };
+
+                    // Throttle error messages (once per second)
+                    if self.frame_counter - self.last_error_frame > 60 {
+                        log_error!("{}", message);
+                        self.last_error_frame = self.frame_counter;
+                    }
+
+                    self.error_message = Some(message);
                 }
             }
         }
@@ -344,21 +611,87 @@ impl ScriptedVisualization {
         draw.background().color(BLACK);
 
         // Execute all queued draw commands
+        let images = self.images.borrow();
         for cmd in self.commands.borrow().iter() {
-            cmd.execute(draw);
+            cmd.execute(draw, &images);
         }
     }
 
     /// Draw the visualization without background (for overlay use)
     pub fn draw_overlay(&self, draw: &Draw) {
         // Execute all queued draw commands without clearing background
+        let images = self.images.borrow();
         for cmd in self.commands.borrow().iter() {
-            cmd.execute(draw);
+            cmd.execute(draw, &images);
         }
     }
 
+    /// Upload images loaded since the last frame (scripts run without GPU access)
+    pub fn upload_images(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.images.borrow_mut().upload(device, queue);
+    }
+
+    /// Declared parameters with their current values
+    pub fn params(&self) -> Vec<(ScriptParam, f64)> {
+        self.params
+            .borrow()
+            .iter()
+            .map(|p| (p.clone(), param_value(p, &self.vars)))
+            .collect()
+    }
+
+    /// Move parameter `index` by `steps` adjust steps (negative lowers it).
+    /// Returns the parameter's name and new value.
+    pub fn adjust_param(&mut self, index: usize, steps: f64) -> Option<(String, f64)> {
+        let param = self.params.borrow().get(index)?.clone();
+        let value = (param_value(&param, &self.vars) + param.step() * steps).clamp(param.min, param.max);
+        self.set_param(&param, value);
+        Some((param.name, value))
+    }
+
+    /// Put parameter `index` back to its default, returning its name and value
+    pub fn reset_param(&mut self, index: usize) -> Option<(String, f64)> {
+        let param = self.params.borrow().get(index)?.clone();
+        self.set_param(&param, param.default);
+        Some((param.name, param.default))
+    }
+
+    fn set_param(&self, param: &ScriptParam, value: f64) {
+        self.vars
+            .borrow_mut()
+            .insert(param_key(&param.name), Dynamic::from(value));
+    }
+
+    /// Latest error to surface on screen: the last run's error, else a failed reload
+    pub fn error_message(&self) -> Option<&str> {
+        self.error_message
+            .as_deref()
+            .or(self.reload_error.as_deref())
+    }
+
+    /// Script file name, for labelling messages
+    pub fn file_name(&self) -> String {
+        self.script_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
     /// Set a custom variable in the script scope
     pub fn set_var(&mut self, name: &str, value: f64) {
         self.scope.set_or_push(name, value);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ops_directive() {
+        assert_eq!(parse_ops_directive("// #[ops = 500000]\nlet x = 1;"), Some(500_000));
+        assert_eq!(parse_ops_directive("let x = 1;\n  //#[ops=1_000_000]"), Some(1_000_000));
+        assert_eq!(parse_ops_directive("// ops = 500000\nlet x = 1;"), None);
+        assert_eq!(parse_ops_directive("// #[ops = lots]"), None);
+    }
+}
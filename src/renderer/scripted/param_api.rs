@@ -0,0 +1,103 @@
+//! Tweakable script parameters.
+//!
+//! `param(name, min, max, default)` declares a float the user can adjust from
+//! the parameter panel while the script runs. Values live in the persistent
+//! variable store under `param:<name>`, which survives hot-reload, so edits to
+//! the script keep the tuned values.
+
+use super::VarStore;
+use rhai::{Dynamic, Engine};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Variable store key prefix for parameter values
+pub const PARAM_KEY_PREFIX: &str = "param:";
+
+/// Presses of the adjust keys to sweep a parameter's whole range
+const PARAM_STEPS: f64 = 50.0;
+
+/// A parameter declared by the running script
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptParam {
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    pub default: f64,
+}
+
+impl ScriptParam {
+    /// Amount one press of the adjust keys moves the value
+    pub fn step(&self) -> f64 {
+        (self.max - self.min) / PARAM_STEPS
+    }
+}
+
+/// Parameters in declaration order (cleared on reload, re-declared by the script)
+pub type ParamStore = Rc<RefCell<Vec<ScriptParam>>>;
+
+/// Variable store key for a parameter's value
+pub fn param_key(name: &str) -> String {
+    format!("{}{}", PARAM_KEY_PREFIX, name)
+}
+
+/// Current value of `param` from the store, clamped to its range (default if unset)
+pub fn param_value(param: &ScriptParam, vars: &VarStore) -> f64 {
+    vars.borrow()
+        .get(&param_key(&param.name))
+        .and_then(|v| v.as_float().ok())
+        .unwrap_or(param.default)
+        .clamp(param.min, param.max)
+}
+
+/// Register the `param` function on the engine
+pub fn register_param_api(engine: &mut Engine, params: ParamStore, vars: VarStore) {
+    // param(name, min, max, default) -> current value
+    engine.register_fn(
+        "param",
+        move |name: &str, min: f64, max: f64, default: f64| -> f64 {
+            let (min, max) = if min <= max { (min, max) } else { (max, min) };
+            let param = ScriptParam {
+                name: name.to_string(),
+                min,
+                max,
+                default: default.clamp(min, max),
+            };
+            let value = param_value(&param, &vars);
+            vars.borrow_mut()
+                .insert(param_key(name), Dynamic::from(value));
+
+            // Declared every frame: later declarations update the range
+            let mut params = params.borrow_mut();
+            match params.iter_mut().find(|p| p.name == name) {
+                Some(existing) => *existing = param,
+                None => params.push(param),
+            }
+            value
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_param_declares_and_keeps_adjusted_value() {
+        let params: ParamStore = Rc::new(RefCell::new(Vec::new()));
+        let vars: VarStore = Rc::new(RefCell::new(HashMap::new()));
+        let mut engine = Engine::new();
+        register_param_api(&mut engine, params.clone(), vars.clone());
+
+        let script = r#"param("speed", 0.0, 5.0, 1.0) + param("size", 10.0, 0.0, 20.0)"#;
+        assert_eq!(engine.eval::<f64>(script).unwrap(), 11.0);
+        assert_eq!(params.borrow().len(), 2);
+        assert_eq!(params.borrow()[1].max, 10.0, "reversed ranges are swapped");
+
+        // An adjusted value is returned until changed again, clamped to the range
+        vars.borrow_mut()
+            .insert(param_key("speed"), Dynamic::from(9.0));
+        assert_eq!(engine.eval::<f64>(script).unwrap(), 15.0);
+        assert_eq!(params.borrow().len(), 2, "re-declaring doesn't duplicate");
+    }
+}
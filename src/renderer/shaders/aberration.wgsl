@@ -0,0 +1,46 @@
+// Chromatic aberration post-process
+// Samples the red and blue channels at small opposite offsets from green,
+// splitting edges into color fringes like a cheap lens
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    // Offsets in UV units (already divided by the texture size, so pixels stay square)
+    red_offset: vec2<f32>,
+    blue_offset: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let lo = vec2<f32>(0.0);
+    let hi = vec2<f32>(1.0);
+    let center = textureSample(t_source, s_source, in.tex_coords);
+    let red = textureSample(t_source, s_source, clamp(in.tex_coords + uniforms.red_offset, lo, hi)).r;
+    let blue = textureSample(t_source, s_source, clamp(in.tex_coords + uniforms.blue_offset, lo, hi)).b;
+    return vec4<f32>(red, center.g, blue, center.a);
+}
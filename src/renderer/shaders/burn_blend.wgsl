@@ -1,5 +1,11 @@
 // Blend shader for compositing overlay textures
-// Uses screen blend: result = 1 - (1 - base) * (1 - blend)
+// Modes (must match BlendMode in zoom.rs):
+//   0 burn:     screen blend shaped by the global burn strength (below 1.0 only
+//               the brightest parts burn through)
+//   1 add:      base + overlay
+//   2 screen:   1 - (1 - base) * (1 - overlay)
+//   3 multiply: base * overlay
+// The overlay contribution is scaled by its per-layer opacity
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
@@ -20,6 +26,16 @@ var t_overlay: texture_2d<f32>;
 @group(0) @binding(2)
 var s_sampler: sampler;
 
+struct BurnUniforms {
+    overlay_alpha: f32,
+    burn_strength: f32,
+    mode: u32,
+    _padding: f32,
+};
+
+@group(0) @binding(3)
+var<uniform> uniforms: BurnUniforms;
+
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
@@ -37,9 +53,26 @@ fn screen_blend(base: vec3<f32>, blend: vec3<f32>) -> vec3<f32> {
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let base = textureSample(t_base, s_sampler, in.tex_coords);
     let overlay = textureSample(t_overlay, s_sampler, in.tex_coords);
+    let alpha = uniforms.overlay_alpha;
 
-    // Screen blend the overlay onto the base
-    let blended = screen_blend(base.rgb, overlay.rgb);
+    var blended: vec3<f32>;
+    switch uniforms.mode {
+        case 1u: {
+            blended = min(base.rgb + overlay.rgb * alpha, vec3<f32>(1.0));
+        }
+        case 2u: {
+            blended = screen_blend(base.rgb, overlay.rgb * alpha);
+        }
+        case 3u: {
+            blended = mix(base.rgb, base.rgb * overlay.rgb, alpha);
+        }
+        default: {
+            // Burn curve: strength 1 is a plain screen, lower strengths raise the
+            // overlay to a higher power so dim areas drop out first
+            let shaped = pow(overlay.rgb, vec3<f32>(1.0 / max(uniforms.burn_strength, 0.05)));
+            blended = screen_blend(base.rgb, shaped * alpha);
+        }
+    }
 
-    return vec4<f32>(blended, max(base.a, overlay.a));
+    return vec4<f32>(blended, max(base.a, overlay.a * alpha));
 }
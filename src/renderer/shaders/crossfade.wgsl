@@ -0,0 +1,54 @@
+// Crossfade between two primary visualizations
+// Each primary is drawn into its own transparent layer (premultiplied alpha,
+// as nannou's default blend leaves it). The layers mix at 1 - progress and
+// progress, and the mix goes over the trails
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    // 0.0 = only the outgoing layer, 1.0 = only the incoming one
+    progress: f32,
+    _padding0: f32,
+    _padding1: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var t_base: texture_2d<f32>;
+
+@group(0) @binding(1)
+var t_incoming: texture_2d<f32>;
+
+@group(0) @binding(2)
+var t_outgoing: texture_2d<f32>;
+
+@group(0) @binding(3)
+var s_source: sampler;
+
+@group(0) @binding(4)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let base = textureSample(t_base, s_source, in.tex_coords);
+    let outgoing = textureSample(t_outgoing, s_source, in.tex_coords);
+    let incoming = textureSample(t_incoming, s_source, in.tex_coords);
+
+    let layer = mix(outgoing, incoming, uniforms.progress);
+    return layer + base * (1.0 - layer.a);
+}
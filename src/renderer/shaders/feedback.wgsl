@@ -1,5 +1,5 @@
 // Feedback buffer shader for trail effect
-// Samples previous frame, applies fade and optional scale transform
+// Samples previous frame, applies fade and optional scale transform about a focal point
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
@@ -14,7 +14,8 @@ struct VertexOutput {
 struct Uniforms {
     fade: f32,
     scale: f32,
-    _padding: vec2<f32>,
+    // Zoom center in UV (0.5, 0.5 = texture center)
+    focal: vec2<f32>,
 };
 
 @group(0) @binding(0)
@@ -36,10 +37,10 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Apply scale transform (zoom toward/away from center)
-    let centered = in.tex_coords - vec2<f32>(0.5, 0.5);
+    // Apply scale transform (zoom toward/away from the focal point)
+    let centered = in.tex_coords - uniforms.focal;
     let scaled = centered * uniforms.scale;
-    let uv = scaled + vec2<f32>(0.5, 0.5);
+    let uv = scaled + uniforms.focal;
 
     // Clamp UVs to avoid sampling outside texture
     let clamped_uv = clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0));
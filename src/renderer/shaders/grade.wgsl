@@ -0,0 +1,68 @@
+// Output grading
+// Scales the final image brightness (auto-brightness gain) and shifts its
+// color temperature (positive = warm, negative = cool), then optionally adds
+// ordered dither so dark gradients don't band once quantized to 8 bits
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    brightness: f32,
+    temperature: f32,
+    dither: f32,
+    _padding0: f32,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+// 4x4 Bayer threshold in (-0.5, 0.5)
+fn bayer4(pixel: vec2<u32>) -> f32 {
+    // `var` so the runtime index is allowed
+    var m = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0,
+    );
+    let i = (pixel.y % 4u) * 4u + (pixel.x % 4u);
+    return (m[i] + 0.5) / 16.0 - 0.5;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_source, in.tex_coords);
+    let t = uniforms.temperature;
+    let tint = vec3<f32>(1.0 + t, 1.0 + t * 0.2, 1.0 - t);
+    var rgb = clamp(color.rgb * uniforms.brightness * tint, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // Dither by one 8-bit step in perceptual space, where the output is quantized
+    if (uniforms.dither > 0.0) {
+        let offset = bayer4(vec2<u32>(in.clip_position.xy)) * uniforms.dither / 255.0;
+        let encoded = clamp(pow(rgb, vec3<f32>(1.0 / 2.2)) + vec3<f32>(offset), vec3<f32>(0.0), vec3<f32>(1.0));
+        rgb = pow(encoded, vec3<f32>(2.2));
+    }
+
+    return vec4<f32>(rgb, color.a);
+}
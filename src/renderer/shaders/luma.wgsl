@@ -0,0 +1,53 @@
+// Average scene luminance probe
+// Rendered into a 1x1 target: averages a grid of taps over the whole frame
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    grid: f32,
+    _padding0: f32,
+    _padding1: f32,
+    _padding2: f32,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let grid = i32(uniforms.grid);
+    var total = 0.0;
+    for (var y = 0; y < grid; y++) {
+        for (var x = 0; x < grid; x++) {
+            let uv = (vec2<f32>(f32(x), f32(y)) + vec2<f32>(0.5)) / uniforms.grid;
+            let color = textureSampleLevel(t_source, s_source, uv, 0.0).rgb;
+            total += dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+        }
+    }
+
+    // Linear luminance; the sRGB target stores it perceptually encoded
+    let luma = total / f32(grid * grid);
+    return vec4<f32>(luma, luma, luma, 1.0);
+}
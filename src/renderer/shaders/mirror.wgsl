@@ -0,0 +1,54 @@
+// Mirror fold for the feedback stage
+// Reflects the left half onto the right and/or the top half onto the bottom.
+// Folding across the center lines only flips coordinates (never scales them),
+// so non-square frames mirror without stretching
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    // 0 = none, 1 = horizontal, 2 = vertical, 3 = quad
+    mode: u32,
+    _padding0: u32,
+    _padding1: vec2<u32>,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var uv = in.tex_coords;
+
+    // Horizontal (and quad): the right half shows the left half reflected
+    if uniforms.mode == 1u || uniforms.mode == 3u {
+        uv.x = 0.5 - abs(uv.x - 0.5);
+    }
+    // Vertical (and quad): the bottom half shows the top half reflected
+    if uniforms.mode == 2u || uniforms.mode == 3u {
+        uv.y = 0.5 - abs(uv.y - 0.5);
+    }
+
+    return textureSample(t_source, s_source, uv);
+}
@@ -0,0 +1,66 @@
+// Photosensitivity limiter
+// Compares the average luminance of the new frame with the previously shown
+// one and, when it jumps by more than max_delta, blends back toward the
+// previous frame so the full-screen change stays within the cap
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    max_delta: f32,
+    has_previous: f32,
+    _padding0: f32,
+    _padding1: f32,
+};
+
+@group(0) @binding(0)
+var t_current: texture_2d<f32>;
+
+@group(0) @binding(1)
+var t_previous: texture_2d<f32>;
+
+// 1x1 average luminance of each frame (from luma.wgsl)
+@group(0) @binding(2)
+var t_luma_current: texture_2d<f32>;
+
+@group(0) @binding(3)
+var t_luma_previous: texture_2d<f32>;
+
+@group(0) @binding(4)
+var s_source: sampler;
+
+@group(0) @binding(5)
+var<uniform> uniforms: Uniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let current = textureSample(t_current, s_source, in.tex_coords);
+    if (uniforms.has_previous < 0.5) {
+        return current;
+    }
+    let previous = textureSample(t_previous, s_source, in.tex_coords);
+
+    let center = vec2<f32>(0.5, 0.5);
+    let luma_current = textureSampleLevel(t_luma_current, s_source, center, 0.0).r;
+    let luma_previous = textureSampleLevel(t_luma_previous, s_source, center, 0.0).r;
+
+    // Averages blend linearly, so this fraction lands exactly on the cap
+    let delta = abs(luma_current - luma_previous);
+    let t = select(1.0, uniforms.max_delta / delta, delta > uniforms.max_delta);
+    return mix(previous, current, t);
+}
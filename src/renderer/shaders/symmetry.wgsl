@@ -0,0 +1,66 @@
+// Radial symmetry post-process
+// Folds the final image into N mirrored wedges around the center
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+struct Uniforms {
+    folds: f32,
+    aspect: f32,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_source: sampler;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+const TAU: f32 = 6.28318530718;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.tex_coords = in.tex_coords;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var uv = in.tex_coords;
+
+    if uniforms.folds > 1.0 {
+        // Work in aspect-corrected space so wedges aren't stretched
+        let p = (uv - vec2<f32>(0.5, 0.5)) * vec2<f32>(uniforms.aspect, 1.0);
+        let radius = length(p);
+        var angle = atan2(p.y, p.x);
+        if angle < 0.0 {
+            angle += TAU;
+        }
+
+        // Fold into the first wedge, mirroring every other wedge so seams line up
+        let segment = TAU / uniforms.folds;
+        let idx = floor(angle / segment);
+        var local = angle - idx * segment;
+        if idx - 2.0 * floor(idx / 2.0) > 0.5 {
+            local = segment - local;
+        }
+
+        let folded = vec2<f32>(cos(local), sin(local)) * radius;
+        uv = folded / vec2<f32>(uniforms.aspect, 1.0) + vec2<f32>(0.5, 0.5);
+    }
+
+    let clamped_uv = clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0));
+    return textureSample(t_source, s_source, clamped_uv);
+}
@@ -1,10 +1,10 @@
 //! Shuffling skeletons visualization.
 //!
 //! Animated skeletons shuffle along screen edges with the following behaviors:
-//! - Maximum of 6 skeletons on screen at any time
+//! - Maximum of 6 skeletons on screen by default (configurable, can rise with energy)
 //! - Spawn at screen corners and move along edges (corner to adjacent corner)
 //! - Removed when they exit the viewport
-//! - Random scales (0.6-2.5x) for variety in size
+//! - Random scales (0.6-2.5x by default) for variety in size
 //! - Facial features: single eye (side view), triangle nose, optional smile (50% chance)
 //! - Bone color assigned based on dominant frequency band at spawn time:
 //!   - Band 0-1 (Bass): Red/Orange
@@ -17,21 +17,21 @@
 //! - Shuffle steps occur only on beat hits, creating rhythmic foot movements
 //! - Edge shufflers: feet point towards edge, head points towards middle of screen
 //! - Shuffler velocity modulated by energy (0.1x-1.1x speed based on music intensity)
-//! - Hue-shifted outlines (180° shift) create complementary color pairs
+//! - Hue-shifted outlines (180° shift by default) create complementary color pairs
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::{shift_hue, Config, SkeletonConfig};
 
-const MAX_SKELETONS: usize = 6;
 const SPAWN_AREA_WIDTH: f32 = 800.0;
 const SPAWN_AREA_HEIGHT: f32 = 600.0;
 const BASE_EDGE_OFFSET: f32 = 20.0;
 const SKELETON_HEIGHT_FACTOR: f32 = 80.0;
 const SHUFFLE_COOLDOWN_FRAMES: u32 = 30; // 0.5 seconds at 60fps
-const BASS_THRESHOLD: f32 = 0.9;
 
 #[derive(Clone, Copy, Debug)]
 enum DanceStyle {
@@ -50,6 +50,9 @@ struct Skeleton {
     animation_phase: f32,
     has_smile: bool,
     bone_color: Rgb<u8>,
+    face_color: Rgb<u8>,
+    /// Outline hue offset from the bone color (degrees)
+    outline_hue_shift: f32,
     shuffle_step: i32,
     shuffle_transition: f32,
     shuffle_cooldown: u32,
@@ -64,10 +67,10 @@ impl Skeleton {
         dominant_band: usize,
         rotation: f32,
         dance_style: DanceStyle,
+        config: &SkeletonConfig,
     ) -> Self {
-        let mut rng = rand::rng();
-        let has_smile = rng.random();
         let bone_color = Self::band_to_color(dominant_band);
+        let [face_r, face_g, face_b] = config.face_color();
 
         Self {
             position,
@@ -77,9 +80,11 @@ impl Skeleton {
             scale,
             rotation,
             dance_style,
-            animation_phase: rng.random_range(0.0..std::f32::consts::TAU),
-            has_smile,
+            animation_phase: 0.0,
+            has_smile: false,
             bone_color,
+            face_color: rgb(face_r, face_g, face_b),
+            outline_hue_shift: config.outline_hue_shift(),
             shuffle_step: 0,
             shuffle_transition: 1.0,
             shuffle_cooldown: 0,
@@ -101,61 +106,8 @@ impl Skeleton {
         }
     }
 
-    fn shift_hue(color: Rgb<u8>, shift: f32) -> Rgb<u8> {
-        let r = color.red as f32 / 255.0;
-        let g = color.green as f32 / 255.0;
-        let b = color.blue as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let mut h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        if h < 0.0 {
-            h += 360.0;
-        }
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        h = (h + shift * 360.0) % 360.0;
-
-        let c = v * s;
-        let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
-        let m = v - c;
-
-        let (r_prime, g_prime, b_prime) = if h < 60.0 {
-            (c, x, 0.0)
-        } else if h < 120.0 {
-            (x, c, 0.0)
-        } else if h < 180.0 {
-            (0.0, c, x)
-        } else if h < 240.0 {
-            (0.0, x, c)
-        } else if h < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        rgb(
-            ((r_prime + m) * 255.0) as u8,
-            ((g_prime + m) * 255.0) as u8,
-            ((b_prime + m) * 255.0) as u8,
-        )
-    }
-
     fn get_outline_color(&self) -> Rgb<u8> {
-        Self::shift_hue(self.bone_color, 0.5)
+        shift_hue(self.bone_color, self.outline_hue_shift)
     }
 
     fn update(&mut self, analysis: &AudioAnalysis) {
@@ -177,8 +129,10 @@ impl Skeleton {
             self.shuffle_cooldown -= 1;
         }
 
-        let bass_hit = (analysis.bands_normalized[0] > BASS_THRESHOLD
-            || analysis.bands_normalized[1] > BASS_THRESHOLD)
+        // Onset in the two lowest default bands (sub-bass and bass), or the finer
+        // bands covering them when more are configured
+        let bass_bands = 2 * analysis.band_onset.len() / DEFAULT_NUM_BANDS;
+        let bass_hit = analysis.band_onset.iter().take(bass_bands).any(|&onset| onset)
             && self.shuffle_cooldown == 0;
 
         if bass_hit {
@@ -278,7 +232,7 @@ impl Skeleton {
         let start = self.start_position;
 
         // Get trail color with hue rotation
-        let trail_color = Self::shift_hue(self.bone_color, self.trail_hue_rotation);
+        let trail_color = shift_hue(self.bone_color, self.trail_hue_rotation);
 
         // Draw tapered line from start (1px) to front foot (5px)
         // Use multiple line segments to create taper effect
@@ -460,7 +414,7 @@ impl Skeleton {
         draw.ellipse()
             .x_y(x + eye_offset * mirror, head_y + 2.0 * scale)
             .radius(eye_size)
-            .color(BLACK);
+            .color(self.face_color);
 
         let nose_x = x + 6.0 * scale * mirror;
         let nose_y = head_y;
@@ -469,7 +423,7 @@ impl Skeleton {
             pt2(nose_x + 1.5 * scale * mirror, nose_y),
             pt2(nose_x - 1.5 * scale * mirror, nose_y - 1.5 * scale),
         ];
-        draw.polygon().points(nose_points).color(BLACK);
+        draw.polygon().points(nose_points).color(self.face_color);
 
         if self.has_smile {
             let smile_start_x = x + 3.0 * scale * mirror;
@@ -488,31 +442,37 @@ impl Skeleton {
             draw.polyline()
                 .weight(1.5 * scale)
                 .points(smile_points)
-                .color(BLACK);
+                .color(self.face_color);
         }
     }
 }
 
 pub struct ShufflingSkeletons {
     skeletons: Vec<Skeleton>,
+    config: SkeletonConfig,
+    /// Spawns and their looks, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for ShufflingSkeletons {
     fn default() -> Self {
         Self {
             skeletons: Vec::new(),
+            config: Config::load().skeletons(),
+            rng: StdRng::from_os_rng(),
         }
     }
 }
 
 impl ShufflingSkeletons {
     fn try_spawn_skeleton(&mut self, analysis: &AudioAnalysis) {
-        if self.skeletons.len() >= MAX_SKELETONS {
+        if self.skeletons.len() >= self.config.cap_for_energy(analysis.energy) {
             return;
         }
 
-        let mut rng = rand::rng();
-        let scale = rng.random_range(0.6..2.5);
+        let rng = &mut self.rng;
+        let (scale_min, scale_max) = self.config.scale_range();
+        let scale = rng.random_range(scale_min..scale_max);
         let skeleton_offset = BASE_EDGE_OFFSET + (SKELETON_HEIGHT_FACTOR * scale);
 
         let dance_style = if rng.random() {
@@ -522,37 +482,39 @@ impl ShufflingSkeletons {
         };
 
         let (start_pos, end_pos, rotation) =
-            self.calculate_edge_shuffle_path(skeleton_offset, &mut rng);
+            Self::calculate_edge_shuffle_path(skeleton_offset, rng);
 
         let crossing_distance = start_pos.distance(end_pos);
         let crossing_frames = rng.random_range(600.0..1200.0);
         let speed = crossing_distance / crossing_frames;
         let velocity = (end_pos - start_pos).normalize() * speed;
 
+        // Bone colors follow the default band split, whatever num_bands is
         let dominant_band = analysis
             .bands
             .iter()
             .enumerate()
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(idx, _)| idx)
+            .map(|(idx, _)| idx * DEFAULT_NUM_BANDS / analysis.bands.len())
             .unwrap_or(0);
 
-        let skeleton = Skeleton::new(
-            start_pos,
-            velocity,
-            scale,
-            dominant_band,
-            rotation,
-            dance_style,
-        );
+        let skeleton = Skeleton {
+            animation_phase: rng.random_range(0.0..std::f32::consts::TAU),
+            has_smile: rng.random(),
+            ..Skeleton::new(
+                start_pos,
+                velocity,
+                scale,
+                dominant_band,
+                rotation,
+                dance_style,
+                &self.config,
+            )
+        };
         self.skeletons.push(skeleton);
     }
 
-    fn calculate_edge_shuffle_path(
-        &self,
-        offset: f32,
-        rng: &mut impl rand::Rng,
-    ) -> (Vec2, Vec2, f32) {
+    fn calculate_edge_shuffle_path(offset: f32, rng: &mut impl Rng) -> (Vec2, Vec2, f32) {
         let half_width = SPAWN_AREA_WIDTH / 2.0;
         let half_height = SPAWN_AREA_HEIGHT / 2.0;
 
@@ -625,8 +587,10 @@ impl Visualization for ShufflingSkeletons {
         let bounds = Rect::from_w_h(SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT);
         self.skeletons.retain(|s| s.is_in_bounds(bounds));
 
-        let mut rng = rand::rng();
-        if self.skeletons.len() < MAX_SKELETONS && rng.random::<f32>() < 0.05 {
+        // Busier tracks spawn more often and allow more dancers (up to the configured cap)
+        let cap = self.config.cap_for_energy(analysis.energy);
+        let chance = self.config.spawn_chance_for_energy(analysis.energy);
+        if self.skeletons.len() < cap && self.rng.random::<f32>() < chance {
             self.try_spawn_skeleton(analysis);
         }
     }
@@ -636,4 +600,26 @@ impl Visualization for ShufflingSkeletons {
             skeleton.draw(draw);
         }
     }
+
+    fn warm_up(&mut self, analysis: &AudioAnalysis, frames: u32) {
+        // Fill up to the cap at once, each newcomer already partway along its path
+        let existing = self.skeletons.len();
+        while self.skeletons.len() < self.config.cap_for_energy(analysis.energy) {
+            self.try_spawn_skeleton(analysis);
+        }
+
+        for skeleton in &mut self.skeletons[existing..] {
+            for _ in 0..self.rng.random_range(0..frames.max(1)) {
+                skeleton.update(analysis);
+            }
+        }
+
+        let bounds = Rect::from_w_h(SPAWN_AREA_WIDTH, SPAWN_AREA_HEIGHT);
+        self.skeletons.retain(|s| s.is_in_bounds(bounds));
+    }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.skeletons.clear();
+        self.rng = rng;
+    }
 }
@@ -6,7 +6,7 @@
 use super::Visualization;
 use nannou::prelude::*;
 
-use crate::audio::{AudioAnalysis, NUM_BANDS};
+use crate::audio::AudioAnalysis;
 
 const NUM_LINES: usize = if cfg!(debug_assertions) { 64 } else { 128 };
 
@@ -54,15 +54,16 @@ impl SolarBeat {
     }
 
     /// Maps a line index to a band with interpolation
-    fn line_to_band_value(&self, line_idx: usize, bands: &[f32; NUM_BANDS]) -> f32 {
+    fn line_to_band_value(&self, line_idx: usize, bands: &[f32]) -> f32 {
         let normalized = line_idx as f32 / (NUM_LINES - 1) as f32;
         // Power scale emphasizes bass frequencies
         let scaled = normalized.powf(2.0);
-        let band_pos = scaled * (NUM_BANDS - 1) as f32;
+        let last_band = bands.len().saturating_sub(1);
+        let band_pos = scaled * last_band as f32;
 
         // Interpolate between bands
-        let low_band = (band_pos as usize).min(NUM_BANDS - 1);
-        let high_band = (low_band + 1).min(NUM_BANDS - 1);
+        let low_band = (band_pos as usize).min(last_band);
+        let high_band = (low_band + 1).min(last_band);
         let t = band_pos - low_band as f32;
 
         bands[low_band] * (1.0 - t) + bands[high_band] * t
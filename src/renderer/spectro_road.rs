@@ -5,17 +5,18 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::{AudioAnalysis, NUM_BANDS};
+use crate::audio::{AudioAnalysis, DEFAULT_NUM_BANDS};
 
 const HISTORY_SIZE: usize = if cfg!(debug_assertions) { 50 } else { 200 };
-/// Number of visual bins to display (interpolated from NUM_BANDS)
+/// Number of visual bins to display (interpolated from the analyzer's bands)
 const DISPLAY_BINS: usize = if cfg!(debug_assertions) { 16 } else { 24 };
 
 pub struct SpectroRoad {
     /// History of band values for scrolling display
-    history: Vec<[f32; NUM_BANDS]>,
+    history: Vec<Vec<f32>>,
     /// Beat-reactive shake offset
     shake_x: f32,
     shake_y: f32,
@@ -29,12 +30,18 @@ pub struct SpectroRoad {
     frame_count: u32,
     /// Counter to slow down history scrolling
     shift_counter: u32,
+    /// Gutter size of each cell (per column, then bin) as a fraction of its
+    /// maximum, rolled once so the layout doesn't shimmer from frame to frame
+    gutters: Vec<[[f32; 2]; DISPLAY_BINS]>,
+    /// Gutter layout and shake, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for SpectroRoad {
     fn default() -> Self {
+        let mut rng = StdRng::from_os_rng();
         Self {
-            history: vec![[0.0; NUM_BANDS]; HISTORY_SIZE],
+            history: vec![vec![0.0; DEFAULT_NUM_BANDS]; HISTORY_SIZE],
             shake_x: 0.0,
             shake_y: 0.0,
             rotation: 0.0,
@@ -42,25 +49,43 @@ impl Default for SpectroRoad {
             bass: 0.0,
             frame_count: 0,
             shift_counter: 0,
+            gutters: Self::roll_gutters(&mut rng),
+            rng,
         }
     }
 }
 
 impl SpectroRoad {
-    /// Interpolate from NUM_BANDS to a specific display bin
-    fn interpolate_band(bands: &[f32; NUM_BANDS], bin_idx: usize) -> f32 {
+    /// Fresh gutter sizes for every cell of the road
+    fn roll_gutters(rng: &mut impl Rng) -> Vec<[[f32; 2]; DISPLAY_BINS]> {
+        (0..HISTORY_SIZE)
+            .map(|_| std::array::from_fn(|_| [rng.random(), rng.random()]))
+            .collect()
+    }
+
+    /// Interpolate from the analyzer's bands to a specific display bin
+    fn interpolate_band(bands: &[f32], bin_idx: usize) -> f32 {
         // Map display bin to band position with log-like scaling for bass emphasis
         let normalized = bin_idx as f32 / (DISPLAY_BINS - 1) as f32;
         let scaled = normalized.powf(1.5); // Slight emphasis on lower frequencies
-        let band_pos = scaled * (NUM_BANDS - 1) as f32;
+        let last_band = bands.len().saturating_sub(1);
+        let band_pos = scaled * last_band as f32;
 
-        let low_band = (band_pos as usize).min(NUM_BANDS - 1);
-        let high_band = (low_band + 1).min(NUM_BANDS - 1);
+        let low_band = (band_pos as usize).min(last_band);
+        let high_band = (low_band + 1).min(last_band);
         let t = band_pos - low_band as f32;
 
         bands[low_band] * (1.0 - t) + bands[high_band] * t
     }
 
+    /// Gutter (width, height) for a cell of `cell_w` x `cell_h`, up to 70% of each dimension
+    fn gutter(&self, col_idx: usize, bin_idx: usize, cell_w: f32, cell_h: f32) -> (f32, f32) {
+        let [fw, fh] = self.gutters[col_idx][bin_idx];
+        let max_w = (cell_w * 0.7).max(2.0);
+        let max_h = (cell_h * 0.7).max(2.0);
+        (1.0 + fw * (max_w - 1.0), 1.0 + fh * (max_h - 1.0))
+    }
+
     /// Calculate border width based on sin wave (15s period)
     fn border_width(&self) -> f32 {
         // Sin wave over 15 seconds (assuming 60fps)
@@ -188,7 +213,7 @@ impl Visualization for SpectroRoad {
         if self.shift_counter % 3 == 0 {
             self.history.remove(0);
             // Use analyzer's already-smoothed bands (0-1 range)
-            self.history.push(analysis.bands);
+            self.history.push(analysis.bands.clone());
         }
 
         // Track intensity with less smoothing for more reactive scaling
@@ -202,7 +227,7 @@ impl Visualization for SpectroRoad {
 
         // Beat-reactive shake: trigger on bass hits
         if analysis.bass > 0.4 {
-            let mut rng = rand::rng();
+            let rng = &mut self.rng;
             let shake_intensity = analysis.bass * 15.0;
             self.shake_x += rng.random_range(-1.0..1.0) * shake_intensity;
             self.shake_y += rng.random_range(-1.0..1.0) * shake_intensity;
@@ -259,10 +284,7 @@ impl Visualization for SpectroRoad {
 
                     let color = self.magnitude_to_color(magnitude);
                     let border_color = self.border_color(color);
-                    let mut rng = rand::rng();
-                    // Gutter can reach up to 70% of each dimension
-                    let gutter_w = rng.random_range(1.0..(col_width * 0.7).max(2.0));
-                    let gutter_h = rng.random_range(1.0..(bin_size * 0.7).max(2.0));
+                    let (gutter_w, gutter_h) = self.gutter(col_idx, bin_idx, col_width, bin_size);
                     // Scale rects dramatically with energy (up to 150% larger at max energy)
                     let intensity_scale = 1.0 + self.intensity * 1.5;
                     let rect_w = (col_width - gutter_w) * intensity_scale;
@@ -300,9 +322,7 @@ impl Visualization for SpectroRoad {
 
                     let color = self.magnitude_to_color(magnitude);
                     let border_color = self.border_color(color);
-                    let mut rng = rand::rng();
-                    let gutter_w = rng.random_range(1.0..(bin_size * 0.7).max(2.0));
-                    let gutter_h = rng.random_range(1.0..(col_height * 0.7).max(2.0));
+                    let (gutter_w, gutter_h) = self.gutter(col_idx, bin_idx, bin_size, col_height);
                     // Scale rects dramatically with energy (up to 150% larger at max energy)
                     let intensity_scale = 1.0 + self.intensity * 1.5;
                     let rect_w = (bin_size - gutter_w) * intensity_scale;
@@ -340,9 +360,7 @@ impl Visualization for SpectroRoad {
 
                     let color = self.magnitude_to_color(magnitude);
                     let border_color = self.border_color(color);
-                    let mut rng = rand::rng();
-                    let gutter_w = rng.random_range(1.0..(col_width * 0.7).max(2.0));
-                    let gutter_h = rng.random_range(1.0..(bin_size * 0.7).max(2.0));
+                    let (gutter_w, gutter_h) = self.gutter(col_idx, bin_idx, col_width, bin_size);
                     // Scale rects dramatically with energy (up to 150% larger at max energy)
                     let intensity_scale = 1.0 + self.intensity * 1.5;
                     let rect_w = (col_width - gutter_w) * intensity_scale;
@@ -380,9 +398,7 @@ impl Visualization for SpectroRoad {
 
                     let color = self.magnitude_to_color(magnitude);
                     let border_color = self.border_color(color);
-                    let mut rng = rand::rng();
-                    let gutter_w = rng.random_range(1.0..(bin_size * 0.7).max(2.0));
-                    let gutter_h = rng.random_range(1.0..(col_height * 0.7).max(2.0));
+                    let (gutter_w, gutter_h) = self.gutter(col_idx, bin_idx, bin_size, col_height);
                     // Scale rects dramatically with energy (up to 150% larger at max energy)
                     let intensity_scale = 1.0 + self.intensity * 1.5;
                     let rect_w = (bin_size - gutter_w) * intensity_scale;
@@ -401,4 +417,33 @@ impl Visualization for SpectroRoad {
             }
         }
     }
+
+    /// Start from an empty road with gutters rolled from `rng`
+    fn reset(&mut self, mut rng: StdRng) {
+        for column in &mut self.history {
+            column.fill(0.0);
+        }
+        self.gutters = Self::roll_gutters(&mut rng);
+        self.rng = rng;
+        self.shake_x = 0.0;
+        self.shake_y = 0.0;
+        self.rotation = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gutters_are_stable_and_bounded() {
+        let road = SpectroRoad::default();
+        for col_idx in [0, HISTORY_SIZE - 1] {
+            for bin_idx in 0..DISPLAY_BINS {
+                let (w, h) = road.gutter(col_idx, bin_idx, 10.0, 4.0);
+                assert!((1.0..=7.0).contains(&w) && (1.0..=2.8).contains(&h));
+                assert_eq!(road.gutter(col_idx, bin_idx, 10.0, 4.0), (w, h));
+            }
+        }
+    }
 }
@@ -7,6 +7,7 @@ use super::Visualization;
 use nannou::prelude::*;
 
 use crate::audio::AudioAnalysis;
+use crate::utils::hsv_to_rgba;
 
 /// Number of rings in the tunnel
 const NUM_RINGS: usize = if cfg!(debug_assertions) { 40 } else { 80 };
@@ -47,36 +48,6 @@ impl Default for SpiralTunnel {
     }
 }
 
-impl SpiralTunnel {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for SpiralTunnel {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
@@ -172,7 +143,7 @@ impl Visualization for SpiralTunnel {
                     .collect();
 
                 let arm_hue = (hue + arm as f32 * 40.0) % 360.0;
-                let color = Self::hsv_to_rgba(arm_hue, saturation, value, alpha);
+                let color = hsv_to_rgba(arm_hue, saturation, value, alpha);
                 let weight = 2.0 + depth_factor * 4.0 + self.bass * 3.0;
 
                 draw.polyline().weight(weight).points(points).color(color);
@@ -186,7 +157,7 @@ impl Visualization for SpiralTunnel {
             let r = glow_radius * (1.0 - t);
             let hue = (self.hue_offset + t * 60.0) % 360.0;
             let alpha = (1.0 - t) * 0.4 * (0.5 + self.bass * 0.5);
-            let color = Self::hsv_to_rgba(hue, 0.8, 0.9, alpha);
+            let color = hsv_to_rgba(hue, 0.8, 0.9, alpha);
 
             draw.ellipse().xy(center).radius(r).color(color);
         }
@@ -5,9 +5,10 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::{AudioAnalysis, NUM_BANDS};
+use crate::audio::{AudioAnalysis, DEFAULT_NUM_BANDS};
 
 const MAX_SQUARES: usize = 60;
 const PULSE_DURATION_SECS: f32 = 10.0;
@@ -28,7 +29,7 @@ struct Square {
 pub struct Squares {
     squares: Vec<Square>,
     // Band values from analyzer (no additional smoothing needed)
-    bands: [f32; NUM_BANDS],
+    bands: Vec<f32>,
 
     // Hue offset that cycles on high peaks
     hue_offset: f32,
@@ -46,13 +47,16 @@ pub struct Squares {
 
     // Peak detection
     peak_detected: bool,
+
+    // Spawns and drift, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for Squares {
     fn default() -> Self {
         Self {
             squares: Vec::with_capacity(MAX_SQUARES),
-            bands: [0.0; NUM_BANDS],
+            bands: vec![0.0; DEFAULT_NUM_BANDS],
             hue_offset: 0.0,
             frame_count: 0,
             translation_x: 0.0,
@@ -60,6 +64,7 @@ impl Default for Squares {
             bounds_w: 800.0,
             bounds_h: 600.0,
             peak_detected: false,
+            rng: StdRng::from_os_rng(),
         }
     }
 }
@@ -67,7 +72,7 @@ impl Default for Squares {
 impl Squares {
     /// Convert band index to hue angle (0-1)
     fn band_to_hue(&self, band_idx: usize) -> f32 {
-        let normalized = band_idx as f32 / NUM_BANDS as f32;
+        let normalized = band_idx as f32 / self.bands.len().max(1) as f32;
         // Map to hue: bass = red/orange, mids = green/cyan, highs = blue/purple
         (normalized + self.hue_offset) % 1.0
     }
@@ -87,17 +92,18 @@ impl Squares {
 
     /// Spawn a new square at a random position
     fn spawn_square(&mut self) {
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         // Random band for color
-        let band_idx = rng.random_range(0..NUM_BANDS);
+        let num_bands = self.bands.len().max(1);
+        let band_idx = rng.random_range(0..num_bands);
 
         // Random position within bounds
         let x = rng.random_range(-self.bounds_w / 2.0..self.bounds_w / 2.0);
         let y = rng.random_range(-self.bounds_h / 2.0..self.bounds_h / 2.0);
 
         // Random size based on band (lower = larger), scaled down for fewer bands
-        let size_factor = 1.0 - (band_idx as f32 / NUM_BANDS as f32);
+        let size_factor = 1.0 - (band_idx as f32 / num_bands as f32);
         let size = 10.0 + size_factor * 40.0 + rng.random_range(0.0..20.0);
 
         // Random rotation direction
@@ -120,7 +126,7 @@ impl Visualization for Squares {
         self.frame_count = self.frame_count.wrapping_add(1);
 
         // Use analyzer's already-smoothed bands directly (no additional smoothing needed)
-        self.bands = analysis.bands;
+        self.bands.clone_from(&analysis.bands);
 
         // Detect high peak and cycle hue (use treble from analysis)
         let peak_now = analysis.treble > 0.5;
@@ -131,9 +137,8 @@ impl Visualization for Squares {
         self.peak_detected = peak_now;
 
         // Move translation based on energy (psychedelic drift)
-        let mut rng = rand::rng();
-        self.translation_x += rng.random_range(-1.0..1.0) * analysis.energy * 2.0;
-        self.translation_y += rng.random_range(-1.0..1.0) * analysis.energy * 2.0;
+        self.translation_x += self.rng.random_range(-1.0..1.0) * analysis.energy * 2.0;
+        self.translation_y += self.rng.random_range(-1.0..1.0) * analysis.energy * 2.0;
 
         // Dampen translation to prevent flying off
         self.translation_x *= 0.95;
@@ -155,10 +160,10 @@ impl Visualization for Squares {
 
         // Spawn new squares based on energy (lower threshold, higher chance)
         let spawn_chance = 0.3 + analysis.energy * 0.7;
-        while rng.random::<f32>() < spawn_chance && self.squares.len() < MAX_SQUARES {
+        while self.rng.random::<f32>() < spawn_chance && self.squares.len() < MAX_SQUARES {
             self.spawn_square();
             // Reduce chance for subsequent spawns this frame
-            if rng.random::<f32>() > 0.5 {
+            if self.rng.random::<f32>() > 0.5 {
                 break;
             }
         }
@@ -220,4 +225,9 @@ impl Visualization for Squares {
                 .color(hsla(hue, saturation, lightness, alpha));
         }
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.squares.clear();
+        self.rng = rng;
+    }
 }
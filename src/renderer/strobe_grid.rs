@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgba;
 
 /// Grid size (odd number for true center cell)
 const GRID_SIZE: usize = if cfg!(debug_assertions) { 9 } else { 13 };
@@ -43,11 +45,19 @@ pub struct StrobeGrid {
     frame_count: u32,
     /// Wave phase for ripple effects
     wave_phase: f32,
+    /// Cell hues, cooldowns and flicker, seeded by `reset`
+    rng: StdRng,
 }
 
 impl Default for StrobeGrid {
     fn default() -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(StdRng::from_os_rng())
+    }
+}
+
+impl StrobeGrid {
+    /// Fresh grid with cell hues rolled from `rng`
+    fn with_rng(mut rng: StdRng) -> Self {
 
         let cells: Vec<Vec<Cell>> = (0..GRID_SIZE)
             .map(|y| {
@@ -84,44 +94,15 @@ impl Default for StrobeGrid {
             treble: 0.0,
             frame_count: 0,
             wave_phase: 0.0,
+            rng,
         }
     }
 }
 
-impl StrobeGrid {
-    fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        srgba(
-            ((r1 + m) * 255.0) as u8,
-            ((g1 + m) * 255.0) as u8,
-            ((b1 + m) * 255.0) as u8,
-            (alpha * 255.0) as u8,
-        )
-    }
-}
-
 impl Visualization for StrobeGrid {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
 
         // Fast attack, moderate decay
         let attack = 0.8;
@@ -171,7 +152,8 @@ impl Visualization for StrobeGrid {
                 let wave = (dist * 0.5 - self.wave_phase).sin() * 0.5 + 0.5;
 
                 // Get band energy for this cell
-                let band_energy = analysis.bands_normalized[cell.band_idx];
+                let band_energy =
+                    band_group(&analysis.bands_normalized, cell.band_idx, DEFAULT_NUM_BANDS);
 
                 // Trigger flash on high energy for matching band
                 if band_energy > 0.5 && cell.cooldown == 0 {
@@ -219,7 +201,7 @@ impl Visualization for StrobeGrid {
                     if cell.brightness > 0.3 {
                         let glow_size = cell_width.min(cell_height) - gap + cell.brightness * 10.0;
                         let glow_alpha = (cell.brightness - 0.3) * 0.3;
-                        let glow_color = Self::hsv_to_rgba(hue, saturation * 0.5, value, glow_alpha);
+                        let glow_color = hsv_to_rgba(hue, saturation * 0.5, value, glow_alpha);
 
                         draw.rect()
                             .x_y(px, py)
@@ -228,7 +210,7 @@ impl Visualization for StrobeGrid {
                     }
 
                     // Draw cell
-                    let color = Self::hsv_to_rgba(hue, saturation, value, alpha);
+                    let color = hsv_to_rgba(hue, saturation, value, alpha);
                     draw.rect()
                         .x_y(px, py)
                         .w_h(cell_width - gap, cell_height - gap)
@@ -258,7 +240,7 @@ impl Visualization for StrobeGrid {
         if self.strobe_intensity > 0.3 {
             let border_alpha = (self.strobe_intensity - 0.3) * 0.5;
             let hue = self.hue_offset;
-            let color = Self::hsv_to_rgba(hue, 0.8, 0.9, border_alpha);
+            let color = hsv_to_rgba(hue, 0.8, 0.9, border_alpha);
 
             draw.rect()
                 .xy(bounds.xy())
@@ -268,4 +250,8 @@ impl Visualization for StrobeGrid {
                 .stroke(color);
         }
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        *self = Self::with_rng(rng);
+    }
 }
@@ -5,9 +5,11 @@
 
 use super::Visualization;
 use nannou::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::audio::AudioAnalysis;
+use crate::audio::{band_group, AudioAnalysis, DEFAULT_NUM_BANDS};
+use crate::utils::hsv_to_rgb;
 
 /// Maximum number of lightning bolts per frame
 const MAX_BOLTS: usize = if cfg!(debug_assertions) { 4 } else { 8 };
@@ -55,6 +57,10 @@ pub struct TeslaCoil {
     kick_intensity: f32,
     /// Treble level for extra effects
     treble: f32,
+    /// Bolt angles, seeded by `reset`
+    rng: StdRng,
+    /// Seeds the bolt shapes `draw` generates, rolled once per update
+    noise_seed: u64,
 }
 
 impl Default for TeslaCoil {
@@ -71,20 +77,28 @@ impl Default for TeslaCoil {
             frame_count: 0,
             kick_intensity: 0.0,
             treble: 0.0,
+            rng: StdRng::from_os_rng(),
+            noise_seed: 0,
         }
     }
 }
 
 impl TeslaCoil {
     /// Generate a lightning bolt from center toward an angle
-    fn generate_bolt(&self, center: Vec2, max_radius: f32, angle: f32, intensity: f32) -> LightningBolt {
-        let mut rng = rand::rng();
+    fn generate_bolt(
+        &self,
+        rng: &mut impl Rng,
+        center: Vec2,
+        max_radius: f32,
+        angle: f32,
+        intensity: f32,
+    ) -> LightningBolt {
         let mut segments = Vec::new();
 
         // Main bolt
         self.generate_bolt_segments(
             &mut segments,
-            &mut rng,
+            rng,
             center,
             angle,
             max_radius * (0.6 + intensity * 0.4),
@@ -163,30 +177,6 @@ impl TeslaCoil {
         }
     }
 
-    /// Convert hue (0-360), saturation (0-1), value (0-1) to RGB
-    fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
-        let hue = hue % 360.0;
-        let c = value * saturation;
-        let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
-        let m = value - c;
-
-        let (r1, g1, b1) = if hue < 60.0 {
-            (c, x, 0.0)
-        } else if hue < 120.0 {
-            (x, c, 0.0)
-        } else if hue < 180.0 {
-            (0.0, c, x)
-        } else if hue < 240.0 {
-            (0.0, x, c)
-        } else if hue < 300.0 {
-            (x, 0.0, c)
-        } else {
-            (c, 0.0, x)
-        };
-
-        (r1 + m, g1 + m, b1 + m)
-    }
-
     /// Get lightning color based on current audio state
     fn get_lightning_color(&self, brightness: f32) -> Srgba<u8> {
         // Base hue cycles through electric blues/purples/cyans
@@ -201,7 +191,7 @@ impl TeslaCoil {
         // Value is the brightness
         let value = brightness.clamp(0.0, 1.0);
 
-        let (r, g, b) = Self::hsv_to_rgb(hue, saturation, value);
+        let (r, g, b) = hsv_to_rgb(hue, saturation, value);
 
         // Alpha for trail blending
         let alpha = (brightness * 200.0) as u8;
@@ -240,7 +230,7 @@ impl TeslaCoil {
 
         // Bright core that pulses with kick
         let core_brightness = 0.6 + self.kick_intensity * 0.4;
-        let (r, g, b) = Self::hsv_to_rgb(self.hue_offset, 0.3, core_brightness);
+        let (r, g, b) = hsv_to_rgb(self.hue_offset, 0.3, core_brightness);
         draw.ellipse()
             .xy(center)
             .radius(orb_radius * 0.3)
@@ -256,6 +246,7 @@ impl TeslaCoil {
 impl Visualization for TeslaCoil {
     fn update(&mut self, analysis: &AudioAnalysis) {
         self.frame_count = self.frame_count.wrapping_add(1);
+        self.noise_seed = self.rng.random();
 
         // Calculate velocities (rate of change)
         self.bass_velocity = (analysis.bass - self.prev_bass).abs();
@@ -282,7 +273,8 @@ impl Visualization for TeslaCoil {
         self.treble = self.treble * 0.8 + analysis.treble * 0.2;
 
         // Kick intensity combines bass and low-mids, boosted on punch detection
-        let kick = (self.bass + analysis.bands_normalized[2] * 0.5) / 1.5;
+        let low_mid = band_group(&analysis.bands_normalized, 2, DEFAULT_NUM_BANDS);
+        let kick = (self.bass + low_mid * 0.5) / 1.5;
         if analysis.punch_detected {
             // Punch gives maximum intensity for dramatic lightning burst
             self.kick_intensity = 1.0;
@@ -304,7 +296,7 @@ impl Visualization for TeslaCoil {
 
         // Only spawn bolts if there's enough energy
         if self.kick_intensity > 0.1 {
-            let mut rng = rand::rng();
+            let rng = &mut self.rng;
 
             // Number of bolts based on intensity
             let num_bolts = ((self.kick_intensity * MAX_BOLTS as f32) as usize).max(1);
@@ -331,6 +323,7 @@ impl Visualization for TeslaCoil {
     fn draw(&self, draw: &Draw, bounds: Rect) {
         let center = bounds.xy();
         let max_radius = bounds.w().min(bounds.h()) / 2.0;
+        let mut rng = StdRng::seed_from_u64(self.noise_seed);
 
         // Draw center orb first (behind lightning)
         self.draw_center_orb(draw, center, max_radius);
@@ -343,7 +336,7 @@ impl Visualization for TeslaCoil {
                 let intensity = first_seg.brightness;
 
                 // Generate actual bolt with proper bounds
-                let actual_bolt = self.generate_bolt(center, max_radius, angle, intensity);
+                let actual_bolt = self.generate_bolt(&mut rng, center, max_radius, angle, intensity);
 
                 // Draw all segments
                 for segment in &actual_bolt.segments {
@@ -374,12 +367,11 @@ impl Visualization for TeslaCoil {
 
         // Add extra bolts on peaks for dramatic effect
         if self.kick_intensity > 0.6 {
-            let mut rng = rand::rng();
             let extra_bolts = ((self.kick_intensity - 0.6) * 10.0) as usize;
 
             for _ in 0..extra_bolts {
                 let angle = rng.random_range(0.0..std::f32::consts::TAU);
-                let bolt = self.generate_bolt(center, max_radius, angle, self.kick_intensity * 0.8);
+                let bolt = self.generate_bolt(&mut rng, center, max_radius, angle, self.kick_intensity * 0.8);
 
                 for segment in &bolt.segments {
                     let color = self.get_lightning_color(segment.brightness * 0.7);
@@ -392,4 +384,9 @@ impl Visualization for TeslaCoil {
             }
         }
     }
+
+    fn reset(&mut self, rng: StdRng) {
+        self.bolts.clear();
+        self.rng = rng;
+    }
 }
@@ -3,6 +3,9 @@
 //! Centralizes all keyboard shortcuts and key mapping logic.
 
 use nannou::prelude::*;
+use std::collections::HashMap;
+
+use crate::log_error;
 
 /// Actions that can be triggered by key presses
 #[derive(Debug, Clone, PartialEq)]
@@ -10,6 +13,7 @@ pub enum Action {
     // App-level
     Quit,
     ShowHelp,
+    Blackout,
 
     // Search mode navigation (audio device search)
     SearchCancel,
@@ -26,13 +30,45 @@ pub enum Action {
     VizPickerMoveDown,
     VizPickerSelect,
     VizPickerToggle,
+    VizPickerOpacityDown,
+    VizPickerOpacityUp,
+
+    // Script parameter panel navigation
+    ParamPanelHide,
+    ParamPanelMoveUp,
+    ParamPanelMoveDown,
+    ParamPanelDecrease,
+    ParamPanelIncrease,
+    ParamPanelReset,
 
     // Normal mode
     StartSearch,
     ToggleDebugViz,
+    ToggleLog,
+    ToggleHud,
+    ToggleParams,
     ToggleLock,
     CycleNext,
+    CyclePrevious,
+    ClearOverlays,
     CycleScript,
+    AddScriptOverlay,
+    ToggleScriptOverlay,
+    CycleSymmetry,
+    CycleMirror,
+    BurnStrengthDown,
+    BurnStrengthUp,
+    InputGainDown,
+    InputGainUp,
+    CycleBlendMode,
+    ToggleSafeMode,
+    ToggleAberration,
+    TapTempo,
+    ClearTapTempo,
+    ToggleFullscreen,
+    Screenshot,
+    SavePreset(u8),
+    RecallPreset(u8),
 }
 
 /// Convert a Key to a character (alphanumeric only)
@@ -87,8 +123,233 @@ pub fn key_to_char(key: Key, shift: bool) -> Option<char> {
     })
 }
 
-/// Parse a key into an action based on current mode
-pub fn parse_key(key: Key, shift: bool, search_active: bool, viz_picker_active: bool) -> Option<Action> {
+/// Config names of the remappable actions (preset slots are `recall_preset_N` / `save_preset_N`)
+const ACTION_NAMES: &[(&str, Action)] = &[
+    ("quit", Action::Quit),
+    ("show_help", Action::ShowHelp),
+    ("blackout", Action::Blackout),
+    ("start_search", Action::StartSearch),
+    ("toggle_debug_viz", Action::ToggleDebugViz),
+    ("toggle_log", Action::ToggleLog),
+    ("toggle_hud", Action::ToggleHud),
+    ("toggle_params", Action::ToggleParams),
+    ("toggle_lock", Action::ToggleLock),
+    ("cycle_next", Action::CycleNext),
+    ("cycle_previous", Action::CyclePrevious),
+    ("clear_overlays", Action::ClearOverlays),
+    ("cycle_script", Action::CycleScript),
+    ("add_script_overlay", Action::AddScriptOverlay),
+    ("toggle_script_overlay", Action::ToggleScriptOverlay),
+    ("cycle_symmetry", Action::CycleSymmetry),
+    ("cycle_mirror", Action::CycleMirror),
+    ("burn_strength_down", Action::BurnStrengthDown),
+    ("burn_strength_up", Action::BurnStrengthUp),
+    ("input_gain_down", Action::InputGainDown),
+    ("input_gain_up", Action::InputGainUp),
+    ("cycle_blend_mode", Action::CycleBlendMode),
+    ("toggle_safe_mode", Action::ToggleSafeMode),
+    ("toggle_aberration", Action::ToggleAberration),
+    ("tap_tempo", Action::TapTempo),
+    ("clear_tap_tempo", Action::ClearTapTempo),
+    ("toggle_fullscreen", Action::ToggleFullscreen),
+    ("screenshot", Action::Screenshot),
+];
+
+/// Keys that can be named in the config besides single letters and digits
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("space", Key::Space),
+    ("escape", Key::Escape),
+    ("esc", Key::Escape),
+    ("enter", Key::Return),
+    ("return", Key::Return),
+    ("tab", Key::Tab),
+    ("backspace", Key::Back),
+    ("delete", Key::Delete),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+    ("/", Key::Slash),
+    ("\\", Key::Backslash),
+    ("[", Key::LBracket),
+    ("]", Key::RBracket),
+    ("-", Key::Minus),
+    ("=", Key::Equals),
+    (",", Key::Comma),
+    (".", Key::Period),
+    (";", Key::Semicolon),
+    ("'", Key::Apostrophe),
+    ("`", Key::Grave),
+    ("f1", Key::F1),
+    ("f2", Key::F2),
+    ("f3", Key::F3),
+    ("f4", Key::F4),
+    ("f5", Key::F5),
+    ("f6", Key::F6),
+    ("f7", Key::F7),
+    ("f8", Key::F8),
+    ("f9", Key::F9),
+    ("f10", Key::F10),
+    ("f11", Key::F11),
+    ("f12", Key::F12),
+];
+
+/// Letter and digit keys, looked up through `key_to_char`
+const CHAR_KEYS: &[Key] = &[
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+    Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R,
+    Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4,
+    Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+];
+
+/// Look up an action by its config name (case-insensitive)
+pub fn action_from_name(name: &str) -> Option<Action> {
+    let name = name.trim().to_ascii_lowercase();
+    if let Some((_, action)) = ACTION_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(action.clone());
+    }
+    let slot = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| (1..=9).contains(n))
+    };
+    slot("recall_preset_")
+        .map(Action::RecallPreset)
+        .or_else(|| slot("save_preset_").map(Action::SavePreset))
+}
+
+/// Default key of every remappable action as (action name, key name), for
+/// `--print-config-defaults`. Preset slots follow the named actions.
+pub fn default_key_names() -> Vec<(String, String)> {
+    let presets = (1..=9).flat_map(|n| [format!("recall_preset_{}", n), format!("save_preset_{}", n)]);
+    ACTION_NAMES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .chain(presets)
+        .filter_map(|name| {
+            let key = default_key_name(&action_from_name(&name)?)?;
+            Some((name, key))
+        })
+        .collect()
+}
+
+/// Name of the key that triggers `action` in normal mode without remapping,
+/// preferring the unshifted key
+fn default_key_name(action: &Action) -> Option<String> {
+    let named = KEY_NAMES.iter().map(|&(name, key)| (name.to_string(), key));
+    let chars = CHAR_KEYS
+        .iter()
+        .filter_map(|&key| Some((key_to_char(key, false)?.to_string(), key)));
+    let keys: Vec<(String, Key)> = named.chain(chars).collect();
+    [false, true].into_iter().find_map(|shift| {
+        let (name, _) = keys
+            .iter()
+            .find(|(_, key)| default_action(*key, shift, false, false, false).as_ref() == Some(action))?;
+        Some(if shift { format!("Shift+{}", name) } else { name.clone() })
+    })
+}
+
+/// Parse a key name like `"x"`, `"F5"`, `"Space"` or `"Shift+t"` into (key, shift)
+fn key_from_name(name: &str) -> Option<(Key, bool)> {
+    let name = name.trim().to_ascii_lowercase();
+    let (name, shift) = match name.strip_prefix("shift+") {
+        Some(rest) => (rest.to_string(), true),
+        None => (name, false),
+    };
+    if let Some((_, key)) = KEY_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some((*key, shift));
+    }
+    let mut chars = name.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    CHAR_KEYS
+        .iter()
+        .find(|&&key| key_to_char(key, false) == Some(c))
+        .map(|&key| (key, shift))
+}
+
+/// User remappings from the config's `[keybindings]` table (action name -> key name).
+/// A remapped action answers to its new key instead of its default one.
+#[derive(Default)]
+pub struct KeyBindings {
+    overrides: HashMap<(Key, bool), Action>,
+}
+
+impl KeyBindings {
+    /// Build from (action name, key name) pairs, skipping unknown names with a warning
+    pub fn from_names<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut overrides = HashMap::new();
+        for (action_name, key_name) in pairs {
+            let Some(action) = action_from_name(action_name) else {
+                log_error!("Unknown action {:?} in [keybindings], ignoring", action_name);
+                continue;
+            };
+            let Some(key) = key_from_name(key_name) else {
+                log_error!("Unknown key {:?} for {} in [keybindings], ignoring", key_name, action_name);
+                continue;
+            };
+            overrides.insert(key, action);
+        }
+        Self { overrides }
+    }
+
+    /// Remapped action for this key, if any (an unshifted binding also matches with Shift held)
+    fn action_for(&self, key: Key, shift: bool) -> Option<&Action> {
+        self.overrides
+            .get(&(key, shift))
+            .or_else(|| self.overrides.get(&(key, false)))
+    }
+
+    fn is_remapped(&self, action: &Action) -> bool {
+        self.overrides.values().any(|a| a == action)
+    }
+}
+
+/// Parse a key into an action based on current mode, consulting the user's
+/// remappings before the default bindings
+pub fn parse_key(
+    key: Key,
+    shift: bool,
+    search_active: bool,
+    viz_picker_active: bool,
+    param_panel_active: bool,
+    bindings: &KeyBindings,
+) -> Option<Action> {
+    if let Some(action) = bindings.action_for(key, shift) {
+        // Remapped keys work in the same modes as the defaults they replace
+        let available = match action {
+            Action::Quit => true,
+            Action::ShowHelp | Action::Blackout => !search_active,
+            _ => !search_active && !viz_picker_active && !param_panel_active,
+        };
+        if available {
+            return Some(action.clone());
+        }
+    }
+
+    let action = default_action(key, shift, search_active, viz_picker_active, param_panel_active)?;
+    if bindings.is_remapped(&action) {
+        None
+    } else {
+        Some(action)
+    }
+}
+
+/// Built-in bindings for the current mode
+fn default_action(
+    key: Key,
+    shift: bool,
+    search_active: bool,
+    viz_picker_active: bool,
+    param_panel_active: bool,
+) -> Option<Action> {
     // Global quit key
     if key == Key::Q {
         return Some(Action::Quit);
@@ -99,6 +360,11 @@ pub fn parse_key(key: Key, shift: bool, search_active: bool, viz_picker_active:
         return Some(Action::ShowHelp);
     }
 
+    // Blackout, also over the picker and parameter panel (search takes letters)
+    if !search_active && key == Key::B {
+        return Some(Action::Blackout);
+    }
+
     // Search mode bindings (audio device search)
     if search_active {
         return match key {
@@ -119,18 +385,146 @@ pub fn parse_key(key: Key, shift: bool, search_active: bool, viz_picker_active:
             Key::Down => Some(Action::VizPickerMoveDown),
             Key::Return => Some(Action::VizPickerSelect),
             Key::T => Some(Action::VizPickerToggle),
+            Key::Left => Some(Action::VizPickerOpacityDown),
+            Key::Right => Some(Action::VizPickerOpacityUp),
+            _ => None,
+        };
+    }
+
+    // Script parameter panel bindings
+    if param_panel_active {
+        return match key {
+            Key::Escape | Key::V => Some(Action::ParamPanelHide),
+            Key::Up => Some(Action::ParamPanelMoveUp),
+            Key::Down => Some(Action::ParamPanelMoveDown),
+            Key::Left => Some(Action::ParamPanelDecrease),
+            Key::Right => Some(Action::ParamPanelIncrease),
+            Key::Return => Some(Action::ParamPanelReset),
             _ => None,
         };
     }
 
+    // Number keys recall preset slots 1-9, Shift+number saves
+    if let Some(slot) = key_to_char(key, false).and_then(|c| c.to_digit(10)) {
+        if slot > 0 {
+            let slot = slot as u8;
+            return Some(if shift {
+                Action::SavePreset(slot)
+            } else {
+                Action::RecallPreset(slot)
+            });
+        }
+    }
+
     // Normal mode bindings
     match key {
         Key::Slash => Some(Action::StartSearch),
         Key::D => Some(Action::ToggleDebugViz),
+        Key::G => Some(Action::ToggleLog),
+        Key::I => Some(Action::ToggleHud),
+        Key::V => Some(Action::ToggleParams),
         Key::L => Some(Action::ToggleLock),
         Key::Space => Some(Action::CycleNext),
+        Key::Back => Some(Action::CyclePrevious),
+        Key::C => Some(Action::ClearOverlays),
+        Key::S if shift => Some(Action::AddScriptOverlay),
         Key::S => Some(Action::CycleScript),
+        Key::O => Some(Action::ToggleScriptOverlay),
+        Key::K => Some(Action::CycleSymmetry),
+        Key::M => Some(Action::CycleMirror),
+        Key::LBracket => Some(Action::BurnStrengthDown),
+        Key::RBracket => Some(Action::BurnStrengthUp),
+        Key::Minus => Some(Action::InputGainDown),
+        Key::Equals => Some(Action::InputGainUp),
+        Key::N => Some(Action::CycleBlendMode),
+        Key::X => Some(Action::ToggleSafeMode),
+        Key::A => Some(Action::ToggleAberration),
+        Key::F => Some(Action::ToggleFullscreen),
+        Key::P => Some(Action::Screenshot),
+        Key::T if shift => Some(Action::ClearTapTempo),
+        Key::T => Some(Action::TapTempo),
         Key::Up | Key::Down => Some(Action::VizPickerShow),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remapped_quit_moves_to_new_key() {
+        let defaults = KeyBindings::default();
+        assert_eq!(parse_key(Key::Q, false, false, false, false, &defaults), Some(Action::Quit));
+
+        let bindings = KeyBindings::from_names([
+            ("quit", "F10"),
+            ("save_preset_2", "Shift+F2"),
+            ("no_such_action", "x"),
+            ("toggle_lock", "NoSuchKey"),
+        ]);
+        assert_eq!(parse_key(Key::F10, false, false, false, false, &bindings), Some(Action::Quit));
+        assert_eq!(parse_key(Key::F10, false, true, false, false, &bindings), Some(Action::Quit));
+        assert_eq!(parse_key(Key::Q, false, false, false, false, &bindings), None);
+        assert_eq!(
+            parse_key(Key::F2, true, false, false, false, &bindings),
+            Some(Action::SavePreset(2))
+        );
+
+        // Invalid entries are skipped, leaving the defaults alone
+        assert_eq!(parse_key(Key::X, false, false, false, false, &bindings), Some(Action::ToggleSafeMode));
+        assert_eq!(parse_key(Key::L, false, false, false, false, &bindings), Some(Action::ToggleLock));
+    }
+
+    #[test]
+    fn test_default_key_names_parse_back_to_their_actions() {
+        let names = default_key_names();
+        assert_eq!(names.len(), ACTION_NAMES.len() + 18);
+        for (action_name, key_name) in &names {
+            let (key, shift) = key_from_name(key_name).unwrap();
+            assert_eq!(
+                default_action(key, shift, false, false, false),
+                action_from_name(action_name),
+                "{} = {}",
+                action_name,
+                key_name
+            );
+        }
+        let key_of = |action: &str| names.iter().find(|(a, _)| a == action).map(|(_, k)| k.as_str());
+        assert_eq!(key_of("cycle_next"), Some("space"));
+        assert_eq!(key_of("clear_tap_tempo"), Some("Shift+t"));
+        assert_eq!(key_of("save_preset_3"), Some("Shift+3"));
+    }
+
+    #[test]
+    fn test_param_panel_takes_arrow_keys() {
+        let defaults = KeyBindings::default();
+        assert_eq!(parse_key(Key::V, false, false, false, false, &defaults), Some(Action::ToggleParams));
+        assert_eq!(parse_key(Key::Up, false, false, false, false, &defaults), Some(Action::VizPickerShow));
+        assert_eq!(parse_key(Key::Up, false, false, false, true, &defaults), Some(Action::ParamPanelMoveUp));
+        assert_eq!(
+            parse_key(Key::Right, false, false, false, true, &defaults),
+            Some(Action::ParamPanelIncrease)
+        );
+        assert_eq!(parse_key(Key::V, false, false, false, true, &defaults), Some(Action::ParamPanelHide));
+        assert_eq!(parse_key(Key::Space, false, false, false, true, &defaults), None);
+    }
+
+    #[test]
+    fn test_blackout_works_over_overlays_but_not_in_search() {
+        let defaults = KeyBindings::default();
+        for (picker, panel) in [(false, false), (true, false), (false, true)] {
+            assert_eq!(
+                parse_key(Key::B, false, false, picker, panel, &defaults),
+                Some(Action::Blackout)
+            );
+        }
+        assert_eq!(
+            parse_key(Key::B, false, true, false, false, &defaults),
+            Some(Action::SearchInput('b'))
+        );
+        let bindings = KeyBindings::from_names([("blackout", "F12")]);
+        assert_eq!(parse_key(Key::F12, false, false, true, false, &bindings), Some(Action::Blackout));
+        assert_eq!(parse_key(Key::B, false, false, false, false, &bindings), None);
+    }
+}
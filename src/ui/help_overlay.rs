@@ -32,19 +32,40 @@ impl HelpOverlay {
         let shortcuts = [
             ("h", "Toggle this help"),
             ("q", "Quit"),
+            ("b", "Blackout (press again to return)"),
             ("", ""),
             ("--- Visualization ---", ""),
             ("l", if locked { "Unlock auto-cycling (currently LOCKED)" } else { "Lock auto-cycling (currently unlocked)" }),
             ("Space", "Cycle to random visualization"),
+            ("Backspace", "Back to the previous visualization"),
             ("Up/Down", "Open viz picker / navigate"),
             ("Scroll", "Open viz picker / navigate"),
             ("Enter/Click", "Select visualization"),
             ("t/Right-click", "Toggle viz as overlay"),
+            ("Drag/Right-click", "Move / center the zoom focus (picker closed)"),
+            ("Left/Right", "Overlay opacity (in picker)"),
+            ("c", "Clear all overlays"),
+            ("[ / ]", "Overlay burn strength down/up"),
+            ("n", "Cycle overlay blend mode"),
+            ("t / T", "Tap tempo / back to auto BPM"),
+            ("1-9 / Shift+1-9", "Recall / save preset slot"),
+            ("x", "Toggle safe mode (on by default, caps brightness flashes)"),
+            ("a", "Toggle chromatic aberration (splits on the bass)"),
+            ("k", "Cycle radial symmetry (off/2/4/6/8)"),
+            ("m", "Cycle mirror (off/horizontal/vertical/quad)"),
             ("Esc", "Close viz picker"),
             ("", ""),
             ("--- Other ---", ""),
+            ("f", "Toggle fullscreen/windowed"),
+            ("p", "Save a screenshot (PNG)"),
+            ("- / =", "Input sensitivity down/up"),
             ("d", "Toggle debug overlay"),
+            ("g", "Toggle on-screen log"),
+            ("i", "Toggle FPS/BPM HUD"),
             ("s", "Cycle Rhai scripts"),
+            ("S", "Add Rhai script as overlay"),
+            ("o", "Toggle script fullscreen/overlay"),
+            ("v", "Script params (arrows adjust, Enter resets)"),
             ("/", "Search audio devices"),
         ];
 
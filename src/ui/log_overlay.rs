@@ -0,0 +1,62 @@
+//! On-screen log of recent status messages.
+
+use nannou::prelude::*;
+
+use crate::utils::recent_log;
+
+/// Manages log overlay visibility
+pub struct LogOverlay {
+    pub visible: bool,
+}
+
+impl LogOverlay {
+    pub fn new() -> Self {
+        Self { visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draw the buffered log lines in the top-left corner, newest at the bottom
+    pub fn draw(&self, draw: &Draw, bounds: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        let lines = recent_log();
+        if lines.is_empty() {
+            return;
+        }
+
+        let padding = 10.0;
+        let line_height = 16.0;
+        let font_size = 12;
+        let width = (bounds.w() * 0.5).min(640.0);
+        let height = line_height * lines.len() as f32 + padding * 2.0;
+        let left = bounds.left() + padding;
+        let top = bounds.top() - padding;
+
+        draw.rect()
+            .x_y(left + width / 2.0, top - height / 2.0)
+            .w_h(width, height)
+            .color(rgba(0.0, 0.0, 0.0, 0.75));
+
+        for (i, line) in lines.iter().enumerate() {
+            let y = top - padding - line_height * (i as f32 + 0.5);
+            draw.text(line)
+                .x_y(left + width / 2.0, y)
+                .w_h(width - padding * 2.0, line_height)
+                .left_justify()
+                .no_line_wrap()
+                .color(rgba(0.85, 0.85, 0.85, 1.0))
+                .font_size(font_size);
+        }
+    }
+}
+
+impl Default for LogOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,201 @@
+//! MIDI controller input.
+//!
+//! Connects to every MIDI input port and turns note-on and control change
+//! messages into `MidiCommand`s, sent from midir's callback thread through a
+//! channel the update loop drains. Ports are rescanned every few seconds, so
+//! unplugged controllers are logged and dropped and plugged-in ones picked up
+//! without a restart.
+
+use midir::{MidiInput, MidiInputConnection};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::bindings::{action_from_name, Action};
+use crate::utils::MidiConfig;
+use crate::{log_error, log_line};
+
+/// How often the port list is checked for connects and disconnects
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+const NOTE_ON: u8 = 0x90;
+const CONTROL_CHANGE: u8 = 0xB0;
+
+/// A control command received from a MIDI controller
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiCommand {
+    /// A note mapped to an action in `[midi.notes]`
+    Action(Action),
+    /// Select the visualization at this index
+    SelectViz(usize),
+    /// Feedback zoom scrub, -1 (out) to 1 (in)
+    Zoom(f32),
+}
+
+/// Note and CC assignments from the config
+struct MidiMapping {
+    notes: HashMap<u8, Action>,
+    viz_base_note: u8,
+    zoom_cc: u8,
+}
+
+impl MidiMapping {
+    fn from_config(config: &MidiConfig) -> Self {
+        let mut notes = HashMap::new();
+        for (note, action_name) in config.notes.iter().flatten() {
+            let Some(note) = note.trim().parse::<u8>().ok().filter(|n| *n < 128) else {
+                log_error!("Invalid MIDI note {:?} in [midi.notes], ignoring", note);
+                continue;
+            };
+            let Some(action) = action_from_name(action_name) else {
+                log_error!("Unknown action {:?} in [midi.notes], ignoring", action_name);
+                continue;
+            };
+            notes.insert(note, action);
+        }
+        Self {
+            notes,
+            viz_base_note: config.viz_base_note(),
+            zoom_cc: config.zoom_cc(),
+        }
+    }
+
+    /// Map a raw MIDI message (any channel) to a command
+    fn command_for(&self, message: &[u8]) -> Option<MidiCommand> {
+        let [status, data1, data2, ..] = *message else {
+            return None;
+        };
+        match status & 0xF0 {
+            // Note-on with velocity 0 is a note-off
+            NOTE_ON if data2 > 0 => match self.notes.get(&data1) {
+                Some(action) => Some(MidiCommand::Action(action.clone())),
+                None => data1
+                    .checked_sub(self.viz_base_note)
+                    .map(|idx| MidiCommand::SelectViz(idx as usize)),
+            },
+            CONTROL_CHANGE if data1 == self.zoom_cc => {
+                Some(MidiCommand::Zoom(((data2 as f32 - 64.0) / 63.0).clamp(-1.0, 1.0)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Connections to all MIDI inputs; commands arrive through `drain`
+pub struct MidiListener {
+    mapping: Arc<MidiMapping>,
+    /// Open connections by port name
+    connections: Vec<(String, MidiInputConnection<()>)>,
+    sender: Sender<MidiCommand>,
+    receiver: Receiver<MidiCommand>,
+    last_scan: Instant,
+}
+
+impl MidiListener {
+    /// Connect to the MIDI inputs present now (others are picked up later)
+    pub fn start(config: &MidiConfig) -> Result<Self, String> {
+        // Fail early if there is no MIDI backend at all
+        MidiInput::new("dj-viz").map_err(|e| format!("MIDI unavailable: {}", e))?;
+        let (sender, receiver) = mpsc::channel();
+        let mut listener = Self {
+            mapping: Arc::new(MidiMapping::from_config(config)),
+            connections: Vec::new(),
+            sender,
+            receiver,
+            last_scan: Instant::now(),
+        };
+        listener.rescan();
+        if listener.connections.is_empty() {
+            log_line!("No MIDI inputs yet, waiting for a controller");
+        }
+        Ok(listener)
+    }
+
+    /// Commands received since the last call, oldest first (also rescans ports now and then)
+    pub fn drain(&mut self) -> Vec<MidiCommand> {
+        if self.last_scan.elapsed() >= RESCAN_INTERVAL {
+            self.rescan();
+        }
+        self.receiver.try_iter().collect()
+    }
+
+    /// Drop connections to ports that went away and connect to new ones
+    fn rescan(&mut self) {
+        self.last_scan = Instant::now();
+        let Ok(input) = MidiInput::new("dj-viz") else {
+            return;
+        };
+        let ports: Vec<_> = input
+            .ports()
+            .into_iter()
+            .filter_map(|port| input.port_name(&port).ok().map(|name| (name, port)))
+            .collect();
+
+        self.connections.retain(|(name, _)| {
+            let present = ports.iter().any(|(port_name, _)| port_name == name);
+            if !present {
+                log_error!("MIDI input disconnected: {}", name);
+            }
+            present
+        });
+
+        for (name, port) in ports {
+            if self.connections.iter().any(|(connected, _)| *connected == name) {
+                continue;
+            }
+            let Ok(input) = MidiInput::new("dj-viz") else {
+                return;
+            };
+            let mapping = Arc::clone(&self.mapping);
+            let sender = self.sender.clone();
+            let callback = move |_timestamp: u64, message: &[u8], _: &mut ()| {
+                if let Some(command) = mapping.command_for(message) {
+                    let _ = sender.send(command);
+                }
+            };
+            match input.connect(&port, "dj-viz-input", callback, ()) {
+                Ok(connection) => {
+                    log_line!("MIDI input connected: {}", name);
+                    self.connections.push((name, connection));
+                }
+                Err(e) => log_error!("Failed to connect MIDI input {}: {}", name, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_maps_notes_and_cc_to_commands() {
+        let config = MidiConfig {
+            viz_base_note: Some(36),
+            zoom_cc: Some(7),
+            notes: Some(BTreeMap::from([
+                ("60".to_string(), "toggle_lock".to_string()),
+                ("200".to_string(), "toggle_lock".to_string()),
+                ("61".to_string(), "no_such_action".to_string()),
+            ])),
+        };
+        let mapping = MidiMapping::from_config(&config);
+
+        // Mapped note on any channel, then notes from the base note select by index
+        assert_eq!(
+            mapping.command_for(&[0x93, 60, 100]),
+            Some(MidiCommand::Action(Action::ToggleLock))
+        );
+        assert_eq!(mapping.command_for(&[0x90, 38, 1]), Some(MidiCommand::SelectViz(2)));
+        assert_eq!(mapping.command_for(&[0x90, 35, 100]), None);
+        assert_eq!(mapping.command_for(&[0x90, 38, 0]), None, "note-off");
+        assert_eq!(mapping.command_for(&[0x80, 38, 64]), None, "note-off");
+
+        assert_eq!(mapping.command_for(&[0xB0, 7, 127]), Some(MidiCommand::Zoom(1.0)));
+        assert_eq!(mapping.command_for(&[0xB0, 7, 64]), Some(MidiCommand::Zoom(0.0)));
+        assert_eq!(mapping.command_for(&[0xB0, 8, 127]), None);
+        assert_eq!(mapping.command_for(&[0xF8]), None, "clock");
+    }
+}
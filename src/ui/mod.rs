@@ -1,4 +1,9 @@
 pub mod bindings;
 pub mod help_overlay;
+pub mod log_overlay;
+pub mod midi;
+pub mod osc;
+pub mod param_panel;
+pub mod status_hud;
 pub mod text_picker;
 pub mod viz_picker;
@@ -0,0 +1,195 @@
+//! OSC remote control over UDP.
+//!
+//! Listens on a background thread and decodes OSC 1.0 packets (messages and
+//! bundles, any argument types) with `rosc` into `OscCommand`s, which the
+//! update loop drains from a channel and applies like the matching keyboard
+//! actions. Supported addresses:
+//!
+//! - `/viz/select <name>` - switch the primary visualization (and lock)
+//! - `/viz/overlay/toggle <name>` - add or remove an overlay
+//! - `/lock <bool>` - lock or unlock auto-cycling (`T`/`F`, or a nonzero number)
+//! - `/feedback/fade <f32>` - feedback trail fade (0-1)
+//!
+//! Other addresses are ignored, so controllers that send extra traffic are fine.
+
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::UdpSocket;
+use std::sync::mpsc::{self, Receiver};
+
+use crate::log_error;
+
+/// Largest datagram accepted (bigger packets are truncated and fail to decode)
+const MAX_PACKET_BYTES: usize = 4096;
+
+/// A control command received over OSC
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscCommand {
+    SelectViz(String),
+    ToggleOverlay(String),
+    Lock(bool),
+    Fade(f32),
+}
+
+/// Background UDP listener; commands arrive through `drain`
+pub struct OscListener {
+    receiver: Receiver<OscCommand>,
+}
+
+impl OscListener {
+    /// Bind `0.0.0.0:port` and start listening
+    pub fn start(port: u16) -> Result<Self, String> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))
+            .map_err(|e| format!("Failed to bind OSC port {}: {}", port, e))?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("osc-listener".to_string())
+            .spawn(move || {
+                let mut buf = [0u8; MAX_PACKET_BYTES];
+                loop {
+                    let len = match socket.recv_from(&mut buf) {
+                        Ok((len, _)) => len,
+                        Err(e) => {
+                            log_error!("OSC receive failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let Some(messages) = parse_packet(&buf[..len]) else {
+                        log_error!("Ignoring malformed OSC packet ({} bytes)", len);
+                        continue;
+                    };
+                    for command in messages.iter().filter_map(command_for) {
+                        if sender.send(command).is_err() {
+                            return; // App is shutting down
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start OSC thread: {}", e))?;
+        Ok(Self { receiver })
+    }
+
+    /// Commands received since the last call, oldest first
+    pub fn drain(&self) -> Vec<OscCommand> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+/// Map a message to a command (None for unknown addresses or missing arguments)
+fn command_for(message: &OscMessage) -> Option<OscCommand> {
+    let first = message.args.first()?;
+    match message.addr.as_str() {
+        "/viz/select" => match first {
+            OscType::String(name) => Some(OscCommand::SelectViz(name.clone())),
+            _ => None,
+        },
+        "/viz/overlay/toggle" => match first {
+            OscType::String(name) => Some(OscCommand::ToggleOverlay(name.clone())),
+            _ => None,
+        },
+        "/lock" => as_bool(first).map(OscCommand::Lock),
+        "/feedback/fade" => as_f32(first).map(|f| OscCommand::Fade(f.clamp(0.0, 1.0))),
+        _ => None,
+    }
+}
+
+/// Numeric argument of any width as a float
+fn as_f32(arg: &OscType) -> Option<f32> {
+    match *arg {
+        OscType::Int(i) => Some(i as f32),
+        OscType::Float(f) => Some(f),
+        OscType::Long(l) => Some(l as f32),
+        OscType::Double(d) => Some(d as f32),
+        _ => None,
+    }
+}
+
+/// `T`/`F`, or a nonzero number
+fn as_bool(arg: &OscType) -> Option<bool> {
+    match arg {
+        OscType::Bool(b) => Some(*b),
+        other => as_f32(other).map(|v| v != 0.0),
+    }
+}
+
+/// Decode a packet (a message or a possibly nested bundle) into its messages
+fn parse_packet(bytes: &[u8]) -> Option<Vec<OscMessage>> {
+    let (_, packet) = rosc::decoder::decode_udp(bytes).ok()?;
+    let mut messages = Vec::new();
+    flatten_into(packet, &mut messages);
+    Some(messages)
+}
+
+/// Bundle elements are applied immediately, whatever their time tag
+fn flatten_into(packet: OscPacket, messages: &mut Vec<OscMessage>) {
+    match packet {
+        OscPacket::Message(message) => messages.push(message),
+        OscPacket::Bundle(bundle) => {
+            for element in bundle.content {
+                flatten_into(element, messages);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosc::{encoder, OscBundle};
+
+    fn message(addr: &str, args: Vec<OscType>) -> OscPacket {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args,
+        })
+    }
+
+    fn commands(packet: &OscPacket) -> Vec<OscCommand> {
+        let bytes = encoder::encode(packet).unwrap();
+        parse_packet(&bytes).unwrap().iter().filter_map(command_for).collect()
+    }
+
+    #[test]
+    fn test_decodes_messages_and_bundles_into_commands() {
+        let select = message("/viz/select", vec![OscType::String("LavaBlobs".to_string())]);
+        let bundle = OscPacket::Bundle(OscBundle {
+            timetag: (0, 1).into(),
+            content: vec![
+                message("/feedback/fade", vec![OscType::Float(1.5)]),
+                message("/lock", vec![OscType::Int(1)]),
+                message("/ping", vec![]),
+            ],
+        });
+
+        assert_eq!(commands(&select), [OscCommand::SelectViz("LavaBlobs".to_string())]);
+        assert_eq!(
+            commands(&bundle),
+            [OscCommand::Fade(1.0), OscCommand::Lock(true)]
+        );
+        assert_eq!(
+            commands(&message("/lock", vec![OscType::Bool(false)])),
+            [OscCommand::Lock(false)]
+        );
+
+        // Wider numbers work too, and arguments of other types after the first are skipped
+        let double_fade = message(
+            "/feedback/fade",
+            vec![OscType::Double(0.25), OscType::Blob(vec![1, 2, 3]), OscType::Nil],
+        );
+        assert_eq!(commands(&double_fade), [OscCommand::Fade(0.25)]);
+        assert_eq!(
+            commands(&message("/lock", vec![OscType::Long(0)])),
+            [OscCommand::Lock(false)]
+        );
+
+        // A nested bundle is flattened
+        let nested = OscPacket::Bundle(OscBundle {
+            timetag: (0, 1).into(),
+            content: vec![bundle, select],
+        });
+        assert_eq!(commands(&nested).len(), 3);
+
+        // Truncated packets are rejected, not misread
+        let bytes = encoder::encode(&double_fade).unwrap();
+        assert!(parse_packet(&bytes[..bytes.len() - 4]).is_none());
+    }
+}
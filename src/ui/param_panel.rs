@@ -0,0 +1,150 @@
+//! Script parameter panel.
+//!
+//! Lists the parameters the running script declared with `param(...)` as
+//! sliders, adjustable from the keyboard. Drawn straight to the frame, like
+//! the viz picker.
+
+use nannou::prelude::*;
+
+use crate::renderer::scripted::ScriptParam;
+
+/// Manages parameter panel visibility and selection
+pub struct ParamPanel {
+    pub active: bool,
+    selected_idx: usize,
+}
+
+impl ParamPanel {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            selected_idx: 0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+    }
+
+    pub fn hide(&mut self) {
+        self.active = false;
+    }
+
+    /// Move selection up (cycles through `count` parameters)
+    pub fn move_up(&mut self, count: usize) {
+        if count > 0 {
+            self.selected_idx = (self.selected(count) + count - 1) % count;
+        }
+    }
+
+    /// Move selection down (cycles through `count` parameters)
+    pub fn move_down(&mut self, count: usize) {
+        if count > 0 {
+            self.selected_idx = (self.selected(count) + 1) % count;
+        }
+    }
+
+    /// Selected parameter index, kept in range when the script drops parameters
+    pub fn selected(&self, count: usize) -> usize {
+        self.selected_idx.min(count.saturating_sub(1))
+    }
+
+    /// Draw the panel at the top-left for `script`'s name and parameters
+    pub fn draw(&self, draw: &Draw, bounds: Rect, script: Option<&(String, Vec<(ScriptParam, f64)>)>) {
+        if !self.active {
+            return;
+        }
+
+        let padding = 16.0;
+        let line_height = 22.0;
+        let font_size = 14;
+        let width = 420.0;
+        let label_width = 130.0;
+        let value_width = 70.0;
+
+        let (title, params) = match script {
+            Some((name, params)) => (format!("Params: {}", name), params.as_slice()),
+            None => ("Params: no script running".to_string(), &[][..]),
+        };
+        let rows = params.len().max(1);
+        let height = line_height * (rows as f32 + 1.0) + padding * 2.0;
+        let x = bounds.left() + padding + width / 2.0;
+        let y = bounds.top() - padding - height / 2.0;
+        let left = x - width / 2.0 + padding;
+
+        draw.rect()
+            .x_y(x, y)
+            .w_h(width, height)
+            .color(rgba(0.0, 0.0, 0.0, 0.85));
+
+        let title_y = y + height / 2.0 - padding - line_height / 2.0;
+        draw.text(&title)
+            .x_y(x, title_y)
+            .w_h(width - padding * 2.0, line_height)
+            .left_justify()
+            .no_line_wrap()
+            .color(rgba(0.5, 0.8, 1.0, 0.9))
+            .font_size(font_size);
+
+        if script.is_some() && params.is_empty() {
+            draw.text("No param(...) declared")
+                .x_y(x, title_y - line_height)
+                .w_h(width - padding * 2.0, line_height)
+                .left_justify()
+                .color(rgba(1.0, 1.0, 1.0, 0.5))
+                .font_size(font_size);
+            return;
+        }
+
+        let selected = self.selected(params.len());
+        let slider_left = left + label_width;
+        let slider_width = width - padding * 2.0 - label_width - value_width;
+        for (i, (param, value)) in params.iter().enumerate() {
+            let row_y = title_y - line_height * (i as f32 + 1.0);
+            let color = if i == selected {
+                rgb(0.3, 0.8, 1.0)
+            } else {
+                rgb(1.0, 1.0, 1.0)
+            };
+            let prefix = if i == selected { "> " } else { "  " };
+
+            draw.text(&format!("{}{}", prefix, param.name))
+                .x_y(left + label_width / 2.0, row_y)
+                .w_h(label_width, line_height)
+                .left_justify()
+                .no_line_wrap()
+                .color(color)
+                .font_size(font_size);
+
+            // Slider track and fill
+            let range = param.max - param.min;
+            let fraction = if range > 0.0 {
+                ((value - param.min) / range) as f32
+            } else {
+                1.0
+            };
+            draw.rect()
+                .x_y(slider_left + slider_width / 2.0, row_y)
+                .w_h(slider_width, 4.0)
+                .color(rgba(1.0, 1.0, 1.0, 0.2));
+            draw.rect()
+                .x_y(slider_left + slider_width * fraction / 2.0, row_y)
+                .w_h(slider_width * fraction, 4.0)
+                .color(color);
+
+            draw.text(&format!("{:.3}", value))
+                .x_y(slider_left + slider_width + value_width / 2.0, row_y)
+                .w_h(value_width - 8.0, line_height)
+                .right_justify()
+                .no_line_wrap()
+                .color(color)
+                .font_size(font_size);
+        }
+    }
+}
+
+impl Default for ParamPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,81 @@
+//! Minimal performance HUD: FPS, BPM and the current visualization.
+//!
+//! A lightweight alternative to the full debug overlay, meant to stay on
+//! during sets. Drawn straight to the frame, so it never ends up in the trails.
+
+use nannou::prelude::*;
+
+use crate::audio::AudioAnalysis;
+
+/// Weight of the previous FPS reading in the moving average
+const FPS_SMOOTHING: f32 = 0.9;
+
+/// Manages HUD visibility and the smoothed frame rate
+pub struct StatusHud {
+    pub visible: bool,
+    fps: f32,
+}
+
+impl StatusHud {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            fps: 0.0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Feed the time since the last frame (smoothed like the debug overlay's FPS)
+    pub fn tick(&mut self, dt: f32) {
+        if dt > 0.0 {
+            self.fps = self.fps * FPS_SMOOTHING + (1.0 / dt) * (1.0 - FPS_SMOOTHING);
+        }
+    }
+
+    /// Draw FPS, BPM and `viz_name` in the bottom-left corner, with a thin energy meter
+    pub fn draw(&self, draw: &Draw, bounds: Rect, analysis: &AudioAnalysis, viz_name: &str) {
+        if !self.visible {
+            return;
+        }
+
+        let bpm = if analysis.bpm > 0.0 {
+            format!("{:.1} BPM", analysis.bpm)
+        } else {
+            "-- BPM".to_string()
+        };
+        let text = format!("{:.0} fps  {}  {}", self.fps, bpm, viz_name);
+
+        let padding = 8.0;
+        let width = 320.0;
+        let height = 34.0;
+        let left = bounds.left() + padding;
+        let bottom = bounds.bottom() + padding;
+
+        draw.rect()
+            .x_y(left + width / 2.0, bottom + height / 2.0)
+            .w_h(width, height)
+            .color(rgba(0.0, 0.0, 0.0, 0.6));
+        draw.text(&text)
+            .x_y(left + width / 2.0, bottom + height - 14.0)
+            .w_h(width - padding * 2.0, 16.0)
+            .left_justify()
+            .no_line_wrap()
+            .color(rgba(0.9, 0.9, 0.9, 1.0))
+            .font_size(12);
+
+        let meter_width = (width - padding * 2.0) * analysis.energy.clamp(0.0, 1.0);
+        draw.rect()
+            .x_y(left + padding + meter_width / 2.0, bottom + 7.0)
+            .w_h(meter_width, 3.0)
+            .color(rgba(0.3, 0.85, 0.5, 0.9));
+    }
+}
+
+impl Default for StatusHud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
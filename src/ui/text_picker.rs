@@ -18,6 +18,60 @@ pub trait TextPickerState {
     fn query(&self) -> &str;
     fn filtered_items(&self) -> &[Self::Item];
     fn selected_index(&self) -> usize;
+
+    /// Shown in place of the list when nothing matches
+    fn empty_message(&self) -> String {
+        "No matches".to_string()
+    }
+}
+
+/// Score for each matched character
+const SCORE_MATCH: i32 = 16;
+/// Bonus for a match right after the previous one
+const BONUS_CONSECUTIVE: i32 = 8;
+/// Bonus for a match at the start of a word (after a separator or a lower-to-upper change)
+const BONUS_BOUNDARY: i32 = 10;
+/// Penalty per skipped character between two matches
+const PENALTY_GAP: i32 = 1;
+
+/// Fuzzy subsequence match (case-insensitive), like fzf: every query character must
+/// appear in `candidate` in order. Returns a score where higher is a better match
+/// (consecutive runs and word starts count most), or None if it doesn't match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let matches = |c: char, q: char| c.to_lowercase().eq(std::iter::once(q));
+    let is_boundary = |i: usize| {
+        i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i - 1].is_lowercase() && chars[i].is_uppercase())
+    };
+
+    // Greedy match from every possible first character, keeping the best
+    (0..chars.len())
+        .filter(|&start| matches(chars[start], query[0]))
+        .filter_map(|start| {
+            let mut score = 0;
+            let mut last: Option<usize> = None;
+            let mut pos = start;
+            for &q in &query {
+                let i = (pos..chars.len()).find(|&i| matches(chars[i], q))?;
+                score += SCORE_MATCH;
+                if is_boundary(i) {
+                    score += BONUS_BOUNDARY;
+                }
+                match last {
+                    Some(prev) if prev + 1 == i => score += BONUS_CONSECUTIVE,
+                    Some(prev) => score -= PENALTY_GAP * (i - prev - 1) as i32,
+                    None => {}
+                }
+                last = Some(i);
+                pos = i + 1;
+            }
+            Some(score)
+        })
+        .max()
 }
 
 /// Draw a text picker overlay on screen
@@ -34,7 +88,8 @@ pub fn draw_text_picker<T: TextPickerState>(draw: &Draw, bounds: Rect, state: &T
 
     // Calculate overlay dimensions - almost full screen width
     let overlay_width = bounds.w() - padding * 2.0;
-    let visible_count = state.filtered_items().len().min(max_visible);
+    // An empty list still keeps a line for the empty message
+    let visible_count = state.filtered_items().len().clamp(1, max_visible);
     let overlay_height = line_height * (visible_count as f32 + 2.0) + padding * 2.0;
 
     // Position at top (centered horizontally)
@@ -93,6 +148,16 @@ pub fn draw_text_picker<T: TextPickerState>(draw: &Draw, bounds: Rect, state: &T
             .font_size(font_size);
     }
 
+    if state.filtered_items().is_empty() {
+        draw.text(&format!("  {}", state.empty_message()))
+            .xy(pt2(text_box_x, sep_y - line_height))
+            .wh(pt2(text_box_width, line_height))
+            .left_justify()
+            .no_line_wrap()
+            .color(rgba(1.0, 1.0, 1.0, 0.5))
+            .font_size(font_size);
+    }
+
     // Show "..." if there are more results
     if state.filtered_items().len() > max_visible {
         let more_y = sep_y - line_height * (max_visible as f32 + 1.0);
@@ -1,12 +1,16 @@
 //! Visualization picker overlay for selecting and toggling visualizations.
 //!
 //! Provides a UI for browsing all available visualizations, selecting the primary
-//! visualization, and toggling overlays on/off.
+//! visualization, and toggling overlays on/off. Each entry shows a small thumbnail,
+//! re-rendered one visualization per frame while the picker is open.
 
 use crate::renderer::VIZ_NAMES;
 use crate::ui::text_picker::{PickerItem, TextPickerState};
 use nannou::prelude::*;
 
+/// Thumbnail texture size in pixels (16:9)
+const THUMBNAIL_SIZE: [u32; 2] = [80, 45];
+
 /// Entry representing a visualization in the picker
 #[derive(Clone, Debug)]
 pub struct VizEntry {
@@ -14,12 +18,89 @@ pub struct VizEntry {
     pub name: &'static str,
     /// Whether this viz is currently active (primary or overlay)
     pub active: bool,
+    /// Blend opacity when used as an overlay (0-1)
+    pub opacity: f32,
+}
+
+impl VizEntry {
+    /// Opacity suffix, only shown when below full strength
+    fn opacity_label(&self) -> String {
+        if self.opacity < 1.0 {
+            format!(" ({:.0}%)", self.opacity * 100.0)
+        } else {
+            String::new()
+        }
+    }
 }
 
 impl PickerItem for VizEntry {
     fn display(&self) -> String {
         let status = if self.active { "[*]" } else { "[ ]" };
-        format!("{} {:2} {}", status, self.index, self.name)
+        format!("{} {:2} {}{}", status, self.index, self.name, self.opacity_label())
+    }
+}
+
+/// Offscreen thumbnail per visualization, refreshed round-robin
+struct VizThumbnails {
+    textures: Vec<wgpu::Texture>,
+    /// Whether each texture has been rendered at least once
+    rendered: Vec<bool>,
+    draw_renderer: nannou::draw::Renderer,
+    /// Visualization to refresh next
+    next: usize,
+}
+
+impl VizThumbnails {
+    fn new(device: &wgpu::Device, count: usize) -> Self {
+        let textures: Vec<wgpu::Texture> = (0..count)
+            .map(|_| {
+                wgpu::TextureBuilder::new()
+                    .size(THUMBNAIL_SIZE)
+                    .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+                    .sample_count(1)
+                    .format(wgpu::TextureFormat::Rgba8UnormSrgb)
+                    .build(device)
+            })
+            .collect();
+        let draw_renderer = nannou::draw::RendererBuilder::new()
+            .build_from_texture_descriptor(device, textures[0].descriptor());
+        Self {
+            rendered: vec![false; count],
+            textures,
+            draw_renderer,
+            next: 0,
+        }
+    }
+
+    /// Render the next thumbnail: `draw_viz(idx, draw)` draws visualization `idx`
+    /// at full window `bounds` into a draw scaled down to the thumbnail
+    fn render_next(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bounds: Rect,
+        draw_viz: impl FnOnce(usize, &Draw),
+    ) {
+        let idx = self.next;
+        self.next = (self.next + 1) % self.textures.len();
+
+        let [w, h] = THUMBNAIL_SIZE;
+        let scale = (w as f32 / bounds.w()).min(h as f32 / bounds.h());
+        let draw = Draw::new();
+        draw.background().color(BLACK);
+        draw_viz(idx, &draw.scale(scale));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Viz Thumbnail Encoder"),
+        });
+        self.draw_renderer
+            .render_to_texture(device, &mut encoder, &draw, &self.textures[idx]);
+        queue.submit(Some(encoder.finish()));
+        self.rendered[idx] = true;
+    }
+
+    fn get(&self, idx: usize) -> Option<&wgpu::Texture> {
+        self.textures.get(idx).filter(|_| self.rendered[idx])
     }
 }
 
@@ -28,6 +109,8 @@ pub struct VizPicker {
     pub active: bool,
     pub entries: Vec<VizEntry>,
     pub selected_idx: usize,
+    /// Created on the first refresh (needs the GPU device)
+    thumbnails: Option<VizThumbnails>,
 }
 
 impl VizPicker {
@@ -39,6 +122,7 @@ impl VizPicker {
                 index: i,
                 name,
                 active: false,
+                opacity: 1.0,
             })
             .collect();
 
@@ -46,7 +130,26 @@ impl VizPicker {
             active: false,
             entries,
             selected_idx: 0,
+            thumbnails: None,
+        }
+    }
+
+    /// Refresh one thumbnail (round-robin) while the picker is open.
+    /// `draw_viz(idx, draw)` must draw visualization `idx` at `bounds`.
+    pub fn update_thumbnails(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bounds: Rect,
+        draw_viz: impl FnOnce(usize, &Draw),
+    ) {
+        if !self.active || self.entries.is_empty() {
+            return;
         }
+        let count = self.entries.len();
+        self.thumbnails
+            .get_or_insert_with(|| VizThumbnails::new(device, count))
+            .render_next(device, queue, bounds, draw_viz);
     }
 
     /// Show the picker
@@ -66,6 +169,13 @@ impl VizPicker {
         }
     }
 
+    /// Record a visualization's overlay opacity for display
+    pub fn set_opacity(&mut self, viz_idx: usize, opacity: f32) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.index == viz_idx) {
+            entry.opacity = opacity;
+        }
+    }
+
     /// Move selection up (cycles)
     pub fn move_up(&mut self) {
         if self.entries.is_empty() {
@@ -126,7 +236,10 @@ pub fn draw_viz_picker(draw: &Draw, bounds: Rect, picker: &VizPicker) {
     let max_visible = 18; // Show all visualizations
 
     // Calculate overlay dimensions
-    let overlay_width = 350.0;
+    let overlay_width = 400.0;
+    let [thumb_w, thumb_h] = THUMBNAIL_SIZE;
+    let thumb_scale = (line_height - 2.0) / thumb_h as f32;
+    let (thumb_w, thumb_h) = (thumb_w as f32 * thumb_scale, thumb_h as f32 * thumb_scale);
     let visible_count = picker.entries.len().min(max_visible);
     let overlay_height = line_height * (visible_count as f32 + 2.0) + padding * 2.0;
 
@@ -178,7 +291,14 @@ pub fn draw_viz_picker(draw: &Draw, bounds: Rect, picker: &VizPicker) {
         // Selection indicator
         let prefix = if is_selected { "> " } else { "  " };
 
-        let text = format!("{}{} {:2} {}", prefix, status, entry.index, entry.name);
+        let text = format!(
+            "{}{} {:2} {}{}",
+            prefix,
+            status,
+            entry.index,
+            entry.name,
+            entry.opacity_label()
+        );
 
         let color = if is_selected {
             rgb(0.3, 0.8, 1.0) // Highlight color
@@ -188,9 +308,25 @@ pub fn draw_viz_picker(draw: &Draw, bounds: Rect, picker: &VizPicker) {
             rgb(1.0, 1.0, 1.0) // White
         };
 
+        // Thumbnail (dark placeholder until first rendered), names to its right
+        let thumb_x = text_left + thumb_w / 2.0;
+        match picker.thumbnails.as_ref().and_then(|t| t.get(entry.index)) {
+            Some(texture) => {
+                draw.texture(texture).x_y(thumb_x, item_y).w_h(thumb_w, thumb_h);
+            }
+            None => {
+                draw.rect()
+                    .x_y(thumb_x, item_y)
+                    .w_h(thumb_w, thumb_h)
+                    .color(rgba(1.0, 1.0, 1.0, 0.1));
+            }
+        }
+
+        let text_x = text_left + thumb_w + 8.0;
+        let text_w = overlay_width - padding * 2.0 - thumb_w - 8.0;
         draw.text(&text)
-            .xy(pt2(text_left + (overlay_width - padding * 2.0) / 2.0, item_y))
-            .wh(pt2(overlay_width - padding * 2.0, line_height))
+            .xy(pt2(text_x + text_w / 2.0, item_y))
+            .wh(pt2(text_w, line_height))
             .left_justify()
             .no_line_wrap()
             .color(color)
@@ -199,10 +335,53 @@ pub fn draw_viz_picker(draw: &Draw, bounds: Rect, picker: &VizPicker) {
 
     // Help text at bottom
     let help_y = sep_y - line_height * (max_visible as f32 + 1.5);
-    draw.text("Enter: select | t/Right-click: toggle overlay | Esc: close")
+    draw.text("Enter: select | t/Right-click: overlay | Left/Right: opacity | Esc: close")
         .xy(pt2(overlay_x, help_y))
         .wh(pt2(overlay_width - padding * 2.0, line_height))
         .center_justify()
         .color(rgba(1.0, 1.0, 1.0, 0.5))
         .font_size(14);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        let instance = wgpu::Instance::default();
+        let adapter = futures::executor::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+        )?;
+        futures::executor::block_on(
+            adapter.request_device(&wgpu::default_device_descriptor(), None),
+        )
+        .ok()
+    }
+
+    #[test]
+    fn test_thumbnails_refresh_one_per_frame() {
+        let Some((device, queue)) = test_device() else {
+            return;
+        };
+        let bounds = Rect::from_w_h(640.0, 360.0);
+        let mut picker = VizPicker::new();
+        let mut drawn = Vec::new();
+
+        // Nothing is rendered while the picker is closed
+        picker.update_thumbnails(&device, &queue, bounds, |idx, _| drawn.push(idx));
+        assert!(drawn.is_empty());
+
+        picker.show();
+        for _ in 0..picker.entries.len() + 1 {
+            picker.update_thumbnails(&device, &queue, bounds, |idx, draw| {
+                drawn.push(idx);
+                draw.rect().w_h(bounds.w(), bounds.h()).color(WHITE);
+            });
+        }
+        let count = picker.entries.len();
+        assert_eq!(drawn[..3], [0, 1, 2]);
+        assert_eq!(drawn[count], 0, "wraps around");
+        let thumbnails = picker.thumbnails.as_ref().unwrap();
+        assert!((0..count).all(|idx| thumbnails.get(idx).is_some()));
+    }
+}
@@ -0,0 +1,90 @@
+//! HSV color helpers shared by the visualizations.
+//!
+//! Hues are in degrees and wrap in both directions (-60 and 300 are the same
+//! hue), so callers can add offsets freely without normalizing first.
+
+use nannou::prelude::*;
+
+/// Convert hue (degrees), saturation (0-1) and value (0-1) to RGB (0-1)
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
+    let hue = hue.rem_euclid(360.0);
+    let c = value * saturation;
+    let x = c * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - c;
+
+    let (r1, g1, b1) = if hue < 60.0 {
+        (c, x, 0.0)
+    } else if hue < 120.0 {
+        (x, c, 0.0)
+    } else if hue < 180.0 {
+        (0.0, c, x)
+    } else if hue < 240.0 {
+        (0.0, x, c)
+    } else if hue < 300.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+
+    (r1 + m, g1 + m, b1 + m)
+}
+
+/// `hsv_to_rgb` with an alpha (0-1), as a drawable color
+pub fn hsv_to_rgba(hue: f32, saturation: f32, value: f32, alpha: f32) -> Srgba<u8> {
+    let (r, g, b) = hsv_to_rgb(hue, saturation, value);
+    srgba(
+        (r * 255.0) as u8,
+        (g * 255.0) as u8,
+        (b * 255.0) as u8,
+        (alpha * 255.0) as u8,
+    )
+}
+
+/// Rotate a color's hue by `degrees`, keeping its saturation and value
+pub fn shift_hue(color: Rgb<u8>, degrees: f32) -> Rgb<u8> {
+    let r = color.red as f32 / 255.0;
+    let g = color.green as f32 / 255.0;
+    let b = color.blue as f32 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    let (r, g, b) = hsv_to_rgb(hue + degrees, saturation, max);
+    rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hue_wraps_and_shifts() {
+        // Hues outside 0-360 land on the same color as their wrapped value
+        assert_eq!(hsv_to_rgb(-60.0, 1.0, 1.0), hsv_to_rgb(300.0, 1.0, 1.0));
+        assert_eq!(hsv_to_rgb(360.0, 1.0, 1.0), hsv_to_rgb(0.0, 1.0, 1.0));
+        assert_eq!(hsv_to_rgb(840.0, 1.0, 1.0), hsv_to_rgb(120.0, 1.0, 1.0));
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (1.0, 0.0, 0.0));
+
+        // Half a turn takes red to cyan, in either direction
+        let red = rgb(255u8, 0, 0);
+        assert_eq!(shift_hue(red, 180.0), rgb(0, 255, 255));
+        assert_eq!(shift_hue(red, -180.0), rgb(0, 255, 255));
+        assert_eq!(shift_hue(red, 360.0), red);
+        // Magenta (300) past the wrap to yellow (60)
+        assert_eq!(shift_hue(rgb(255, 0, 255), 120.0), rgb(255, 255, 0));
+        // Greys have no hue to shift
+        assert_eq!(shift_hue(rgb(128, 128, 128), 90.0), rgb(128, 128, 128));
+    }
+}
@@ -2,11 +2,24 @@
 //!
 //! Handles loading and saving user preferences to `~/.dj-viz.toml`.
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::audio::{WindowFn, MAX_NUM_BANDS, MIN_NUM_BANDS};
+use crate::renderer::VIZ_NAMES;
+use crate::ui::bindings::default_key_names;
+
 const DEFAULT_DEVICE_TIMEOUT_SECS: u64 = 3;
+const DEFAULT_SCRIPT_MAX_OPERATIONS: u64 = 200_000;
+const DEFAULT_INPUT_TARGET_LEVEL: f32 = 0.5;
+
+/// Upper bound for script operation limits (config and per-script `#[ops = N]`),
+/// so a typo can't let a runaway loop stall the render thread for seconds
+pub const SCRIPT_MAX_OPERATIONS_CAP: u64 = 5_000_000;
 
 const CONFIG_TEMPLATE: &str = r#"# dj-viz configuration file
 
@@ -17,9 +30,26 @@ const CONFIG_TEMPLATE: &str = r#"# dj-viz configuration file
 # last_device = "Device Name"
 # last_device_is_input = false
 
+# Input sensitivity: peak level the auto-gain aims for (0.1-1.0, auto-saved when
+# adjusted live with - and =)
+# input_target_level = 0.5
+
 # Last selected PipeWire stream target (auto-saved)
 # pw_link_target = "Spotify:output_FL"
 
+# Rhai script operation limit per frame (default: 200000, max: 5000000)
+# Scripts can override it with a `// #[ops = N]` comment line
+# script_max_operations = 200000
+
+# Start-up scene: boot into a fixed primary plus overlays with auto-cycling locked
+# (names as listed by --dump-catalog; overlays beyond 3 are ignored)
+# start_viz = "LavaBlobs"
+# start_overlays = ["Squares", "BeatBars"]
+
+# Monitor to open on, by index as listed at startup (default: primary display)
+# --monitor <index> overrides it
+# monitor = 1
+
 # =============================================================================
 # Detection Thresholds
 # =============================================================================
@@ -34,40 +64,192 @@ const CONFIG_TEMPLATE: &str = r#"# dj-viz configuration file
 # break_silence_frames = 90       # Frames without beat to trigger break (~1.5s)
 # break_cooldown_frames = 180     # Cooldown between break detections (~3s)
 
+# Silence detection (no music, e.g. between tracks)
+# silence_threshold = 0.05        # Energy below this counts as silence
+# silence_frames = 90             # Frames of silence before silence_detected (~1.5s)
+# dim_on_silence = true           # Fade the visuals to black while silent
+
+# Noise gate (mic or noisy loopback): learns each band's hum level while quiet and subtracts it
+# noise_gate = false
+# noise_gate_threshold_db = -50   # Frames quieter than this (dBFS) teach the noise floor (-90 to -20)
+
 # Instrument detection (spectral complexity)
 # complexity_threshold = 0.15     # Band energy threshold to count as active
 # complexity_change_ratio = 1.5   # Ratio change to trigger add/remove
 
+# Per-band onsets (band_onset in scripts and visualizations)
+# band_onset_ratio = 2.0          # Band amplitude this many times its recent average is an onset (1.05-10)
+
+# Band smoothing (per frame: higher = follows the raw level faster)
+# band_attack = 0.7               # Rising bands (0-1)
+# band_decay = 0.25               # Falling bands (0-1)
+# Per-band overrides, lowest band first (e.g. slow bass decay, snappy treble).
+# Lists shorter or longer than num_bands are stretched across the bands
+# band_attack_per_band = [0.7, 0.7, 0.7, 0.7, 0.7, 0.7, 0.7, 0.7]
+# band_decay_per_band = [0.1, 0.15, 0.2, 0.25, 0.25, 0.3, 0.35, 0.4]
+
+# Latency compensation (only shifts beat_phase / is_beat, not the raw analysis)
+# latency_compensation_ms = 0     # Fire predicted beats this much earlier (0-500)
+# fast_onsets = true              # Time kicks from the newest ~12 ms too (a frame or two sooner)
+
+# Stereo analysis
+# stereo_spectrum = false         # Per-channel spectrum/bands and stereo_width (doubles FFT work)
+# fft_size = 2048                 # FFT window (power of two, 512-16384); larger = finer bass, more latency
+# fft_window = "hann"             # hann, hamming, blackman_harris (least leakage) or rectangular (sharpest)
+# num_bands = 8                   # Frequency bands (8-32); counts other than 8 are log-spaced
+
 # Visualization switching
 # cooldown_frames = 45            # Base cooldown between switches (~0.75s)
 # energy_drop_rate = -0.15        # Rise rate below this = energy dropping
+# quantize_switches = false       # Hold switches (auto and manual) until the next downbeat
+# contrast_selection = false      # Prefer geometric viz for sparse mixes, organic for dense ones
+# crossfade_frames = 30           # Primary crossfade length (~0.5s, 0 = hard cut)
+# max_overlays = 3                # Built-in overlays layered over the primary (0-8, more = more GPU)
+# The default heuristic reacts to punches, breaks, transitions and instruments
+# coming and going, adding/removing overlays gradually. switch_on_viz_change
+# instead gives the analyzer's viz change trigger (a drastic band change at high
+# energy) top priority: it always cuts to a new primary.
+# switch_on_viz_change = false
 
 # =============================================================================
-# Visualization Energy Ranges [min, max]
+# Skeleton Visualizations (DancingSkeletons / ShufflingSkeletons)
 # =============================================================================
-# Each visualization has an energy range it works best at.
-# Format: [[min, max], ...] indexed by visualization order.
-
-# viz_energy_ranges = [
-#   [0.5, 1.0],  # SolarBeat
-#   [0.3, 0.8],  # SpectroRoad
-#   [0.2, 0.6],  # Squares
-#   [0.6, 1.0],  # TeslaCoil
-#   [0.3, 0.7],  # Kaleidoscope
-#   [0.2, 0.5],  # LavaBlobs
-#   [0.4, 0.9],  # BeatBars
-#   [0.3, 0.7],  # CrtPhosphor
-#   [0.5, 1.0],  # BlackHole
-#   [0.1, 0.6],  # GravityFlames
-#   [0.1, 0.4],  # FractalTree
-#   [0.3, 0.7],  # DancingSkeletons
-#   [0.2, 0.6],  # ShufflingSkeletons
-#   [0.5, 1.0],  # PsychedelicSpiral
-#   [0.6, 1.0],  # SpiralTunnel
-#   [0.1, 0.5],  # ParticleNebula
-#   [0.3, 0.7],  # FreqMandala
-#   [0.7, 1.0],  # StrobeGrid
-# ]
+
+# skeleton_max_count = 6              # Max skeletons on screen at low energy
+# skeleton_energy_max_count = 6       # Max skeletons at full energy (capped at 24)
+# skeleton_spawn_chance = 0.05        # Spawn probability per frame
+# skeleton_energy_spawn_boost = 0.0   # Extra spawn chance at full energy (0.0 = none, 1.0 = double)
+# skeleton_scale_min = 0.6            # Smallest skeleton scale
+# skeleton_scale_max = 2.5            # Largest skeleton scale
+# skeleton_face_color = [0, 0, 0]     # Eyes, nose and smile color (RGB 0-255)
+# skeleton_outline_hue_shift = 180.0  # Outline hue offset from the bone color (degrees)
+
+# =============================================================================
+# Fractal Tree
+# =============================================================================
+
+# Color branches by spectral centroid (red bass to violet air, shared hue scale)
+# instead of by the loudest band
+# fractal_tree_centroid_hue = false
+
+# =============================================================================
+# Display
+# =============================================================================
+
+# Auto-brightness: scale the output toward a target average luminance
+# auto_brightness = false             # Off by default
+# auto_brightness_target = 0.35       # Desired average luminance (0.05-0.9)
+# auto_brightness_rate_secs = 3.0     # Seconds to settle after the scene changes
+
+# Color temperature: tint warm on bass-heavy sections, cool on treble-heavy ones
+# color_temperature = false           # Off by default
+# color_temperature_strength = 0.15   # Tint at extreme spectral balance (0.0-0.5)
+
+# Dithering: breaks up color banding in dark gradients and long trails.
+# Most useful on projectors, which make 8-bit banding very visible
+# dither = false                      # Off by default (keeps output bit-exact)
+
+# Chromatic aberration: splits red and blue apart on the bass (toggle live with a)
+# chromatic_aberration = false        # Off by default
+
+# Overlay burn strength (adjust live with [ and ])
+# 1.0 = full screen blend, lower values only let the overlay's highlights burn through
+# burn_strength = 1.0                 # 0.05-1.0
+
+# Safe mode: caps frame-to-frame brightness changes of the whole picture, for
+# every viz including scripts (toggle live with x). On by default because strobes
+# and beat flashes can trigger photosensitive seizures; only turn it off when you
+# know the audience
+# safe_mode = true
+# Largest change in average luminance per second (0.12-6.0), scaled by each
+# frame's duration so it holds at any refresh rate. The default 0.6 needs 1/6 s
+# for a 10% swing, so full-screen flashes stay under the ~3 per second
+# guideline; raise it for snappier but riskier flashes
+# safe_max_luma_rate = 0.6
+
+# =============================================================================
+# Feedback Trails
+# =============================================================================
+# Trail length and the slow zoom drift of the feedback buffer. TOML tables
+# swallow every key below them, so keep the table sections at the end of the file.
+
+# [feedback]
+# fade = 0.97                         # Previous frame kept per frame (0-1, higher = longer trails)
+# base_zoom_amount = 0.006            # Zoom in/out per frame at the peak of the oscillation
+# bass_zoom_gain = 0.012              # Extra zoom per frame at full bass
+# oscillation_seconds = 30.0          # Period of the zoom in/out cycle
+
+# =============================================================================
+# Visualization Energy Ranges
+# =============================================================================
+# The energy range [min, max] each visualization works best at, by name.
+# Unlisted visualizations keep their defaults (shown below).
+
+# [energy_ranges]
+# SolarBeat = [0.5, 0.9]
+# SpectroRoad = [0.8, 1.0]
+# Squares = [0.4, 0.6]
+# TeslaCoil = [0.6, 1.0]
+# Kaleidoscope = [0.3, 0.7]
+# LavaBlobs = [0.1, 0.5]
+# BeatBars = [0.4, 0.9]
+# CrtPhosphor = [0.3, 0.7]
+# BlackHole = [0.5, 1.0]
+# GravityFlames = [0.1, 0.6]
+# FractalTree = [0.0, 0.4]
+# DancingSkeletons = [0.1, 0.6]
+# ShufflingSkeletons = [0.7, 1.0]
+# PsychedelicSpiral = [0.3, 0.9]
+# SpiralTunnel = [0.6, 1.0]
+# ParticleNebula = [0.1, 0.5]
+# FreqMandala = [0.3, 0.7]
+# StrobeGrid = [0.5, 1.0]
+# Oscilloscope = [0.2, 0.8]
+
+# =============================================================================
+# Visualization Weights
+# =============================================================================
+# How often auto-cycling picks each visualization, by name (default 1.0).
+# Above 1.0 favors it, 0.0 keeps it out of auto-cycling entirely (it can
+# still be picked by hand).
+
+# [viz_weights]
+# LavaBlobs = 2.0
+# StrobeGrid = 0.0
+
+# =============================================================================
+# Key Bindings
+# =============================================================================
+# Remap actions to other keys: action = "key". A remapped action no longer
+# answers to its default key. Keys are letters, digits, F1-F12, punctuation,
+# or names like Space, Escape, Enter, Tab, Backspace, Up, Down, Left, Right,
+# optionally prefixed with "Shift+".
+# Actions: quit, show_help, blackout, start_search, toggle_debug_viz,
+# toggle_log, toggle_hud, toggle_params, toggle_lock, cycle_next, cycle_previous,
+# clear_overlays, cycle_script, add_script_overlay, toggle_script_overlay,
+# cycle_symmetry, cycle_mirror, burn_strength_down, burn_strength_up,
+# input_gain_down, input_gain_up, cycle_blend_mode, toggle_safe_mode,
+# toggle_aberration, tap_tempo, clear_tap_tempo, toggle_fullscreen, screenshot,
+# recall_preset_1-9, save_preset_1-9
+
+# [keybindings]
+# quit = "F10"
+# cycle_next = "Enter"
+
+# =============================================================================
+# MIDI (with --midi)
+# =============================================================================
+# Note-on messages select visualizations by index, counting up from
+# viz_base_note, unless the note is mapped to an action in [midi.notes]
+# (action names as in [keybindings]). A control change scrubs the feedback zoom.
+
+# [midi]
+# viz_base_note = 36                  # C1: first pad selects visualization 0
+# zoom_cc = 1                         # Mod wheel; 64 = no extra zoom
+
+# [midi.notes]
+# 60 = "toggle_lock"
+# 62 = "cycle_next"
 "#;
 
 /// Detection thresholds configuration
@@ -83,13 +265,47 @@ pub struct DetectionConfig {
     pub break_silence_frames: Option<u32>, // Frames without beat to trigger break
     pub break_cooldown_frames: Option<u32>, // Cooldown between break detections
 
+    // Silence detection
+    pub silence_threshold: Option<f32>,
+    pub silence_frames: Option<u32>,
+    pub dim_on_silence: Option<bool>,
+
+    // Noise gate
+    pub noise_gate: Option<bool>,
+    pub noise_gate_threshold_db: Option<f32>,
+
     // Instrument detection
     pub complexity_threshold: Option<f32>,
     pub complexity_change_ratio: Option<f32>,
 
+    // Per-band onset detection
+    pub band_onset_ratio: Option<f32>,
+
     // Visualization switching
     pub cooldown_frames: Option<u32>,
     pub energy_drop_rate: Option<f32>,
+    pub quantize_switches: Option<bool>,
+    pub contrast_selection: Option<bool>,
+    pub switch_on_viz_change: Option<bool>,
+    pub crossfade_frames: Option<u32>,
+    pub max_overlays: Option<usize>,
+    pub viz_weights: Option<BTreeMap<String, f32>>,
+
+    // Band smoothing (per-band arrays override the scalars)
+    pub band_attack: Option<f32>,
+    pub band_decay: Option<f32>,
+    pub band_attack_per_band: Option<Vec<f32>>,
+    pub band_decay_per_band: Option<Vec<f32>>,
+
+    // Beat clock
+    pub latency_compensation_ms: Option<f32>,
+    pub fast_onsets: Option<bool>,
+
+    // Stereo analysis
+    pub stereo_spectrum: Option<bool>,
+    pub fft_size: Option<usize>,
+    pub fft_window: Option<WindowFn>,
+    pub num_bands: Option<usize>,
 }
 
 impl Default for DetectionConfig {
@@ -101,10 +317,32 @@ impl Default for DetectionConfig {
             punch_cooldown_frames: None,
             break_silence_frames: None,
             break_cooldown_frames: None,
+            silence_threshold: None,
+            silence_frames: None,
+            dim_on_silence: None,
+            noise_gate: None,
+            noise_gate_threshold_db: None,
             complexity_threshold: None,
             complexity_change_ratio: None,
+            band_onset_ratio: None,
             cooldown_frames: None,
             energy_drop_rate: None,
+            quantize_switches: None,
+            contrast_selection: None,
+            switch_on_viz_change: None,
+            crossfade_frames: None,
+            max_overlays: None,
+            viz_weights: None,
+            band_attack: None,
+            band_decay: None,
+            band_attack_per_band: None,
+            band_decay_per_band: None,
+            latency_compensation_ms: None,
+            fast_onsets: None,
+            stereo_spectrum: None,
+            fft_size: None,
+            fft_window: None,
+            num_bands: None,
         }
     }
 }
@@ -132,6 +370,25 @@ impl DetectionConfig {
         self.break_cooldown_frames.unwrap_or(180) // 3 seconds between break detections
     }
 
+    // Silence detection defaults
+    pub fn silence_threshold(&self) -> f32 {
+        self.silence_threshold.unwrap_or(0.05)
+    }
+    pub fn silence_frames(&self) -> u32 {
+        self.silence_frames.unwrap_or(90) // ~1.5 seconds at 60fps
+    }
+    pub fn dim_on_silence(&self) -> bool {
+        self.dim_on_silence.unwrap_or(true)
+    }
+
+    // Noise gate defaults
+    pub fn noise_gate(&self) -> bool {
+        self.noise_gate.unwrap_or(false)
+    }
+    pub fn noise_gate_threshold_db(&self) -> f32 {
+        self.noise_gate_threshold_db.unwrap_or(-50.0).clamp(-90.0, -20.0)
+    }
+
     // Instrument detection defaults
     pub fn complexity_threshold(&self) -> f32 {
         self.complexity_threshold.unwrap_or(0.15)
@@ -140,6 +397,11 @@ impl DetectionConfig {
         self.complexity_change_ratio.unwrap_or(1.5)
     }
 
+    // Per-band onset defaults
+    pub fn band_onset_ratio(&self) -> f32 {
+        self.band_onset_ratio.unwrap_or(2.0).clamp(1.05, 10.0)
+    }
+
     // Visualization switching defaults
     pub fn cooldown_frames(&self) -> u32 {
         self.cooldown_frames.unwrap_or(45)
@@ -147,36 +409,273 @@ impl DetectionConfig {
     pub fn energy_drop_rate(&self) -> f32 {
         self.energy_drop_rate.unwrap_or(-0.15)
     }
+    pub fn quantize_switches(&self) -> bool {
+        self.quantize_switches.unwrap_or(false)
+    }
+    pub fn contrast_selection(&self) -> bool {
+        self.contrast_selection.unwrap_or(false)
+    }
+    pub fn switch_on_viz_change(&self) -> bool {
+        self.switch_on_viz_change.unwrap_or(false)
+    }
+    pub fn crossfade_frames(&self) -> u32 {
+        self.crossfade_frames.unwrap_or(30) // ~0.5 seconds at 60fps
+    }
+    pub fn max_overlays(&self) -> usize {
+        self.max_overlays.unwrap_or(3).min(8)
+    }
+    /// Auto-cycle weight for a visualization (1.0 unless configured, never negative)
+    pub fn viz_weight(&self, name: &str) -> f32 {
+        self.viz_weights
+            .iter()
+            .flatten()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map_or(DEFAULT_VIZ_WEIGHT, |(_, &weight)| weight.max(0.0))
+    }
+
+    // Band smoothing defaults (fast attack, faster decay so bands don't stay high)
+    pub fn band_attack(&self) -> Vec<f32> {
+        let per_band = self.band_attack_per_band.as_deref();
+        smoothing_factors(per_band, self.band_attack, 0.7, self.num_bands())
+    }
+    pub fn band_decay(&self) -> Vec<f32> {
+        let per_band = self.band_decay_per_band.as_deref();
+        smoothing_factors(per_band, self.band_decay, 0.25, self.num_bands())
+    }
+
+    // Beat clock defaults
+    pub fn latency_compensation_ms(&self) -> f32 {
+        self.latency_compensation_ms.unwrap_or(0.0).clamp(0.0, 500.0)
+    }
+    pub fn fast_onsets(&self) -> bool {
+        self.fast_onsets.unwrap_or(true)
+    }
+
+    // Stereo analysis defaults
+    pub fn stereo_spectrum(&self) -> bool {
+        self.stereo_spectrum.unwrap_or(false)
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.fft_size.unwrap_or(2048)
+    }
+    pub fn fft_window(&self) -> WindowFn {
+        self.fft_window.unwrap_or_default()
+    }
+    pub fn num_bands(&self) -> usize {
+        self.num_bands.unwrap_or(8).clamp(MIN_NUM_BANDS, MAX_NUM_BANDS)
+    }
+}
+
+/// `num_bands` smoothing factors from an optional per-band list (stretched to
+/// the band count), else the scalar, else `default`. Clamped so a band can't
+/// freeze (0) or overshoot (>1).
+fn smoothing_factors(
+    per_band: Option<&[f32]>,
+    scalar: Option<f32>,
+    default: f32,
+    num_bands: usize,
+) -> Vec<f32> {
+    let fallback = scalar.unwrap_or(default);
+    (0..num_bands)
+        .map(|i| match per_band {
+            Some(factors) if !factors.is_empty() => factors[i * factors.len() / num_bands],
+            _ => fallback,
+        })
+        .map(|factor| factor.clamp(0.01, 1.0))
+        .collect()
+}
+
+/// Hard cap on skeletons per visualization (each one is dozens of draw calls)
+const SKELETON_COUNT_CAP: usize = 24;
+
+/// Skeleton visualization spawn configuration
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SkeletonConfig {
+    pub skeleton_max_count: Option<usize>,
+    pub skeleton_energy_max_count: Option<usize>,
+    pub skeleton_spawn_chance: Option<f32>,
+    pub skeleton_energy_spawn_boost: Option<f32>,
+    pub skeleton_scale_min: Option<f32>,
+    pub skeleton_scale_max: Option<f32>,
+    pub skeleton_face_color: Option<[u8; 3]>,
+    pub skeleton_outline_hue_shift: Option<f32>,
+}
+
+impl SkeletonConfig {
+    pub fn max_count(&self) -> usize {
+        self.skeleton_max_count
+            .unwrap_or(6)
+            .clamp(1, SKELETON_COUNT_CAP)
+    }
+    pub fn energy_max_count(&self) -> usize {
+        self.skeleton_energy_max_count
+            .unwrap_or_else(|| self.max_count())
+            .clamp(self.max_count(), SKELETON_COUNT_CAP)
+    }
+    pub fn spawn_chance(&self) -> f32 {
+        self.skeleton_spawn_chance.unwrap_or(0.05).clamp(0.0, 1.0)
+    }
+    pub fn energy_spawn_boost(&self) -> f32 {
+        self.skeleton_energy_spawn_boost.unwrap_or(0.0).max(0.0)
+    }
+    pub fn scale_range(&self) -> (f32, f32) {
+        let min = self.skeleton_scale_min.unwrap_or(0.6).max(0.1);
+        let max = self.skeleton_scale_max.unwrap_or(2.5).max(min + 0.01);
+        (min, max)
+    }
+    pub fn face_color(&self) -> [u8; 3] {
+        self.skeleton_face_color.unwrap_or([0, 0, 0])
+    }
+    /// Outline hue offset in degrees (0-360)
+    pub fn outline_hue_shift(&self) -> f32 {
+        self.skeleton_outline_hue_shift.unwrap_or(180.0).rem_euclid(360.0)
+    }
+
+    /// Skeleton cap at the given energy, rising from `max_count` to `energy_max_count`
+    pub fn cap_for_energy(&self, energy: f32) -> usize {
+        let extra = (self.energy_max_count() - self.max_count()) as f32;
+        self.max_count() + (extra * energy.clamp(0.0, 1.0)).round() as usize
+    }
+
+    /// Per-frame spawn probability at the given energy
+    pub fn spawn_chance_for_energy(&self, energy: f32) -> f32 {
+        (self.spawn_chance() * (1.0 + self.energy_spawn_boost() * energy.clamp(0.0, 1.0))).min(1.0)
+    }
+}
+
+/// Output display configuration
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DisplayConfig {
+    pub auto_brightness: Option<bool>,
+    pub auto_brightness_target: Option<f32>,
+    pub auto_brightness_rate_secs: Option<f32>,
+    pub color_temperature: Option<bool>,
+    pub color_temperature_strength: Option<f32>,
+    pub dither: Option<bool>,
+    pub chromatic_aberration: Option<bool>,
+    pub burn_strength: Option<f32>,
+    pub safe_mode: Option<bool>,
+    pub safe_max_luma_rate: Option<f32>,
+}
+
+impl DisplayConfig {
+    pub fn auto_brightness(&self) -> bool {
+        self.auto_brightness.unwrap_or(false)
+    }
+    pub fn auto_brightness_target(&self) -> f32 {
+        self.auto_brightness_target.unwrap_or(0.35).clamp(0.05, 0.9)
+    }
+    pub fn auto_brightness_rate_secs(&self) -> f32 {
+        self.auto_brightness_rate_secs.unwrap_or(3.0).max(0.1)
+    }
+    pub fn color_temperature(&self) -> bool {
+        self.color_temperature.unwrap_or(false)
+    }
+    pub fn color_temperature_strength(&self) -> f32 {
+        self.color_temperature_strength.unwrap_or(0.15).clamp(0.0, 0.5)
+    }
+    pub fn dither(&self) -> bool {
+        self.dither.unwrap_or(false)
+    }
+    pub fn chromatic_aberration(&self) -> bool {
+        self.chromatic_aberration.unwrap_or(false)
+    }
+    pub fn burn_strength(&self) -> f32 {
+        self.burn_strength.unwrap_or(1.0).clamp(0.05, 1.0)
+    }
+    pub fn safe_mode(&self) -> bool {
+        self.safe_mode.unwrap_or(true)
+    }
+    pub fn safe_max_luma_rate(&self) -> f32 {
+        self.safe_max_luma_rate.unwrap_or(0.6).clamp(0.12, 6.0)
+    }
 }
 
-/// Default energy ranges for visualizations
-pub const DEFAULT_VIZ_ENERGY_RANGES: &[[f32; 2]; 18] = &[
-    [0.5, 0.9], // SolarBeat
-    [0.8, 1.0], // SpectroRoad
-    [0.4, 0.6], // Squares
-    [0.6, 1.0], // TeslaCoil
-    [0.3, 0.7], // Kaleidoscope
-    [0.1, 0.5], // LavaBlobs
-    [0.4, 0.9], // BeatBars
-    [0.3, 0.7], // CrtPhosphor
-    [0.5, 1.0], // BlackHole
-    [0.1, 0.6], // GravityFlames
-    [0.0, 0.4], // FractalTree
-    [0.1, 0.6], // DancingSkeletons
-    [0.7, 1.0], // ShufflingSkeletons
-    [0.3, 0.9], // PsychedelicSpiral
-    [0.6, 1.0], // SpiralTunnel
-    [0.1, 0.5], // ParticleNebula
-    [0.3, 0.7], // FreqMandala
-    [0.5, 1.0], // StrobeGrid
+/// Feedback trail configuration (`[feedback]` table)
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct FeedbackConfig {
+    pub fade: Option<f32>,
+    pub base_zoom_amount: Option<f32>,
+    pub bass_zoom_gain: Option<f32>,
+    pub oscillation_seconds: Option<f32>,
+}
+
+impl FeedbackConfig {
+    pub fn fade(&self) -> f32 {
+        self.fade.unwrap_or(0.97).clamp(0.0, 1.0)
+    }
+    pub fn base_zoom_amount(&self) -> f32 {
+        self.base_zoom_amount.unwrap_or(0.006)
+    }
+    pub fn bass_zoom_gain(&self) -> f32 {
+        self.bass_zoom_gain.unwrap_or(0.012)
+    }
+    pub fn oscillation_seconds(&self) -> f32 {
+        self.oscillation_seconds.unwrap_or(30.0).max(1.0)
+    }
+}
+
+/// MIDI controller mapping (`[midi]` table)
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct MidiConfig {
+    /// Notes from this one up select visualizations by index
+    pub viz_base_note: Option<u8>,
+    /// Control change that scrubs the feedback zoom (centered at 64)
+    pub zoom_cc: Option<u8>,
+    /// Note number -> action name (same names as `[keybindings]`), checked first
+    pub notes: Option<BTreeMap<String, String>>,
+}
+
+impl MidiConfig {
+    pub fn viz_base_note(&self) -> u8 {
+        self.viz_base_note.unwrap_or(36).min(127) // C1, the first pad on most controllers
+    }
+    pub fn zoom_cc(&self) -> u8 {
+        self.zoom_cc.unwrap_or(1).min(127) // Mod wheel
+    }
+}
+
+/// Auto-cycle weight of a visualization not listed in `[viz_weights]`
+const DEFAULT_VIZ_WEIGHT: f32 = 1.0;
+
+/// Default energy ranges for visualizations, by name
+pub const DEFAULT_ENERGY_RANGES: &[(&str, [f32; 2])] = &[
+    ("SolarBeat", [0.5, 0.9]),
+    ("SpectroRoad", [0.8, 1.0]),
+    ("Squares", [0.4, 0.6]),
+    ("TeslaCoil", [0.6, 1.0]),
+    ("Kaleidoscope", [0.3, 0.7]),
+    ("LavaBlobs", [0.1, 0.5]),
+    ("BeatBars", [0.4, 0.9]),
+    ("CrtPhosphor", [0.3, 0.7]),
+    ("BlackHole", [0.5, 1.0]),
+    ("GravityFlames", [0.1, 0.6]),
+    ("FractalTree", [0.0, 0.4]),
+    ("DancingSkeletons", [0.1, 0.6]),
+    ("ShufflingSkeletons", [0.7, 1.0]),
+    ("PsychedelicSpiral", [0.3, 0.9]),
+    ("SpiralTunnel", [0.6, 1.0]),
+    ("ParticleNebula", [0.1, 0.5]),
+    ("FreqMandala", [0.3, 0.7]),
+    ("StrobeGrid", [0.5, 1.0]),
+    ("Oscilloscope", [0.2, 0.8]),
 ];
 
-#[derive(Serialize, Deserialize, Default)]
+/// Energy range for visualizations with neither a configured nor a default range
+pub const FALLBACK_ENERGY_RANGE: [f32; 2] = [0.0, 1.0];
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
 pub struct Config {
     pub last_device: Option<String>,
     pub last_device_is_input: Option<bool>,
+    pub input_target_level: Option<f32>,
     pub device_timeout_secs: Option<u64>,
     pub pw_link_target: Option<String>,
+    pub script_max_operations: Option<u64>,
+    pub start_viz: Option<String>,
+    pub start_overlays: Option<Vec<String>>,
+    pub monitor: Option<usize>,
 
     // Detection thresholds (flattened for simpler TOML)
     pub punch_floor_threshold: Option<f32>,
@@ -185,13 +684,66 @@ pub struct Config {
     pub punch_cooldown_frames: Option<u32>,
     pub break_silence_frames: Option<u32>,
     pub break_cooldown_frames: Option<u32>,
+    pub silence_threshold: Option<f32>,
+    pub silence_frames: Option<u32>,
+    pub dim_on_silence: Option<bool>,
+    pub noise_gate: Option<bool>,
+    pub noise_gate_threshold_db: Option<f32>,
     pub complexity_threshold: Option<f32>,
     pub complexity_change_ratio: Option<f32>,
+    pub band_onset_ratio: Option<f32>,
     pub cooldown_frames: Option<u32>,
     pub energy_drop_rate: Option<f32>,
+    pub quantize_switches: Option<bool>,
+    pub contrast_selection: Option<bool>,
+    pub switch_on_viz_change: Option<bool>,
+    pub crossfade_frames: Option<u32>,
+    pub max_overlays: Option<usize>,
+    pub band_attack: Option<f32>,
+    pub band_decay: Option<f32>,
+    pub band_attack_per_band: Option<Vec<f32>>,
+    pub band_decay_per_band: Option<Vec<f32>>,
+    pub latency_compensation_ms: Option<f32>,
+    pub fast_onsets: Option<bool>,
+    pub stereo_spectrum: Option<bool>,
+    pub fft_size: Option<usize>,
+    pub fft_window: Option<WindowFn>,
+    pub num_bands: Option<usize>,
+
+    // Skeleton visualizations
+    pub skeleton_max_count: Option<usize>,
+    pub skeleton_energy_max_count: Option<usize>,
+    pub skeleton_spawn_chance: Option<f32>,
+    pub skeleton_energy_spawn_boost: Option<f32>,
+    pub skeleton_scale_min: Option<f32>,
+    pub skeleton_scale_max: Option<f32>,
+    pub skeleton_face_color: Option<[u8; 3]>,
+    pub skeleton_outline_hue_shift: Option<f32>,
 
-    // Visualization energy ranges
-    pub viz_energy_ranges: Option<Vec<[f32; 2]>>,
+    // Fractal tree
+    pub fractal_tree_centroid_hue: Option<bool>,
+
+    // Display
+    pub auto_brightness: Option<bool>,
+    pub auto_brightness_target: Option<f32>,
+    pub auto_brightness_rate_secs: Option<f32>,
+    pub color_temperature: Option<bool>,
+    pub color_temperature_strength: Option<f32>,
+    pub dither: Option<bool>,
+    pub chromatic_aberration: Option<bool>,
+    pub burn_strength: Option<f32>,
+    pub safe_mode: Option<bool>,
+    pub safe_max_luma_rate: Option<f32>,
+
+    // TOML tables, so they must stay after every plain key
+    pub feedback: Option<FeedbackConfig>,
+    /// Visualization name -> [min, max] energy range
+    pub energy_ranges: Option<BTreeMap<String, [f32; 2]>>,
+    /// Visualization name -> auto-cycle weight
+    pub viz_weights: Option<BTreeMap<String, f32>>,
+    /// Action name -> key name remappings
+    pub keybindings: Option<BTreeMap<String, String>>,
+    pub midi: Option<MidiConfig>,
 }
 
 impl Config {
@@ -208,7 +760,7 @@ impl Config {
         // Create template file if it doesn't exist
         if !path.exists() {
             let _ = fs::write(&path, CONFIG_TEMPLATE);
-            println!("Created config template at {:?}", path);
+            eprintln!("Created config template at {:?}", path);
         }
 
         fs::read_to_string(&path)
@@ -217,11 +769,239 @@ impl Config {
             .unwrap_or_default()
     }
 
+    /// `load`, but reporting what it would silently ignore: syntax errors, unknown
+    /// keys and out-of-range values, one human-readable line each. On `Err`,
+    /// `load` still gives a usable config (bad values clamped or defaulted).
+    pub fn load_checked() -> Result<Self, Vec<String>> {
+        let config = Self::load();
+        match Self::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(text) => Self::parse_checked(&text),
+            None => Ok(config),
+        }
+    }
+
+    /// Parse config text, listing every problem instead of dropping it
+    fn parse_checked(text: &str) -> Result<Self, Vec<String>> {
+        let invalid =
+            |e: toml::de::Error| vec![format!("Invalid config: {}", e.to_string().trim_end())];
+        let config: Self = toml::from_str(text).map_err(invalid)?;
+        let table: toml::Table = toml::from_str(text).map_err(invalid)?;
+
+        let mut problems = unknown_keys::<Self>(&table, "");
+        for (name, check) in [
+            ("feedback", unknown_keys::<FeedbackConfig> as fn(&toml::Table, &str) -> Vec<String>),
+            ("midi", unknown_keys::<MidiConfig>),
+        ] {
+            if let Some(toml::Value::Table(nested)) = table.get(name) {
+                problems.extend(check(nested, &format!("{}.", name)));
+            }
+        }
+        problems.extend(config.range_problems());
+
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Values outside what their accessors accept (they'd be clamped or defaulted)
+    fn range_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let p = &mut problems;
+        let max_operations = self.script_max_operations;
+        check_range(p, "script_max_operations", max_operations, 1, SCRIPT_MAX_OPERATIONS_CAP);
+        check_range(p, "max_overlays", self.max_overlays, 0, 8);
+        let threshold_db = self.noise_gate_threshold_db;
+        check_range(p, "noise_gate_threshold_db", threshold_db, -90.0, -20.0);
+        check_range(p, "band_onset_ratio", self.band_onset_ratio, 1.05, 10.0);
+        check_range(p, "band_attack", self.band_attack, 0.01, 1.0);
+        check_range(p, "band_decay", self.band_decay, 0.01, 1.0);
+        for (key, factors) in [
+            ("band_attack_per_band", &self.band_attack_per_band),
+            ("band_decay_per_band", &self.band_decay_per_band),
+        ] {
+            for (i, &factor) in factors.iter().flatten().enumerate() {
+                check_range(p, &format!("{}[{}]", key, i), Some(factor), 0.01, 1.0);
+            }
+        }
+        check_range(p, "latency_compensation_ms", self.latency_compensation_ms, 0.0, 500.0);
+        check_range(p, "fft_size", self.fft_size, 512, 16384);
+        check_range(p, "num_bands", self.num_bands, MIN_NUM_BANDS, MAX_NUM_BANDS);
+        check_range(p, "skeleton_max_count", self.skeleton_max_count, 1, SKELETON_COUNT_CAP);
+        check_range(p, "skeleton_spawn_chance", self.skeleton_spawn_chance, 0.0, 1.0);
+        if let (Some(min), Some(max)) = (self.skeleton_scale_min, self.skeleton_scale_max) {
+            if min > max {
+                p.push(format!(
+                    "skeleton_scale_min = {} is above skeleton_scale_max = {}, max raised",
+                    min, max
+                ));
+            }
+        }
+        check_range(p, "auto_brightness_target", self.auto_brightness_target, 0.05, 0.9);
+        check_range(p, "color_temperature_strength", self.color_temperature_strength, 0.0, 0.5);
+        check_range(p, "burn_strength", self.burn_strength, 0.05, 1.0);
+        check_range(p, "safe_max_luma_rate", self.safe_max_luma_rate, 0.12, 6.0);
+
+        let feedback = self.feedback();
+        check_range(p, "feedback.fade", feedback.fade, 0.0, 1.0);
+        let midi = self.midi();
+        check_range(p, "midi.viz_base_note", midi.viz_base_note, 0, 127);
+        check_range(p, "midi.zoom_cc", midi.zoom_cc, 0, 127);
+
+        for (name, [min, max]) in self.energy_ranges.iter().flatten() {
+            if min > max {
+                p.push(format!(
+                    "energy_ranges.{} = [{}, {}] has min above max, using the default range",
+                    name, min, max
+                ));
+            }
+        }
+        for (name, weight) in self.viz_weights.iter().flatten() {
+            if *weight < 0.0 {
+                p.push(format!("viz_weights.{} = {} is negative, treated as 0", name, weight));
+            }
+        }
+        problems
+    }
+
+    /// Config with every tunable set to its built-in default.
+    /// Device and monitor selections and the start scene stay unset since they have no meaningful default.
+    pub fn defaults() -> Self {
+        let detection = DetectionConfig::default();
+        let skeletons = SkeletonConfig::default();
+        let (scale_min, scale_max) = skeletons.scale_range();
+        let display = DisplayConfig::default();
+        let feedback = FeedbackConfig::default();
+        let midi = MidiConfig::default();
+
+        Self {
+            last_device: None,
+            last_device_is_input: None,
+            input_target_level: Some(DEFAULT_INPUT_TARGET_LEVEL),
+            device_timeout_secs: Some(DEFAULT_DEVICE_TIMEOUT_SECS),
+            pw_link_target: None,
+            script_max_operations: Some(DEFAULT_SCRIPT_MAX_OPERATIONS),
+            start_viz: None,
+            start_overlays: None,
+            monitor: None,
+            punch_floor_threshold: Some(detection.punch_floor_threshold()),
+            punch_spike_threshold: Some(detection.punch_spike_threshold()),
+            punch_rise_rate: Some(detection.punch_rise_rate()),
+            punch_cooldown_frames: Some(detection.punch_cooldown_frames()),
+            break_silence_frames: Some(detection.break_silence_frames()),
+            break_cooldown_frames: Some(detection.break_cooldown_frames()),
+            silence_threshold: Some(detection.silence_threshold()),
+            silence_frames: Some(detection.silence_frames()),
+            dim_on_silence: Some(detection.dim_on_silence()),
+            noise_gate: Some(detection.noise_gate()),
+            noise_gate_threshold_db: Some(detection.noise_gate_threshold_db()),
+            complexity_threshold: Some(detection.complexity_threshold()),
+            complexity_change_ratio: Some(detection.complexity_change_ratio()),
+            band_onset_ratio: Some(detection.band_onset_ratio()),
+            cooldown_frames: Some(detection.cooldown_frames()),
+            energy_drop_rate: Some(detection.energy_drop_rate()),
+            quantize_switches: Some(detection.quantize_switches()),
+            contrast_selection: Some(detection.contrast_selection()),
+            switch_on_viz_change: Some(detection.switch_on_viz_change()),
+            crossfade_frames: Some(detection.crossfade_frames()),
+            max_overlays: Some(detection.max_overlays()),
+            band_attack: Some(detection.band_attack()[0]),
+            band_decay: Some(detection.band_decay()[0]),
+            band_attack_per_band: None,
+            band_decay_per_band: None,
+            latency_compensation_ms: Some(detection.latency_compensation_ms()),
+            fast_onsets: Some(detection.fast_onsets()),
+            stereo_spectrum: Some(detection.stereo_spectrum()),
+            fft_size: Some(detection.fft_size()),
+            fft_window: Some(detection.fft_window()),
+            num_bands: Some(detection.num_bands()),
+            skeleton_max_count: Some(skeletons.max_count()),
+            skeleton_energy_max_count: Some(skeletons.energy_max_count()),
+            skeleton_spawn_chance: Some(skeletons.spawn_chance()),
+            skeleton_energy_spawn_boost: Some(skeletons.energy_spawn_boost()),
+            skeleton_scale_min: Some(scale_min),
+            skeleton_scale_max: Some(scale_max),
+            skeleton_face_color: Some(skeletons.face_color()),
+            skeleton_outline_hue_shift: Some(skeletons.outline_hue_shift()),
+            fractal_tree_centroid_hue: Some(Config::default().fractal_tree_centroid_hue()),
+            auto_brightness: Some(display.auto_brightness()),
+            auto_brightness_target: Some(display.auto_brightness_target()),
+            auto_brightness_rate_secs: Some(display.auto_brightness_rate_secs()),
+            color_temperature: Some(display.color_temperature()),
+            color_temperature_strength: Some(display.color_temperature_strength()),
+            dither: Some(display.dither()),
+            chromatic_aberration: Some(display.chromatic_aberration()),
+            burn_strength: Some(display.burn_strength()),
+            safe_mode: Some(display.safe_mode()),
+            safe_max_luma_rate: Some(display.safe_max_luma_rate()),
+            feedback: Some(FeedbackConfig {
+                fade: Some(feedback.fade()),
+                base_zoom_amount: Some(feedback.base_zoom_amount()),
+                bass_zoom_gain: Some(feedback.bass_zoom_gain()),
+                oscillation_seconds: Some(feedback.oscillation_seconds()),
+            }),
+            energy_ranges: Some(
+                DEFAULT_ENERGY_RANGES
+                    .iter()
+                    .map(|&(name, range)| (name.to_string(), range))
+                    .collect(),
+            ),
+            viz_weights: Some(
+                VIZ_NAMES
+                    .iter()
+                    .map(|&name| (name.to_string(), DEFAULT_VIZ_WEIGHT))
+                    .collect(),
+            ),
+            keybindings: Some(default_key_names().into_iter().collect()),
+            midi: Some(MidiConfig {
+                viz_base_note: Some(midi.viz_base_note()),
+                zoom_cc: Some(midi.zoom_cc()),
+                notes: None,
+            }),
+        }
+    }
+
+    /// Render the fully-populated defaults as TOML (for `--print-config-defaults`)
+    pub fn defaults_toml() -> String {
+        let mut value = toml::Value::try_from(Self::defaults()).expect("config serializes to TOML");
+        // Every float in Config is f32; print it as such instead of its widened f64 digits
+        shorten_floats(&mut value);
+        let body = toml::to_string(&value).expect("config serializes to TOML");
+        format!(
+            "# dj-viz default configuration (generated by --print-config-defaults)\n\
+             # Copy to ~/.dj-viz.toml and edit; any key can be removed to keep its default.\n\n{}",
+            body
+        )
+    }
+
     pub fn device_timeout_secs(&self) -> u64 {
         self.device_timeout_secs
             .unwrap_or(DEFAULT_DEVICE_TIMEOUT_SECS)
     }
 
+    /// Auto-gain target peak level (clamped by `SourcePipe::set_target_level`)
+    pub fn input_target_level(&self) -> f32 {
+        self.input_target_level.unwrap_or(DEFAULT_INPUT_TARGET_LEVEL)
+    }
+
+    /// Rhai operation limit per script run, clamped to `SCRIPT_MAX_OPERATIONS_CAP`
+    pub fn script_max_operations(&self) -> u64 {
+        self.script_max_operations
+            .unwrap_or(DEFAULT_SCRIPT_MAX_OPERATIONS)
+            .clamp(1, SCRIPT_MAX_OPERATIONS_CAP)
+    }
+
+    /// Whether FractalTree colors branches by spectral centroid
+    pub fn fractal_tree_centroid_hue(&self) -> bool {
+        self.fractal_tree_centroid_hue.unwrap_or(false)
+    }
+
+    /// Overlays to start with alongside `start_viz` (empty if unset)
+    pub fn start_overlays(&self) -> &[String] {
+        self.start_overlays.as_deref().unwrap_or_default()
+    }
+
     pub fn save(&self) {
         if let Some(path) = Self::path() {
             if let Ok(content) = toml::to_string(self) {
@@ -237,6 +1017,11 @@ impl Config {
         self.save();
     }
 
+    pub fn set_input_target_level(&mut self, level: f32) {
+        self.input_target_level = Some(level);
+        self.save();
+    }
+
     /// Get detection configuration with defaults
     pub fn detection(&self) -> DetectionConfig {
         DetectionConfig {
@@ -246,17 +1031,236 @@ impl Config {
             punch_cooldown_frames: self.punch_cooldown_frames,
             break_silence_frames: self.break_silence_frames,
             break_cooldown_frames: self.break_cooldown_frames,
+            silence_threshold: self.silence_threshold,
+            silence_frames: self.silence_frames,
+            dim_on_silence: self.dim_on_silence,
+            noise_gate: self.noise_gate,
+            noise_gate_threshold_db: self.noise_gate_threshold_db,
             complexity_threshold: self.complexity_threshold,
             complexity_change_ratio: self.complexity_change_ratio,
+            band_onset_ratio: self.band_onset_ratio,
             cooldown_frames: self.cooldown_frames,
             energy_drop_rate: self.energy_drop_rate,
+            quantize_switches: self.quantize_switches,
+            contrast_selection: self.contrast_selection,
+            switch_on_viz_change: self.switch_on_viz_change,
+            crossfade_frames: self.crossfade_frames,
+            max_overlays: self.max_overlays,
+            viz_weights: self.viz_weights.clone(),
+            band_attack: self.band_attack,
+            band_decay: self.band_decay,
+            band_attack_per_band: self.band_attack_per_band.clone(),
+            band_decay_per_band: self.band_decay_per_band.clone(),
+            latency_compensation_ms: self.latency_compensation_ms,
+            fast_onsets: self.fast_onsets,
+            stereo_spectrum: self.stereo_spectrum,
+            fft_size: self.fft_size,
+            fft_window: self.fft_window,
+            num_bands: self.num_bands,
+        }
+    }
+
+    /// Get skeleton visualization configuration
+    pub fn skeletons(&self) -> SkeletonConfig {
+        SkeletonConfig {
+            skeleton_max_count: self.skeleton_max_count,
+            skeleton_energy_max_count: self.skeleton_energy_max_count,
+            skeleton_spawn_chance: self.skeleton_spawn_chance,
+            skeleton_energy_spawn_boost: self.skeleton_energy_spawn_boost,
+            skeleton_scale_min: self.skeleton_scale_min,
+            skeleton_scale_max: self.skeleton_scale_max,
+            skeleton_face_color: self.skeleton_face_color,
+            skeleton_outline_hue_shift: self.skeleton_outline_hue_shift,
+        }
+    }
+
+    /// Get output display configuration
+    pub fn display(&self) -> DisplayConfig {
+        DisplayConfig {
+            auto_brightness: self.auto_brightness,
+            auto_brightness_target: self.auto_brightness_target,
+            auto_brightness_rate_secs: self.auto_brightness_rate_secs,
+            color_temperature: self.color_temperature,
+            color_temperature_strength: self.color_temperature_strength,
+            dither: self.dither,
+            chromatic_aberration: self.chromatic_aberration,
+            burn_strength: self.burn_strength,
+            safe_mode: self.safe_mode,
+            safe_max_luma_rate: self.safe_max_luma_rate,
+        }
+    }
+
+    /// Get feedback trail configuration
+    pub fn feedback(&self) -> FeedbackConfig {
+        self.feedback.clone().unwrap_or_default()
+    }
+
+    /// Get MIDI controller mapping
+    pub fn midi(&self) -> MidiConfig {
+        self.midi.clone().unwrap_or_default()
+    }
+
+    /// Energy range for a visualization by name: configured, else its default
+    pub fn energy_range(&self, name: &str) -> [f32; 2] {
+        let configured = self
+            .energy_ranges
+            .iter()
+            .flatten()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, &range)| range)
+            .filter(|[min, max]| min <= max);
+        configured
+            .or_else(|| {
+                DEFAULT_ENERGY_RANGES
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                    .map(|&(_, range)| range)
+            })
+            .unwrap_or(FALLBACK_ENERGY_RANGE)
+    }
+}
+
+/// Splits a TOML table into the keys `T` reads and everything else
+#[derive(Deserialize)]
+struct KeyProbe<T> {
+    #[serde(flatten)]
+    _known: T,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+/// Keys of `table` that `T` doesn't read (typos, removed settings), as `<prefix><key>`
+fn unknown_keys<T: DeserializeOwned>(table: &toml::Table, prefix: &str) -> Vec<String> {
+    toml::Value::Table(table.clone())
+        .try_into::<KeyProbe<T>>()
+        .map(|probe| {
+            probe
+                .unknown
+                .into_keys()
+                .map(|key| format!("Unknown key {}{}, ignored", prefix, key))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Report `value` for `key` when it lies outside `min..=max`
+fn check_range<T: PartialOrd + Display>(
+    problems: &mut Vec<String>,
+    key: &str,
+    value: Option<T>,
+    min: T,
+    max: T,
+) {
+    if let Some(value) = value {
+        if value < min || value > max {
+            problems.push(format!("{} = {} is outside {} to {}, clamped", key, value, min, max));
+        }
+    }
+}
+
+/// Replace widened f32 values (0.05 -> 0.05000000074505806) with their shortest f32 form
+fn shorten_floats(value: &mut toml::Value) {
+    match value {
+        toml::Value::Float(f) => {
+            *f = (*f as f32).to_string().parse().unwrap_or(*f);
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(shorten_floats),
+        toml::Value::Table(table) => table.iter_mut().for_each(|(_, v)| shorten_floats(v)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_printed_defaults_reparse_to_equal_config() {
+        let printed = Config::defaults_toml();
+        let parsed: Config = toml::from_str(&printed).expect("printed defaults should parse");
+        assert_eq!(parsed, Config::defaults());
+    }
+
+    #[test]
+    fn test_printed_defaults_cover_every_key() {
+        // Per-machine state and opt-in overrides, which have no default to print
+        let mut unset = vec![
+            "band_attack_per_band",
+            "band_decay_per_band",
+            "last_device",
+            "last_device_is_input",
+            "midi.notes",
+            "monitor",
+            "pw_link_target",
+            "start_overlays",
+            "start_viz",
+        ];
+
+        // JSON keeps None fields as null, so it names every field of a struct
+        let fields = |value: serde_json::Value| -> Vec<String> {
+            value.as_object().unwrap().keys().cloned().collect()
+        };
+        let printed: toml::Table = toml::from_str(&Config::defaults_toml()).unwrap();
+        let mut missing: Vec<String> = fields(serde_json::to_value(Config::default()).unwrap())
+            .into_iter()
+            .filter(|key| !printed.contains_key(key))
+            .collect();
+        for (name, keys) in [
+            ("feedback", fields(serde_json::to_value(FeedbackConfig::default()).unwrap())),
+            ("midi", fields(serde_json::to_value(MidiConfig::default()).unwrap())),
+        ] {
+            let table = printed[name].as_table().unwrap();
+            missing.extend(
+                keys.into_iter()
+                    .filter(|key| !table.contains_key(key))
+                    .map(|key| format!("{}.{}", name, key)),
+            );
         }
+        missing.sort_unstable();
+        unset.sort_unstable();
+        assert_eq!(missing, unset);
+
+        // Name-keyed tables list every entry
+        let len = |name: &str| printed[name].as_table().unwrap().len();
+        assert_eq!(len("energy_ranges"), DEFAULT_ENERGY_RANGES.len());
+        assert_eq!(len("viz_weights"), VIZ_NAMES.len());
+        assert_eq!(len("keybindings"), default_key_names().len());
+    }
+
+    #[test]
+    fn test_checked_parse_reports_bad_values_and_keys() {
+        assert_eq!(Config::parse_checked(&Config::defaults_toml()), Ok(Config::defaults()));
+
+        let problems = Config::parse_checked(
+            "fade = 0.9\n\
+             [feedback]\nfade = 1.5\n\
+             [energy_ranges]\nSquares = [0.8, 0.2]\nLavaBlobs = [0.1, 0.5]\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            problems,
+            [
+                "Unknown key fade, ignored",
+                "feedback.fade = 1.5 is outside 0 to 1, clamped",
+                "energy_ranges.Squares = [0.8, 0.2] has min above max, using the default range",
+            ]
+        );
+
+        // The lenient parse still runs with the bad values clamped / defaulted
+        let config: Config =
+            toml::from_str("[feedback]\nfade = 1.5\n[energy_ranges]\nSquares = [0.8, 0.2]").unwrap();
+        assert_eq!(config.feedback().fade(), 1.0);
+        assert_eq!(config.energy_range("Squares"), [0.4, 0.6]);
+
+        let syntax = Config::parse_checked("fade = ").unwrap_err();
+        assert_eq!(syntax.len(), 1);
+        assert!(syntax[0].starts_with("Invalid config"), "{}", syntax[0]);
     }
 
-    /// Get visualization energy ranges (with defaults if not configured)
-    pub fn viz_energy_ranges(&self) -> Vec<[f32; 2]> {
-        self.viz_energy_ranges
-            .clone()
-            .unwrap_or_else(|| DEFAULT_VIZ_ENERGY_RANGES.to_vec())
+    #[test]
+    fn test_printed_defaults_use_short_floats() {
+        let printed = Config::defaults_toml();
+        assert!(printed.contains("skeleton_spawn_chance = 0.05\n"));
+        assert!(!printed.contains("0.0500000"));
     }
 }
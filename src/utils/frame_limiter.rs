@@ -0,0 +1,66 @@
+//! Frame rate cap for eco mode.
+//!
+//! nannou's loop modes can't cap the rate (its `Rate` mode behaves like
+//! `RefreshSync`), so the update handler sleeps until the next frame is due.
+//! Deadlines advance by a fixed interval rather than from the wake-up time, so
+//! the average rate holds even when vsync rounds individual frames.
+//! The renderer is told the capped rate so it steps animations to match.
+
+use std::time::{Duration, Instant};
+
+/// Sleeps in `wait` so frames start at most `fps` times per second
+pub struct FrameLimiter {
+    interval: Duration,
+    /// When the next frame may start (None before the first frame)
+    next_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    pub fn new(fps: f32) -> Self {
+        Self {
+            interval: Duration::from_secs_f32(1.0 / fps.max(1.0)),
+            next_frame: None,
+        }
+    }
+
+    /// Block until the next frame is due
+    pub fn wait(&mut self) {
+        let now = Instant::now();
+        let due = self.next_frame.unwrap_or(now);
+        if due > now {
+            std::thread::sleep(due - now);
+        }
+        self.next_frame = Some(next_deadline(due, Instant::now(), self.interval));
+    }
+}
+
+/// Deadline after a frame that was due at `due`. A frame running later than a
+/// whole interval restarts the schedule instead of rushing to catch up
+fn next_deadline(due: Instant, now: Instant, interval: Duration) -> Instant {
+    let next = due + interval;
+    if next < now {
+        now
+    } else {
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadlines_keep_the_rate_without_catching_up() {
+        let interval = Duration::from_millis(33);
+        let start = Instant::now();
+
+        // Woken a little late (vsync): the schedule stays on the fixed grid
+        let due = next_deadline(start, start + Duration::from_millis(5), interval);
+        assert_eq!(due, start + interval);
+
+        // A frame slower than the interval starts the next one right away, once
+        let late = start + Duration::from_millis(100);
+        assert_eq!(next_deadline(start, late, interval), late);
+        assert_eq!(next_deadline(late, late, interval), late + interval);
+    }
+}
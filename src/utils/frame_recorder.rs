@@ -0,0 +1,157 @@
+//! PNG sequence recording.
+//!
+//! Frames read back from the GPU are handed to a writer thread through a short
+//! bounded queue, so PNG encoding and disk writes never stall the render loop.
+//! When the disk can't keep up the queue fills and further frames are dropped
+//! (and counted) instead of piling up in memory.
+
+use nannou::image;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+
+/// Frames waiting to be written before new ones are dropped (~130ms at 60fps)
+const QUEUE_FRAMES: usize = 8;
+
+struct QueuedFrame {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// Writes frames as `frame-000000.png`, `frame-000001.png`, ... into a directory
+pub struct FrameRecorder {
+    dir: PathBuf,
+    sender: Option<SyncSender<QueuedFrame>>,
+    writer: Option<JoinHandle<()>>,
+    /// Frames queued so far (also the next file number)
+    queued: u64,
+    /// Frames dropped because the writer fell behind
+    dropped: u64,
+}
+
+impl FrameRecorder {
+    /// Create `dir` if needed and start the writer thread
+    pub fn new(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        let (sender, receiver) = mpsc::sync_channel::<QueuedFrame>(QUEUE_FRAMES);
+        let writer = std::thread::Builder::new()
+            .name("frame-recorder".to_string())
+            .spawn(move || {
+                for frame in receiver {
+                    if let Err(e) = image::save_buffer(
+                        &frame.path,
+                        &frame.rgba,
+                        frame.width,
+                        frame.height,
+                        image::ColorType::Rgba8,
+                    ) {
+                        eprintln!("Failed to write {}: {}", frame.path.display(), e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start recorder thread: {}", e))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            sender: Some(sender),
+            writer: Some(writer),
+            queued: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Queue one tightly packed RGBA8 frame. Returns false if it was dropped
+    /// because the writer is still busy with earlier frames.
+    pub fn push(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> bool {
+        self.queue(width, height, rgba, false)
+    }
+
+    /// Like `push`, but waits for room in the queue instead of dropping the frame
+    /// (for offline rendering, where every frame must be written)
+    pub fn push_blocking(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> bool {
+        self.queue(width, height, rgba, true)
+    }
+
+    fn queue(&mut self, width: u32, height: u32, rgba: Vec<u8>, block: bool) -> bool {
+        let Some(sender) = &self.sender else {
+            return false;
+        };
+        if rgba.len() != (width * height * 4) as usize {
+            return false;
+        }
+        let frame = QueuedFrame {
+            path: self.dir.join(format!("frame-{:06}.png", self.queued)),
+            width,
+            height,
+            rgba,
+        };
+        let sent = if block {
+            sender.send(frame).map_err(|e| TrySendError::Disconnected(e.0))
+        } else {
+            sender.try_send(frame)
+        };
+        match sent {
+            Ok(()) => {
+                self.queued += 1;
+                true
+            }
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                self.dropped += 1;
+                false
+            }
+        }
+    }
+
+    /// Frames dropped so far because the disk couldn't keep up
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Stop accepting frames and wait for the queued ones to be written
+    pub fn finish(&mut self) {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_numbered_pngs() {
+        let dir = std::env::temp_dir().join(format!("dj-viz-record-test-{}", std::process::id()));
+        let mut recorder = FrameRecorder::new(&dir).unwrap();
+        let pixels = [255u8, 0, 0, 255].repeat(6);
+        for _ in 0..3 {
+            // The writer may fall behind, but never by a whole queue of tiny frames
+            assert!(recorder.push(3, 2, pixels.clone()));
+        }
+        assert!(!recorder.push(3, 2, vec![0; 5]), "wrong-sized frames are rejected");
+        // Blocking pushes wait out a full queue instead of dropping
+        for _ in 0..QUEUE_FRAMES * 2 {
+            assert!(recorder.push_blocking(3, 2, pixels.clone()));
+        }
+        recorder.finish();
+        assert_eq!(recorder.dropped(), 0);
+
+        let last = 3 + QUEUE_FRAMES * 2 - 1;
+        assert!(dir.join(format!("frame-{:06}.png", last)).exists());
+        let frame = image::open(dir.join("frame-000002.png")).unwrap().to_rgba8();
+        assert_eq!(frame.dimensions(), (3, 2));
+        assert_eq!(frame.get_pixel(2, 1).0, [255, 0, 0, 255]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,71 @@
+//! On-screen log buffer.
+//!
+//! Status messages (device selection, viz switches, script reloads) go to the
+//! terminal as before and are also kept in a small ring buffer, so they can be
+//! shown in fullscreen where stdout isn't visible. Use `log_line!` / `log_error!`
+//! in place of `println!` / `eprintln!`.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Lines kept for the on-screen log
+const LOG_CAPACITY: usize = 20;
+
+static LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Append a message to the log buffer, one entry per non-empty line,
+/// dropping the oldest entries past capacity
+pub fn push_log(message: &str) {
+    let Ok(mut log) = LOG.lock() else {
+        return;
+    };
+    for line in message.lines().map(str::trim_end).filter(|l| !l.trim().is_empty()) {
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(line.to_string());
+    }
+}
+
+/// Snapshot of the buffered lines, oldest first
+pub fn recent_log() -> Vec<String> {
+    LOG.lock().map(|log| log.iter().cloned().collect()).unwrap_or_default()
+}
+
+/// `println!` that is also kept in the on-screen log
+#[macro_export]
+macro_rules! log_line {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        println!("{}", message);
+        $crate::utils::push_log(&message);
+    }};
+}
+
+/// `eprintln!` that is also kept in the on-screen log
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        eprintln!("{}", message);
+        $crate::utils::push_log(&message);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_keeps_most_recent_lines() {
+        for i in 0..LOG_CAPACITY + 5 {
+            push_log(&format!("line {}\n", i));
+        }
+        push_log("\n");
+
+        let lines = recent_log();
+        assert_eq!(lines.len(), LOG_CAPACITY);
+        assert_eq!(lines.last().map(String::as_str), Some("line 24"));
+        assert_eq!(lines.first().map(String::as_str), Some("line 5"));
+    }
+}
@@ -1,9 +1,20 @@
 mod audio_info;
+mod color;
 mod config;
+mod frame_limiter;
+mod frame_recorder;
+mod log_buffer;
 mod screensaver;
 mod viewport;
 
 pub use audio_info::log_audio_info;
-pub use config::{Config, DetectionConfig};
+pub use color::{hsv_to_rgb, hsv_to_rgba, shift_hue};
+pub use config::{
+    Config, DetectionConfig, DisplayConfig, FeedbackConfig, MidiConfig, SkeletonConfig,
+    SCRIPT_MAX_OPERATIONS_CAP,
+};
+pub use frame_limiter::FrameLimiter;
+pub use frame_recorder::FrameRecorder;
+pub use log_buffer::{push_log, recent_log};
 pub use screensaver::ScreensaverInhibitor;
 pub use viewport::{get_crossing_path, get_random_edge_coord};
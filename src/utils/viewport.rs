@@ -10,6 +10,7 @@ use rand::Rng;
 /// connecting the center to the edge point.
 ///
 /// # Arguments
+/// * `rng` - Source of the random edge and position
 /// * `bounds_width` - Width of the viewport
 /// * `bounds_height` - Height of the viewport
 /// * `pushback` - Distance to move the point outward from the center (in pixels)
@@ -17,11 +18,14 @@ use rand::Rng;
 /// # Example
 /// ```
 /// // Get a random point on the edge, 50px outside the viewport
-/// let point = get_random_edge_coord(800.0, 600.0, 50.0);
+/// let point = get_random_edge_coord(&mut rng, 800.0, 600.0, 50.0);
 /// ```
-pub fn get_random_edge_coord(bounds_width: f32, bounds_height: f32, pushback: f32) -> Vec2 {
-    let mut rng = rand::rng();
-
+pub fn get_random_edge_coord(
+    rng: &mut impl Rng,
+    bounds_width: f32,
+    bounds_height: f32,
+    pushback: f32,
+) -> Vec2 {
     // Pick a random edge (0=left, 1=right, 2=top, 3=bottom)
     let edge = rng.random_range(0..4);
 
@@ -67,6 +71,7 @@ pub fn get_random_edge_coord(bounds_width: f32, bounds_height: f32, pushback: f3
 /// The path crosses through the center of the viewport, ensuring diagonal traversal.
 ///
 /// # Arguments
+/// * `rng` - Source of the random start position
 /// * `bounds_width` - Width of the viewport
 /// * `bounds_height` - Height of the viewport
 /// * `offset` - Distance outside viewport boundaries for start/end points
@@ -77,11 +82,16 @@ pub fn get_random_edge_coord(bounds_width: f32, bounds_height: f32, pushback: f3
 /// # Example
 /// ```
 /// // Get a crossing path with 50px offset
-/// let (start, end) = get_crossing_path(800.0, 600.0, 50.0);
+/// let (start, end) = get_crossing_path(&mut rng, 800.0, 600.0, 50.0);
 /// let velocity = (end - start).normalize() * speed;
 /// ```
-pub fn get_crossing_path(bounds_width: f32, bounds_height: f32, offset: f32) -> (Vec2, Vec2) {
-    let start = get_random_edge_coord(bounds_width, bounds_height, offset);
+pub fn get_crossing_path(
+    rng: &mut impl Rng,
+    bounds_width: f32,
+    bounds_height: f32,
+    offset: f32,
+) -> (Vec2, Vec2) {
+    let start = get_random_edge_coord(rng, bounds_width, bounds_height, offset);
 
     // Calculate end position: opposite side through center
     // The skeleton crosses from one edge to the opposite edge